@@ -0,0 +1,4 @@
+// src/analytics/mod.rs
+
+pub mod performance_report;
+pub mod monte_carlo;
@@ -0,0 +1,255 @@
+// src/analytics/monte_carlo.rs
+
+use tracing::info;
+use std::fmt;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use super::performance_report::ClosedTrade;
+
+/// How the trade sequences of the simulations are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResamplingMethod {
+    Shuffle,                             // Same trades in a random order
+    Bootstrap,                           // Same number of trades drawn with replacement
+}
+
+impl fmt::Display for ResamplingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResamplingMethod::Shuffle => write!(f, "shuffle"),
+            ResamplingMethod::Bootstrap => write!(f, "bootstrap"),
+        }
+    }
+}
+
+/// Settings of the Monte Carlo analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloSettings {
+    pub simulations: usize,              // Number of simulated trade sequences
+    pub method: ResamplingMethod,
+    pub seed: u64,                       // Seed of the random number generator
+    pub max_slippage: f64,               // Max slippage per side as share of the price (uniform in 0..max), 0 disables it
+    pub fee_perturbation: f64,           // Fees are scaled by a uniform factor in 1 ± this value, 0 disables it
+    pub risk_levels: Vec<f64>,           // `risk_per_trade_percent` levels to calculate the probability of ruin for
+    pub ruin_drawdown: f64,              // Drawdown from the initial balance that counts as ruin (e.g. 0.5 for 50%)
+}
+
+/// Summary of a sampled distribution.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub p5: f64,                         // 5th percentile
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+/// Probability of ruin when every trade risks `risk_per_trade_percent` of the balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuinProbability {
+    pub risk_per_trade_percent: f64,
+    pub probability_of_ruin: f64,        // Share of the simulations that hit the ruin drawdown
+    pub final_equity: Distribution,      // Final balance in sats
+}
+
+/// Result of the Monte Carlo analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonteCarloReport {
+    pub simulations: usize,
+    pub method: ResamplingMethod,
+    pub seed: u64,
+    pub final_equity: Distribution,      // Final balance in sats (trade sizes as traded)
+    pub max_drawdown: Distribution,      // Max drawdown relative to the peak
+    pub probability_of_loss: f64,        // Share of the simulations that end below the initial balance
+    pub ruin: Vec<RuinProbability>,
+}
+
+/// Summarizes samples as mean, standard deviation and percentiles (nearest rank).
+pub fn calculate_distribution(samples: &[f64]) -> Distribution {
+    if samples.is_empty() {
+        return Distribution::default();
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    let percentile = |p: f64| sorted[((p * (sorted.len() - 1) as f64).round() as usize).min(sorted.len() - 1)];
+    let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+    let variance = sorted.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / sorted.len() as f64;
+
+    Distribution {
+        mean,
+        std_dev: variance.sqrt(),
+        min: sorted[0],
+        p5: percentile(0.05),
+        p25: percentile(0.25),
+        median: percentile(0.5),
+        p75: percentile(0.75),
+        p95: percentile(0.95),
+        max: sorted[sorted.len() - 1],
+    }
+}
+
+// Net P&L of a trade with random slippage on both sides and perturbed fees
+fn perturbed_net_pl(trade: &ClosedTrade, settings: &MonteCarloSettings, rng: &mut StdRng) -> f64 {
+    let mut net_pl = trade.pl - trade.fees();
+    if settings.fee_perturbation > 0.0 {
+        let factor = 1.0 + rng.gen_range(-settings.fee_perturbation..=settings.fee_perturbation);
+        net_pl = trade.pl - trade.fees() * factor.max(0.0);
+    }
+    if settings.max_slippage > 0.0 && trade.entry_price > 0.0 && trade.exit_price > 0.0 {
+        let entry_slippage = rng.gen_range(0.0..=settings.max_slippage);
+        let exit_slippage = rng.gen_range(0.0..=settings.max_slippage);
+        // Position value in sats at entry and exit
+        let entry_sats = trade.quantity / trade.entry_price * 100_000_000.0;
+        let exit_sats = trade.quantity / trade.exit_price * 100_000_000.0;
+        net_pl -= entry_sats * entry_slippage + exit_sats * exit_slippage;
+    }
+    net_pl
+}
+
+// Lowest equity relative to its running peak, along the sequence of P&L changes
+fn sequence_drawdown(initial_balance: f64, changes: impl Iterator<Item = f64>) -> (f64, f64) {
+    let mut equity = initial_balance;
+    let mut peak = equity;
+    let mut max_drawdown: f64 = 0.0;
+    for change in changes {
+        equity += change;
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - equity) / peak);
+        }
+    }
+    (equity, max_drawdown)
+}
+
+/// Runs Monte Carlo simulations of the trade sequence.
+///
+/// Every simulation draws a sequence of trades (shuffle or bootstrap) and applies the optional
+/// slippage and fee perturbation. The final equity and max drawdown distributions use the trades
+/// with their traded size. For the probability of ruin the net P&L of every trade is expressed in
+/// R-multiples (1R is the average net loss of the input trades) and compounded at each
+/// `risk_per_trade_percent` level: a trade changes the balance by `balance * risk * R`.
+///
+/// # Parameters
+/// - `trades`: The closed trades.
+/// - `initial_balance_sats`: Balance before the first trade.
+/// - `settings`: The simulation settings.
+///
+/// # Returns
+/// - `None` if there are no trades or no simulations, otherwise the report.
+pub fn run_monte_carlo(trades: &[ClosedTrade], initial_balance_sats: f64, settings: &MonteCarloSettings) -> Option<MonteCarloReport> {
+    if trades.is_empty() || settings.simulations == 0 {
+        return None;
+    }
+
+    // One R is the average net loss, or the average absolute net P&L without losing trades
+    let losses: Vec<f64> = trades.iter().map(|trade| trade.net_pl()).filter(|pl| *pl < 0.0).collect();
+    let unit_risk = if losses.is_empty() {
+        trades.iter().map(|trade| trade.net_pl().abs()).sum::<f64>() / trades.len() as f64
+    } else {
+        -losses.iter().sum::<f64>() / losses.len() as f64
+    };
+
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let mut final_equities = Vec::with_capacity(settings.simulations);
+    let mut max_drawdowns = Vec::with_capacity(settings.simulations);
+    let mut ruined = vec![0usize; settings.risk_levels.len()];
+    let mut risk_final_equities = vec![Vec::with_capacity(settings.simulations); settings.risk_levels.len()];
+    let mut sequence: Vec<&ClosedTrade> = trades.iter().collect();
+
+    for _ in 0..settings.simulations {
+        match settings.method {
+            ResamplingMethod::Shuffle => sequence.shuffle(&mut rng),
+            ResamplingMethod::Bootstrap => {
+                for slot in sequence.iter_mut() {
+                    *slot = &trades[rng.gen_range(0..trades.len())];
+                }
+            }
+        }
+        let net_pls: Vec<f64> = sequence.iter().map(|trade| perturbed_net_pl(trade, settings, &mut rng)).collect();
+
+        let (final_equity, max_drawdown) = sequence_drawdown(initial_balance_sats, net_pls.iter().copied());
+        final_equities.push(final_equity);
+        max_drawdowns.push(max_drawdown);
+
+        if unit_risk > 0.0 {
+            let ruin_balance = initial_balance_sats * (1.0 - settings.ruin_drawdown);
+            for (level, risk) in settings.risk_levels.iter().enumerate() {
+                let mut balance = initial_balance_sats;
+                let mut is_ruined = false;
+                for net_pl in &net_pls {
+                    balance += balance * risk * net_pl / unit_risk;
+                    if balance <= ruin_balance {
+                        is_ruined = true;
+                        break;
+                    }
+                }
+                if is_ruined {
+                    ruined[level] += 1;
+                }
+                risk_final_equities[level].push(balance.max(0.0));
+            }
+        }
+    }
+
+    let simulations = settings.simulations as f64;
+    let ruin = settings
+        .risk_levels
+        .iter()
+        .enumerate()
+        .map(|(level, risk)| RuinProbability {
+            risk_per_trade_percent: *risk,
+            probability_of_ruin: ruined[level] as f64 / simulations,
+            final_equity: calculate_distribution(&risk_final_equities[level]),
+        })
+        .collect();
+
+    Some(MonteCarloReport {
+        simulations: settings.simulations,
+        method: settings.method,
+        seed: settings.seed,
+        probability_of_loss: final_equities.iter().filter(|equity| **equity < initial_balance_sats).count() as f64 / simulations,
+        final_equity: calculate_distribution(&final_equities),
+        max_drawdown: calculate_distribution(&max_drawdowns),
+        ruin,
+    })
+}
+
+/// Logs the distributions and the probabilities of ruin.
+pub fn log_monte_carlo_report(report: &MonteCarloReport) {
+    info!(simulations = report.simulations, method = %report.method, seed = report.seed, "Monte Carlo");
+    let equity = &report.final_equity;
+    info!(
+        p5 = equity.p5,
+        p25 = equity.p25,
+        median = equity.median,
+        p75 = equity.p75,
+        p95 = equity.p95,
+        "Final equity (sats)"
+    );
+    let drawdown = &report.max_drawdown;
+    info!(
+        p5 = drawdown.p5,
+        p25 = drawdown.p25,
+        median = drawdown.median,
+        p75 = drawdown.p75,
+        p95 = drawdown.p95,
+        "Max drawdown"
+    );
+    info!(probability = report.probability_of_loss, "Probability of loss");
+
+    for ruin in &report.ruin {
+        info!(
+            risk_per_trade = ruin.risk_per_trade_percent,
+            probability = ruin.probability_of_ruin,
+            median_final_equity = ruin.final_equity.median,
+            "Probability of ruin"
+        );
+    }
+}
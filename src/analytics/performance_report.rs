@@ -0,0 +1,362 @@
+// src/analytics/performance_report.rs
+
+use tracing::info;
+use std::collections::BTreeMap;
+use std::error::Error;
+use serde::{Deserialize, Serialize};
+use crate::backtest::run_backtest::BacktestTrade;
+use crate::futures::trade::{Side, Trade};
+use crate::utils::get_timestamps::format_timestamp;
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// A closed trade, the common input of the analytics (API, backtest or paper trades).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosedTrade {
+    pub side: Side,                      // Buy or sell
+    pub quantity: f64,                   // Quantity in USD
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub entry_time: i64,                 // Timestamp (ms) of the entry
+    pub exit_time: i64,                  // Timestamp (ms) of the exit
+    pub pl: f64,                         // Gross P&L in sats
+    pub opening_fee: f64,                // Opening fee in sats
+    pub closing_fee: f64,                // Closing fee in sats
+    pub carry_fees: f64,                 // Sum of carry (funding) fees in sats
+}
+
+impl ClosedTrade {
+    /// Opening, closing and carry fees in sats.
+    pub fn fees(&self) -> f64 {
+        self.opening_fee + self.closing_fee + self.carry_fees
+    }
+
+    /// P&L in sats after all fees.
+    pub fn net_pl(&self) -> f64 {
+        self.pl - self.fees()
+    }
+
+    /// Net P&L converted to USD at the exit price.
+    pub fn net_pl_usd(&self) -> f64 {
+        self.net_pl() * self.exit_price / 100_000_000.0
+    }
+}
+
+impl From<&Trade> for ClosedTrade {
+    fn from(trade: &Trade) -> Self {
+        let entry_time = trade.market_filled_ts.filter(|ts| *ts > 0).unwrap_or(trade.creation_ts);
+        Self {
+            side: trade.side,
+            quantity: trade.quantity,
+            entry_price: trade.entry_price.unwrap_or(trade.price),
+            exit_price: trade.exit_price.unwrap_or(trade.price),
+            entry_time: entry_time as i64,
+            exit_time: trade.closed_ts.unwrap_or(trade.last_update_ts) as i64,
+            pl: trade.pl,
+            opening_fee: trade.opening_fee,
+            closing_fee: trade.closing_fee,
+            carry_fees: trade.sum_carry_fees,
+        }
+    }
+}
+
+impl From<&BacktestTrade> for ClosedTrade {
+    fn from(trade: &BacktestTrade) -> Self {
+        Self {
+            side: trade.side,
+            quantity: trade.quantity,
+            entry_price: trade.entry_price,
+            exit_price: trade.exit_price,
+            entry_time: trade.entry_time,
+            exit_time: trade.exit_time,
+            pl: trade.pl,
+            opening_fee: trade.opening_fee,
+            closing_fee: trade.closing_fee,
+            carry_fees: 0.0,
+        }
+    }
+}
+
+/// Point of the daily equity curve.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub day: i64,                        // Timestamp (ms) of the start of the day (UTC)
+    pub equity: f64,                     // Equity in sats at the end of the day
+}
+
+/// Performance metrics of a list of closed trades.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub trade_count: usize,
+    pub initial_balance_sats: f64,
+    pub final_balance_sats: f64,
+    pub gross_pl: f64,                   // Sum of the P&L before fees (sats)
+    pub net_pl: f64,                     // Sum of the P&L after fees (sats)
+    pub net_pl_usd: f64,                 // Net P&L in USD (at the exit prices)
+    pub win_rate: f64,                   // Share of trades with a positive net P&L
+    pub average_win: f64,                // Average net P&L of winning trades (sats)
+    pub average_loss: f64,               // Average net P&L of losing trades (sats, negative)
+    pub average_win_usd: f64,
+    pub average_loss_usd: f64,
+    pub profit_factor: Option<f64>,      // Gross profit / gross loss (net of fees), None without losses
+    pub expectancy: f64,                 // Expected net P&L per trade (sats)
+    pub max_drawdown: f64,               // Largest drop of the equity from its peak (sats)
+    pub max_drawdown_percent: f64,       // Largest drop relative to the peak
+    pub max_drawdown_duration_ms: i64,   // Longest time the equity stayed below a previous peak
+    pub sharpe: Option<f64>,             // Annualized Sharpe ratio of the daily returns
+    pub sortino: Option<f64>,            // Annualized Sortino ratio of the daily returns
+    pub opening_fees: f64,
+    pub closing_fees: f64,
+    pub carry_fees: f64,
+    pub total_fees: f64,
+    pub fee_share_of_gross_pl: Option<f64>, // Total fees / gross P&L, None if the gross P&L is not positive
+    pub exposure_time_ms: i64,           // Time with at least one open trade
+    pub exposure_percent: f64,           // Exposure time relative to the first entry until the last exit
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+/// Calculates the performance metrics of closed trades.
+/// Trades are ordered by their exit time for the equity curve. The Sharpe and Sortino ratios are
+/// calculated on the daily returns (calendar days in UTC, days without trades have a return of 0)
+/// and annualized with the square root of 365.
+///
+/// # Parameters
+/// - `trades`: The closed trades.
+/// - `initial_balance_sats`: Balance before the first trade.
+pub fn calculate_performance_report(trades: &[ClosedTrade], initial_balance_sats: f64) -> PerformanceReport {
+    if trades.is_empty() {
+        return PerformanceReport {
+            initial_balance_sats,
+            final_balance_sats: initial_balance_sats,
+            ..PerformanceReport::default()
+        };
+    }
+
+    let mut trades: Vec<&ClosedTrade> = trades.iter().collect();
+    trades.sort_by_key(|trade| trade.exit_time);
+
+    let wins: Vec<&&ClosedTrade> = trades.iter().filter(|trade| trade.net_pl() > 0.0).collect();
+    let losses: Vec<&&ClosedTrade> = trades.iter().filter(|trade| trade.net_pl() < 0.0).collect();
+    let average = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+
+    let average_win = average(&wins.iter().map(|trade| trade.net_pl()).collect::<Vec<f64>>());
+    let average_loss = average(&losses.iter().map(|trade| trade.net_pl()).collect::<Vec<f64>>());
+    let gross_profit: f64 = wins.iter().map(|trade| trade.net_pl()).sum();
+    let gross_loss: f64 = -losses.iter().map(|trade| trade.net_pl()).sum::<f64>();
+    let win_rate = wins.len() as f64 / trades.len() as f64;
+    let loss_rate = losses.len() as f64 / trades.len() as f64;
+
+    // Drawdown on the equity after every trade
+    let mut equity = initial_balance_sats;
+    let mut peak = equity;
+    let mut peak_time = trades[0].entry_time;
+    let mut max_drawdown: f64 = 0.0;
+    let mut max_drawdown_percent: f64 = 0.0;
+    let mut max_drawdown_duration_ms = 0;
+    for trade in &trades {
+        equity += trade.net_pl();
+        if equity >= peak {
+            peak = equity;
+            peak_time = trade.exit_time;
+        } else {
+            max_drawdown = max_drawdown.max(peak - equity);
+            if peak > 0.0 {
+                max_drawdown_percent = max_drawdown_percent.max((peak - equity) / peak);
+            }
+        }
+        if equity < peak {
+            max_drawdown_duration_ms = max_drawdown_duration_ms.max(trade.exit_time - peak_time);
+        }
+    }
+
+    let equity_curve = calculate_daily_equity_curve(&trades, initial_balance_sats);
+    let (sharpe, sortino) = calculate_daily_ratios(&equity_curve, initial_balance_sats);
+
+    let gross_pl: f64 = trades.iter().map(|trade| trade.pl).sum();
+    let opening_fees: f64 = trades.iter().map(|trade| trade.opening_fee).sum();
+    let closing_fees: f64 = trades.iter().map(|trade| trade.closing_fee).sum();
+    let carry_fees: f64 = trades.iter().map(|trade| trade.carry_fees).sum();
+    let total_fees = opening_fees + closing_fees + carry_fees;
+
+    let exposure_time_ms = calculate_exposure_time(&trades);
+    let first_entry = trades.iter().map(|trade| trade.entry_time).min().unwrap_or_default();
+    let last_exit = trades.iter().map(|trade| trade.exit_time).max().unwrap_or_default();
+    let period_ms = last_exit - first_entry;
+
+    PerformanceReport {
+        trade_count: trades.len(),
+        initial_balance_sats,
+        final_balance_sats: equity,
+        gross_pl,
+        net_pl: equity - initial_balance_sats,
+        net_pl_usd: trades.iter().map(|trade| trade.net_pl_usd()).sum(),
+        win_rate,
+        average_win,
+        average_loss,
+        average_win_usd: average(&wins.iter().map(|trade| trade.net_pl_usd()).collect::<Vec<f64>>()),
+        average_loss_usd: average(&losses.iter().map(|trade| trade.net_pl_usd()).collect::<Vec<f64>>()),
+        profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+        expectancy: win_rate * average_win + loss_rate * average_loss,
+        max_drawdown,
+        max_drawdown_percent,
+        max_drawdown_duration_ms,
+        sharpe,
+        sortino,
+        opening_fees,
+        closing_fees,
+        carry_fees,
+        total_fees,
+        fee_share_of_gross_pl: (gross_pl > 0.0).then(|| total_fees / gross_pl),
+        exposure_time_ms,
+        exposure_percent: if period_ms > 0 { exposure_time_ms as f64 / period_ms as f64 } else { 0.0 },
+        equity_curve,
+    }
+}
+
+// Equity at the end of every day from the first entry to the last exit (trades sorted by exit time)
+fn calculate_daily_equity_curve(trades: &[&ClosedTrade], initial_balance_sats: f64) -> Vec<EquityPoint> {
+    let first_day = trades.iter().map(|trade| trade.entry_time).min().unwrap_or_default().div_euclid(MS_PER_DAY);
+    let last_day = trades.iter().map(|trade| trade.exit_time).max().unwrap_or_default().div_euclid(MS_PER_DAY);
+
+    let mut pl_per_day: BTreeMap<i64, f64> = BTreeMap::new();
+    for trade in trades {
+        *pl_per_day.entry(trade.exit_time.div_euclid(MS_PER_DAY)).or_default() += trade.net_pl();
+    }
+
+    let mut equity = initial_balance_sats;
+    (first_day..=last_day)
+        .map(|day| {
+            equity += pl_per_day.get(&day).copied().unwrap_or_default();
+            EquityPoint { day: day * MS_PER_DAY, equity }
+        })
+        .collect()
+}
+
+// Annualized Sharpe and Sortino ratios of the daily returns
+fn calculate_daily_ratios(equity_curve: &[EquityPoint], initial_balance_sats: f64) -> (Option<f64>, Option<f64>) {
+    let mut previous = initial_balance_sats;
+    let mut returns = Vec::with_capacity(equity_curve.len());
+    for point in equity_curve {
+        if previous > 0.0 {
+            returns.push(point.equity / previous - 1.0);
+        }
+        previous = point.equity;
+    }
+    if returns.len() < 2 {
+        return (None, None);
+    }
+
+    let annualization = 365f64.sqrt();
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let std_dev = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64).sqrt();
+    let downside_dev = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64).sqrt();
+
+    let sharpe = (std_dev > 0.0).then(|| mean / std_dev * annualization);
+    let sortino = (downside_dev > 0.0).then(|| mean / downside_dev * annualization);
+    (sharpe, sortino)
+}
+
+// Union of the time intervals with an open trade
+fn calculate_exposure_time(trades: &[&ClosedTrade]) -> i64 {
+    let mut intervals: Vec<(i64, i64)> = trades
+        .iter()
+        .map(|trade| (trade.entry_time, trade.exit_time.max(trade.entry_time)))
+        .collect();
+    intervals.sort();
+
+    let mut exposure = 0;
+    let mut current: Option<(i64, i64)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((current_start, current_end)) if start <= current_end => Some((current_start, current_end.max(end))),
+            Some((current_start, current_end)) => {
+                exposure += current_end - current_start;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some((start, end)) = current {
+        exposure += end - start;
+    }
+    exposure
+}
+
+fn format_duration(ms: i64) -> String {
+    let hours = ms / 3_600_000;
+    if hours >= 24 {
+        format!("{}d {}h", hours / 24, hours % 24)
+    } else {
+        format!("{}h {}m", hours, (ms % 3_600_000) / 60_000)
+    }
+}
+
+fn format_ratio(value: Option<f64>) -> String {
+    value.map(|value| format!("{:.2}", value)).unwrap_or("-".to_string())
+}
+
+// Label and value rows shared by the terminal table and the HTML report
+fn report_rows(report: &PerformanceReport) -> Vec<(&'static str, String)> {
+    vec![
+        ("Trades", report.trade_count.to_string()),
+        ("Initial balance", format!("{:.0} sats", report.initial_balance_sats)),
+        ("Final balance", format!("{:.0} sats", report.final_balance_sats)),
+        ("Gross P&L", format!("{:.0} sats", report.gross_pl)),
+        ("Net P&L", format!("{:.0} sats ({:.2}$)", report.net_pl, report.net_pl_usd)),
+        ("Win rate", format!("{:.1}%", report.win_rate * 100.0)),
+        ("Average win", format!("{:.0} sats ({:.2}$)", report.average_win, report.average_win_usd)),
+        ("Average loss", format!("{:.0} sats ({:.2}$)", report.average_loss, report.average_loss_usd)),
+        ("Profit factor", format_ratio(report.profit_factor)),
+        ("Expectancy", format!("{:.0} sats per trade", report.expectancy)),
+        ("Max drawdown", format!("{:.0} sats ({:.2}%)", report.max_drawdown, report.max_drawdown_percent * 100.0)),
+        ("Max drawdown duration", format_duration(report.max_drawdown_duration_ms)),
+        ("Sharpe (daily, annualized)", format_ratio(report.sharpe)),
+        ("Sortino (daily, annualized)", format_ratio(report.sortino)),
+        (
+            "Fees (opening/closing/carry)",
+            format!("{:.0} / {:.0} / {:.0} sats", report.opening_fees, report.closing_fees, report.carry_fees),
+        ),
+        (
+            "Fees share of gross P&L",
+            report.fee_share_of_gross_pl.map(|share| format!("{:.1}%", share * 100.0)).unwrap_or("-".to_string()),
+        ),
+        ("Exposure time", format!("{} ({:.1}%)", format_duration(report.exposure_time_ms), report.exposure_percent * 100.0)),
+    ]
+}
+
+/// Logs the report as a table.
+pub fn log_performance_report(report: &PerformanceReport) {
+    for (label, value) in report_rows(report) {
+        info!(metric = label, %value, "Performance report");
+    }
+}
+
+/// Writes the report as pretty printed JSON.
+pub fn write_performance_report_json(path: &str, report: &PerformanceReport) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Writes the report as a standalone HTML page (metrics table and daily equity table).
+pub fn write_performance_report_html(path: &str, report: &PerformanceReport) -> Result<(), Box<dyn Error>> {
+    let metric_rows: String = report_rows(report)
+        .iter()
+        .map(|(label, value)| format!("<tr><th>{}</th><td>{}</td></tr>\n", label, value))
+        .collect();
+    let equity_rows: String = report
+        .equity_curve
+        .iter()
+        .map(|point| format!("<tr><td>{}</td><td>{:.0}</td></tr>\n", format_timestamp(point.day), point.equity))
+        .collect();
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Performance Report</title>\n\
+         <style>body {{ font-family: monospace; margin: 2em; }} table {{ border-collapse: collapse; margin-bottom: 2em; }} \
+         th, td {{ border: 1px solid #ccc; padding: 4px 12px; text-align: left; }}</style>\n</head>\n<body>\n\
+         <h1>Performance Report</h1>\n<table>\n{}</table>\n<h2>Daily Equity (sats)</h2>\n<table>\n<tr><th>Day</th><th>Equity</th></tr>\n{}</table>\n</body>\n</html>\n",
+        metric_rows, equity_rows
+    );
+    std::fs::write(path, html)?;
+    Ok(())
+}
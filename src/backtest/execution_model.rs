@@ -0,0 +1,109 @@
+// src/backtest/execution_model.rs
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+use serde::{Deserialize, Serialize};
+
+/// Bid/ask spread around the mid price of a simulated fill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SpreadModel {
+    #[default]
+    None,
+    Fixed { spread: f64 },               // Full spread in USD
+    AtrProportional { factor: f64 },     // Full spread as share of the ATR (falls back to no spread without ATR)
+}
+
+/// Random slippage of a simulated fill, as share of the price (positive values are adverse).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SlippageModel {
+    #[default]
+    None,
+    Fixed { slippage: f64 },             // Always the same slippage
+    Uniform { max: f64 },                // Uniform between 0 and `max`
+    Normal { mean: f64, std_dev: f64 },  // Normal distribution, negative samples improve the price
+}
+
+/// Execution model of the simulations (backtest and paper trading).
+/// Market orders are filled `latency_ticks` ticks after the signal (candles in the backtest, price
+/// updates in paper trading) at the mid price plus half the spread and the slippage, against the trader.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionModel {
+    pub spread: SpreadModel,
+    pub slippage: SlippageModel,
+    pub latency_ticks: usize,            // Ticks between the signal and the fill
+    pub seed: u64,                       // Seed of the slippage samples
+}
+
+/// A simulated fill.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub signal_price: f64,               // Price at signal time
+    pub mid_price: f64,                  // Price after the latency
+    pub fill_price: f64,                 // Price the order was filled at
+    pub half_spread: f64,                // Half spread paid in USD
+    pub slippage: f64,                   // Slippage in USD (positive is adverse)
+}
+
+impl Fill {
+    /// Fill price minus signal price.
+    pub fn price_difference(&self) -> f64 {
+        self.fill_price - self.signal_price
+    }
+
+    /// Difference between fill and signal price relative to the signal price, positive if the fill
+    /// is worse than the signal price.
+    pub fn adverse_difference_percent(&self, is_buy: bool) -> f64 {
+        if self.signal_price <= 0.0 {
+            return 0.0;
+        }
+        let difference = self.price_difference() / self.signal_price;
+        if is_buy { difference } else { -difference }
+    }
+}
+
+impl ExecutionModel {
+    /// Half of the spread in USD.
+    pub fn half_spread(&self, atr: Option<f64>) -> f64 {
+        match self.spread {
+            SpreadModel::None => 0.0,
+            SpreadModel::Fixed { spread } => spread / 2.0,
+            SpreadModel::AtrProportional { factor } => atr.map(|atr| atr * factor / 2.0).unwrap_or_default(),
+        }
+    }
+
+    /// Samples the slippage as share of the price.
+    pub fn sample_slippage(&self, rng: &mut StdRng) -> f64 {
+        match self.slippage {
+            SlippageModel::None => 0.0,
+            SlippageModel::Fixed { slippage } => slippage,
+            SlippageModel::Uniform { max } if max > 0.0 => rng.gen_range(0.0..=max),
+            SlippageModel::Uniform { .. } => 0.0,
+            SlippageModel::Normal { mean, std_dev } => match Normal::new(mean, std_dev) {
+                Ok(normal) => normal.sample(rng),
+                Err(_) => mean,
+            },
+        }
+    }
+
+    /// Fills a market order at `mid_price` (the price after the latency).
+    ///
+    /// # Parameters
+    /// - `signal_price`: Price at signal time.
+    /// - `mid_price`: Price when the order reaches the market.
+    /// - `is_buy`: Buying pays the ask, selling receives the bid.
+    /// - `atr`: ATR for the ATR-proportional spread.
+    /// - `rng`: Random number generator of the simulation.
+    pub fn fill(&self, signal_price: f64, mid_price: f64, is_buy: bool, atr: Option<f64>, rng: &mut StdRng) -> Fill {
+        let half_spread = self.half_spread(atr);
+        let slippage = mid_price * self.sample_slippage(rng);
+        let cost = half_spread + slippage;
+        Fill {
+            signal_price,
+            mid_price,
+            fill_price: if is_buy { mid_price + cost } else { mid_price - cost },
+            half_spread,
+            slippage,
+        }
+    }
+}
@@ -0,0 +1,5 @@
+// src/backtest/mod.rs
+
+pub mod run_backtest;
+pub mod optimize_params;
+pub mod execution_model;
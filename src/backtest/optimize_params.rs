@@ -0,0 +1,373 @@
+// src/backtest/optimize_params.rs
+
+use tracing::{info, warn};
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use crate::config::SignalSettings;
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use super::run_backtest::{run_backtest, BacktestMetrics, BacktestSettings, StrategyParams};
+
+/// Metric the parameter sets are ranked by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptimizationMetric {
+    NetPl,
+    Sharpe,
+    ProfitFactor,
+    MaxDrawdown,
+}
+
+impl OptimizationMetric {
+    /// Score of the metrics, higher is better (the drawdown is negated).
+    pub fn score(&self, metrics: &BacktestMetrics) -> f64 {
+        match self {
+            OptimizationMetric::NetPl => metrics.net_pl,
+            OptimizationMetric::Sharpe => metrics.sharpe,
+            OptimizationMetric::ProfitFactor => metrics.profit_factor,
+            OptimizationMetric::MaxDrawdown => -metrics.max_drawdown,
+        }
+    }
+}
+
+impl FromStr for OptimizationMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "net_pl" | "pl" => Ok(OptimizationMetric::NetPl),
+            "sharpe" => Ok(OptimizationMetric::Sharpe),
+            "profit_factor" => Ok(OptimizationMetric::ProfitFactor),
+            "max_drawdown" | "drawdown" => Ok(OptimizationMetric::MaxDrawdown),
+            _ => Err(format!("Unknown optimization metric: {} (net_pl, sharpe, profit_factor, max_drawdown)", s)),
+        }
+    }
+}
+
+impl fmt::Display for OptimizationMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizationMetric::NetPl => write!(f, "net P&L"),
+            OptimizationMetric::Sharpe => write!(f, "Sharpe"),
+            OptimizationMetric::ProfitFactor => write!(f, "profit factor"),
+            OptimizationMetric::MaxDrawdown => write!(f, "max drawdown"),
+        }
+    }
+}
+
+/// How the parameter sets are ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ranking {
+    pub metric: OptimizationMetric,      // Metric the parameter sets are ranked by
+    pub min_trades: usize,               // Parameter sets with fewer trades are ranked below all others (e.g. no trades, no drawdown)
+}
+
+/// Values to search per parameter. Empty lists keep the value of the base parameters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParameterGrid {
+    pub ma_periods: Vec<usize>,
+    pub rsi_periods: Vec<usize>,
+    pub bb_std_dev_multipliers: Vec<f64>,
+    pub gap_values: Vec<f64>,
+    pub weights: Vec<[f64; 4]>,          // Weight sets: [bollinger, rsi, ma_ema, atr]
+}
+
+/// How the parameter grid is searched.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SearchMode {
+    Grid,                                // Every combination of the grid
+    Random { samples: usize, seed: u64 }, // Random combinations of the grid (seeded, without repetition)
+}
+
+impl ParameterGrid {
+    /// All combinations of the grid, based on `base`.
+    pub fn combinations(&self, base: &StrategyParams) -> Vec<StrategyParams> {
+        fn or_base<T: Clone>(values: &[T], base: T) -> Vec<T> {
+            if values.is_empty() { vec![base] } else { values.to_vec() }
+        }
+
+        let ma_periods = or_base(&self.ma_periods, base.ma_period);
+        let rsi_periods = or_base(&self.rsi_periods, base.rsi_period);
+        let multipliers = or_base(&self.bb_std_dev_multipliers, base.bb_std_dev_multiplier);
+        let gap_values = or_base(&self.gap_values, base.gap_value);
+        let weights = or_base(
+            &self.weights,
+            [base.bollinger_weight, base.rsi_weight, base.ma_ema_weight, base.atr_weight],
+        );
+
+        let mut combinations = Vec::new();
+        for ma_period in &ma_periods {
+            for rsi_period in &rsi_periods {
+                for multiplier in &multipliers {
+                    for gap_value in &gap_values {
+                        for [bollinger_weight, rsi_weight, ma_ema_weight, atr_weight] in &weights {
+                            combinations.push(StrategyParams {
+                                ma_period: *ma_period,
+                                rsi_period: *rsi_period,
+                                bb_std_dev_multiplier: *multiplier,
+                                gap_value: *gap_value,
+                                bollinger_weight: *bollinger_weight,
+                                rsi_weight: *rsi_weight,
+                                ma_ema_weight: *ma_ema_weight,
+                                atr_weight: *atr_weight,
+                                ..base.clone()
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        combinations
+    }
+
+    /// Candidates of the search mode.
+    pub fn candidates(&self, base: &StrategyParams, mode: SearchMode) -> Vec<StrategyParams> {
+        let mut combinations = self.combinations(base);
+        if let SearchMode::Random { samples, seed } = mode {
+            let mut rng = StdRng::seed_from_u64(seed);
+            combinations.shuffle(&mut rng);
+            combinations.truncate(samples);
+        }
+        combinations
+    }
+}
+
+/// Backtest result of a single parameter set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationResult {
+    pub params: StrategyParams,
+    pub metrics: BacktestMetrics,
+    pub score: f64,                      // Score of the ranking metric
+    pub eligible: bool,                  // Whether the parameter set reached the minimum trade count
+}
+
+/// Backtests all candidates on `threads` threads and ranks them by the ranking metric (best first).
+/// Parameter sets below the minimum trade count are ranked after all others.
+///
+/// # Parameters
+/// - `ohlc_data`: The OHLC history (oldest first).
+/// - `candidates`: The parameter sets to test.
+/// - `signal_settings`: Base signal settings.
+/// - `settings`: The simulation settings.
+/// - `ranking`: The ranking metric and minimum trade count.
+/// - `start_index`: First candle on which trades may be opened.
+/// - `threads`: Number of worker threads.
+pub fn optimize_params(
+    ohlc_data: &[OhlcHistoryEntry],
+    candidates: &[StrategyParams],
+    signal_settings: &SignalSettings,
+    settings: &BacktestSettings,
+    ranking: Ranking,
+    start_index: usize,
+    threads: usize,
+) -> Vec<OptimizationResult> {
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = candidates.len().div_ceil(threads.max(1));
+
+    let mut results: Vec<OptimizationResult> = thread::scope(|scope| {
+        let handles: Vec<_> = candidates
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|params| {
+                            let result = run_backtest(ohlc_data, params, signal_settings, settings, start_index);
+                            OptimizationResult {
+                                params: params.clone(),
+                                score: ranking.metric.score(&result.metrics),
+                                eligible: result.metrics.trade_count >= ranking.min_trades,
+                                metrics: result.metrics,
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Optimizer thread panicked"))
+            .collect()
+    });
+
+    results.sort_by(|a, b| b.eligible.cmp(&a.eligible).then(b.score.total_cmp(&a.score)));
+    results
+}
+
+/// Rolling window sizes of the walk-forward analysis (in candles).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WalkForwardSettings {
+    pub in_sample_candles: usize,        // Candles the parameters are optimized on
+    pub out_of_sample_candles: usize,    // Following candles the best parameters are validated on (also the step size)
+}
+
+/// A single walk-forward window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalkForwardWindow {
+    pub in_sample_from: i64,             // Timestamp (ms) of the first in-sample candle
+    pub out_of_sample_from: i64,         // Timestamp (ms) of the first out-of-sample candle
+    pub out_of_sample_to: i64,           // Timestamp (ms) of the last out-of-sample candle
+    pub best_params: StrategyParams,     // Best parameters of the in-sample optimization
+    pub in_sample: BacktestMetrics,
+    pub out_of_sample: BacktestMetrics,
+}
+
+/// Report of an optimization with walk-forward validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    pub metric: OptimizationMetric,
+    pub min_trades: usize,               // Minimum trade count of a ranked parameter set
+    pub candidates: usize,               // Number of tested parameter sets
+    pub ranking: Vec<OptimizationResult>, // Full period ranking (best first)
+    pub walk_forward: Vec<WalkForwardWindow>,
+    pub out_of_sample_net_pl: f64,       // Sum of the out-of-sample net P&L of all windows
+    pub efficiency: Option<f64>,         // Mean out-of-sample / in-sample score (below ~0.5 hints at overfitting)
+}
+
+/// Walk-forward analysis: optimizes on rolling in-sample windows and validates the best parameters on
+/// the following out-of-sample window. The indicators of the out-of-sample backtest are warmed up on
+/// the candles before the window, but trades are only opened inside the window.
+///
+/// Windows without a parameter set that reached the minimum trade count in-sample are skipped.
+///
+/// # Returns
+/// - One `WalkForwardWindow` per step, empty if the data is shorter than one in-sample and out-of-sample window.
+pub fn walk_forward(
+    ohlc_data: &[OhlcHistoryEntry],
+    candidates: &[StrategyParams],
+    signal_settings: &SignalSettings,
+    settings: &BacktestSettings,
+    ranking: Ranking,
+    walk_forward_settings: &WalkForwardSettings,
+    threads: usize,
+) -> Vec<WalkForwardWindow> {
+    let in_sample = walk_forward_settings.in_sample_candles;
+    let out_of_sample = walk_forward_settings.out_of_sample_candles.max(1);
+    let mut windows = Vec::new();
+
+    let mut start = 0;
+    while start + in_sample + out_of_sample <= ohlc_data.len() {
+        let in_sample_data = &ohlc_data[start..start + in_sample];
+        let results = optimize_params(in_sample_data, candidates, signal_settings, settings, ranking, 0, threads);
+        let Some(best) = results.into_iter().next().filter(|best| best.eligible) else {
+            start += out_of_sample;
+            continue;
+        };
+
+        let out_of_sample_data = &ohlc_data[..start + in_sample + out_of_sample];
+        let out_of_sample_start = start + in_sample;
+        let validation = run_backtest(out_of_sample_data, &best.params, signal_settings, settings, out_of_sample_start);
+
+        windows.push(WalkForwardWindow {
+            in_sample_from: ohlc_data[start].time,
+            out_of_sample_from: ohlc_data[out_of_sample_start].time,
+            out_of_sample_to: ohlc_data[start + in_sample + out_of_sample - 1].time,
+            best_params: best.params,
+            in_sample: best.metrics,
+            out_of_sample: validation.metrics,
+        });
+
+        start += out_of_sample;
+    }
+
+    windows
+}
+
+/// Runs the full period ranking and the walk-forward analysis.
+pub fn build_optimization_report(
+    ohlc_data: &[OhlcHistoryEntry],
+    candidates: &[StrategyParams],
+    signal_settings: &SignalSettings,
+    settings: &BacktestSettings,
+    ranking: Ranking,
+    walk_forward_settings: &WalkForwardSettings,
+    threads: usize,
+) -> OptimizationReport {
+    let metric = ranking.metric;
+    let results = optimize_params(ohlc_data, candidates, signal_settings, settings, ranking, 0, threads);
+    let windows = walk_forward(ohlc_data, candidates, signal_settings, settings, ranking, walk_forward_settings, threads);
+
+    let out_of_sample_net_pl = windows.iter().map(|window| window.out_of_sample.net_pl).sum();
+    let ratios: Vec<f64> = windows
+        .iter()
+        .filter(|window| metric.score(&window.in_sample) > 0.0)
+        .map(|window| metric.score(&window.out_of_sample) / metric.score(&window.in_sample))
+        .collect();
+    let efficiency = (!ratios.is_empty()).then(|| ratios.iter().sum::<f64>() / ratios.len() as f64);
+
+    OptimizationReport {
+        metric,
+        min_trades: ranking.min_trades,
+        candidates: candidates.len(),
+        ranking: results,
+        walk_forward: windows,
+        out_of_sample_net_pl,
+        efficiency,
+    }
+}
+
+/// Writes the report as pretty printed JSON.
+pub fn write_optimization_report(path: &str, report: &OptimizationReport) -> Result<(), Box<dyn std::error::Error>> {
+    let json = serde_json::to_string_pretty(report)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Logs the best parameter sets and the walk-forward windows.
+pub fn log_optimization_report(report: &OptimizationReport, top: usize) {
+    info!(
+        candidates = report.candidates,
+        metric = %report.metric,
+        min_trades = report.min_trades,
+        "Optimization"
+    );
+    for (rank, result) in report.ranking.iter().take(top).enumerate() {
+        let params = &result.params;
+        let metrics = &result.metrics;
+        info!(
+            rank = rank + 1,
+            ma_period = params.ma_period,
+            rsi_period = params.rsi_period,
+            bb_std_dev = params.bb_std_dev_multiplier,
+            gap = params.gap_value,
+            weights = %format!("{:.2}/{:.2}/{:.2}/{:.2}", params.bollinger_weight, params.rsi_weight, params.ma_ema_weight, params.atr_weight),
+            trades = metrics.trade_count,
+            net_pl = metrics.net_pl,
+            win_rate = metrics.win_rate,
+            profit_factor = metrics.profit_factor,
+            max_drawdown = metrics.max_drawdown,
+            eligible = result.eligible,
+            "Parameter set"
+        );
+    }
+
+    if report.walk_forward.is_empty() {
+        warn!("No walk-forward window: not enough data or no parameter set reached the minimum trade count");
+        return;
+    }
+    for (i, window) in report.walk_forward.iter().enumerate() {
+        let params = &window.best_params;
+        info!(
+            window = i + 1,
+            ma_period = params.ma_period,
+            rsi_period = params.rsi_period,
+            bb_std_dev = params.bb_std_dev_multiplier,
+            gap = params.gap_value,
+            in_sample_pl = window.in_sample.net_pl,
+            in_sample_trades = window.in_sample.trade_count,
+            out_of_sample_pl = window.out_of_sample.net_pl,
+            out_of_sample_trades = window.out_of_sample.trade_count,
+            "Walk-forward window"
+        );
+    }
+    info!(net_pl = report.out_of_sample_net_pl, efficiency = ?report.efficiency, "Walk-forward out-of-sample");
+    if report.efficiency.is_some_and(|efficiency| efficiency < 0.5) {
+        warn!("Out-of-sample results well below in-sample, likely overfitted");
+    }
+}
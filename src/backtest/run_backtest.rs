@@ -0,0 +1,424 @@
+// src/backtest/run_backtest.rs
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use crate::config::{RegimeSettings, SignalSettings, StrategyWeights};
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use crate::futures::trade::Side;
+use crate::math::get_indicators::Indicators;
+use crate::math::get_market_regime::{calculate_regime_indicators, classify_market_regime, RegimeIndicators};
+use crate::math::get_signals::calculate_signal_explanation;
+use crate::math::get_stoploss_takeprofit::calculate_stoploss_takeprofit;
+use crate::math::indicator_set::{default_indicator_registry, IndicatorKind, IndicatorSet, IndicatorSource, IndicatorSpec, IndicatorValue, ATR};
+use crate::math::price_indicators::calculate_atr;
+use crate::utils::connect_ws::PriceData;
+use super::execution_model::ExecutionModel;
+
+/// Strategy parameters that are tuned by the optimizer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyParams {
+    pub ma_period: usize,                // Period for the moving average (MA)
+    pub ema_period: usize,               // Period for the exponential moving average (EMA)
+    pub bb_period: usize,                // Period for the Bollinger Bands
+    pub bb_std_dev_multiplier: f64,      // Multiplier for standard deviation in Bollinger Bands
+    pub rsi_period: usize,               // Period for the relative strength index (RSI)
+    pub atr_period: usize,               // Period for the average true range (ATR)
+    pub gap_value: f64,                  // Gap value for strong buy/sell signals
+    pub bollinger_weight: f64,           // Weight for the Bollinger Bands signal
+    pub rsi_weight: f64,                 // Weight for the RSI signal
+    pub ma_ema_weight: f64,              // Weight for the MA/EMA signal
+    pub atr_weight: f64,                 // Weight for the ATR signal
+}
+
+impl StrategyParams {
+    /// Signal settings with the weights and gap value of these parameters (basis settings from `base`).
+    /// The trend and high volatility weights are derived from the tuned weights (see
+    /// `SignalSettings::with_base_weights`).
+    pub fn signal_settings(&self, base: &SignalSettings) -> SignalSettings {
+        let weights = StrategyWeights {
+            bollinger_weight: self.bollinger_weight,
+            rsi_weight: self.rsi_weight,
+            ma_ema_weight: self.ma_ema_weight,
+            atr_weight: self.atr_weight,
+            trend_following: false,
+        };
+        SignalSettings {
+            gap_value: self.gap_value,
+            ..base.with_base_weights(weights)
+        }
+    }
+
+    /// Number of candles needed before the first signal can be evaluated.
+    pub fn warmup_candles(&self) -> usize {
+        let longest = self.ma_period.max(self.ema_period).max(self.bb_period).max(self.rsi_period).max(self.atr_period);
+        longest * 3 + 1
+    }
+}
+
+/// Simulation settings of a backtest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestSettings {
+    pub initial_balance_sats: u64,       // Starting balance in sats
+    pub leverage: f64,                   // Leverage of every trade
+    pub risk_per_trade_percent: f64,     // Share of the balance lost if the stoploss is hit (e.g. 0.01 for 1%)
+    pub risk_to_reward_ratio: f64,       // Risk handling for takeprofit
+    pub risk_to_loss_ratio: f64,         // Risk handling for stoploss
+    pub fee_rate: f64,                   // Trading fee rate per side (e.g. 0.001 for 0.1%)
+    #[serde(default)]
+    pub execution: ExecutionModel,       // Spread, slippage and latency of the simulated fills
+    #[serde(skip)]
+    pub regime_settings: Option<RegimeSettings>, // Regime based weights, `None` uses the base weights only
+}
+
+/// A simulated trade.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub side: Side,                      // Buy or sell
+    pub entry_time: i64,                 // Timestamp (ms) of the entry candle
+    pub exit_time: i64,                  // Timestamp (ms) of the exit candle
+    pub signal_price: f64,               // Close of the signal candle
+    pub entry_price: f64,                // Fill price of the entry
+    pub exit_price: f64,
+    pub quantity: f64,                   // Quantity in USD
+    pub pl: f64,                         // Gross P&L in sats
+    pub opening_fee: f64,                // Opening fee in sats
+    pub closing_fee: f64,                // Closing fee in sats
+    pub exit_reason: String,             // "takeprofit", "stoploss" or "end of data"
+}
+
+impl BacktestTrade {
+    /// Opening and closing fees in sats.
+    pub fn fees(&self) -> f64 {
+        self.opening_fee + self.closing_fee
+    }
+
+    /// P&L in sats after fees.
+    pub fn net_pl(&self) -> f64 {
+        self.pl - self.fees()
+    }
+
+    /// Difference between the entry fill and the signal price relative to the signal price,
+    /// positive if the fill is worse than the signal price.
+    pub fn entry_slippage_percent(&self) -> f64 {
+        if self.signal_price <= 0.0 {
+            return 0.0;
+        }
+        let difference = (self.entry_price - self.signal_price) / self.signal_price;
+        if self.side.is_buy() { difference } else { -difference }
+    }
+}
+
+/// Summary metrics of a backtest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BacktestMetrics {
+    pub trade_count: usize,
+    pub net_pl: f64,                     // Net P&L in sats
+    pub win_rate: f64,                   // Share of trades with a positive net P&L
+    pub profit_factor: f64,              // Gross profit / gross loss (net of fees), capped at 100
+    pub sharpe: f64,                     // Mean / standard deviation of the per trade returns
+    pub max_drawdown: f64,               // Largest drop of the balance from its peak, relative to the peak
+    pub average_entry_slippage: f64,     // Average entry fill vs. signal price (relative, positive is adverse)
+}
+
+/// Result of a backtest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub trades: Vec<BacktestTrade>,
+    pub final_balance_sats: f64,
+    pub metrics: BacktestMetrics,
+}
+
+// Position that is open during the simulation
+struct OpenPosition {
+    is_buy: bool,
+    entry_time: i64,
+    signal_price: f64,
+    entry_price: f64,
+    atr: f64,
+    quantity: f64,
+    stoploss: f64,
+    takeprofit: f64,
+}
+
+/// Calculates the indicators of the last candle of `window` (oldest first).
+pub fn calculate_candle_indicators(
+    window: &[OhlcHistoryEntry],
+    params: &StrategyParams,
+    regime_settings: Option<&RegimeSettings>,
+) -> Indicators {
+    // OHLC indicators of the default registry, the ATR is calculated from the recent candles below
+    let registry: Vec<IndicatorSpec> = default_indicator_registry(
+        params.ma_period,
+        params.ema_period,
+        params.bb_period,
+        params.bb_std_dev_multiplier,
+        params.rsi_period,
+        params.atr_period,
+    )
+    .into_iter()
+    .filter(|spec| !matches!(spec.kind, IndicatorKind::Atr { .. }))
+    .collect();
+    let mut values = IndicatorSet::calculate(&registry, window, None, None);
+
+    // ATR of the most recent candles
+    let atr = if window.len() > params.atr_period {
+        let recent = &window[window.len() - params.atr_period - 1..];
+        let highs: Vec<f64> = recent.iter().map(|entry| entry.high).collect();
+        let lows: Vec<f64> = recent.iter().map(|entry| entry.low).collect();
+        let recent_closes: Vec<f64> = recent.iter().map(|entry| entry.close).collect();
+        calculate_atr(&highs, &lows, &recent_closes, params.atr_period)
+    } else {
+        None
+    };
+    if let Some(atr) = atr {
+        values.insert(IndicatorSource::Ohlc, ATR, IndicatorValue::Single(atr));
+    }
+
+    let (regime_indicators, regime) = match regime_settings {
+        Some(regime_settings) => {
+            let regime_indicators = calculate_regime_indicators(
+                window,
+                params.ema_period,
+                params.bb_period,
+                params.bb_std_dev_multiplier,
+                params.atr_period,
+                regime_settings,
+            );
+            (regime_indicators, classify_market_regime(&regime_indicators, regime_settings))
+        }
+        None => (RegimeIndicators::default(), None),
+    };
+
+    Indicators {
+        ohlc_data: Vec::new(),
+        price_data: Vec::new(),
+        index_price_data: Vec::new(),
+        values,
+        regime_indicators,
+        regime,
+        basis: None,
+    }
+}
+
+/// Replays OHLC candles (oldest first) through the signal scorer and simulates one position at a time.
+/// A signal (Buy/Sell or stronger) at the close of a candle opens a trade at that close, with the
+/// stoploss and takeprofit of `calculate_stoploss_takeprofit`. Exits are checked against the high and
+/// low of the following candles; if both levels are inside one candle the stoploss is assumed first.
+/// The quantity is sized so that hitting the stoploss loses `risk_per_trade_percent` of the balance.
+/// Market orders (entries, stoploss and end of data exits) are filled through the execution model of
+/// the settings: the entry `latency_ticks` candles after the signal candle, with spread and slippage.
+/// Takeprofits are limit orders and fill at their level.
+///
+/// # Parameters
+/// - `ohlc_data`: The OHLC history (oldest first).
+/// - `params`: The strategy parameters.
+/// - `signal_settings`: Base signal settings (regime weights and basis thresholds).
+/// - `settings`: The simulation settings.
+/// - `start_index`: First candle on which trades may be opened (earlier candles only warm up the indicators).
+///
+/// # Returns
+/// - The `BacktestResult` with the simulated trades and metrics.
+pub fn run_backtest(
+    ohlc_data: &[OhlcHistoryEntry],
+    params: &StrategyParams,
+    signal_settings: &SignalSettings,
+    settings: &BacktestSettings,
+    start_index: usize,
+) -> BacktestResult {
+    let signal_settings = params.signal_settings(signal_settings);
+    let warmup = params.warmup_candles();
+    let mut balance = settings.initial_balance_sats as f64;
+    let mut trades: Vec<BacktestTrade> = Vec::new();
+    let mut position: Option<OpenPosition> = None;
+    let execution = &settings.execution;
+    let mut rng = StdRng::seed_from_u64(execution.seed);
+
+    for i in start_index.max(warmup - 1)..ohlc_data.len() {
+        let candle = &ohlc_data[i];
+
+        // Exits of the open position
+        if let Some(open) = &position {
+            if open.entry_time < candle.time {
+                let exit = if open.is_buy {
+                    if candle.low <= open.stoploss {
+                        Some((open.stoploss, "stoploss"))
+                    } else if candle.high >= open.takeprofit {
+                        Some((open.takeprofit, "takeprofit"))
+                    } else {
+                        None
+                    }
+                } else if candle.high >= open.stoploss {
+                    Some((open.stoploss, "stoploss"))
+                } else if candle.low <= open.takeprofit {
+                    Some((open.takeprofit, "takeprofit"))
+                } else {
+                    None
+                };
+
+                if let Some((level, exit_reason)) = exit {
+                    // Stoplosses are market orders once triggered
+                    let exit_price = if exit_reason == "stoploss" {
+                        execution.fill(level, level, !open.is_buy, Some(open.atr), &mut rng).fill_price
+                    } else {
+                        level
+                    };
+                    let trade = close_position(open, candle.time, exit_price, exit_reason, settings.fee_rate);
+                    balance += trade.net_pl();
+                    trades.push(trade);
+                    position = None;
+                }
+            }
+        }
+
+        if position.is_some() || balance <= 0.0 {
+            continue;
+        }
+
+        // Signal at the close of the candle
+        let window = &ohlc_data[i + 1 - warmup..=i];
+        let indicators = calculate_candle_indicators(window, params, settings.regime_settings.as_ref());
+        let price_data = PriceData {
+            last_price: candle.close,
+            last_tick_direction: String::new(),
+            time: candle.time,
+        };
+        let explanation = calculate_signal_explanation(&price_data, &indicators, &signal_settings);
+        if explanation.bucket == 0 {
+            continue;
+        }
+
+        let Some(atr) = indicators.atr() else {
+            continue;
+        };
+        let is_buy = explanation.bucket > 0;
+
+        // Market order filled after the latency
+        let Some(fill_candle) = ohlc_data.get(i + execution.latency_ticks) else {
+            break;
+        };
+        let fill = execution.fill(candle.close, fill_candle.close, is_buy, Some(atr), &mut rng);
+        let entry_price = fill.fill_price;
+
+        let Ok((takeprofit, stoploss)) = calculate_stoploss_takeprofit(
+            entry_price,
+            atr,
+            settings.leverage,
+            is_buy,
+            settings.risk_to_reward_ratio,
+            settings.risk_to_loss_ratio,
+        ) else {
+            continue;
+        };
+        if stoploss <= 0.0 || takeprofit <= 0.0 {
+            continue;
+        }
+
+        // Quantity (USD) that loses the risk amount at the stoploss
+        let loss_per_usd = (1.0 / entry_price - 1.0 / stoploss).abs() * 100_000_000.0;
+        let quantity = (balance * settings.risk_per_trade_percent / loss_per_usd).floor().max(1.0);
+
+        position = Some(OpenPosition {
+            is_buy,
+            entry_time: fill_candle.time,
+            signal_price: candle.close,
+            entry_price,
+            atr,
+            quantity,
+            stoploss,
+            takeprofit,
+        });
+    }
+
+    // Close the remaining position at the last close
+    if let (Some(open), Some(last)) = (&position, ohlc_data.last()) {
+        let exit_price = execution.fill(last.close, last.close, !open.is_buy, Some(open.atr), &mut rng).fill_price;
+        let trade = close_position(open, last.time, exit_price, "end of data", settings.fee_rate);
+        balance += trade.net_pl();
+        trades.push(trade);
+    }
+
+    let metrics = calculate_backtest_metrics(&trades, settings.initial_balance_sats as f64);
+
+    BacktestResult {
+        trades,
+        final_balance_sats: balance,
+        metrics,
+    }
+}
+
+// P&L of an inverse futures contract (quantity in USD, P&L in sats)
+fn close_position(open: &OpenPosition, exit_time: i64, exit_price: f64, exit_reason: &str, fee_rate: f64) -> BacktestTrade {
+    let pl = if open.is_buy {
+        open.quantity * (1.0 / open.entry_price - 1.0 / exit_price)
+    } else {
+        open.quantity * (1.0 / exit_price - 1.0 / open.entry_price)
+    } * 100_000_000.0;
+
+    BacktestTrade {
+        side: Side::from_is_buy(open.is_buy),
+        entry_time: open.entry_time,
+        exit_time,
+        signal_price: open.signal_price,
+        entry_price: open.entry_price,
+        exit_price,
+        quantity: open.quantity,
+        pl,
+        opening_fee: open.quantity / open.entry_price * fee_rate * 100_000_000.0,
+        closing_fee: open.quantity / exit_price * fee_rate * 100_000_000.0,
+        exit_reason: exit_reason.to_string(),
+    }
+}
+
+/// Calculates the summary metrics of simulated trades.
+///
+/// # Parameters
+/// - `trades`: The trades in the order they were closed.
+/// - `initial_balance_sats`: Balance before the first trade.
+pub fn calculate_backtest_metrics(trades: &[BacktestTrade], initial_balance_sats: f64) -> BacktestMetrics {
+    if trades.is_empty() {
+        return BacktestMetrics::default();
+    }
+
+    let net_pls: Vec<f64> = trades.iter().map(|trade| trade.net_pl()).collect();
+    let gross_profit: f64 = net_pls.iter().filter(|pl| **pl > 0.0).sum();
+    let gross_loss: f64 = -net_pls.iter().filter(|pl| **pl < 0.0).sum::<f64>();
+
+    // Per trade returns and drawdown of the balance
+    let mut balance = initial_balance_sats;
+    let mut peak = balance;
+    let mut max_drawdown: f64 = 0.0;
+    let mut returns = Vec::with_capacity(net_pls.len());
+    for pl in &net_pls {
+        if balance > 0.0 {
+            returns.push(pl / balance);
+        }
+        balance += pl;
+        peak = peak.max(balance);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - balance) / peak);
+        }
+    }
+
+    let mean = returns.iter().sum::<f64>() / returns.len().max(1) as f64;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len().max(1) as f64;
+    let sharpe = if variance > 0.0 { mean / variance.sqrt() } else { 0.0 };
+
+    let profit_factor = if gross_loss > 0.0 {
+        (gross_profit / gross_loss).min(100.0)
+    } else if gross_profit > 0.0 {
+        100.0
+    } else {
+        0.0
+    };
+
+    BacktestMetrics {
+        trade_count: trades.len(),
+        net_pl: net_pls.iter().sum(),
+        win_rate: net_pls.iter().filter(|pl| **pl > 0.0).count() as f64 / trades.len() as f64,
+        profit_factor,
+        sharpe,
+        max_drawdown,
+        average_entry_slippage: trades.iter().map(|trade| trade.entry_slippage_percent()).sum::<f64>() / trades.len() as f64,
+    }
+}
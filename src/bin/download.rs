@@ -0,0 +1,90 @@
+// src/bin/download.rs
+
+use tracing::{error, info};
+use std::error::Error;
+use trading_backend::config::{load_config, load_download_settings, load_logging_settings};
+use trading_backend::futures::get_index_history::IndexHistoryEntry;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+use trading_backend::futures::get_price_history::PriceHistoryEntry;
+use trading_backend::utils::candle_store::{
+    download_index_history, download_ohlcs, download_price_history, export_series, CandleStore, DownloadSettings, DownloadSummary, HistorySeries,
+};
+use trading_backend::utils::get_timestamps::format_timestamp;
+use trading_backend::utils::logging::{init_logging, LoggingSettings};
+
+#[tokio::main]
+async fn main() {
+    // Console output only, no log files for the command line tools
+    if let Err(e) = init_logging(&LoggingSettings { log_dir: None, ..load_logging_settings() }) {
+        eprintln!("Error initializing logging: {}", e);
+    }
+
+    let config = load_config().await;
+    let settings = load_download_settings();
+    let store = match CandleStore::open(&settings.store_dir) {
+        Ok(store) => store,
+        Err(e) => {
+            error!(dir = %settings.store_dir, error = %e, "Error opening candle store");
+            return;
+        }
+    };
+    info!(
+        from = %format_timestamp(settings.from),
+        to = %format_timestamp(settings.to),
+        dir = %settings.store_dir,
+        "Downloading"
+    );
+
+    // Resumes from the stored entries, an interrupted download keeps the chunks stored so far
+    let mut series = Vec::new();
+    for &timeframe in &settings.timeframes {
+        let summary = download_ohlcs(&config.api_url, &store, timeframe, settings.from, settings.to).await;
+        series.push(log_download(HistorySeries::Ohlc(timeframe), summary));
+    }
+    if settings.include_price_data {
+        let summary = download_price_history(&config.api_url, &store, settings.from, settings.to).await;
+        series.push(log_download(HistorySeries::Price, summary));
+    }
+    if settings.include_index_data {
+        let summary = download_index_history(&config.api_url, &store, settings.from, settings.to).await;
+        series.push(log_download(HistorySeries::Index, summary));
+    }
+
+    if let Some(export_dir) = &settings.export_dir {
+        for series in series.into_iter().flatten() {
+            export(&store, &series, &settings, export_dir);
+        }
+    }
+}
+
+// Logs the outcome of a download, returns the series if it is stored
+fn log_download(series: HistorySeries, summary: Result<DownloadSummary, Box<dyn Error>>) -> Option<HistorySeries> {
+    match summary {
+        Ok(summary) => {
+            let coverage = match summary.coverage {
+                Some((first, last)) => format!("{} - {}", format_timestamp(first), format_timestamp(last)),
+                None => "empty".to_string(),
+            };
+            info!(%series, added = summary.added, requests = summary.requests, stored = %coverage, "Download finished");
+            Some(series)
+        }
+        Err(e) => {
+            error!(%series, error = %e, "Error downloading");
+            None
+        }
+    }
+}
+
+fn export(store: &CandleStore, series: &HistorySeries, settings: &DownloadSettings, export_dir: &str) {
+    for &format in &settings.export_formats {
+        let result = match series {
+            HistorySeries::Ohlc(_) => export_series::<OhlcHistoryEntry>(store, series, settings.from, settings.to, export_dir, format),
+            HistorySeries::Price => export_series::<PriceHistoryEntry>(store, series, settings.from, settings.to, export_dir, format),
+            HistorySeries::Index => export_series::<IndexHistoryEntry>(store, series, settings.from, settings.to, export_dir, format),
+        };
+        match result {
+            Ok((path, entries)) => info!(%series, entries, path = %path.display(), "Exported"),
+            Err(e) => error!(%series, error = %e, "Error exporting"),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+// src/bin/optimize.rs
+
+use tracing::{error, info};
+use trading_backend::backtest::optimize_params::{build_optimization_report, log_optimization_report, write_optimization_report, Ranking};
+use trading_backend::backtest::run_backtest::StrategyParams;
+use trading_backend::config::{load_config, load_optimizer_config, load_signal_settings, load_logging_settings};
+use trading_backend::utils::logging::{init_logging, LoggingSettings};
+use trading_backend::futures::get_ohlcs_history::GetOhlcsParams;
+use trading_backend::utils::candle_store::{init_candle_store, load_ohlcs_history};
+use trading_backend::utils::get_timestamps::{get_current_time_ms, get_time_n_days_ago_ms};
+
+#[tokio::main]
+async fn main() {
+    // Console output only, no log files for the command line tools
+    if let Err(e) = init_logging(&LoggingSettings { log_dir: None, ..load_logging_settings() }) {
+        eprintln!("Error initializing logging: {}", e);
+    }
+
+    let config = load_config().await;
+    let optimizer_config = load_optimizer_config().await;
+    let signal_settings = load_signal_settings().await;
+    if let Some(dir) = &config.candle_store_dir {
+        if let Err(e) = init_candle_store(dir) {
+            error!(%dir, error = %e, "Error opening candle store");
+        }
+    }
+
+    let ohlc_params = GetOhlcsParams {
+        range: config.timeframe,
+        from: get_time_n_days_ago_ms(optimizer_config.history_days),
+        to: get_current_time_ms(),
+        limit: Some(1000),
+        debug: true,
+    };
+    let mut ohlc_data = match load_ohlcs_history(&config.api_url, ohlc_params).await {
+        Ok(ohlc_data) => ohlc_data,
+        Err(e) => {
+            error!(error = %e, "Error fetching OHLC history");
+            return;
+        }
+    };
+    ohlc_data.sort_by_key(|entry| entry.time);
+    ohlc_data.dedup_by_key(|entry| entry.time);
+
+    // Current configuration as base of the parameter grid
+    let base = StrategyParams {
+        ma_period: config.ma_period,
+        ema_period: config.ema_period,
+        bb_period: config.bb_period,
+        bb_std_dev_multiplier: config.bb_std_dev_multiplier,
+        rsi_period: config.rsi_period,
+        atr_period: config.atr_period,
+        gap_value: signal_settings.gap_value,
+        bollinger_weight: signal_settings.bollinger_weight,
+        rsi_weight: signal_settings.rsi_weight,
+        ma_ema_weight: signal_settings.ma_ema_weight,
+        atr_weight: signal_settings.atr_weight,
+    };
+    let candidates = optimizer_config.grid.candidates(&base, optimizer_config.search_mode);
+
+    info!(
+        candidates = candidates.len(),
+        candles = ohlc_data.len(),
+        threads = optimizer_config.threads,
+        "Backtesting parameter sets"
+    );
+
+    let report = build_optimization_report(
+        &ohlc_data,
+        &candidates,
+        &signal_settings,
+        &optimizer_config.backtest,
+        Ranking { metric: optimizer_config.metric, min_trades: optimizer_config.min_trades },
+        &optimizer_config.walk_forward,
+        optimizer_config.threads,
+    );
+
+    log_optimization_report(&report, 10);
+
+    match write_optimization_report(&optimizer_config.report_path, &report) {
+        Ok(()) => info!(path = %optimizer_config.report_path, "Report written"),
+        Err(e) => error!(error = %e, "Error writing report"),
+    }
+}
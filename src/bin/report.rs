@@ -0,0 +1,69 @@
+// src/bin/report.rs
+
+use tracing::{error, info};
+use std::env;
+use trading_backend::analytics::monte_carlo::{log_monte_carlo_report, run_monte_carlo};
+use trading_backend::analytics::performance_report::{
+    calculate_performance_report, log_performance_report, write_performance_report_html, write_performance_report_json, ClosedTrade,
+};
+use trading_backend::config::{load_config, load_monte_carlo_settings, load_logging_settings};
+use trading_backend::utils::logging::{init_logging, LoggingSettings};
+use trading_backend::futures::get_trades::{get_trades, GetTradesParams};
+use trading_backend::futures::trade::TradeStatus;
+use trading_backend::utils::get_timestamps::get_time_n_days_ago_ms;
+use trading_backend::utils::get_user::get_user;
+
+#[tokio::main]
+async fn main() {
+    // Console output only, no log files for the command line tools
+    if let Err(e) = init_logging(&LoggingSettings { log_dir: None, ..load_logging_settings() }) {
+        eprintln!("Error initializing logging: {}", e);
+    }
+
+    let config = load_config().await;
+    let days: i64 = env::var("LN_REPORT_DAYS").ok().and_then(|days| days.parse().ok()).unwrap_or(30);
+
+    let params = GetTradesParams {
+        r#type: TradeStatus::Closed,
+        from: Some(get_time_n_days_ago_ms(days) as u64),
+        to: None,
+        limit: Some(1000),
+    };
+    let trades = match get_trades(&config.api_url, Some(params)).await {
+        Ok(trades) => trades,
+        Err(e) => {
+            error!(error = %e, "Error fetching closed trades");
+            return;
+        }
+    };
+    let balance = match get_user(&config.api_url).await {
+        Ok(user) => user.balance,
+        Err(e) => {
+            error!(error = %e, "Error fetching user");
+            return;
+        }
+    };
+
+    // The starting balance is the current balance minus the net P&L of the period
+    let closed_trades: Vec<ClosedTrade> = trades.iter().map(ClosedTrade::from).collect();
+    let net_pl: f64 = closed_trades.iter().map(|trade| trade.net_pl()).sum();
+    let report = calculate_performance_report(&closed_trades, balance - net_pl);
+
+    info!(days, trades = closed_trades.len(), "Closed trades");
+    log_performance_report(&report);
+
+    let monte_carlo_settings = load_monte_carlo_settings().await;
+    if let Some(monte_carlo) = run_monte_carlo(&closed_trades, balance - net_pl, &monte_carlo_settings) {
+        log_monte_carlo_report(&monte_carlo);
+    }
+
+    for (path, result) in [
+        ("performance_report.json", write_performance_report_json("performance_report.json", &report)),
+        ("performance_report.html", write_performance_report_html("performance_report.html", &report)),
+    ] {
+        match result {
+            Ok(()) => info!(%path, "Report written"),
+            Err(e) => error!(%path, error = %e, "Error writing report"),
+        }
+    }
+}
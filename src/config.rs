@@ -1,17 +1,33 @@
 // src/config.rs
 
+use tracing::warn;
 use dotenv::dotenv;
 use std::{env, sync::Arc, time::Duration};
-use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
+use crate::analytics::monte_carlo::{MonteCarloSettings, ResamplingMethod};
+use crate::backtest::optimize_params::{OptimizationMetric, ParameterGrid, SearchMode, WalkForwardSettings};
+use crate::backtest::execution_model::{ExecutionModel, SlippageModel, SpreadModel};
+use crate::backtest::run_backtest::BacktestSettings;
+use crate::futures::trade::TradeStatus;
+use crate::math::get_market_regime::MarketRegime;
+use crate::math::indicator_set::{default_indicator_registry, IndicatorSpec};
+use crate::utils::api_cache::CacheSettings;
+use crate::utils::candle_store::{DownloadSettings, ExportFormat};
+use crate::utils::logging::{LogFormat, LogRotation, LoggingSettings};
+use crate::utils::rate_limiter::RateLimitSettings;
+use crate::utils::session_replay::ReplaySettings;
+use crate::utils::notifier::{EventKind, NotificationSettings, PayloadFormat, WebhookSettings};
+use crate::utils::reconcile_state::ExternalTradePolicy;
+use crate::utils::shutdown::ExitPolicy;
 use crate::utils::{get_timestamps::{
     format_timestamp, get_current_time_ms, get_time_n_days_ago_ms
-}, log_bot_params::get_interval_from_range};
+}, timeframe::Timeframe};
 
 // Configuration for the bot's settings and signal parameters
 pub struct BotConfig {
     pub api_url: Arc<String>,                 // URL for the API endpoint (loaded from environment variables)
-    pub range: String,                   // Time range for (OHLCs) data interval (e.g., 1 minute, 1 hour)
+    pub timeframe: Timeframe,            // Timeframe of the OHLCs, a candle is the data refresh interval (e.g., 1 minute, 6 hours)
     pub from: Option<i64>,               // Starting timestamp for data fetching (optional)
     pub to: Option<i64>,                 // Ending timestamp for data fetching (optional)
     pub formatted_from: String,          // Formatted 'from' timestamp for API calls
@@ -22,23 +38,168 @@ pub struct BotConfig {
     pub bb_std_dev_multiplier: f64,      // Multiplier for standard deviation in Bollinger Bands
     pub rsi_period: usize,               // Period for the relative strength index (RSI) calculation
     pub atr_period: usize,               // Period for the average true range (ATR) calculation
-    pub trade_type: String,              // Defines the trade type: "running", "open", or "closed"
+    pub indicators: Vec<IndicatorSpec>,  // Indicator registry: the defaults of the periods above and LN_INDICATORS
+    pub trade_type: TradeStatus,         // Status of the trades to fetch: running, open or closed
     pub include_price_data: bool,        // Whether to include price data (might slow down the bot)
     pub include_index_data: bool,        // Whether to include index data (might slow down the bot)
-    pub interval: Duration,              // The interval for data fetching (calculated based on range)
+    pub index_refresh_interval: Duration, // Refresh interval of the index data, independent of the candles
     pub risk_per_trade_percent: f64,     // Risk handling for trade quantity
     pub risk_to_reward_ratio: f64,       // Risk handling for takeprofit
     pub risk_to_loss_ratio: f64,         // Risk handling for stoploss
-    pub trade_gap_seconds: u64           // Min gap bewtween opening two trades in seconds
+    pub trade_gap_seconds: u64,          // Min gap bewtween opening two trades in seconds
+    pub scale_out_targets: Vec<f64>,     // Take-profit targets in multiples of the stoploss distance (R), one child trade per target
+    pub emulate_partial_closes: bool,    // Close child trades from the bot when their target is hit instead of server-side takeprofits
+    pub position_check_interval: Duration, // Interval for revalidating running trades and position groups
+    pub margin_policy: MarginPolicy,     // Margin top up and cash-in rules for running trades
+    pub signal_conditioning: SignalConditioning, // Rules that decide when a signal is actionable
+    pub trade_journal_path: String,      // File for persisting created trades with their signal breakdown (JSON lines)
+    pub dry_run: bool,                   // Paper trade on the live price feed instead of creating trades on LN Markets
+    pub execution_model: ExecutionModel, // Spread, slippage and latency of the paper fills
+    pub paper_fee_rate: f64,             // Trading fee rate per side of the paper fills
+    pub metrics_addr: Option<String>,    // Listen address of the `/metrics` endpoint, None disables it
+    pub control_addr: String,            // Listen address of the control API
+    pub control_token: Option<String>,   // Bearer token of the control API, None disables it
+    pub tui: bool,                       // Terminal dashboard instead of console logs
+    pub notifications: NotificationSettings, // Webhooks, event filter and thresholds of the notifications
+    pub exit_policy: ExitPolicy,         // Open orders and running trades on shutdown (leave, cancel_orders, close_all)
+    pub shutdown_timeout: Duration,      // Max wait for order requests in flight on shutdown
+    pub state_path: String,              // File the bot state is persisted to (on interval and on shutdown)
+    pub external_trade_policy: ExternalTradePolicy, // Trades the bot did not create: adopt or ignore
+    pub rate_limit: RateLimitSettings,   // Request budget of the LN Markets REST API
+    pub api_cache: CacheSettings,        // TTLs of the cached ticker and user data
+    pub record_path: Option<String>,     // File the market data of the session is recorded to, None disables the recording
+    pub replay: Option<ReplaySettings>,  // Recorded session replayed instead of the live market data (dry run)
+    pub candle_store_dir: Option<String>, // Local store the history data is read from first, None fetches it from the API only
+}
+
+// Rules to rescue or de-risk running trades in the position loop
+#[derive(Debug, Clone)]
+pub struct MarginPolicy {
+    pub enable_margin_topup: bool,       // Whether margin is added to trades close to liquidation
+    pub topup_distance_percent: f64,     // Distance of the price to the liquidation price that triggers a top up (e.g. 0.02 for 2%)
+    pub topup_sats: u64,                 // Margin added per top up in sats
+    pub enable_cash_in: bool,            // Whether unrealized profit is cashed in
+    pub cash_in_threshold_sats: f64,     // Unrealized profit in sats that triggers a cash-in
+}
+
+// Rules that decide when a Buy/Sell signal is actionable (hysteresis, confirmation, cooldowns)
+#[derive(Debug, Clone)]
+pub struct SignalConditioning {
+    pub entry_threshold: f64,            // Absolute signal score required to enter a direction
+    pub exit_threshold: f64,             // Absolute signal score the direction must fall below before it can be entered again
+    pub confirmation_ticks: u32,         // Number of consecutive ticks a signal must persist
+    pub confirmation_seconds: u64,       // Or: seconds a signal must persist (whatever is reached first)
+    pub loss_cooldown_seconds: u64,      // Cooldown per direction after a losing trade
+}
+
+impl Default for SignalConditioning {
+    fn default() -> Self {
+        Self {
+            entry_threshold: 0.35,       // Above the Buy/Sell bucket edge (0.2), weak signals are not entered
+            exit_threshold: 0.1,         // Below the entry threshold for hysteresis
+            confirmation_ticks: 3,
+            confirmation_seconds: 10,
+            loss_cooldown_seconds: 300,
+        }
+    }
 }
 
 // Configuration for the signal weights and gap value
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignalSettings {
     pub bollinger_weight: f64,           // Weight for the Bollinger Bands signal
     pub rsi_weight: f64,                 // Weight for the RSI signal
     pub ma_ema_weight: f64,              // Weight for the MA/EMA signal
     pub atr_weight: f64,                 // Weight for the ATR signal
     pub gap_value: f64,                  // Gap value for triggering buy/sell signals based on indicator thresholds
+    pub basis_weight: f64,               // Weight for the basis (futures - index) signal, in addition to the weights above (0.0 disables it)
+    pub basis_z_threshold: f64,          // Basis z-score beyond which the basis is expected to revert (buy/sell)
+    pub basis_strong_z_threshold: f64,   // Basis z-score beyond which the reversion signal is strong
+    pub basis_window: usize,             // Number of candles the basis mean and standard deviation are calculated over
+    pub trend_weights: StrategyWeights,  // Weights in trending markets (MA/EMA follow the trend)
+    pub high_volatility_weights: StrategyWeights, // Weights in high volatility markets
+}
+
+// Weights of the indicators for one market regime
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StrategyWeights {
+    pub bollinger_weight: f64,           // Weight for the Bollinger Bands signal
+    pub rsi_weight: f64,                 // Weight for the RSI signal
+    pub ma_ema_weight: f64,              // Weight for the MA/EMA signal
+    pub atr_weight: f64,                 // Weight for the ATR signal
+    pub trend_following: bool,           // Whether MA/EMA signals follow the trend (price above average is bullish) instead of reverting to the mean
+}
+
+impl SignalSettings {
+    /// Settings with new base weights (ranging markets). The trend and high volatility weights are
+    /// derived from them: every regime keeps its ratio to the current base weight per indicator (e.g.
+    /// the halved high volatility weights), or takes the new weight if the current base weight is 0.
+    /// The optimizer and the control API both derive the regime weights this way.
+    pub fn with_base_weights(&self, weights: StrategyWeights) -> SignalSettings {
+        SignalSettings {
+            bollinger_weight: weights.bollinger_weight,
+            rsi_weight: weights.rsi_weight,
+            ma_ema_weight: weights.ma_ema_weight,
+            atr_weight: weights.atr_weight,
+            trend_weights: self.scale_regime_weights(&self.trend_weights, &weights),
+            high_volatility_weights: self.scale_regime_weights(&self.high_volatility_weights, &weights),
+            ..self.clone()
+        }
+    }
+
+    // Scales the regime weights by the new base weight relative to the current one
+    fn scale_regime_weights(&self, regime: &StrategyWeights, weights: &StrategyWeights) -> StrategyWeights {
+        let scale = |regime_weight: f64, base_weight: f64, weight: f64| {
+            if base_weight == 0.0 {
+                weight
+            } else {
+                regime_weight * weight / base_weight
+            }
+        };
+        StrategyWeights {
+            bollinger_weight: scale(regime.bollinger_weight, self.bollinger_weight, weights.bollinger_weight),
+            rsi_weight: scale(regime.rsi_weight, self.rsi_weight, weights.rsi_weight),
+            ma_ema_weight: scale(regime.ma_ema_weight, self.ma_ema_weight, weights.ma_ema_weight),
+            atr_weight: scale(regime.atr_weight, self.atr_weight, weights.atr_weight),
+            trend_following: regime.trend_following,
+        }
+    }
+
+    /// Weights of the active strategy for the given market regime.
+    /// Ranging markets (or an unknown regime) use the mean-reversion base weights.
+    pub fn weights_for(&self, regime: Option<MarketRegime>) -> StrategyWeights {
+        match regime {
+            Some(MarketRegime::TrendingUp) | Some(MarketRegime::TrendingDown) => self.trend_weights,
+            Some(MarketRegime::HighVolatility) => self.high_volatility_weights,
+            Some(MarketRegime::Ranging) | None => StrategyWeights {
+                bollinger_weight: self.bollinger_weight,
+                rsi_weight: self.rsi_weight,
+                ma_ema_weight: self.ma_ema_weight,
+                atr_weight: self.atr_weight,
+                trend_following: false,
+            },
+        }
+    }
+}
+
+// Thresholds for the market regime classification
+#[derive(Debug, Clone)]
+pub struct RegimeSettings {
+    pub adx_period: usize,               // Period for the average directional index (ADX) calculation
+    pub adx_trend_threshold: f64,        // ADX above which the market is trending
+    pub ema_slope_lookback: usize,       // Number of candles the EMA slope is measured over
+    pub ema_slope_threshold: f64,        // Relative EMA change per candle above which the market is trending (e.g. 0.0002 for 0.02%)
+    pub high_volatility_atr_percent: f64, // ATR relative to the price above which the market is highly volatile
+    pub high_volatility_bandwidth: f64,  // Bollinger bandwidth ((upper - lower) / middle) above which the market is highly volatile
+}
+
+// Formats the 'from' and 'to' timestamps of the configured range ('from' and 'to' are meant to be
+// edited, missing values fall back to 14 days ago and now)
+fn format_time_range(from: Option<i64>, to: Option<i64>) -> (String, String) {
+    (
+        format_timestamp(from.unwrap_or_else(|| get_time_n_days_ago_ms(14))),
+        format_timestamp(to.unwrap_or_else(get_current_time_ms)),
+    )
 }
 
 // Loads the bot's configuration settings
@@ -53,13 +214,12 @@ pub async fn load_config() -> BotConfig {
     );
 
     // Time range configuration
-    let range = "30".to_string(); // Possible values: 1, 5, 10, 15, 30, 60, 120, 180, 240, 1D, 1W, 1M, 3M. Example "1" for each minute
+    // Native LN Markets ranges: 1, 3, 5, 10, 15, 30, 45 minutes, 1, 2, 3, 4 hours, 1 day, 1 week, 1 and 3 months.
+    // Other timeframes (e.g. Timeframe::Hours(6) or Timeframe::Minutes(7)) are resampled from a native one
+    let timeframe = Timeframe::Minutes(30);
     let from = Some(get_time_n_days_ago_ms(5)); // Default to 1 hour ago for 'from' timestamp
     let to = None; // Default to current time for 'to' timestamp
 
-    // Interval for fetching data based on range
-    let interval = get_interval_from_range(&range).await;
-
     // Indicator periods
     let ma_period = 14;
     let ema_period = 12;
@@ -67,27 +227,95 @@ pub async fn load_config() -> BotConfig {
     let bb_std_dev_multiplier = 2.0;
     let rsi_period = 9;
     let atr_period = 7;
+    let indicators = load_indicator_registry(default_indicator_registry(
+        ma_period, ema_period, bb_period, bb_std_dev_multiplier, rsi_period, atr_period,
+    ));
 
-    // Define the trade type (can be "running", "open", or "closed")
-    let trade_type = "running".to_string();
+    // Status of the trades to fetch (TradeStatus::Running, TradeStatus::Open or TradeStatus::Closed)
+    let trade_type = TradeStatus::Running;
 
     // Optional data inclusion for price and index data
     let include_price_data = false;    // Set to true if price data should be included (may increase initialization time)
     let include_index_data = false;    // Set to true if index data should be included (may increase initialization time)
+    let index_refresh_interval = Duration::from_secs(60); // The basis follows the index between candles
 
     // Format the 'from' and 'to' timestamps
-    let formatted_from = format_timestamp(from.unwrap_or_else(|| get_time_n_days_ago_ms(14)));
-    let formatted_to = format_timestamp(to.unwrap_or_else(get_current_time_ms));
+    let (formatted_from, formatted_to) = format_time_range(from, to);
 
     // Trade risk
     let risk_per_trade_percent = 0.01; // 1%
     let risk_to_reward_ratio = 0.25;
     let risk_to_loss_ratio = 0.25;
 
+    // Scale-out exits (e.g. vec![1.0, 2.0, 3.0] for 1R, 2R and 3R), empty keeps one trade per signal with the risk-to-reward takeprofit
+    let scale_out_targets = Vec::new();
+    let emulate_partial_closes = false; // Set to true to close child trades from the bot instead of server-side takeprofits
+    let position_check_interval = Duration::from_secs(30);
+
+    // Margin top up and cash-in for running trades
+    let margin_policy = MarginPolicy {
+        enable_margin_topup: false,
+        topup_distance_percent: 0.02, // top up when the price is within 2% of the liquidation price
+        topup_sats: 1000,
+        enable_cash_in: false,
+        cash_in_threshold_sats: 5000.0,
+    };
+
+    // Signal conditioning (entry threshold above the Buy/Sell bucket edge and the exit threshold for hysteresis)
+    let signal_conditioning = SignalConditioning::default();
+
+    // Persistence of created trades and their signal breakdown
+    let trade_journal_path = env::var("LN_TRADE_JOURNAL_PATH").unwrap_or("trade_journal.jsonl".to_string());
+
+    // Record the market data of the session (LN_RECORD_PATH) or replay a recorded one (LN_REPLAY_PATH)
+    let replay = load_replay_settings();
+    let record_path = env::var("LN_RECORD_PATH").ok().filter(|path| !path.trim().is_empty() && replay.is_none());
+
+    // Local history store (LN_CANDLE_STORE_DIR, empty disables it), replays are served from the session only
+    let candle_store_dir = load_candle_store_dir().filter(|_| replay.is_none());
+
+    // Dry run: paper trades filled through the execution model (LN_DRY_RUN=true), always on replays
+    let dry_run = replay.is_some() || env::var("LN_DRY_RUN").map(|value| value == "true" || value == "1").unwrap_or(false);
+    let execution_model = load_execution_model().await;
+    let paper_fee_rate = 0.001; // 0.1% per side
+
+    // Prometheus metrics endpoint (LN_METRICS_ADDR, empty disables it)
+    let metrics_addr = match env::var("LN_METRICS_ADDR") {
+        Ok(addr) if addr.trim().is_empty() => None,
+        Ok(addr) => Some(addr),
+        Err(_) => Some("127.0.0.1:9100".to_string()),
+    };
+
+    // Control API, only served with a bearer token (LN_CONTROL_TOKEN)
+    let control_addr = env::var("LN_CONTROL_ADDR").unwrap_or("127.0.0.1:9101".to_string());
+    let control_token = env::var("LN_CONTROL_TOKEN").ok().filter(|token| !token.trim().is_empty());
+
+    // Shutdown: exit policy, wait for order requests in flight and persisted state
+    let exit_policy = match env::var("LN_EXIT_POLICY") {
+        Ok(policy) => policy.parse().unwrap_or_else(|e: String| {
+            warn!(error = %e, "Invalid exit policy, leaving positions on exit");
+            ExitPolicy::Leave
+        }),
+        Err(_) => ExitPolicy::Leave,
+    };
+    let shutdown_timeout = Duration::from_secs(
+        env::var("LN_SHUTDOWN_TIMEOUT_SECONDS").ok().and_then(|value| value.parse().ok()).unwrap_or(10),
+    );
+    let state_path = env::var("LN_STATE_PATH").unwrap_or("bot_state.json".to_string());
+
+    // Reconciliation on startup: trades on the account that the bot did not create
+    let external_trade_policy = match env::var("LN_EXTERNAL_TRADES") {
+        Ok(policy) => policy.parse().unwrap_or_else(|e: String| {
+            warn!(error = %e, "Invalid external trade policy, ignoring external trades");
+            ExternalTradePolicy::Ignore
+        }),
+        Err(_) => ExternalTradePolicy::Ignore,
+    };
+
     // Return the full BotConfig struct with all settings
     BotConfig {
         api_url,
-        range,
+        timeframe,
         from,
         to,
         formatted_from,
@@ -98,14 +326,38 @@ pub async fn load_config() -> BotConfig {
         bb_std_dev_multiplier,
         rsi_period,
         atr_period,
+        indicators,
         trade_type,
         include_price_data,
         include_index_data,
-        interval,
+        index_refresh_interval,
         risk_per_trade_percent,
         risk_to_reward_ratio,
         risk_to_loss_ratio,
-        trade_gap_seconds
+        trade_gap_seconds,
+        scale_out_targets,
+        emulate_partial_closes,
+        position_check_interval,
+        margin_policy,
+        signal_conditioning,
+        trade_journal_path,
+        dry_run,
+        execution_model,
+        paper_fee_rate,
+        metrics_addr,
+        control_addr,
+        control_token,
+        tui: load_tui_enabled(),
+        notifications: load_notification_settings(),
+        exit_policy,
+        shutdown_timeout,
+        state_path,
+        external_trade_policy,
+        rate_limit: load_rate_limit_settings(),
+        api_cache: load_cache_settings(),
+        record_path,
+        replay,
+        candle_store_dir,
     }
 }
 
@@ -118,12 +370,36 @@ pub async fn load_signal_settings() -> SignalSettings {
     let atr_weight = 0.25;        // Weight for the ATR signal
     let gap_value = 15.0;         // Gap value for triggering strong buy/sell signals
 
+    // Basis (futures - index) mean reversion, requires index data
+    let basis_weight = 0.15;
+    let basis_z_threshold = 1.5;
+    let basis_strong_z_threshold = 2.5;
+    let basis_window = 48;        // e.g. 24h of 30 minute candles
+
     // Check that the sum of weights equals 1.0 with a tolerance of 0.001
     let weight_sum: f64 = bollinger_weight + rsi_weight + ma_ema_weight + atr_weight;
     if (weight_sum - 1.0).abs() > 0.001 { // Allow a small margin for floating point precision errors
-        println!("{}", format!("WARNING: The sum of weights does not equal 1.0! Sum: {}", weight_sum).yellow());
+        warn!(weight_sum, "The sum of weights does not equal 1.0");
     }
 
+    // Trending markets: follow the trend with the averages, less weight on mean-reversion indicators
+    let trend_weights = StrategyWeights {
+        bollinger_weight: 0.15,
+        rsi_weight: 0.20,
+        ma_ema_weight: 0.40,
+        atr_weight: 0.25,
+        trend_following: true,
+    };
+
+    // High volatility: halved weights, only strong agreement of all indicators leads to a signal
+    let high_volatility_weights = StrategyWeights {
+        bollinger_weight: bollinger_weight * 0.5,
+        rsi_weight: rsi_weight * 0.5,
+        ma_ema_weight: ma_ema_weight * 0.5,
+        atr_weight: atr_weight * 0.5,
+        trend_following: false,
+    };
+
     // Return the SignalSettings struct with the weights and gap value
     SignalSettings {
         bollinger_weight,
@@ -131,5 +407,312 @@ pub async fn load_signal_settings() -> SignalSettings {
         ma_ema_weight,
         atr_weight,
         gap_value,
+        basis_weight,
+        basis_z_threshold,
+        basis_strong_z_threshold,
+        basis_window,
+        trend_weights,
+        high_volatility_weights,
+    }
+}
+
+// Loads the thresholds for the market regime classification
+pub async fn load_regime_settings() -> RegimeSettings {
+    RegimeSettings {
+        adx_period: 14,
+        adx_trend_threshold: 25.0,
+        ema_slope_lookback: 5,
+        ema_slope_threshold: 0.0002,       // 0.02% per candle
+        high_volatility_atr_percent: 0.01, // ATR above 1% of the price
+        high_volatility_bandwidth: 0.06,   // Bands wider than 6% of the middle band
+    }
+}
+
+// Loads the execution model of the simulations (backtest and paper trading)
+pub async fn load_execution_model() -> ExecutionModel {
+    ExecutionModel {
+        spread: SpreadModel::Fixed { spread: 1.0 },                    // 1$ between bid and ask
+        slippage: SlippageModel::Uniform { max: 0.0002 },             // Up to 0.02% against the order
+        latency_ticks: 1,                                             // Filled one candle / price update after the signal
+        seed: 42,
+    }
+}
+
+// Configuration for the parameter optimizer (optimize binary)
+pub struct OptimizerConfig {
+    pub history_days: i64,               // Days of OHLC history the optimizer runs on
+    pub metric: OptimizationMetric,      // Metric the parameter sets are ranked by
+    pub min_trades: usize,               // Parameter sets with fewer trades are ranked below all others
+    pub search_mode: SearchMode,         // Full grid or seeded random search
+    pub grid: ParameterGrid,             // Values to search per parameter
+    pub walk_forward: WalkForwardSettings, // Rolling in-sample and out-of-sample windows (candles)
+    pub backtest: BacktestSettings,      // Simulation settings
+    pub threads: usize,                  // Number of worker threads
+    pub report_path: String,             // JSON report file
+}
+
+// Loads the optimizer configuration
+pub async fn load_optimizer_config() -> OptimizerConfig {
+    let metric = env::var("LN_OPTIMIZE_METRIC")
+        .ok()
+        .and_then(|metric| metric.parse().ok())
+        .unwrap_or(OptimizationMetric::NetPl);
+    let min_trades = env::var("LN_OPTIMIZE_MIN_TRADES")
+        .ok()
+        .and_then(|min_trades| min_trades.parse().ok())
+        .unwrap_or(10);
+
+    let grid = ParameterGrid {
+        ma_periods: vec![10, 14, 20, 30],
+        rsi_periods: vec![7, 9, 14],
+        bb_std_dev_multipliers: vec![1.5, 2.0, 2.5],
+        gap_values: vec![10.0, 15.0, 25.0],
+        weights: vec![
+            [0.25, 0.30, 0.20, 0.25], // default weights
+            [0.35, 0.35, 0.15, 0.15], // mean reversion heavy
+            [0.15, 0.20, 0.40, 0.25], // averages heavy
+        ],
+    };
+
+    let backtest = BacktestSettings {
+        initial_balance_sats: 1_000_000,
+        leverage: 1.0,
+        risk_per_trade_percent: 0.01,
+        risk_to_reward_ratio: 0.25,
+        risk_to_loss_ratio: 0.25,
+        fee_rate: 0.001, // 0.1% per side
+        execution: load_execution_model().await,
+        regime_settings: Some(load_regime_settings().await),
+    };
+
+    OptimizerConfig {
+        history_days: 30,
+        metric,
+        min_trades,
+        search_mode: SearchMode::Random { samples: 100, seed: 42 }, // SearchMode::Grid tests all 324 combinations
+        grid,
+        walk_forward: WalkForwardSettings {
+            in_sample_candles: 480,       // 10 days of 30 minute candles
+            out_of_sample_candles: 144,   // 3 days of 30 minute candles
+        },
+        backtest,
+        threads: std::thread::available_parallelism().map(|threads| threads.get()).unwrap_or(4),
+        report_path: "optimization_report.json".to_string(),
+    }
+}
+
+// Loads the Monte Carlo settings of the performance report
+pub async fn load_monte_carlo_settings() -> MonteCarloSettings {
+    let seed = env::var("LN_MONTE_CARLO_SEED")
+        .ok()
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(42);
+
+    MonteCarloSettings {
+        simulations: 10_000,
+        method: ResamplingMethod::Bootstrap,
+        seed,
+        max_slippage: 0.0005,              // Up to 0.05% per side
+        fee_perturbation: 0.2,             // Fees between 80% and 120%
+        risk_levels: vec![0.005, 0.01, 0.02, 0.05],
+        ruin_drawdown: 0.5,                // Losing half of the balance
+    }
+}
+
+// Loads the webhooks (LN_WEBHOOKS=<format>=<url>,...) and the filter and thresholds of the notifications
+pub fn load_notification_settings() -> NotificationSettings {
+    dotenv().ok();
+    let env_or = |key: &str, default: u64| env::var(key).ok().and_then(|value| value.parse().ok()).unwrap_or(default);
+    let chat_id = env::var("LN_TELEGRAM_CHAT_ID").ok();
+
+    let webhooks = env::var("LN_WEBHOOKS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .filter_map(|entry| match entry.parse::<WebhookSettings>() {
+            Ok(webhook) => Some(webhook),
+            Err(e) => {
+                warn!(error = %e, "Ignoring webhook");
+                None
+            }
+        })
+        .map(|webhook| match webhook.format {
+            PayloadFormat::Telegram => WebhookSettings { chat_id: chat_id.clone(), ..webhook },
+            _ => webhook,
+        })
+        .collect();
+
+    let events = match env::var("LN_NOTIFY_EVENTS") {
+        Ok(events) => events
+            .split(',')
+            .filter(|event| !event.trim().is_empty())
+            .filter_map(|event| match event.parse::<EventKind>() {
+                Ok(kind) => Some(kind),
+                Err(e) => {
+                    warn!(error = %e, "Ignoring notification event");
+                    None
+                }
+            })
+            .collect(),
+        Err(_) => EventKind::ALL.into_iter().collect(),
+    };
+
+    NotificationSettings {
+        webhooks,
+        events,
+        max_per_minute: env_or("LN_NOTIFY_MAX_PER_MINUTE", 20) as usize,
+        dedup_seconds: env_or("LN_NOTIFY_DEDUP_SECONDS", 300),
+        stale_ws_seconds: env_or("LN_WS_STALE_SECONDS", 60),
+        api_error_threshold: env_or("LN_API_ERROR_THRESHOLD", 5),
+        api_error_window_seconds: env_or("LN_API_ERROR_WINDOW_SECONDS", 300),
+    }
+}
+
+// Adds the indicators of LN_INDICATORS (e.g. ohlc:ema:50,slow_rsi=price:rsi:21,ohlc:bb:20:2.5) to the registry,
+// an indicator with the source and ID of a default replaces it
+pub fn load_indicator_registry(defaults: Vec<IndicatorSpec>) -> Vec<IndicatorSpec> {
+    dotenv().ok();
+    let mut registry = defaults;
+    for entry in env::var("LN_INDICATORS").unwrap_or_default().split(',').filter(|entry| !entry.trim().is_empty()) {
+        match entry.parse::<IndicatorSpec>() {
+            Ok(spec) => {
+                registry.retain(|existing| existing.source != spec.source || existing.id != spec.id);
+                registry.push(spec);
+            }
+            Err(e) => warn!(error = %e, "Ignoring indicator"),
+        }
+    }
+    registry
+}
+
+// Loads the request budget of the REST API (token bucket refill rate, burst and retries after a 429)
+pub fn load_rate_limit_settings() -> RateLimitSettings {
+    dotenv().ok();
+    let defaults = RateLimitSettings::default();
+    let env_f64 = |key: &str, default: f64| {
+        env::var(key).ok().and_then(|value| value.parse::<f64>().ok()).filter(|value| *value > 0.0).unwrap_or(default)
+    };
+    RateLimitSettings {
+        requests_per_second: env_f64("LN_RATE_LIMIT_PER_SECOND", defaults.requests_per_second),
+        burst: env_f64("LN_RATE_LIMIT_BURST", defaults.burst),
+        max_retries: env::var("LN_RATE_LIMIT_RETRIES").ok().and_then(|value| value.parse().ok()).unwrap_or(defaults.max_retries),
+    }
+}
+
+// Loads the TTLs of the cached ticker and user data in milliseconds
+pub fn load_cache_settings() -> CacheSettings {
+    dotenv().ok();
+    let defaults = CacheSettings::default();
+    let env_ms = |key: &str, default: Duration| {
+        env::var(key).ok().and_then(|value| value.parse().ok()).map(Duration::from_millis).unwrap_or(default)
+    };
+    CacheSettings {
+        ticker_ttl: env_ms("LN_TICKER_CACHE_MS", defaults.ticker_ttl),
+        user_ttl: env_ms("LN_USER_CACHE_MS", defaults.user_ttl),
+    }
+}
+
+// Loads the recorded session to replay (LN_REPLAY_PATH) and its speed (LN_REPLAY_SPEED, 0 for as fast as possible)
+pub fn load_replay_settings() -> Option<ReplaySettings> {
+    dotenv().ok();
+    let path = env::var("LN_REPLAY_PATH").ok().filter(|path| !path.trim().is_empty())?;
+    let speed = match env::var("LN_REPLAY_SPEED") {
+        Ok(speed) => speed.parse().ok().filter(|speed: &f64| *speed >= 0.0).unwrap_or_else(|| {
+            warn!(%speed, "Invalid LN_REPLAY_SPEED, replaying at original speed");
+            1.0
+        }),
+        Err(_) => 1.0,
+    };
+    Some(ReplaySettings { path, speed })
+}
+
+// Loads the directory of the local history store (LN_CANDLE_STORE_DIR, empty disables it)
+pub fn load_candle_store_dir() -> Option<String> {
+    dotenv().ok();
+    match env::var("LN_CANDLE_STORE_DIR") {
+        Ok(dir) if dir.trim().is_empty() => None,
+        Ok(dir) => Some(dir),
+        Err(_) => Some("data/candles".to_string()),
+    }
+}
+
+// Loads the series, span (LN_DOWNLOAD_FROM/LN_DOWNLOAD_TO as YYYY-MM-DD or LN_DOWNLOAD_DAYS) and exports of the download tool
+pub fn load_download_settings() -> DownloadSettings {
+    dotenv().ok();
+    let list = |key: &str, default: &str| -> Vec<String> {
+        env::var(key)
+            .unwrap_or(default.to_string())
+            .split(',')
+            .map(|entry| entry.trim().to_string())
+            .filter(|entry| !entry.is_empty())
+            .collect()
+    };
+    let flag = |key: &str| env::var(key).map(|value| value == "true" || value == "1").unwrap_or(false);
+    let date_ms = |key: &str| {
+        let date = env::var(key).ok()?;
+        match chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d") {
+            Ok(date) => date.and_hms_opt(0, 0, 0).map(|time| time.and_utc().timestamp_millis()),
+            Err(e) => {
+                warn!(key, %date, error = %e, "Invalid date");
+                None
+            }
+        }
+    };
+
+    let days = env::var("LN_DOWNLOAD_DAYS").ok().and_then(|days| days.parse().ok()).unwrap_or(30);
+    let export_formats = list("LN_EXPORT_FORMATS", "csv,parquet")
+        .iter()
+        .filter_map(|format| match format.parse::<ExportFormat>() {
+            Ok(format) => Some(format),
+            Err(e) => {
+                warn!(error = %e, "Ignoring export format");
+                None
+            }
+        })
+        .collect();
+
+    DownloadSettings {
+        store_dir: load_candle_store_dir().unwrap_or("data/candles".to_string()),
+        timeframes: list("LN_DOWNLOAD_TIMEFRAMES", "30")
+            .iter()
+            .filter_map(|timeframe| match timeframe.parse::<Timeframe>() {
+                Ok(timeframe) => Some(timeframe),
+                Err(e) => {
+                    warn!(error = %e, "Ignoring timeframe");
+                    None
+                }
+            })
+            .collect(),
+        include_price_data: flag("LN_DOWNLOAD_PRICE"),
+        include_index_data: flag("LN_DOWNLOAD_INDEX"),
+        from: date_ms("LN_DOWNLOAD_FROM").unwrap_or_else(|| get_time_n_days_ago_ms(days)),
+        to: date_ms("LN_DOWNLOAD_TO").unwrap_or_else(get_current_time_ms),
+        export_dir: env::var("LN_EXPORT_DIR").ok().filter(|dir| !dir.trim().is_empty()),
+        export_formats,
+    }
+}
+
+// Whether the terminal dashboard replaces the console logs (LN_TUI=true)
+pub fn load_tui_enabled() -> bool {
+    dotenv().ok();
+    env::var("LN_TUI").map(|value| value == "true" || value == "1").unwrap_or(false)
+}
+
+// Loads the log sinks and levels (LN_LOG or RUST_LOG for the levels per module)
+pub fn load_logging_settings() -> LoggingSettings {
+    dotenv().ok();
+    let filter = env::var("LN_LOG")
+        .or_else(|_| env::var("RUST_LOG"))
+        .unwrap_or("info".to_string());
+    let log_dir = env::var("LN_LOG_DIR").unwrap_or("logs".to_string());
+
+    LoggingSettings {
+        filter,
+        console: !load_tui_enabled(), // The dashboard owns the terminal
+        console_format: env::var("LN_LOG_FORMAT").ok().and_then(|format| format.parse().ok()).unwrap_or(LogFormat::Pretty),
+        log_dir: (!log_dir.is_empty()).then_some(log_dir), // LN_LOG_DIR= disables the log files
+        file_prefix: "trading-bot.log".to_string(),
+        file_format: env::var("LN_LOG_FILE_FORMAT").ok().and_then(|format| format.parse().ok()).unwrap_or(LogFormat::Json),
+        rotation: env::var("LN_LOG_ROTATION").ok().and_then(|rotation| rotation.parse().ok()).unwrap_or(LogRotation::Daily),
     }
 }
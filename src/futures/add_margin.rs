@@ -0,0 +1,59 @@
+// src/futures/add_margin.rs
+
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::Serialize;
+use std::error::Error;
+use crate::futures::trade::Trade;
+use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+
+/// Represents the request body to add margin to a running trade.
+#[derive(Serialize)]
+pub struct AddMarginParams<'a> {
+    pub id: &'a str,   // The trade ID
+    pub amount: u64,   // The margin to add in sats
+}
+
+/// Adds margin to a running trade by sending a POST request to the API.
+/// Adding margin moves the liquidation price away from the current price.
+///
+/// # Arguments
+/// - `api_url`: The base URL of the API endpoint to interact with.
+/// - `trade_id`: The ID of the running trade.
+/// - `amount`: The margin to add in sats.
+///
+/// # Returns
+/// - A `Result` that contains the `Trade` if successful, or an error if the request fails.
+pub async fn add_margin(
+    api_url: &str,
+    trade_id: &str,
+    amount: u64,
+) -> Result<Trade, Box<dyn Error>> {
+    let params = AddMarginParams { id: trade_id, amount };
+    let params_json = serde_json::to_string(&params)?;
+
+    let mut headers: HeaderMap = get_headers("/v2/futures/add-margin", "POST", Some(&params_json))?;
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    let url = format!("{}/futures/add-margin", api_url);
+
+    let client = http_client();
+    let response = client
+        .post(&url)
+        .headers(headers)
+        .body(params_json)
+        .send_metered("futures/add_margin")
+        .await?;
+
+    if response.status().is_success() {
+        let trade: Trade = response.json().await?;
+        Ok(trade)
+    } else {
+        let error_message = format!(
+            "Error adding margin: {} - {}",
+            response.status(),
+            response.text().await?
+        );
+        Err(error_message.into())
+    }
+}
@@ -0,0 +1,55 @@
+// src/futures/cancel_trade.rs
+
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::Serialize;
+use std::error::Error;
+use crate::futures::trade::Trade;
+use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+
+/// Represents the request body to cancel an open trade (unfilled limit order).
+#[derive(Serialize)]
+pub struct CancelTradeParams<'a> {
+    pub id: &'a str,   // The trade ID to be canceled
+}
+
+/// Cancels an open trade (limit order that is not filled yet) by sending a POST request to the API.
+///
+/// # Arguments
+/// - `api_url`: The base URL of the API endpoint to interact with.
+/// - `trade_id`: The ID of the open trade.
+///
+/// # Returns
+/// - A `Result` that contains the canceled `Trade` if successful, or an error if the request fails.
+pub async fn cancel_trade(
+    api_url: &str,
+    trade_id: &str,
+) -> Result<Trade, Box<dyn Error>> {
+    let params = CancelTradeParams { id: trade_id };
+    let params_json = serde_json::to_string(&params)?;
+
+    let mut headers: HeaderMap = get_headers("/v2/futures/cancel", "POST", Some(&params_json))?;
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    let url = format!("{}/futures/cancel", api_url);
+
+    let client = http_client();
+    let response = client
+        .post(&url)
+        .headers(headers)
+        .body(params_json)
+        .send_metered("futures/cancel_trade")
+        .await?;
+
+    if response.status().is_success() {
+        let trade: Trade = response.json().await?;
+        Ok(trade)
+    } else {
+        let error_message = format!(
+            "Error canceling trade: {} - {}",
+            response.status(),
+            response.text().await?
+        );
+        Err(error_message.into())
+    }
+}
@@ -0,0 +1,59 @@
+// src/futures/cash_in.rs
+
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::Serialize;
+use std::error::Error;
+use crate::futures::trade::Trade;
+use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+
+/// Represents the request body to cash in profit of a running trade.
+#[derive(Serialize)]
+pub struct CashInParams<'a> {
+    pub id: &'a str,   // The trade ID
+    pub amount: u64,   // The profit to cash in, in sats
+}
+
+/// Cashes in unrealized profit of a running trade by sending a POST request to the API.
+/// The cashed-in profit is credited to the balance and is no longer at risk.
+///
+/// # Arguments
+/// - `api_url`: The base URL of the API endpoint to interact with.
+/// - `trade_id`: The ID of the running trade.
+/// - `amount`: The profit to cash in, in sats.
+///
+/// # Returns
+/// - A `Result` that contains the `Trade` if successful, or an error if the request fails.
+pub async fn cash_in(
+    api_url: &str,
+    trade_id: &str,
+    amount: u64,
+) -> Result<Trade, Box<dyn Error>> {
+    let params = CashInParams { id: trade_id, amount };
+    let params_json = serde_json::to_string(&params)?;
+
+    let mut headers: HeaderMap = get_headers("/v2/futures/cash-in", "POST", Some(&params_json))?;
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    let url = format!("{}/futures/cash-in", api_url);
+
+    let client = http_client();
+    let response = client
+        .post(&url)
+        .headers(headers)
+        .body(params_json)
+        .send_metered("futures/cash_in")
+        .await?;
+
+    if response.status().is_success() {
+        let trade: Trade = response.json().await?;
+        Ok(trade)
+    } else {
+        let error_message = format!(
+            "Error cashing in: {} - {}",
+            response.status(),
+            response.text().await?
+        );
+        Err(error_message.into())
+    }
+}
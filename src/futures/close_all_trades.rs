@@ -1,48 +1,18 @@
 // src/futures/close_all_trades.rs
 
-use reqwest::{Client, header::HeaderMap};
+use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use std::error::Error;
+use crate::futures::trade::Trade;
 use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
 
 // Represents the structure of the API response after attempting to close all trades.
 // This struct holds various details about each trade, including fees, margin, and status.
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)] // Used to prevent warnings about unused fields during development.
 pub struct CloseAllTradesResponse {
-    pub trades: Vec<CloseTradeResponse>,  // A list of trades that were closed
-}
-
-// Represents the structure of the response from closing a single trade.
-// This is the same struct used in `close_trade.rs`.
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)]
-pub struct CloseTradeResponse {
-    pub uid: String,           // Unique identifier for the trade
-    #[serde(rename = "type")]
-    pub type_: String,         // Type of the trade (e.g., "buy", "sell")
-    pub id: String,            // The trade ID
-    pub side: String,          // The side of the trade ("buy" or "sell")
-    pub opening_fee: f64,      // The fee incurred for opening the trade
-    pub closing_fee: f64,      // The fee incurred for closing the trade
-    pub maintenance_margin: f64, // Maintenance margin required for the trade
-    pub quantity: f64,         // The quantity involved in the trade
-    pub margin: f64,           // The margin used for the trade
-    pub leverage: f64,         // The leverage applied to the trade
-    pub price: f64,            // The price at which the trade was executed
-    pub liquidation: f64,      // Liquidation price of the trade
-    pub pl: f64,               // Profit or loss from the trade
-    pub creation_ts: u64,      // Timestamp when the trade was created
-    pub market_filled_ts: u64, // Timestamp when the trade was filled in the market
-    pub closed_ts: Option<u64>, // Timestamp when the trade was closed
-    pub open: bool,            // Indicates whether the trade is open
-    pub running: bool,         // Indicates whether the trade is still running
-    pub canceled: bool,        // Indicates whether the trade was canceled
-    pub closed: bool,          // Indicates whether the trade was closed
-    pub last_update_ts: u64,   // Timestamp of the last update on the trade
-    pub sum_carry_fees: f64,   // The total carry fees for the trade
-    pub entry_price: Option<f64>, // Entry price, if available
-    pub entry_margin: Option<f64>, // Entry margin, if available
+    pub trades: Vec<Trade>,  // A list of trades that were closed
 }
 
 /// Asynchronously closes all running trades by sending a DELETE request to the API.
@@ -53,7 +23,7 @@ pub struct CloseTradeResponse {
 ///
 /// # Returns
 /// - A `Result` that contains the `CloseAllTradesResponse` if successful, or an error if the request fails.
-pub async fn _close_all_trades(
+pub async fn close_all_trades(
     api_url: &str
 ) -> Result<CloseAllTradesResponse, Box<dyn Error>> {
     // Construct the URL for closing all trades.
@@ -61,11 +31,11 @@ pub async fn _close_all_trades(
 
     let headers: HeaderMap = get_headers("/v2/futures/all/close", "DELETE", None)?;
 
-    let client = Client::new();
+    let client = http_client();
     let response = client
         .delete(&url)
         .headers(headers)
-        .send()
+        .send_metered("futures/close_all")
         .await?;
 
     if response.status().is_success() {
@@ -1,60 +1,107 @@
 // src/futures/create_trade.rs
 
-use reqwest::{Client, header::{HeaderMap, HeaderValue, CONTENT_TYPE}};
-use serde::{Deserialize, Serialize};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::{Serialize, Serializer};
 use std::error::Error;
+use crate::futures::trade::{OrderType, Side, Trade};
 use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+
+/// Type of an order: market orders fill at the current price, limit orders at their price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    Market,
+    Limit { price: u64 },                // Limit price of the order
+}
+
+/// Size of a trade, either by quantity or by margin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sizing {
+    Quantity(u64),                       // Quantity in USD (min tradable 1 USD)
+    Margin(u64),                         // Margin in sats
+}
 
 // Represents the parameters required to create a new trade.
-#[derive(Serialize, Debug)]
+// Built with `CreateTradeParams::market` or `CreateTradeParams::limit`, the order kind and the sizing
+// are enums so that a market order with a price, a limit order without one or a trade sized by both
+// the quantity and the margin cannot be built.
+#[derive(Debug, Clone, PartialEq)]
 pub struct CreateTradeParams {
-    pub r#type: String,  // "m" for market, "l" for limit
-    pub side: String,    // "b" for buy, "s" for sell
+    kind: OrderKind,                     // Market or limit order (with its price)
+    side: Side,                          // Buy or sell
+    leverage: u64,                       // Leverage to apply to the trade
+    sizing: Sizing,                      // Quantity or margin of the trade
+    takeprofit: Option<u64>,             // Optional: Take-profit price for the trade
+    stoploss: Option<u64>,               // Optional: Stop-loss price for the trade
+}
+
+// Request body of `POST /futures`
+#[derive(Serialize)]
+struct CreateTradeBody {
+    r#type: OrderType,
+    side: Side,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub margin: Option<u64>,  // Optional: Margin amount (used if quantity is not provided) in Satoshis
-    pub leverage: u64,   // Leverage to apply to the trade
+    margin: Option<u64>,
+    leverage: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub price: Option<u64>,  // Optional: Price for limit orders (required if type = "l")
+    price: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub quantity: Option<u64>,  // Optional: Quantity of the asset (used if margin is not provided) Quantity in USD (min tradable 1 USD)
+    quantity: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub takeprofit: Option<u64>,  // Optional: Take-profit price for the trade
+    takeprofit: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub stoploss: Option<u64>,  // Optional: Stop-loss price for the trade
+    stoploss: Option<u64>,
+}
+
+impl CreateTradeParams {
+    /// Parameters of a market order.
+    pub fn market(side: Side, leverage: u64, sizing: Sizing, takeprofit: Option<u64>, stoploss: Option<u64>) -> Self {
+        Self { kind: OrderKind::Market, side, leverage, sizing, takeprofit, stoploss }
+    }
+
+    /// Parameters of a limit order at `price`.
+    pub fn limit(side: Side, leverage: u64, price: u64, sizing: Sizing, takeprofit: Option<u64>, stoploss: Option<u64>) -> Self {
+        Self { kind: OrderKind::Limit { price }, side, leverage, sizing, takeprofit, stoploss }
+    }
+
+    pub fn kind(&self) -> OrderKind {
+        self.kind
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn sizing(&self) -> Sizing {
+        self.sizing
+    }
+
+    fn body(&self) -> CreateTradeBody {
+        let (r#type, price) = match self.kind {
+            OrderKind::Market => (OrderType::Market, None),
+            OrderKind::Limit { price } => (OrderType::Limit, Some(price)),
+        };
+        let (quantity, margin) = match self.sizing {
+            Sizing::Quantity(quantity) => (Some(quantity), None),
+            Sizing::Margin(margin) => (None, Some(margin)),
+        };
+        CreateTradeBody {
+            r#type,
+            side: self.side,
+            margin,
+            leverage: self.leverage,
+            price,
+            quantity,
+            takeprofit: self.takeprofit,
+            stoploss: self.stoploss,
+        }
+    }
 }
 
-// Represents the response from the create trade endpoint, detailing the created trade's parameters.
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)] // Suppresses warnings for unused fields in the response struct.
-pub struct TradeResponse {
-    pub id: String,
-    pub uid: String,
-    #[serde(rename = "type")]
-    pub type_: String,
-    pub side: String,
-    pub opening_fee: u64,
-    pub closing_fee: u64,
-    pub maintenance_margin: u64,
-    pub quantity: u64,
-    pub margin: u64,
-    pub leverage: u64,
-    pub price: f64,
-    pub liquidation: f64,
-    pub stoploss: u64,
-    pub takeprofit: u64,
-    pub exit_price: Option<f64>,
-    pub pl: u64,
-    pub creation_ts: u64,
-    pub market_filled_ts: Option<u64>,
-    pub closed_ts: Option<u64>,
-    pub open: bool,
-    pub running: bool,
-    pub canceled: bool,
-    pub closed: bool,
-    pub last_update_ts: u64,
-    pub sum_carry_fees: u64,
-    pub entry_price: Option<f64>,
-    pub entry_margin: Option<u64>,
+impl Serialize for CreateTradeParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.body().serialize(serializer)
+    }
 }
 
 /// Creates a new trade on the server by sending the provided parameters.
@@ -64,19 +111,19 @@ pub struct TradeResponse {
 /// - `params`: The parameters required to create the trade (including side, type, leverage, etc.).
 /// 
 /// # Returns
-/// - `Ok(TradeResponse)`: If the trade was created successfully, returns the details of the created trade.
+/// - `Ok(Trade)`: If the trade was created successfully, returns the details of the created trade.
 /// - `Err(Box<dyn Error>)`: If the request fails, returns an error message.
 pub async fn create_trade(
     api_url: &str,
     params: CreateTradeParams
-) -> Result<TradeResponse, Box<dyn Error>> {
+) -> Result<Trade, Box<dyn Error>> {
     let params_json = serde_json::to_string(&params)?;
-    //println!("Request Body: {}", params_json);
+    //debug!("Request Body: {}", params_json);
 
     let mut headers: HeaderMap = get_headers("/v2/futures", "POST", Some(&params_json))?;
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
     
-    let client = Client::new();
+    let client = http_client();
 
     // Construct the URL for the 'create trade' API endpoint.
     let url = format!("{}/futures", api_url);
@@ -86,11 +133,11 @@ pub async fn create_trade(
         .post(&url)
         .headers(headers)
         .body(params_json)    
-        .send()
+        .send_metered("futures/create_trade")
         .await?;
 
     if response.status().is_success() {
-        let trade: TradeResponse = response.json().await?;
+        let trade: Trade = response.json().await?;
         Ok(trade)
     } else {
         let error_message = format!("Error creating trade: {}", response.status());
@@ -98,142 +145,56 @@ pub async fn create_trade(
     }
 }
 
-/// Creates a limit buy order on the server.
-/// This function constructs a `CreateTradeParams` struct for a limit buy order and calls `create_trade`.
+/// Creates a limit order on the server.
+/// This function constructs the `CreateTradeParams` of a limit order and calls `create_trade`.
 /// 
 /// # Arguments
 /// - `api_url`: The base URL of the API endpoint.
+/// - `side`: Buy or sell.
 /// - `leverage`: The leverage to apply to the trade.
-/// - `price`: The limit price for the buy order.
-/// - `quantity`: Optional quantity of the asset to buy.
+/// - `price`: The limit price of the order.
+/// - `sizing`: Quantity or margin of the trade.
 /// - `takeprofit`: Optional take-profit price for the order.
 /// - `stoploss`: Optional stop-loss price for the order.
 /// 
 /// # Returns
-/// - `Result<TradeResponse, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
-pub async fn _create_limit_buy_order(
+/// - `Result<Trade, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
+pub async fn _create_limit_order(
     api_url: &str,
+    side: Side,
     leverage: u64,
     price: u64,
-    quantity: Option<u64>,
+    sizing: Sizing,
     takeprofit: Option<u64>,
     stoploss: Option<u64>
-) -> Result<TradeResponse, Box<dyn Error>> {
-    let params = CreateTradeParams {
-        side: "b".to_string(),  // "b" indicates a buy order.
-        r#type: "l".to_string(),  // "l" indicates a limit order.
-        margin: None,
-        leverage,
-        price: Some(price), // Limit order requires a price.
-        quantity,
-        takeprofit,
-        stoploss,
-    };
+) -> Result<Trade, Box<dyn Error>> {
+    let params = CreateTradeParams::limit(side, leverage, price, sizing, takeprofit, stoploss);
     // Delegate the actual trade creation to the `create_trade` function.
     create_trade(api_url, params).await
 }
 
-/// Creates a limit sell order on the server.
-/// This function constructs a `CreateTradeParams` struct for a limit sell order and calls `create_trade`.
+/// Creates a market order on the server.
+/// This function constructs the `CreateTradeParams` of a market order and calls `create_trade`.
 /// 
 /// # Arguments
 /// - `api_url`: The base URL of the API endpoint.
+/// - `side`: Buy or sell.
 /// - `leverage`: The leverage to apply to the trade.
-/// - `price`: The limit price for the sell order.
-/// - `quantity`: Optional quantity of the asset to sell.
+/// - `sizing`: Quantity or margin of the trade.
 /// - `takeprofit`: Optional take-profit price for the order.
 /// - `stoploss`: Optional stop-loss price for the order.
 /// 
 /// # Returns
-/// - `Result<TradeResponse, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
-pub async fn _create_limit_sell_order(
+/// - `Result<Trade, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
+pub async fn create_market_order(
     api_url: &str,
+    side: Side,
     leverage: u64,
-    price: u64,
-    quantity: Option<u64>,
-    takeprofit: Option<u64>,
-    stoploss: Option<u64>
-) -> Result<TradeResponse, Box<dyn Error>> {
-    let params = CreateTradeParams {
-        side: "s".to_string(),  // "s" indicates a sell order.
-        r#type: "l".to_string(),  // "l" indicates a limit order.
-        margin: None,
-        leverage,
-        price: Some(price),
-        quantity,
-        takeprofit,
-        stoploss,
-    };
-    // Delegate the actual trade creation to the `create_trade` function.
-    create_trade(api_url, params).await
-}
-
-/// Creates a market buy order on the server.
-/// This function constructs a `CreateTradeParams` struct for a market buy order and calls `create_trade`.
-/// 
-/// # Arguments
-/// - `api_url`: The base URL of the API endpoint.
-/// - `leverage`: The leverage to apply to the trade.
-/// - `quantity`: Optional quantity of the asset to buy.
-/// - `margin`: Optional margin to apply to the trade.
-/// - `takeprofit`: Optional take-profit price for the order.
-/// - `stoploss`: Optional stop-loss price for the order.
-/// 
-/// # Returns
-/// - `Result<TradeResponse, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
-pub async fn create_market_buy_order(
-    api_url: &str,
-    leverage: u64,
-    quantity: Option<u64>,
-    margin: Option<u64>,
-    takeprofit: Option<u64>,
-    stoploss: Option<u64>
-) -> Result<TradeResponse, Box<dyn Error>> {
-    let params = CreateTradeParams {
-        side: "b".to_string(),  // "b" indicates a buy order.
-        r#type: "m".to_string(),  // "m" indicates a market order.
-        margin,
-        leverage,
-        price: None, // No price for market orders.
-        quantity,
-        takeprofit,
-        stoploss,
-    };
-    // Delegate the actual trade creation to the `create_trade` function.
-    create_trade(api_url, params).await
-}
-
-/// Creates a market sell order on the server.
-/// This function constructs a `CreateTradeParams` struct for a market sell order and calls `create_trade`.
-/// 
-/// # Arguments
-/// - `api_url`: The base URL of the API endpoint.
-/// - `leverage`: The leverage to apply to the trade.
-/// - `quantity`: Optional quantity of the asset to sell.
-/// - `margin`: Optional margin to apply to the trade.
-/// - `takeprofit`: Optional take-profit price for the order.
-/// - `stoploss`: Optional stop-loss price for the order.
-/// 
-/// # Returns
-/// - `Result<TradeResponse, Box<dyn Error>>`: The response from the API call, or an error if the trade could not be created.
-pub async fn create_market_sell_order(
-    api_url: &str,
-    leverage: u64,
-    quantity: Option<u64>,
-    margin: Option<u64>,
+    sizing: Sizing,
     takeprofit: Option<u64>,
     stoploss: Option<u64>
-) -> Result<TradeResponse, Box<dyn Error>> {
-    let params = CreateTradeParams {
-        side: "s".to_string(),  // "s" indicates a sell order.
-        r#type: "m".to_string(),  // "m" indicates a market order.
-        margin,
-        leverage,
-        price: None, // No price for market orders.
-        quantity,
-        takeprofit,
-        stoploss,
-    };
+) -> Result<Trade, Box<dyn Error>> {
+    let params = CreateTradeParams::market(side, leverage, sizing, takeprofit, stoploss);
     // Delegate the actual trade creation to the `create_trade` function.
     create_trade(api_url, params).await
 }
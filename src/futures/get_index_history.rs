@@ -1,12 +1,14 @@
 // src/futures/get_index_history.rs
 
+use tracing::{debug, info};
 use reqwest::header::HeaderMap;
 use crate::utils::get_headers::{encode_query_params, get_headers};
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::session_recorder::record_response;
+use crate::utils::session_replay::replayed_response;
 use crate::utils::get_timestamps::{format_timestamp, get_current_time_ms, get_time_n_days_ago_ms};
 use serde::Deserialize;
 use std::error::Error;
-use std::io::Write;
-use colored::Colorize;
 
 // Struct to represent a single entry in the index history, containing the timestamp and value
 #[derive(Deserialize, Debug, Clone)]
@@ -27,7 +29,7 @@ pub async fn get_index_history(
     let from = from.unwrap_or_else(|| get_time_n_days_ago_ms(7)); // Default: 7 days ago
     let limit = limit.unwrap_or(1000);
 
-    println!("{}", format!("Fetch index history from: {} - to: {}", format_timestamp(from), format_timestamp(to)).dimmed());
+    info!(from = %format_timestamp(from), to = %format_timestamp(to), "Fetch index history");
 
     let mut all_index_data: Vec<IndexHistoryEntry> = Vec::new();
     let current_from = from;
@@ -45,76 +47,78 @@ pub async fn get_index_history(
         });
         let query_option = encode_query_params(&params);
 
-        let headers: HeaderMap = get_headers("/v2/futures/history/index", "GET", query_option.as_deref())?;
-
-        let client = reqwest::Client::new();
-        let url = format!("{}/futures/history/index", api_url);
-
-        let response = client
-            .get(url)
-            .headers(headers)
-            .query(&params)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            let body = response.text().await?;
-            let index_history: Vec<IndexHistoryEntry> = serde_json::from_str(&body)?;
-
-            // If no data is returned, break the loop
-            if index_history.is_empty() {
-                break;
+        // Replay: recorded page of the session
+        let body = match replayed_response("futures/index_history") {
+            Some(body) => body,
+            None => {
+                let headers: HeaderMap = get_headers("/v2/futures/history/index", "GET", query_option.as_deref())?;
+
+                let client = http_client();
+                let url = format!("{}/futures/history/index", api_url);
+
+                let response = client
+                    .get(url)
+                    .headers(headers)
+                    .query(&params)
+                    .send_metered("futures/index_history")
+                    .await?;
+
+                if !response.status().is_success() {
+                    let error: Box<dyn Error> = Box::new(response.error_for_status().unwrap_err());
+                    return Err(error);
+                }
+                let body = response.text().await?;
+                record_response("futures/index_history", &body);
+                body
             }
+        };
+        let index_history: Vec<IndexHistoryEntry> = serde_json::from_str(&body)?;
 
-            // Add the fetched data to our collection
-            all_index_data.extend(index_history.clone());
-
-            // Get the oldest timestamp from the fetched data (last entry in the list)
-            if let Some(last_entry) = index_history.last() {
-                let last_time = last_entry.time;
+        // If no data is returned, break the loop
+        if index_history.is_empty() {
+            break;
+        }
 
-                // Update the 'current_to' to the last time minus 1ms for the next API call
-                current_to = last_time - 1;
-            }
+        // Add the fetched data to our collection
+        all_index_data.extend(index_history.clone());
 
-            // Only stop if the fetched range has covered enough time
-            if current_to <= from {
-                break;
-            }
+        // Get the oldest timestamp from the fetched data (last entry in the list)
+        if let Some(last_entry) = index_history.last() {
+            let last_time = last_entry.time;
 
-            let fetched_from = index_history.first().map(|e| e.time).unwrap_or(0);
-            let fetched_to = index_history.last().map(|e| e.time).unwrap_or(0);
+            // Update the 'current_to' to the last time minus 1ms for the next API call
+            current_to = last_time - 1;
+        }
 
-            let remaining_time = current_to - current_from;
-            let current_time_span = fetched_from - fetched_to;
+        // Only stop if the fetched range has covered enough time
+        if current_to <= from {
+            break;
+        }
 
-            total_time_span += current_time_span;
-            request_count += 1;
+        let fetched_from = index_history.first().map(|e| e.time).unwrap_or(0);
+        let fetched_to = index_history.last().map(|e| e.time).unwrap_or(0);
 
-            let avg_time_span = if request_count > 0 {
-                total_time_span as f64 / request_count as f64
-            } else {
-                0.0
-            };
+        let remaining_time = current_to - current_from;
+        let current_time_span = fetched_from - fetched_to;
 
-            let remaining_requests = if avg_time_span > 0.0 {
-                (remaining_time as f64 / avg_time_span).ceil() as usize
-            } else {
-                0
-            };
+        total_time_span += current_time_span;
+        request_count += 1;
 
-            print!("\r{: <width$}", format!("...init index history: remaining ~ {} seconds", remaining_requests).dimmed(), width = 50);
-            std::io::stdout().flush().unwrap();
+        let avg_time_span = if request_count > 0 {
+            total_time_span as f64 / request_count as f64
+        } else {
+            0.0
+        };
 
+        let remaining_requests = if avg_time_span > 0.0 {
+            (remaining_time as f64 / avg_time_span).ceil() as usize
         } else {
-            let error: Box<dyn Error> = Box::new(response.error_for_status().unwrap_err());
-            return Err(error);
-        }
+            0
+        };
 
-        // Sleep for a while to avoid hitting the API rate limits
-        std::thread::sleep(std::time::Duration::from_secs(1));
+        debug!(remaining_requests, "...init index history: remaining ~ {} seconds", remaining_requests);
     }
 
-    println!("\r{: <width$}", "Index data retrieval complete.".green(), width = 50);
+    info!("Index data retrieval complete");
     Ok(all_index_data)
 }
@@ -1,10 +1,13 @@
-use reqwest::{Client, header::HeaderMap};
+use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use std::error::Error;
 use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::session_recorder::record_response;
+use crate::utils::session_replay::replayed_response;
 
 /// Struct to represent the market data response from the API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct FuturesMarket {
     pub active: bool,
@@ -13,7 +16,7 @@ pub struct FuturesMarket {
 }
 
 /// Sub-struct for limits
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct Limits {
     pub quantity: MinMax,
@@ -22,7 +25,7 @@ pub struct Limits {
 }
 
 /// Struct for min and max values
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct MinMax {
     pub min: u64,
@@ -32,14 +35,14 @@ pub struct MinMax {
 }
 
 /// Struct for count limits
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct CountLimit {
     pub max: u64,
 }
 
 /// Struct for fees
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct Fees {
     pub carry: CarryFee,
@@ -47,7 +50,7 @@ pub struct Fees {
 }
 
 /// Carry fees structure
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct CarryFee {
     pub min: f64,
@@ -55,7 +58,7 @@ pub struct CarryFee {
 }
 
 /// Trading fees structure
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct TradingFees {
@@ -63,7 +66,7 @@ pub struct TradingFees {
 }
 
 /// Struct for individual fee tiers
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct Tier {
@@ -80,11 +83,16 @@ pub struct Tier {
 /// - `Ok(FuturesMarket)` if the request succeeds and data is parsed.
 /// - `Err(Box<dyn Error>)` if the request fails or parsing fails.
 pub async fn get_market(api_url: &str) -> Result<FuturesMarket, Box<dyn Error>> {
+    // Replay: recorded response of the session
+    if let Some(body) = replayed_response("futures/market") {
+        return Ok(serde_json::from_str(&body)?);
+    }
+
     // Generate the required headers for the API request
     let headers: HeaderMap = get_headers("/v2/futures/market", "GET", None)?;
 
-    // Create a new HTTP client
-    let client = Client::new();
+    // Shared HTTP client
+    let client = http_client();
 
     // Construct the full URL
     let url = format!("{}{}", api_url, "/futures/market");
@@ -93,12 +101,13 @@ pub async fn get_market(api_url: &str) -> Result<FuturesMarket, Box<dyn Error>>
     let response = client
         .get(&url)
         .headers(headers)
-        .send()
+        .send_metered("futures/market")
         .await?;
 
     // Check for a successful response and deserialize JSON
     if response.status().is_success() {
         let response_text = response.text().await?;
+        record_response("futures/market", &response_text);
         let market_data: FuturesMarket = serde_json::from_str(&response_text)?;
         Ok(market_data)
     } else {
@@ -1,16 +1,19 @@
 // src/futures/get_ohlc_history.rs
 
+use tracing::{debug, info};
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
-use std::io::Write;
-use colored::Colorize;
-use tokio::time::Duration;
 
 use crate::utils::get_headers::get_headers;
 use crate::utils::get_headers::encode_query_params;
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::session_recorder::record_response;
+use crate::utils::session_replay::replayed_response;
 use crate::utils::get_timestamps::format_timestamp;
 use crate::utils::get_timestamps::get_current_time_ms;
 use crate::utils::get_timestamps::get_time_n_days_ago_ms;
+use crate::utils::timeframe::{serialize_lnm_range, Timeframe};
+use crate::math::resample_ohlcs::resample_ohlcs;
 
 /// Represents a single OHLC entry, containing the timestamp and the open, high, low, and close values.
 #[derive(Deserialize, Debug, Clone)]
@@ -25,9 +28,10 @@ pub struct OhlcHistoryEntry {
 }
 
 /// Represents the query parameters for fetching OHLC history.
-#[derive(Serialize, Debug)]
-pub struct GetOhlcsParams<'a> {
-    pub range: &'a str,  // The range (e.g., "1D", "1H")
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct GetOhlcsParams {
+    #[serde(serialize_with = "serialize_lnm_range")]
+    pub range: Timeframe, // The timeframe of the candles, resampled from a native LN Markets range if needed
     pub from: i64,       // The start timestamp
     pub to: i64,         // The end timestamp
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -35,7 +39,7 @@ pub struct GetOhlcsParams<'a> {
     pub debug: bool,
 }
 
-impl<'a> Default for GetOhlcsParams<'a> {
+impl Default for GetOhlcsParams {
     fn default() -> Self {
         // Get the current timestamp from the utility function
         let current_timestamp = get_current_time_ms();
@@ -45,7 +49,7 @@ impl<'a> Default for GetOhlcsParams<'a> {
 
         // Return the default parameters
         Self {
-            range: "1D", // Default to 1 day range
+            range: Timeframe::Days(1), // Default to 1 day range
             from: seven_days_ago,
             to: current_timestamp,
             limit: Some(100), // Default limit to 100
@@ -56,26 +60,32 @@ impl<'a> Default for GetOhlcsParams<'a> {
 
 pub async fn get_ohlcs_history(
     api_url: &str,
-    params: GetOhlcsParams<'_>,
+    params: GetOhlcsParams,
 ) -> Result<Vec<OhlcHistoryEntry>, Box<dyn std::error::Error>> {
 
+    // Timeframes LN Markets does not serve are resampled from the native source, fetched from the
+    // open of the first to the end of the last candle
+    let timeframe = params.range;
+    let source = timeframe.source();
+    let resample = source != timeframe;
+
     let mut all_ohlc_data: Vec<OhlcHistoryEntry> = Vec::new();
-    let mut current_from = params.from;
-    let current_to = params.to;
+    let mut current_from = if resample { timeframe.align(params.from) } else { params.from };
+    let current_to = if resample { (timeframe.next_open(params.to) - 1).min(get_current_time_ms()) } else { params.to };
     let limit = params.limit.unwrap_or(1000);
 
     let mut total_time_span = 0i64;
     let mut request_count = 0usize;
 
     if params.debug {
-        println!("{}", format!("Fetch OHLC history from: {} - to: {}", format_timestamp(current_from), format_timestamp(current_to)).dimmed());
+        info!(from = %format_timestamp(current_from), to = %format_timestamp(current_to), "Fetch OHLC history");
     }
 
-    let client = reqwest::Client::new();
+    let client = http_client();
 
     while current_from < current_to {
         let params = GetOhlcsParams {
-            range: params.range,
+            range: source,
             from: current_from,
             to: current_to,
             limit: Some(limit),
@@ -83,76 +93,80 @@ pub async fn get_ohlcs_history(
         };
         
         let query_option = encode_query_params(&params);
-        let headers: HeaderMap = get_headers("/v2/futures/ohlcs", "GET", query_option.as_deref())?;
-
-        let url = format!("{}/futures/ohlcs?{}", api_url, query_option.unwrap_or_default());
-
-        let response = client
-            .get(url)
-            .headers(headers)
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            let response_text = response.text().await?;
-            let ohlc_history: Vec<OhlcHistoryEntry> = serde_json::from_str(&response_text)?;
-
-            if ohlc_history.is_empty() {
-                break;
+        // Replay: recorded page of the session
+        let response_text = match replayed_response("futures/ohlcs") {
+            Some(body) => body,
+            None => {
+                let headers: HeaderMap = get_headers("/v2/futures/ohlcs", "GET", query_option.as_deref())?;
+
+                let url = format!("{}/futures/ohlcs?{}", api_url, query_option.unwrap_or_default());
+
+                let response = client
+                    .get(url)
+                    .headers(headers)
+                    .send_metered("futures/ohlcs")
+                    .await?;
+
+                if !response.status().is_success() {
+                    let error_message = format!(
+                        "Failed to fetch OHLC history: {} - {:?}",
+                        response.status(),
+                        response.text().await?
+                    );
+                    return Err(Box::new(std::io::Error::other(error_message)));
+                }
+                let body = response.text().await?;
+                record_response("futures/ohlcs", &body);
+                body
             }
+        };
+        let ohlc_history: Vec<OhlcHistoryEntry> = serde_json::from_str(&response_text)?;
 
-            all_ohlc_data.extend(ohlc_history.clone());
+        if ohlc_history.is_empty() {
+            break;
+        }
 
-            if let Some(last_entry) = ohlc_history.last() {
-                current_from = last_entry.time + 1;
-            }
+        all_ohlc_data.extend(ohlc_history.clone());
 
+        if let Some(last_entry) = ohlc_history.last() {
+            current_from = last_entry.time + 1;
+        }
 
-            let fetched_from = ohlc_history.first().map(|e| e.time).unwrap_or(0);
-            let fetched_to = ohlc_history.last().map(|e| e.time).unwrap_or(0);
-            let remaining_time = current_to - current_from;
-            let current_time_span = fetched_to - fetched_from;
 
-            total_time_span += current_time_span;
-            request_count += 1;
+        let fetched_from = ohlc_history.first().map(|e| e.time).unwrap_or(0);
+        let fetched_to = ohlc_history.last().map(|e| e.time).unwrap_or(0);
+        let remaining_time = current_to - current_from;
+        let current_time_span = fetched_to - fetched_from;
 
-            let avg_time_span = if request_count > 0 {
-                total_time_span as f64 / request_count as f64
-            } else {
-                0.0
-            };
+        total_time_span += current_time_span;
+        request_count += 1;
 
-            let remaining_requests = if avg_time_span > 0.0 {
-                (remaining_time as f64 / avg_time_span).ceil() as usize
-            } else {
-                0
-            };
-   
-            if params.debug {
-                print!("\r{: <width$}", format!("...init OHLCs history: remaining ~ {} seconds", remaining_requests).dimmed(), width = 50);
-                std::io::stdout().flush().unwrap();
-            }
+        let avg_time_span = if request_count > 0 {
+            total_time_span as f64 / request_count as f64
+        } else {
+            0.0
+        };
+
+        let remaining_requests = if avg_time_span > 0.0 {
+            (remaining_time as f64 / avg_time_span).ceil() as usize
         } else {
-            let error_message = format!(
-                "Failed to fetch OHLC history: {} - {:?}",
-                response.status(),
-                response.text().await?
-            );
-            return Err(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                error_message
-            )));
+            0
+        };
+   
+        if params.debug {
+            debug!(remaining_requests, "...init OHLCs history: remaining requests (estimate)");
         }
 
         if current_to <= params.from {
             break;
         }
-
-        tokio::time::sleep(Duration::from_secs(1)).await;
     }
 
     if params.debug {
-        println!("\r{: <width$}", "OHLCs data retrieval complete.".green(), width = 50);
+        info!("OHLCs data retrieval complete");
+    }
+    if resample {
+        return Ok(resample_ohlcs(&all_ohlc_data, timeframe));
     }
     Ok(all_ohlc_data)
 }
@@ -8,4 +8,8 @@ pub mod create_trade;
 pub mod get_market;
 pub mod close_trade;
 pub mod close_all_trades;
-pub mod get_ohlcs_history;
\ No newline at end of file
+pub mod get_ohlcs_history;
+pub mod add_margin;
+pub mod cash_in;
+pub mod cancel_trade;
+pub mod trade;
\ No newline at end of file
@@ -2,6 +2,9 @@
 
 use reqwest::header::HeaderMap;
 use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::session_recorder::record_response;
+use crate::utils::session_replay::replayed_response;
 use serde::Deserialize;
 use std::error::Error;
 
@@ -17,7 +20,7 @@ use std::error::Error;
 /// - `bid_price`: The current bid price (the price at which buyers are willing to buy).
 /// - `carry_fee_rate`: The carry fee rate for the futures contract.
 /// - `carry_fee_timestamp`: The timestamp when the carry fee rate was last updated.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct FuturesTicker {
@@ -43,22 +46,28 @@ pub async fn get_futures_ticker(
 ) -> Result<FuturesTicker, Box<dyn Error>> {
     // Generate the necessary headers for the API request using the provided credentials.
     // The get_headers function handles the creation of headers like authentication and API signature.
+    // Replay: recorded response of the session
+    if let Some(body) = replayed_response("futures/ticker") {
+        return Ok(serde_json::from_str(&body)?);
+    }
+
     let headers: HeaderMap = get_headers("/v2/futures/ticker", "GET", None)?;
 
-    // Create a new HTTP client to make requests.
-    let client = reqwest::Client::new();
+    // Shared HTTP client to make requests.
+    let client = http_client();
 
     // Send the GET request to the /futures/ticker endpoint of the API.
     let response = client
         .get(format!("{}/futures/ticker", api_url))  // Construct the full URL with the base API URL and endpoint.
         .headers(headers)  // Attach the necessary headers for authentication.
-        .send()  // Send the request to the API.
+        .send_metered("futures/ticker")  // Send the request to the API (recorded in the REST metrics).
         .await?;  // Await the response asynchronously.
 
     // Check if the response status code indicates success (200 OK).
     if response.status().is_success() {
         // If successful, retrieve the response body as text (JSON string).
         let body = response.text().await?;
+        record_response("futures/ticker", &body);
 
         // Deserialize the JSON response body into the FuturesTicker struct.
         // The serde_json::from_str function converts the JSON string into the struct.
@@ -0,0 +1,128 @@
+// src/futures/trade.rs
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Side of a trade, `"b"` and `"s"` on the wire.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    #[default]
+    #[serde(rename = "b")]
+    Buy,                                 // Long
+    #[serde(rename = "s")]
+    Sell,                                // Short
+}
+
+impl Side {
+    pub fn from_is_buy(is_buy: bool) -> Self {
+        if is_buy { Side::Buy } else { Side::Sell }
+    }
+
+    pub fn is_buy(self) -> bool {
+        self == Side::Buy
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Buy => write!(f, "Buy"),
+            Side::Sell => write!(f, "Sell"),
+        }
+    }
+}
+
+/// Order type of a trade, `"m"` and `"l"` on the wire.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    #[default]
+    #[serde(rename = "m")]
+    Market,                              // Filled at the current price
+    #[serde(rename = "l")]
+    Limit,                               // Filled once the price reaches the limit price
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderType::Market => write!(f, "Market Order"),
+            OrderType::Limit => write!(f, "Limit Order"),
+        }
+    }
+}
+
+/// Status of a trade, the `type` filter of the trades endpoint.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeStatus {
+    #[default]
+    Open,                                // Limit order that is not filled yet
+    Running,                             // Filled, the position is open
+    Closed,                              // Closed or canceled
+}
+
+impl TradeStatus {
+    pub const ALL: [TradeStatus; 3] = [TradeStatus::Running, TradeStatus::Open, TradeStatus::Closed];
+}
+
+impl fmt::Display for TradeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TradeStatus::Open => write!(f, "open"),
+            TradeStatus::Running => write!(f, "running"),
+            TradeStatus::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A futures trade as returned by the LN Markets API (trades, create, close, cash-in and add-margin
+/// endpoints). Amounts are in sats, prices and quantities in USD.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Trade {
+    pub id: String,                      // The trade ID
+    pub uid: String,                     // The user ID
+    #[serde(rename = "type")]
+    pub order_type: OrderType,           // Market or limit order
+    pub side: Side,                      // Buy or sell
+    pub opening_fee: f64,                // The fee incurred for opening the trade
+    pub closing_fee: f64,                // The fee incurred (or reserved) for closing the trade
+    pub maintenance_margin: f64,         // Maintenance margin required for the trade
+    pub quantity: f64,                   // Quantity in USD
+    pub margin: f64,                     // Margin of the trade
+    pub leverage: f64,                   // Leverage of the trade
+    pub price: f64,                      // The price at which the trade was executed (limit price of open trades)
+    pub liquidation: f64,                // Liquidation price of the trade
+    pub stoploss: f64,                   // Stop-loss price, 0 if none
+    pub takeprofit: f64,                 // Take-profit price, 0 if none
+    pub pl: f64,                         // Profit or loss (unrealized while running)
+    pub creation_ts: u64,                // Timestamp when the trade was created
+    #[serde(default)]
+    pub market_filled_ts: Option<u64>,   // Timestamp when the trade was filled, `None` for open limit orders
+    pub open: bool,                      // Whether the trade is an open limit order
+    pub running: bool,                   // Whether the trade is running
+    pub canceled: bool,                  // Whether the trade was canceled
+    pub closed: bool,                    // Whether the trade was closed
+    pub last_update_ts: u64,             // Timestamp of the last update on the trade
+    pub sum_carry_fees: f64,             // The total carry fees of the trade
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_price: Option<f64>,        // Entry price, if available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_margin: Option<f64>,       // Entry margin, if available
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_price: Option<f64>,         // Exit price of closed trades
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_ts: Option<u64>,          // Timestamp when the trade was closed
+}
+
+impl Trade {
+    /// Status of the trade, derived from its flags (canceled trades count as closed).
+    pub fn status(&self) -> TradeStatus {
+        if self.closed || self.canceled {
+            TradeStatus::Closed
+        } else if self.running {
+            TradeStatus::Running
+        } else {
+            TradeStatus::Open
+        }
+    }
+}
@@ -4,3 +4,5 @@ pub mod math;
 pub mod futures;
 pub mod utils;
 pub mod config;
+pub mod backtest;
+pub mod analytics;
@@ -1,62 +1,216 @@
 // src/main.rs
 
-use config::load_config;
-use utils::update_history_data::update_history_data;
+use tracing::{error, info, info_span, Instrument};
+use trading_backend::config::{load_config, load_logging_settings, load_regime_settings, load_signal_settings};
+use trading_backend::utils::update_history_data::{update_history_data, HistoryBuffers, HistoryUpdate};
 use tokio::signal;
-use tokio::sync::{Mutex, mpsc};
-use utils::log_bot_params::{log_bot_params, log_spot_price, log_updated_indicators};
-use utils::process_signals::process_signals;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use trading_backend::utils::log_bot_params::{log_bot_params, log_spot_price, log_updated_indicators};
+use trading_backend::utils::process_signals::{process_signals, SignalProcessingSettings};
+use trading_backend::utils::manage_positions::{manage_positions, ClosedTradeEvent};
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use colored::Colorize;
-use utils::connect_ws::ws_price_feed;
-use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
-use math::get_indicators::update_price_indicators;
-use utils::init_bot_params::{init_bot_params, BotParams};
-use utils::set_updated_indicators::set_updated_indicators;
-use math::get_signals::{get_signals, SignalData, SignalResponse};
-
-mod config;
-mod utils;
-mod futures;
-mod math;
+use std::time::Instant;
+use trading_backend::utils::connect_ws::ws_price_feed;
+use trading_backend::math::get_basis::calculate_basis_stats;
+use trading_backend::math::get_indicators::IndicatorParams;
+use trading_backend::math::indicator_set::IndicatorSet;
+use trading_backend::math::get_market_regime::{calculate_regime_indicators, classify_market_regime};
+use trading_backend::utils::init_bot_params::{init_bot_params, BotParams};
+use trading_backend::utils::get_timestamps::get_time_n_days_ago_ms;
+use trading_backend::utils::logging::init_logging;
+use trading_backend::utils::rate_limiter::init_rate_limiter;
+use trading_backend::utils::api_cache::init_api_cache;
+use trading_backend::utils::candle_store::init_candle_store;
+use trading_backend::utils::session_recorder::{read_session, start_recording};
+use trading_backend::utils::session_replay::{init_replay, is_replaying, replay_session, split_session};
+use trading_backend::utils::metrics::{metrics, metrics_router, serve_http};
+use trading_backend::utils::control_api::{control_router, ControlState};
+use trading_backend::utils::dashboard::run_dashboard;
+use trading_backend::utils::notifier::{health_watchdog, start_notifications, NotificationDispatcher};
+use trading_backend::utils::shutdown::{apply_exit_policy, log_shutdown_summary, shutdown_channel, until_shutdown, ShutdownSummary};
+use trading_backend::utils::state_store::{load_state, persist_state, save_state, BotState};
+use trading_backend::utils::reconcile_state::{log_reconcile_report, reconcile_on_startup};
+use trading_backend::utils::runtime_settings::RuntimeSettings;
+use trading_backend::utils::paper_exchange::{log_paper_events, PaperEvent, PaperExchange};
+use trading_backend::utils::set_updated_indicators::{set_updated_indicators, IndicatorUpdate};
+use trading_backend::math::get_signals::{get_signals, SignalData, SignalResponse};
 
 #[tokio::main]
 async fn main() {
+    // Keep the guard alive to flush the log files on shutdown
+    let _log_guard = match init_logging(&load_logging_settings()) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Error initializing logging: {}", e);
+            None
+        }
+    };
+
     let config = load_config().await;
+    init_rate_limiter(config.rate_limit);
+    init_api_cache(config.api_cache);
+    if let Some(dir) = &config.candle_store_dir {
+        if let Err(e) = init_candle_store(dir) {
+            error!(%dir, error = %e, "Error opening candle store");
+        }
+    }
+
+    // Replay of a recorded session: the REST functions serve the recorded responses, the stream
+    // replaces the price feed and the history updates
+    let replay = match &config.replay {
+        Some(replay) => match read_session(&replay.path) {
+            Ok(frames) => {
+                let (startup, stream) = split_session(frames);
+                init_replay(&startup);
+                info!(path = %replay.path, frames = startup.len() + stream.len(), speed = replay.speed, "Replay, dry run");
+                Some((stream, replay.speed))
+            }
+            Err(e) => {
+                error!(path = %replay.path, error = %e, "Error reading session");
+                return;
+            }
+        },
+        None => None,
+    };
     let api_url = config.api_url.clone();
     let bot_params: Arc<Mutex<BotParams>>;
+    let started = Instant::now();
+
+    // Shutdown signal of all tasks
+    let (shutdown_trigger, shutdown) = shutdown_channel();
+
+    // Record the market data of the session (written until the shutdown)
+    let recorder = config.record_path.as_deref().and_then(|path| match start_recording(path, shutdown.clone()) {
+        Ok(recorder) => {
+            info!(%path, "Recording session");
+            Some(recorder)
+        }
+        Err(e) => {
+            error!(%path, error = %e, "Error recording session");
+            None
+        }
+    });
+
+    // Prometheus metrics endpoint
+    if let Some(addr) = config.metrics_addr.clone() {
+        tokio::spawn(until_shutdown(shutdown.clone(), async move {
+            info!(%addr, "Metrics endpoint: http://{}/metrics", addr);
+            if let Err(e) = serve_http(&addr, metrics_router()).await {
+                error!(%addr, error = %e, "Error serving metrics");
+            }
+        }.instrument(info_span!("metrics"))));
+    }
+
+    // Webhook notifications and the watchdog reporting a stale websocket or piling up API errors
+    if !config.notifications.webhooks.is_empty() {
+        info!(webhooks = config.notifications.webhooks.len(), "Notifications");
+        start_notifications(NotificationDispatcher::from_settings(&config.notifications));
+        tokio::spawn(until_shutdown(
+            shutdown.clone(),
+            health_watchdog(config.notifications.clone()).instrument(info_span!("notifications")),
+        ));
+    }
 
     // init signals channels
     let (signal_tx, signal_rx) = mpsc::channel::<SignalData>(15);
     let signal_tx = Arc::new(Mutex::new(signal_tx));
     let signal_tx_clone1 = Arc::clone(&signal_tx);
     let (signal_result_tx, signal_result_rx) = mpsc::channel::<SignalResponse>(15);
+    let (closed_trade_tx, closed_trade_rx) = mpsc::channel::<ClosedTradeEvent>(15);
+
+    // Settings that can be changed through the control API
+    let runtime = RuntimeSettings {
+        paused: false,
+        dry_run: config.dry_run,
+        risk_per_trade_percent: config.risk_per_trade_percent,
+        risk_to_reward_ratio: config.risk_to_reward_ratio,
+        risk_to_loss_ratio: config.risk_to_loss_ratio,
+        signal_settings: load_signal_settings().await,
+    };
 
     // init bot params
-    match init_bot_params(
-        &config.api_url,
-        &config.range,
-        config.from,
-        config.to,
-        config.ma_period,
-        config.ema_period,
-        config.bb_period,
-        config.bb_std_dev_multiplier,
-        config.rsi_period,
-        config.atr_period,
-        &config.trade_type,
-        config.include_price_data,
-        config.include_index_data
-    ).await {
+    let indicator_params = IndicatorParams {
+        timeframe: config.timeframe,
+        from: config.from,
+        to: config.to,
+        registry: &config.indicators,
+        ema_period: config.ema_period,
+        bb_period: config.bb_period,
+        bb_std_dev_multiplier: config.bb_std_dev_multiplier,
+        atr_period: config.atr_period,
+        include_price_data: config.include_price_data,
+        include_index_data: config.include_index_data,
+    };
+    match init_bot_params(&config.api_url, &indicator_params, config.trade_type, runtime).await {
         Ok(initialized_bot_params) => {
             bot_params = Arc::new(Mutex::new(initialized_bot_params));
-            println!("\n{} Bot Params Initialization {}\n", "===" .bold(), "===");
+            info!("Bot params initialization");
             
-            log_bot_params(&*bot_params.lock().await, &config.trade_type, config.formatted_from, config.formatted_to);
-    
-            println!("{}", "===" .bold());
+            log_bot_params(&*bot_params.lock().await, config.trade_type, config.formatted_from, config.formatted_to);
+
+            {
+                let bot_params = bot_params.lock().await;
+                if let Some(market_data) = &bot_params.market_data {
+                    metrics().max_trades.set(market_data.limits.count.max as i64);
+                }
+                if let Some(user) = &bot_params.user_data {
+                    metrics().balance_sats.set(user.balance);
+                }
+                if let Some(indicators) = &bot_params.indicators {
+                    metrics().record_indicators(indicators);
+                }
+            }
+
+            // State of the last run (saved on interval and on shutdown), replays start from scratch
+            let state = match load_state(&config.state_path) {
+                Ok(state) if !is_replaying() => state,
+                Ok(_) => None,
+                Err(e) => {
+                    error!(path = %config.state_path, error = %e, "Error loading state");
+                    None
+                }
+            };
+
+            // Runtime settings of the last run (pause, dry run, risk, signal weights)
+            if let Some(state) = &state {
+                let mut bot_params = bot_params.lock().await;
+                bot_params.runtime.restore(&state.runtime);
+                let runtime = &bot_params.runtime;
+                info!(paused = runtime.paused, dry_run = runtime.dry_run, "Runtime settings restored");
+            }
 
+            // Dry run: paper exchange of the last run or with the current balance
+            let dry_run = bot_params.lock().await.runtime.dry_run;
+            if dry_run {
+                let mut bot_params = bot_params.lock().await;
+                let balance = bot_params.user_data.as_ref().map(|user| user.balance).unwrap_or_default();
+                let exchange = match state.as_ref().and_then(|state| state.paper.clone()) {
+                    Some(paper) => PaperExchange::restore(config.execution_model, config.paper_fee_rate, paper),
+                    None => PaperExchange::new(config.execution_model, balance, config.paper_fee_rate),
+                };
+                info!(balance = exchange.balance, "Dry run: paper trading");
+                bot_params.paper_exchange = Some(exchange);
+            }
+
+            // Reconcile the state with the trades of the account, trades closed while the bot was
+            // down are processed like trades closed while running (P&L, cooldowns, notifications)
+            if !is_replaying() {
+                match reconcile_on_startup(&api_url, &bot_params, state.as_ref(), &config.trade_journal_path, config.external_trade_policy).await {
+                    Ok(report) => {
+                        log_reconcile_report(&report, config.external_trade_policy);
+                        let closed_trade_tx = closed_trade_tx.clone();
+                        tokio::spawn(async move {
+                            for closed_trade in report.closed_while_down {
+                                let _ = closed_trade_tx.send(closed_trade).await;
+                            }
+                        });
+                    }
+                    Err(e) => error!(error = %e, "Error reconciling state"),
+                }
+            }
+    
             // add bot_params.indicators to signals channel
             let bot_params_locked = bot_params.lock().await;
             if let Some(indicators) = &bot_params_locked.indicators {
@@ -70,46 +224,76 @@ async fn main() {
             }
         }
         Err(e) => {
-            eprintln!("{}", format!("Error initializing Bot Params: {}", e).red());
+            error!(error = %e, "Error initializing bot params");
             return;
         }
     }
 
+    // History updates of a replay follow the recorded OHLC responses instead of the interval
+    let (history_tick_tx, history_tick_rx) = mpsc::channel::<()>(1);
+    let history_ticks = replay.is_some().then_some(history_tick_rx);
+
     // update bot params (history data and derived indicators) continuously
     if let Some(ref indicators) = &bot_params.lock().await.indicators {
         let bot_params_clone: Arc<Mutex<BotParams>> = Arc::clone(&bot_params);
 
         let ohlc_data = Arc::new(Mutex::new(indicators.ohlc_data.clone()));
         let ohlc_data_clone = Arc::clone(&ohlc_data);
-        let (tx, mut rx) = mpsc::channel::<Vec<OhlcHistoryEntry>>(5);
+        let price_data = config.include_price_data.then(|| Arc::new(Mutex::new(indicators.price_data.clone())));
+        let index_data = config.include_index_data.then(|| Arc::new(Mutex::new(indicators.index_price_data.clone())));
+        let (tx, mut rx) = mpsc::channel::<HistoryUpdate>(5);
     
-        // task to update ohlc data on interval (index and price history data not integrated in v0.1.0)
-        tokio::spawn(async move {
-            if let Err(e) = update_history_data(&config.api_url, config.interval, ohlc_data_clone, &config.range, tx).await {
-                eprintln!("Error in update_data task: {}", e);
+        // task to update ohlc, price and index data on interval
+        tokio::spawn(until_shutdown(shutdown.clone(), async move {
+            // Empty buffers start at the configured range (same fallback as the initial indicators)
+            let from = config.from.unwrap_or_else(|| get_time_n_days_ago_ms(1));
+            let buffers = HistoryBuffers { ohlc_data: ohlc_data_clone, price_data, index_data };
+            if let Err(e) = update_history_data(&config.api_url, config.timeframe, from, buffers, config.index_refresh_interval, tx, history_ticks).await {
+                error!(error = %e, "Error in update_data task");
             }
-        });
+        }.instrument(info_span!("history_update"))));
 
         // task to process updated OHLC data for fresh indicators by interval
-        tokio::spawn(async move {
-            while let Some(ohlc_data) = rx.recv().await {
-                let (ma, ema, bollinger_bands, rsi, atr, price_ma, price_ema, price_bollinger_bands, price_rsi, index_ma, index_ema, index_bollinger_bands, index_rsi) =
-                    update_price_indicators(
-                        &ohlc_data,
-                        config.ma_period,
-                        config.ema_period,
-                        config.bb_period,
-                        config.bb_std_dev_multiplier,
-                        config.rsi_period,
-                        config.atr_period,
-                        None,
-                        None,
-                    );
+        tokio::spawn(until_shutdown(shutdown.clone(), async move {
+            let regime_settings = load_regime_settings().await;
+            let basis_window = load_signal_settings().await.basis_window;
+
+            while let Some(history_update) = rx.recv().await {
+                let ohlc_data = history_update.ohlc_data;
+                let values = IndicatorSet::calculate(
+                    &config.indicators,
+                    &ohlc_data,
+                    history_update.price_data.as_deref(),
+                    history_update.index_data.as_deref(),
+                );
+                let regime_indicators = calculate_regime_indicators(
+                    &ohlc_data,
+                    config.ema_period,
+                    config.bb_period,
+                    config.bb_std_dev_multiplier,
+                    config.atr_period,
+                    &regime_settings,
+                );
+                let regime = classify_market_regime(&regime_indicators, &regime_settings);
+                let basis = history_update.index_data.as_ref().and_then(|index_data| {
+                    calculate_basis_stats(&ohlc_data, index_data, basis_window, history_update.ticker_index)
+                });
 
                 let mut bot_params = bot_params_clone.lock().await;
-                set_updated_indicators(&mut bot_params, ohlc_data, ma, ema, bollinger_bands, rsi, atr, price_ma, price_ema, price_bollinger_bands, price_rsi, index_ma, index_ema, index_bollinger_bands, index_rsi);
+                set_updated_indicators(&mut bot_params, IndicatorUpdate {
+                    ohlc_data,
+                    values,
+                    regime_indicators,
+                    regime,
+                    price_data: history_update.price_data,
+                    index_data: history_update.index_data,
+                    basis,
+                });
                 
                 log_updated_indicators(&bot_params);
+                if let Some(indicators) = &bot_params.indicators {
+                    metrics().record_indicators(indicators);
+                }
 
                 // add indicators to signal channel
                 let signal_data = SignalData {
@@ -119,9 +303,9 @@ async fn main() {
                 let signal_tx_locked = signal_tx.lock().await;
                 signal_tx_locked.send(signal_data).await.unwrap();
             }
-        });
+        }.instrument(info_span!("history_update"))));
     } else {
-        eprintln!("Indicators not initialized.");
+        error!("Indicators not initialized.");
     }
     
     // channel for price data
@@ -129,21 +313,61 @@ async fn main() {
     // channel for shutdown signal
     let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
 
-    // Start the WebSocket task
-    let handle = tokio::spawn(async move {
-        let ws_endpoint = env::var("LN_MAINNET_API_WS_ENDPOINT").expect("WebSocket Endpoint Not Found");
-        let method = env::var("LN_PRICE_METHOD").expect("Price Method for Price Feed Not Found");
-        if let Err(e) = ws_price_feed(shutdown_rx, &ws_endpoint, &method, price_tx).await {
-            eprintln!("Error: {}", e);
-        }
-    });
+    // channel for the end of a replay
+    let (replay_done_tx, replay_done_rx) = oneshot::channel::<()>();
+
+    // Start the WebSocket task, or the replay of the recorded session
+    let handle = match replay {
+        Some((stream, speed)) => tokio::spawn(async move {
+            replay_session(stream, speed, price_tx, history_tick_tx, shutdown_rx).await;
+            let _ = replay_done_tx.send(());
+        }.instrument(info_span!("replay"))),
+        None => tokio::spawn(async move {
+            let ws_endpoint = env::var("LN_MAINNET_API_WS_ENDPOINT").expect("WebSocket Endpoint Not Found");
+            let method = env::var("LN_PRICE_METHOD").expect("Price Method for Price Feed Not Found");
+            if let Err(e) = ws_price_feed(shutdown_rx, &ws_endpoint, &method, price_tx).await {
+                error!(error = %e, "Error in price feed");
+            }
+        }.instrument(info_span!("ws_feed"))),
+    };
 
     // Continuously process spot price data feed and send to signal channel
-    tokio::spawn({
+    tokio::spawn(until_shutdown(shutdown.clone(), {
+        let bot_params = Arc::clone(&bot_params);
+        let closed_trade_tx = closed_trade_tx.clone();
         async move {
             while let Some(price_data) = price_rx.recv().await {
                 
                 log_spot_price(&price_data).await;
+                metrics().record_price_tick(price_data.last_price);
+
+                // Fills and exits of the paper exchange
+                let mut closed_paper_trades = Vec::new();
+                let mut bot_params = bot_params.lock().await;
+                let dry_run = bot_params.runtime.dry_run;
+                if let Some(exchange) = bot_params.paper_exchange.as_mut() {
+                    let events = exchange.on_price(price_data.last_price, price_data.time);
+                    log_paper_events(&events, exchange.balance);
+                    if dry_run {
+                        metrics().running_trades.set(exchange.positions.len() as i64);
+                        metrics().balance_sats.set(exchange.balance);
+                        metrics().unrealized_pl_sats.set(exchange.unrealized_pl(price_data.last_price));
+                    }
+                    for event in events {
+                        if let PaperEvent::Closed { id, trade, .. } = event {
+                            closed_paper_trades.push(ClosedTradeEvent {
+                                trade_id: id,
+                                side: trade.side,
+                                pl: trade.net_pl(),
+                                closed_ts: trade.exit_time,
+                            });
+                        }
+                    }
+                }
+                drop(bot_params);
+                for closed_trade in closed_paper_trades {
+                    let _ = closed_trade_tx.send(closed_trade).await;
+                }
     
                 let signal_data = SignalData {
                     price_data: Some(price_data.clone()),
@@ -153,44 +377,177 @@ async fn main() {
                 // Lock the Mutex and send the data
                 let signal_tx_lock = signal_tx_clone1.lock().await;
                 if let Err(e) = signal_tx_lock.send(signal_data).await {
-                    eprintln!("Error sending signal data: {}", e);
+                    error!(error = %e, "Error sending signal data");
                 }
             }
         }
-    });
+        .instrument(info_span!("ws_feed"))
+    }));
 
     // get signal
-    tokio::spawn(async move {
-        get_signals(signal_rx, signal_result_tx).await;
+    tokio::spawn(until_shutdown(shutdown.clone(), {
+        let bot_params = Arc::clone(&bot_params);
+        async move {
+            get_signals(signal_rx, signal_result_tx, bot_params).await;
+        }
+        .instrument(info_span!("signals"))
+    }));
+
+    // Control API (pause/resume, dry run, closes, risk and signal weights, status)
+    if let Some(token) = config.control_token.clone() {
+        let state = ControlState {
+            api_url: api_url.to_string().into(),
+            bot_params: Arc::clone(&bot_params),
+            token: token.into(),
+            execution_model: config.execution_model,
+            paper_fee_rate: config.paper_fee_rate,
+            closed_trade_tx: closed_trade_tx.clone(),
+        };
+        let addr = config.control_addr.clone();
+        tokio::spawn(until_shutdown(shutdown.clone(), async move {
+            info!(%addr, "Control API: http://{}", addr);
+            if let Err(e) = serve_http(&addr, control_router(state)).await {
+                error!(%addr, error = %e, "Error serving control API");
+            }
+        }.instrument(info_span!("control_api"))));
+    }
+
+    // Terminal dashboard, exits on q or Ctrl+C (raw mode swallows SIGINT)
+    let dashboard_stop = Arc::new(AtomicBool::new(false));
+    let dashboard = config.tui.then(|| {
+        let api_url: Arc<str> = api_url.to_string().into();
+        let bot_params = Arc::clone(&bot_params);
+        let closed_trade_tx = closed_trade_tx.clone();
+        let handle = tokio::runtime::Handle::current();
+        let stop = Arc::clone(&dashboard_stop);
+        tokio::task::spawn_blocking(move || run_dashboard(api_url, bot_params, closed_trade_tx, handle, stop))
     });
 
-    // process signal (log signal & create trade)
-    tokio::spawn({
+    // process signal (log signal & create trade), returns the aborted order requests
+    let signals_handle = tokio::spawn({
         let bot_params = Arc::clone(&bot_params);
         let api_url = Arc::clone(&api_url).to_string().into();
+        let shutdown = shutdown.clone();
         async move {
             process_signals(
                 signal_result_rx,
+                closed_trade_rx,
                 api_url,
                 bot_params,
-                config.trade_gap_seconds,
-                config.risk_per_trade_percent,
-                config.risk_to_reward_ratio,
-                config.risk_to_loss_ratio,
+                SignalProcessingSettings {
+                    trade_gap_seconds: config.trade_gap_seconds,
+                    scale_out_targets: config.scale_out_targets,
+                    emulate_partial_closes: config.emulate_partial_closes,
+                    signal_conditioning: config.signal_conditioning,
+                    candle_seconds: config.timeframe.duration().as_secs(),
+                    trade_journal_path: config.trade_journal_path,
+                    order_timeout: config.shutdown_timeout,
+                },
+                shutdown,
             )
-            .await;
+            .await
         }
+        .instrument(info_span!("execution"))
     });
-    
-    // TO DO: revalidate running trades on interval
 
-    signal::ctrl_c().await.expect("failed to listen for shutdown event");
-    println!("{}", "");
-    println!("Ctrl+C received, bot shutdown...");
+    // persist the state on interval for crash recovery (not for replays, the state of the live bot is kept)
+    if !is_replaying() {
+        tokio::spawn(until_shutdown(
+            shutdown.clone(),
+            persist_state(Arc::clone(&bot_params), config.state_path.clone(), config.position_check_interval),
+        ));
+    }
+
+    // revalidate running trades and position groups on interval (trades of the account, not for replays)
+    let positions_handle = (!is_replaying()).then(|| tokio::spawn({
+        let bot_params = Arc::clone(&bot_params);
+        let api_url: Arc<str> = api_url.to_string().into();
+        let closed_trade_tx = closed_trade_tx.clone();
+        let shutdown = shutdown.clone();
+        async move {
+            manage_positions(api_url, bot_params, config.position_check_interval, config.margin_policy, closed_trade_tx, shutdown).await;
+        }
+        .instrument(info_span!("execution"))
+    }));
+
+    // Runs until Ctrl+C, the dashboard exits or the replay finished
+    let dashboard_exit = async {
+        match dashboard {
+            Some(dashboard) => {
+                if let Ok(Err(e)) = dashboard.await {
+                    error!(error = %e, "Error running dashboard");
+                }
+            }
+            None => std::future::pending().await,
+        }
+    };
+    tokio::select! {
+        result = signal::ctrl_c() => result.expect("failed to listen for shutdown event"),
+        _ = dashboard_exit => {}
+        Ok(()) = replay_done_rx => {}
+    }
+    info!("Shutdown requested, stopping tasks...");
 
+    // Stop accepting signals, stop the position checks and all other tasks
+    shutdown_trigger.trigger();
+    dashboard_stop.store(true, Ordering::Relaxed);
     let _ = shutdown_tx.send(()).await;
 
     // Wait for the WebSocket task to finish
     handle.await.expect("Error shutting down the trading bot.");
-    println!("Bot stopped successfully.")
+
+    // Wait for the recorded frames to be written
+    if let Some(recorder) = recorder {
+        match recorder.await {
+            Ok(frames) => info!(frames, "Session recorded"),
+            Err(e) => error!(error = %e, "Error finishing session recording"),
+        }
+    }
+
+    // Wait for the position checks and the order requests in flight (up to the shutdown timeout)
+    if let Some(positions_handle) = positions_handle {
+        if let Err(e) = positions_handle.await {
+            error!(error = %e, "Error stopping position checks");
+        }
+    }
+    let aborted_orders = signals_handle.await.unwrap_or_else(|e| {
+        error!(error = %e, "Error stopping signal processing");
+        0
+    });
+
+    // Exit policy (leave, cancel open orders or close all trades)
+    let exit = apply_exit_policy(config.exit_policy, &api_url, &bot_params, &closed_trade_tx).await;
+
+    // Persist the state
+    let (state, open_positions) = {
+        let bot_params = bot_params.lock().await;
+        let open_positions = match &bot_params.paper_exchange {
+            Some(exchange) if bot_params.runtime.dry_run => exchange.positions.len(),
+            _ => bot_params.running_trades.len(),
+        };
+        (BotState::capture(&bot_params), open_positions)
+    };
+    let mut errors = exit.errors;
+    // Replays keep the state of the live bot
+    let state_saved = !is_replaying() && match save_state(&config.state_path, &state) {
+        Ok(()) => true,
+        Err(e) => {
+            errors.push(format!("Error saving state to {}: {}", config.state_path, e));
+            false
+        }
+    };
+
+    log_shutdown_summary(&ShutdownSummary {
+        uptime_seconds: started.elapsed().as_secs(),
+        trades_opened: metrics().trades_opened.get(),
+        realized_pl_sats: metrics().realized_pl_sats.get() + exit.closed_pl,
+        aborted_orders,
+        exit_policy: config.exit_policy,
+        canceled_orders: exit.canceled.len(),
+        closed_trades: exit.closed.len(),
+        open_positions,
+        state_saved,
+        errors,
+    });
+    info!("Bot stopped successfully.")
 }
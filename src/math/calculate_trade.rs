@@ -1,6 +1,7 @@
 // src/utils/calculate_trade.rs
 
 use crate::futures::get_market::FuturesMarket;
+use crate::futures::trade::Side;
 
 pub struct TradeParams {
     pub margin_sats: f64,
@@ -10,7 +11,7 @@ pub struct TradeParams {
 }
 
 pub fn calculate_trade_params(
-    trade_type: &str,
+    side: Side,
     entry_price: f64,
     leverage: u64,
     trade_quantity: f64,
@@ -34,18 +35,17 @@ pub fn calculate_trade_params(
         .ok_or("No matching fee tier found")?;
 
     // Liquidation Price
-    let liquidation_price = match trade_type {
-        "b" => {
+    let liquidation_price = match side {
+        Side::Buy => {
             // for Buy (Long): Liquidation Price = 1 / (1 / Entry Price + Trade Margin / Quantity)
             let inverse_liquidation = (1.0 / entry_price) + (margin / trade_quantity);
             1.0 / inverse_liquidation
         }
-        "s" => {
+        Side::Sell => {
             // for Sell (Short): Liquidation Price = 1 / (1 / Entry Price - Trade Margin / Quantity)
             let inverse_liquidation = (1.0 / entry_price) - (margin / trade_quantity);
             1.0 / inverse_liquidation
         }
-        _ => return Err("Invalid trade type, expected 'b' for Buy or 's' for Sell".to_string()),
     };
 
     let opening_fee_reserved = (trade_quantity / entry_price) * trading_fee_rate;
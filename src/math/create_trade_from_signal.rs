@@ -1,45 +1,74 @@
-// src/futures/create_trade.rs
+// src/math/create_trade_from_signal.rs
 
-use crate::futures::create_trade::{create_market_buy_order, create_market_sell_order};
-use crate::futures::ticker::get_futures_ticker;
+use tracing::error;
+use crate::futures::create_trade::{create_market_order, Sizing};
 use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Side, TradeStatus};
 use crate::math::get_stoploss_takeprofit::calculate_stoploss_takeprofit;
 use crate::math::get_trade_quantity::calculate_trade_quantity;
 use crate::math::calculate_trade::calculate_trade_params;
-use crate::utils::get_user::get_user;
+use crate::math::get_scale_out_targets::calculate_scale_out_targets;
+use crate::utils::filter_signals::SkipReason;
+use crate::utils::api_cache::{api_cache, get_futures_ticker_cached, get_user_cached};
 use crate::utils::init_bot_params::BotParams;
-use crate::utils::log_bot_params::log_forecast_trade;
+use crate::utils::get_timestamps::get_current_time_ms;
+use crate::utils::log_bot_params::{log_forecast_trade, log_scale_out_targets, log_signal_explanation};
+use crate::utils::position_group::{PositionChild, PositionGroup};
+use crate::utils::trade_journal::{append_trade_journal, TradeJournalEntry};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use super::get_indicators::Indicators;
-use super::get_signals::Signal;
+use super::get_signals::{Signal, SignalExplanation};
 
 pub enum CreateTradeResult {
     TradeCreated,
-    NoTradeCreated(String)
+    NoTradeCreated(SkipReason)
+}
+
+/// Sizing, exit and journal settings of the trades created from signals (live and paper).
+#[derive(Debug, Clone)]
+pub struct TradeSettings {
+    pub leverage: u64,                   // Leverage of the trades
+    pub risk_per_trade_percent: f64,     // Risk handling for trade quantity
+    pub risk_to_reward_ratio: f64,       // Risk handling for takeprofit
+    pub risk_to_loss_ratio: f64,         // Risk handling for stoploss
+    pub scale_out_targets: Vec<f64>,     // Take-profit targets in multiples of the stoploss distance (R), one child trade per target
+    pub emulate_partial_closes: bool,    // Close child trades from the bot when their target is hit instead of server-side takeprofits
+    pub trade_journal_path: String,      // File the created trades are persisted to with their signal breakdown
 }
 
 // Function to create a trade based on the signal
 pub async fn create_trade_from_signal(
     signal: Signal,
+    explanation: Option<SignalExplanation>,
     api_url: &str,
     bot_params: Arc<Mutex<BotParams>>,
     indicators: Option<Indicators>,
-    leverage: Option<u64>,
-    risk_per_trade_percent: f64,
-    risk_to_reward_ratio: f64,
-    risk_to_loss_ratio: f64
+    settings: &TradeSettings,
 ) -> Result<CreateTradeResult, String> {
 
-    let leverage = leverage.unwrap_or(20);
-    let bot_params = bot_params.lock().await;
-    let max_trades = bot_params.market_data.as_ref().unwrap().limits.count.max;
-    //let max_trades = 25;
+    let TradeSettings {
+        leverage,
+        risk_per_trade_percent,
+        risk_to_reward_ratio,
+        risk_to_loss_ratio,
+        ref scale_out_targets,
+        emulate_partial_closes,
+        ref trade_journal_path,
+    } = *settings;
+    // Copy the market data out, the lock is not held during the requests
+    let futures_market = bot_params
+        .lock()
+        .await
+        .market_data
+        .clone()
+        .ok_or("Market data is not available")?;
+    let max_trades = futures_market.limits.count.max;
 
     // Fetch running trades and check if trade count limit is reached
     let trade_params = GetTradesParams {
-        r#type: "running",
+        r#type: TradeStatus::Running,
         from: None,
         to: None,
         limit: None,
@@ -47,49 +76,39 @@ pub async fn create_trade_from_signal(
     let active_trades = match get_trades(api_url, Some(trade_params)).await {
         Ok(trades) => trades,
         Err(e) => {
-            return Ok(CreateTradeResult::NoTradeCreated(format!(
+            return Ok(CreateTradeResult::NoTradeCreated(SkipReason::Error(format!(
                 "Error fetching active trades: {}",
                 e
-            )))
+            ))))
         }
     };
     if active_trades.len() >= max_trades as usize {
-        return Ok(CreateTradeResult::NoTradeCreated(
-            "Limit reached".to_string(),
-        ));
+        return Ok(CreateTradeResult::NoTradeCreated(SkipReason::TradeLimit { max: max_trades }));
     }
 
-    // Fetch user and futures ticker data
-    let user_data = match get_user(api_url).await {
+    // Fetch user and futures ticker data (cached, bursts of signals share the requests)
+    let user_data = match get_user_cached(api_url).await {
         Ok(user) => user,
         Err(e) => return Err(format!("Error fetching user data: {}", e)),
     };
-    let ticker = match get_futures_ticker(api_url).await {
+    let ticker = match get_futures_ticker_cached(api_url).await {
         Ok(ticker) => ticker,
         Err(e) => return Err(format!("Error fetching futures ticker: {}", e)),
     };
 
     // set entry_price and exit_price
-    let (entry_p, trade_type) = match signal {
-        Signal::Buy | Signal::StrongBuy => (ticker.ask_price, "b"),
-        Signal::Sell | Signal::StrongSell => (ticker.bid_price, "s"),
-        Signal::Hold => {
-            return Ok(CreateTradeResult::NoTradeCreated(
-                "Hold signal received on create_trade".to_string(),
-            ));
-        }
-        _ => {
-            return Ok(CreateTradeResult::NoTradeCreated(
-                "No valid trading signal".to_string(),
-            ));
+    let (entry_p, side) = match signal {
+        Signal::Buy | Signal::StrongBuy => (ticker.ask_price, Side::Buy),
+        Signal::Sell | Signal::StrongSell => (ticker.bid_price, Side::Sell),
+        Signal::Hold | Signal::Undefined => {
+            return Ok(CreateTradeResult::NoTradeCreated(SkipReason::InvalidSignal(signal)));
         }
     };
-    
-    // futures market data (eg for fees)
-    let futures_market = bot_params
-        .market_data
-        .as_ref()
-        .ok_or("Market data is not available")?;
+
+    // The ATR sizes the stoploss, no trade before the indicators are warmed up
+    let Some(atr) = indicators.as_ref().and_then(|i| i.atr()) else {
+        return Ok(CreateTradeResult::NoTradeCreated(SkipReason::MissingIndicators));
+    };
 
     // calculate quantity for trade
     let quantity = match calculate_trade_quantity(
@@ -98,22 +117,19 @@ pub async fn create_trade_from_signal(
         risk_per_trade_percent,
         max_trades,
         leverage as f64,
-        indicators.as_ref().and_then(|i| i.atr),
-        futures_market,
+        Some(atr),
+        &futures_market,
     ) {
-        Ok(final_quantity) => Some(final_quantity as u64),
+        Ok(final_quantity) => final_quantity as u64,
         Err(e) => return Err(format!("Error calculating trade quantity: {}", e)),
     };
-    if quantity.is_none() {
-        return Err("Quantity calculation failed".to_string());
-    }   
 
     // calculate takeprofit and stoploss for trade
     let (takeprofit, stoploss) = match calculate_stoploss_takeprofit(
         entry_p,
-        indicators.as_ref().and_then(|i| i.atr).unwrap(),
+        atr,
         leverage as f64,
-        trade_type == "b",
+        side.is_buy(),
         risk_to_reward_ratio,
         risk_to_loss_ratio
     ) {
@@ -121,65 +137,137 @@ pub async fn create_trade_from_signal(
         Err(e) => return Err(format!("Error calculating stoploss/takeprofit: {}", e)),
     };
 
-    let trade_params = calculate_trade_params(trade_type, entry_p, leverage, quantity.map(|q| q as f64).unwrap_or(1.0), futures_market)
+    let trade_params = calculate_trade_params(side, entry_p, leverage, quantity as f64, &futures_market)
         .map_err(|e| format!("Error calculating trade parameters: {}", e))?;
 
+    // No order is sent if the balance does not cover the margin
+    if !is_balance_sufficient(user_data.balance, trade_params.margin_sats) {
+        return Ok(CreateTradeResult::NoTradeCreated(SkipReason::Balance));
+    }
+
     log_forecast_trade(
         entry_p,
         takeprofit,
         stoploss,
         &trade_params
     );
+    if let Some(explanation) = &explanation {
+        log_signal_explanation(explanation);
+    }
 
-    // Execute trade based on the signal
-    match signal {
-        Signal::Buy | Signal::StrongBuy => {
-            is_balance_sufficient(user_data.balance, trade_params.margin_sats)?;
-            create_market_buy_order(
-                api_url,
-                leverage,
-                quantity,
-                None,
-                takeprofit,
-                stoploss,
-            )
-            .await
-            .map_err(|e| format!("Error creating buy order: {}", e))?;
-
-            return Ok(CreateTradeResult::TradeCreated);
+    // Journal entry of the created trade(s), persisted with the signal breakdown
+    let journal_entry = |trade_ids: Vec<String>, group_id: Option<String>, quantity: u64, takeprofit: Option<u64>| TradeJournalEntry {
+        time: get_current_time_ms(),
+        trade_ids,
+        group_id,
+        side,
+        quantity,
+        leverage,
+        entry_price: entry_p,
+        takeprofit,
+        stoploss,
+        explanation: explanation.clone(),
+    };
+
+    // Scale-out: split the position into child trades with staggered takeprofits and a shared stoploss
+    // (a single target is a group with one child)
+    if !scale_out_targets.is_empty() {
+        // The stoploss is the risk unit of the targets
+        let stoploss_price = stoploss.ok_or_else(|| "Stoploss is required for scale-out targets".to_string())? as f64;
+        let targets = calculate_scale_out_targets(
+            entry_p,
+            stoploss_price,
+            side.is_buy(),
+            quantity,
+            scale_out_targets,
+        )
+        .map_err(|e| format!("Error calculating scale-out targets: {}", e))?;
+
+        if active_trades.len() + targets.len() > max_trades as usize {
+            return Ok(CreateTradeResult::NoTradeCreated(SkipReason::TradeLimit { max: max_trades }));
         }
-        Signal::Sell | Signal::StrongSell => {
-            is_balance_sufficient(user_data.balance, trade_params.margin_sats)?;
-            create_market_sell_order(
-                api_url,
-                leverage,
-                quantity,
-                None,
-                takeprofit,
-                stoploss,
-            )
-            .await
-            .map_err(|e| format!("Error creating sell order: {}", e))?;
-
-            return Ok(CreateTradeResult::TradeCreated);
+
+        log_scale_out_targets(&targets, stoploss_price, emulate_partial_closes);
+
+        let mut group = PositionGroup::new(side, entry_p, stoploss_price, emulate_partial_closes);
+        let mut error = None;
+        bot_params.lock().await.trade_ownership.begin_order();
+        for (i, target) in targets.iter().enumerate() {
+            // Emulated partial closes are handled by the position loop, no takeprofit on the server
+            let child_takeprofit = if emulate_partial_closes { None } else { Some(target.takeprofit as u64) };
+            match create_market_order(api_url, side, leverage, Sizing::Quantity(target.quantity), child_takeprofit, stoploss).await {
+                Ok(trade) => group.children.push(PositionChild {
+                    trade_id: trade.id,
+                    quantity: target.quantity,
+                    takeprofit: target.takeprofit,
+                    r_multiple: target.r_multiple,
+                    running: true,
+                    pl: 0.0,
+                    fees: trade.opening_fee,
+                }),
+                Err(e) => {
+                    error = Some(format!("Error creating child trade {}/{}: {}", i + 1, targets.len(), e));
+                    break;
+                }
+            }
         }
-        Signal::Hold => {
-            return Ok(CreateTradeResult::NoTradeCreated(
-                "Hold signal received on create_trade".to_string(),
-            ));
+
+        // Keep track of the children that were created, also if a later child failed
+        let trade_ids: Vec<String> = group.children.iter().map(|child| child.trade_id.clone()).collect();
+        let entry = journal_entry(
+            trade_ids.clone(),
+            Some(group.id.clone()),
+            group.quantity(),
+            targets.first().map(|target| target.takeprofit as u64),
+        );
+        {
+            let mut bot_params = bot_params.lock().await;
+            bot_params.trade_ownership.end_order(trade_ids);
+            if !group.children.is_empty() {
+                bot_params.position_groups.push(group);
+            }
         }
-        Signal::Undefined => {
-            return Ok(CreateTradeResult::NoTradeCreated(format!(
-                "{:?} signal received on create_trade",
-                signal
-            )));
+        if !entry.trade_ids.is_empty() {
+            record_trade(trade_journal_path, &entry);
+            // The balance changed, the next signal fetches the user data again
+            api_cache().user.invalidate().await;
         }
+        return match error {
+            Some(error) => Err(error),
+            None => Ok(CreateTradeResult::TradeCreated),
+        };
     }
+
+    // Execute trade based on the signal
+    bot_params.lock().await.trade_ownership.begin_order();
+    let result = create_market_order(
+        api_url,
+        side,
+        leverage,
+        Sizing::Quantity(quantity),
+        takeprofit,
+        stoploss,
+    )
+    .await
+    .map_err(|e| format!("Error creating {} order: {}", side.to_string().to_lowercase(), e));
+    bot_params
+        .lock()
+        .await
+        .trade_ownership
+        .end_order(result.as_ref().ok().map(|trade| trade.id.clone()));
+    let trade = result?;
+
+    record_trade(trade_journal_path, &journal_entry(vec![trade.id], None, quantity, takeprofit));
+    api_cache().user.invalidate().await;
+    Ok(CreateTradeResult::TradeCreated)
 }
 
-fn is_balance_sufficient(user_balance: f64, required_margin: f64) -> Result<(), String> {
-    if user_balance <= required_margin {
-        return Err("Insufficient balance for creating a trade".to_string());
+fn is_balance_sufficient(user_balance: f64, required_margin: f64) -> bool {
+    user_balance > required_margin
+}
+// Persists a created trade, a failing journal must not fail the trade
+fn record_trade(trade_journal_path: &str, entry: &TradeJournalEntry) {
+    if let Err(e) = append_trade_journal(trade_journal_path, entry) {
+        error!(error = %e, "Error writing trade journal");
     }
-    Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,99 @@
+// src/math/get_basis.rs
+
+use crate::futures::get_index_history::IndexHistoryEntry;
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+
+/// Statistics of the basis (futures price - index) over a rolling window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BasisStats {
+    pub index: f64,          // Latest index value
+    pub basis: f64,          // Latest basis: last close - latest index
+    pub mean: f64,           // Mean basis over the window
+    pub std_dev: f64,        // Standard deviation of the basis over the window
+    pub z_score: f64,        // Z-score of the latest basis
+    pub samples: usize,      // Number of basis samples in the window
+}
+
+impl BasisStats {
+    /// Z-score of the basis at the given futures price against the latest index.
+    pub fn z_score_at(&self, last_price: f64) -> f64 {
+        (last_price - self.index - self.mean) / self.std_dev
+    }
+}
+
+/// Calculates the basis history by matching every OHLC close with the nearest index value.
+///
+/// # Parameters
+/// - `ohlc_data`: The OHLC history of the futures price.
+/// - `index_data`: The index history (any order).
+///
+/// # Returns
+/// - The basis (close - index) per candle, oldest first. Empty if there is no index data.
+pub fn calculate_basis_history(ohlc_data: &[OhlcHistoryEntry], index_data: &[IndexHistoryEntry]) -> Vec<f64> {
+    if index_data.is_empty() {
+        return Vec::new();
+    }
+
+    let mut index_sorted: Vec<&IndexHistoryEntry> = index_data.iter().collect();
+    index_sorted.sort_by_key(|entry| entry.time);
+
+    let mut ohlc_sorted: Vec<&OhlcHistoryEntry> = ohlc_data.iter().collect();
+    ohlc_sorted.sort_by_key(|entry| entry.time);
+
+    ohlc_sorted
+        .iter()
+        .map(|candle| {
+            let position = index_sorted.partition_point(|entry| entry.time < candle.time);
+            let nearest = [position.checked_sub(1), Some(position)]
+                .into_iter()
+                .flatten()
+                .filter_map(|i| index_sorted.get(i))
+                .min_by_key(|entry| (entry.time - candle.time).abs())
+                .expect("index data is not empty");
+            candle.close - nearest.value
+        })
+        .collect()
+}
+
+/// Calculates the basis statistics over the last `window` candles.
+///
+/// # Parameters
+/// - `ohlc_data`: The OHLC history of the futures price.
+/// - `index_data`: The index history.
+/// - `window`: Number of basis samples for the mean and standard deviation.
+/// - `current_index`: Most recent index value (e.g. from the futures ticker), defaults to the latest index history entry.
+///
+/// # Returns
+/// - `Some(BasisStats)`, or `None` if there are fewer than 2 samples or the basis did not vary.
+pub fn calculate_basis_stats(
+    ohlc_data: &[OhlcHistoryEntry],
+    index_data: &[IndexHistoryEntry],
+    window: usize,
+    current_index: Option<f64>,
+) -> Option<BasisStats> {
+    let basis_history = calculate_basis_history(ohlc_data, index_data);
+    let samples = &basis_history[basis_history.len().saturating_sub(window)..];
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    let variance = samples.iter().map(|basis| (basis - mean).powi(2)).sum::<f64>() / samples.len() as f64;
+    let std_dev = variance.sqrt();
+    if std_dev == 0.0 {
+        return None;
+    }
+
+    let last_close = ohlc_data.iter().max_by_key(|entry| entry.time)?.close;
+    let index = current_index.or_else(|| index_data.iter().max_by_key(|entry| entry.time).map(|entry| entry.value))?;
+    let basis = last_close - index;
+
+    Some(BasisStats {
+        index,
+        basis,
+        mean,
+        std_dev,
+        z_score: (basis - mean) / std_dev,
+        samples: samples.len(),
+    })
+}
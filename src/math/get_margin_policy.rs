@@ -0,0 +1,68 @@
+// src/math/get_margin_policy.rs
+
+/// Calculates the distance between the current price and the liquidation price,
+/// relative to the current price (e.g. 0.02 for 2%). The distance is negative if the price
+/// is already beyond the liquidation price (below for buys, above for sells).
+///
+/// # Parameters
+/// - `last_price`: The current price.
+/// - `liquidation`: The liquidation price of the trade.
+/// - `is_buy`: Whether the trade is a buy (true) or sell (false) trade.
+///
+/// # Returns
+/// - The signed relative distance to liquidation, or `None` if the prices are invalid.
+pub fn calculate_liquidation_distance(last_price: f64, liquidation: f64, is_buy: bool) -> Option<f64> {
+    if last_price <= 0.0 || liquidation <= 0.0 {
+        return None;
+    }
+    let distance = if is_buy { last_price - liquidation } else { liquidation - last_price };
+    Some(distance / last_price)
+}
+
+/// Decides whether a running trade should get a margin top up.
+/// Margin is added when the price comes within `topup_distance_percent` of the liquidation price
+/// and the balance covers the top up. A trade already beyond its liquidation price is lost and
+/// gets no margin.
+///
+/// # Parameters
+/// - `last_price`: The current price.
+/// - `liquidation`: The liquidation price of the trade.
+/// - `is_buy`: Whether the trade is a buy (true) or sell (false) trade.
+/// - `topup_distance_percent`: Distance to liquidation that triggers a top up (e.g. 0.02 for 2%).
+/// - `topup_sats`: Margin to add per top up in sats.
+/// - `balance_sats`: The available balance in sats.
+///
+/// # Returns
+/// - The margin to add in sats, or `None` if no top up is needed or the balance is insufficient.
+pub fn calculate_margin_topup(
+    last_price: f64,
+    liquidation: f64,
+    is_buy: bool,
+    topup_distance_percent: f64,
+    topup_sats: u64,
+    balance_sats: f64,
+) -> Option<u64> {
+    let distance = calculate_liquidation_distance(last_price, liquidation, is_buy)?;
+    if distance <= 0.0 || distance > topup_distance_percent || topup_sats == 0 {
+        return None;
+    }
+    if balance_sats <= topup_sats as f64 {
+        return None;
+    }
+    Some(topup_sats)
+}
+
+/// Decides how much unrealized profit of a running trade should be cashed in.
+///
+/// # Parameters
+/// - `pl`: Unrealized profit or loss of the trade in sats.
+/// - `threshold_sats`: Profit that must be exceeded before cashing in.
+///
+/// # Returns
+/// - The profit to cash in (in sats), or `None` if the profit is below the threshold.
+pub fn calculate_cash_in_amount(pl: f64, threshold_sats: f64) -> Option<u64> {
+    if pl <= 0.0 || pl <= threshold_sats {
+        return None;
+    }
+    Some(pl.floor() as u64)
+}
@@ -0,0 +1,127 @@
+// src/math/get_market_regime.rs
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::config::RegimeSettings;
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use super::price_indicators::{calculate_adx_ohlc, calculate_atr, calculate_bollinger_bands, calculate_exponential_moving_average};
+
+/// Market regime derived from trend strength and volatility.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarketRegime {
+    TrendingUp,
+    TrendingDown,
+    Ranging,
+    HighVolatility,
+}
+
+impl fmt::Display for MarketRegime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarketRegime::TrendingUp => write!(f, "Trending Up"),
+            MarketRegime::TrendingDown => write!(f, "Trending Down"),
+            MarketRegime::Ranging => write!(f, "Ranging"),
+            MarketRegime::HighVolatility => write!(f, "High Volatility"),
+        }
+    }
+}
+
+/// Indicators the market regime is classified from.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RegimeIndicators {
+    pub adx: Option<f64>,            // Average directional index (trend strength, 0-100)
+    pub ema_slope: Option<f64>,      // Relative EMA change per candle (e.g. 0.001 for +0.1%)
+    pub atr_percent: Option<f64>,    // ATR relative to the last close
+    pub bb_bandwidth: Option<f64>,   // Bollinger bandwidth: (upper - lower) / middle
+}
+
+/// Classifies the market regime.
+/// High volatility takes precedence over trends. A market is trending if the EMA slope exceeds the
+/// threshold and, if available, the ADX confirms the trend strength. Everything else is ranging.
+///
+/// # Parameters
+/// - `indicators`: The regime indicators.
+/// - `settings`: Thresholds for the classification.
+///
+/// # Returns
+/// - The `MarketRegime`, or `None` if neither the EMA slope nor the volatility could be calculated.
+pub fn classify_market_regime(indicators: &RegimeIndicators, settings: &RegimeSettings) -> Option<MarketRegime> {
+    let high_atr = indicators.atr_percent.is_some_and(|atr_percent| atr_percent > settings.high_volatility_atr_percent);
+    let wide_bands = indicators.bb_bandwidth.is_some_and(|bandwidth| bandwidth > settings.high_volatility_bandwidth);
+    if high_atr || wide_bands {
+        return Some(MarketRegime::HighVolatility);
+    }
+
+    let ema_slope = match indicators.ema_slope {
+        Some(ema_slope) => ema_slope,
+        None if indicators.atr_percent.is_some() || indicators.bb_bandwidth.is_some() => return Some(MarketRegime::Ranging),
+        None => return None,
+    };
+
+    let trend_confirmed = indicators.adx.is_none_or(|adx| adx >= settings.adx_trend_threshold);
+    if trend_confirmed && ema_slope >= settings.ema_slope_threshold {
+        Some(MarketRegime::TrendingUp)
+    } else if trend_confirmed && ema_slope <= -settings.ema_slope_threshold {
+        Some(MarketRegime::TrendingDown)
+    } else {
+        Some(MarketRegime::Ranging)
+    }
+}
+
+/// Calculates the regime indicators from OHLC data (oldest first).
+///
+/// # Parameters
+/// - `ohlc_data`: The OHLC history.
+/// - `ema_period`: Period of the EMA the slope is measured on.
+/// - `bb_period`: Period for the Bollinger Bands.
+/// - `bb_std_dev_multiplier`: Multiplier for the standard deviation of the Bollinger Bands.
+/// - `atr_period`: Period for the ATR.
+/// - `settings`: ADX period and EMA slope lookback.
+pub fn calculate_regime_indicators(
+    ohlc_data: &[OhlcHistoryEntry],
+    ema_period: usize,
+    bb_period: usize,
+    bb_std_dev_multiplier: f64,
+    atr_period: usize,
+    settings: &RegimeSettings,
+) -> RegimeIndicators {
+    let closes: Vec<f64> = ohlc_data.iter().map(|entry| entry.close).collect();
+    let last_close = closes.last().copied().filter(|close| *close > 0.0);
+
+    let adx = calculate_adx_ohlc(ohlc_data, settings.adx_period);
+
+    // EMA slope: relative change of the EMA over the lookback, per candle
+    let lookback = settings.ema_slope_lookback.max(1);
+    let ema_slope = if closes.len() > lookback {
+        let ema_now = calculate_exponential_moving_average(&closes, ema_period);
+        let ema_before = calculate_exponential_moving_average(&closes[..closes.len() - lookback], ema_period);
+        match (ema_now, ema_before) {
+            (Some(now), Some(before)) if before > 0.0 => Some((now - before) / before / lookback as f64),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    // ATR of the most recent candles relative to the last close
+    let atr_percent = if ohlc_data.len() > atr_period {
+        let recent = &ohlc_data[ohlc_data.len() - atr_period - 1..];
+        let highs: Vec<f64> = recent.iter().map(|entry| entry.high).collect();
+        let lows: Vec<f64> = recent.iter().map(|entry| entry.low).collect();
+        let recent_closes: Vec<f64> = recent.iter().map(|entry| entry.close).collect();
+        calculate_atr(&highs, &lows, &recent_closes, atr_period).zip(last_close).map(|(atr, close)| atr / close)
+    } else {
+        None
+    };
+
+    let bb_bandwidth = calculate_bollinger_bands(&closes, bb_period, bb_std_dev_multiplier)
+        .filter(|(_, middle, _)| *middle > 0.0)
+        .map(|(lower, middle, upper)| (upper - lower) / middle);
+
+    RegimeIndicators {
+        adx,
+        ema_slope,
+        atr_percent,
+        bb_bandwidth,
+    }
+}
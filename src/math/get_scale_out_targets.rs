@@ -0,0 +1,86 @@
+// src/math/get_scale_out_targets.rs
+
+/// A single exit target of a scaled-out position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleOutTarget {
+    pub quantity: u64,      // Quantity (USD) closed at this target
+    pub takeprofit: f64,    // Take-profit price of the target
+    pub r_multiple: f64,    // Distance to entry in multiples of the stoploss distance (R)
+}
+
+/// Splits the intended trade quantity into staggered take-profit targets that share one stoploss.
+/// The risk unit R is the distance between entry and stoploss, so a target of `2.0` is placed
+/// twice as far from the entry as the stoploss (e.g. 1R, 2R, 3R).
+///
+/// # Parameters
+/// - `entry_price`: The entry price of the trade.
+/// - `stoploss`: The shared stoploss price of all targets.
+/// - `is_buy`: Whether the trade is a buy (true) or sell (false) trade.
+/// - `total_quantity`: The quantity (USD) of the whole position.
+/// - `r_multiples`: The targets in multiples of R (e.g. `[1.0, 2.0, 3.0]`), sorted nearest first.
+///
+/// # Returns
+/// - A vector of `ScaleOutTarget`, one per child trade. The quantity is split evenly, the remainder
+///   is added to the nearest target. If the quantity is too small to give every target at least 1 USD,
+///   only the nearest targets are kept.
+///
+/// # Errors
+/// - Returns an error if no targets are given, a target is not positive, the stoploss is on the
+///   wrong side of the entry, or the quantity is zero.
+pub fn calculate_scale_out_targets(
+    entry_price: f64,
+    stoploss: f64,
+    is_buy: bool,
+    total_quantity: u64,
+    r_multiples: &[f64],
+) -> Result<Vec<ScaleOutTarget>, String> {
+    if r_multiples.is_empty() {
+        return Err("At least one scale-out target is required.".to_string());
+    }
+    if r_multiples.iter().any(|r| r.is_nan() || *r <= 0.0) {
+        return Err("Scale-out targets must be greater than 0.".to_string());
+    }
+    // Nearest first, so a small quantity keeps the nearest targets
+    let mut r_multiples = r_multiples.to_vec();
+    r_multiples.sort_by(f64::total_cmp);
+    if total_quantity == 0 {
+        return Err("Quantity must be greater than 0.".to_string());
+    }
+
+    // Risk unit (R): distance between entry and stoploss
+    let risk_unit = if is_buy {
+        entry_price - stoploss
+    } else {
+        stoploss - entry_price
+    };
+    if risk_unit <= 0.0 {
+        return Err("Stoploss must be below the entry for buys and above the entry for sells.".to_string());
+    }
+
+    // Every child trade needs at least 1 USD (market minimum)
+    let target_count = r_multiples.len().min(total_quantity as usize);
+    let base_quantity = total_quantity / target_count as u64;
+    let remainder = total_quantity % target_count as u64;
+
+    let targets = r_multiples
+        .iter()
+        .take(target_count)
+        .enumerate()
+        .map(|(i, r_multiple)| {
+            let distance = risk_unit * r_multiple;
+            let takeprofit = if is_buy {
+                entry_price + distance // For buy, Take-Profit is above the entry price
+            } else {
+                entry_price - distance // For sell, Take-Profit is below the entry price
+            };
+
+            ScaleOutTarget {
+                quantity: if i == 0 { base_quantity + remainder } else { base_quantity },
+                takeprofit,
+                r_multiple: *r_multiple,
+            }
+        })
+        .collect();
+
+    Ok(targets)
+}
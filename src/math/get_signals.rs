@@ -1,9 +1,15 @@
 // src/math/get_signals.rs
 
-use crate::{config::load_signal_settings, utils::connect_ws::PriceData};
-use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+use crate::{config::{load_signal_settings, SignalSettings}, utils::connect_ws::PriceData};
+use crate::utils::init_bot_params::BotParams;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
 use super::get_indicators::Indicators;
-use colored::Colorize;
+use super::get_market_regime::MarketRegime;
+use super::indicator_set::{IndicatorSource, BOLLINGER_BANDS, EMA, MA, RSI};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Clone)]
 #[allow(dead_code)]
@@ -12,7 +18,7 @@ pub struct SignalData {
     pub indicators: Option<Indicators>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Signal {
     StrongSell,
     Sell,
@@ -22,26 +28,34 @@ pub enum Signal {
     Undefined,
 }
 
-impl Signal {
-    pub fn to_string(&self) -> String {
-        match self {
-            Signal::StrongSell => "Signal: Strong Sell 🚫".red().to_string(),
-            Signal::Sell => "Signal: Sell 🚩".red().to_string(),
-            Signal::Hold => "Signal: Hold".white().to_string(),
-            Signal::Buy => "Signal: Buy ✅".green().to_string(),
-            Signal::StrongBuy => "Signal: Strong Buy 💚".green().to_string(),
-            Signal::Undefined => "Signal: Undefined".yellow().to_string(),
-        }
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Signal::StrongSell => "Strong Sell",
+            Signal::Sell => "Sell",
+            Signal::Hold => "Hold",
+            Signal::Buy => "Buy",
+            Signal::StrongBuy => "Strong Buy",
+            Signal::Undefined => "Undefined",
+        };
+        write!(f, "{}", label)
     }
 }
 
 #[derive(Clone)]
 pub struct SignalResponse {
     pub signal: Signal,
+    pub explanation: SignalExplanation,  // Breakdown of the score the signal was derived from
     pub indicators: Option<Indicators>,
 }
 
-pub async fn get_signals(mut rx: mpsc::Receiver<SignalData>, tx: mpsc::Sender<SignalResponse>) {
+/// Derives a signal from every price or indicator update with the current signal settings of the bot.
+///
+/// # Parameters
+/// - `rx`: Price and indicator updates.
+/// - `tx`: Channel for the derived signals.
+/// - `bot_params`: Shared bot params holding the runtime signal settings.
+pub async fn get_signals(mut rx: mpsc::Receiver<SignalData>, tx: mpsc::Sender<SignalResponse>, bot_params: Arc<Mutex<BotParams>>) {
     let mut last_signal: Option<SignalData> = None;
 
     while let Some(signal_data) = rx.recv().await {
@@ -62,8 +76,9 @@ pub async fn get_signals(mut rx: mpsc::Receiver<SignalData>, tx: mpsc::Sender<Si
         last_signal = Some(updated_signal.clone());
 
         if let (Some(price_data), Some(indicators)) = (updated_signal.price_data, updated_signal.indicators) {
-            let signal_value = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
-            let signal = match signal_value {
+            let settings = bot_params.lock().await.runtime.signal_settings.clone();
+            let explanation = explain_signal(&price_data, &indicators, &settings);
+            let signal = match explanation.bucket {
                 -2 => Signal::StrongSell,
                 -1 => Signal::Sell,
                 0  => Signal::Hold,
@@ -74,171 +89,278 @@ pub async fn get_signals(mut rx: mpsc::Receiver<SignalData>, tx: mpsc::Sender<Si
 
             let signal_response = SignalResponse {
                 signal,
+                explanation,
                 indicators: Some(indicators),
             };
 
             if tx.send(signal_response).await.is_err() {
-                eprintln!("Error sending signal");
+                error!("Error sending signal");
             }
         }
     }
 }
 
-pub async fn calculate_ohlc_with_price_signal(price_data: &PriceData, indicators: &Indicators) -> i32 {
-    let mut signal = 0.0;
+/// Weighted contribution of a single indicator to the signal score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorContribution {
+    pub indicator: String,               // Indicator name (e.g. "rsi")
+    pub value: f64,                      // Raw indicator value
+    pub threshold: Option<f64>,          // Threshold the price or value crossed (None if no condition triggered)
+    pub condition: String,               // Condition that triggered (e.g. "rsi > 80")
+    pub weight: f64,                     // Weight of the indicator
+    pub contribution: f64,               // Weighted contribution to the score
+}
+
+/// Breakdown of a signal: per-indicator contributions, final score and bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalExplanation {
+    pub price: f64,                      // Price the signal was derived from
+    pub time: i64,                       // Timestamp (ms) of the price
+    pub contributions: Vec<IndicatorContribution>,
+    pub score: f64,                      // Sum of all contributions
+    pub bucket: i32,                     // Signal value (-2 strong sell to 2 strong buy)
+    pub regime: Option<MarketRegime>,    // Market regime the weights were selected for
+    pub invalid_reason: Option<String>,  // Reason if the data was invalid (results in Hold)
+}
+
+impl SignalExplanation {
+    fn invalid(price_data: &PriceData, reason: String) -> Self {
+        Self {
+            price: price_data.last_price,
+            time: price_data.time,
+            contributions: Vec::new(),
+            score: 0.0,
+            bucket: 0, // Hold Signal
+            regime: None,
+            invalid_reason: Some(reason),
+        }
+    }
+
+    /// Indicator with the largest absolute contribution.
+    pub fn main_driver(&self) -> Option<&IndicatorContribution> {
+        self.contributions
+            .iter()
+            .filter(|c| c.contribution != 0.0)
+            .max_by(|a, b| a.contribution.abs().total_cmp(&b.contribution.abs()))
+    }
+}
+
+// Adds a contribution: `factor` is the signal strength (-2 strong sell to 2 strong buy)
+fn add_contribution(
+    contributions: &mut Vec<IndicatorContribution>,
+    indicator: &str,
+    value: f64,
+    weight: f64,
+    factor: f64,
+    threshold: Option<f64>,
+    condition: &str,
+) {
+    contributions.push(IndicatorContribution {
+        indicator: indicator.to_string(),
+        value,
+        threshold,
+        condition: condition.to_string(),
+        weight,
+        contribution: weight * factor,
+    });
+}
+
+pub async fn calculate_ohlc_with_price_signal(price_data: &PriceData, indicators: &Indicators) -> SignalExplanation {
+    let settings = load_signal_settings().await;
+    explain_signal(price_data, indicators, &settings)
+}
+
+/// Scores the price against the indicators and logs the score (or why the signal is invalid).
+pub fn explain_signal(price_data: &PriceData, indicators: &Indicators, settings: &SignalSettings) -> SignalExplanation {
+    let explanation = calculate_signal_explanation(price_data, indicators, settings);
+
+    match &explanation.invalid_reason {
+        Some(reason) => info!(reason = %reason, "Invalid signal data"),
+        None => debug!(score = explanation.score, price = explanation.price, "Signal value"),
+    }
+
+    explanation
+}
 
+/// Scores the price against the indicators with the given signal settings.
+///
+/// # Parameters
+/// - `price_data`: The price the signal is derived from.
+/// - `indicators`: The indicators (OHLC based, market regime and basis).
+/// - `settings`: Weights, gap value and basis thresholds.
+///
+/// # Returns
+/// - The `SignalExplanation` with the per-indicator contributions, score and bucket.
+pub fn calculate_signal_explanation(price_data: &PriceData, indicators: &Indicators, settings: &SignalSettings) -> SignalExplanation {
     if price_data.last_price <= 0.0 {
-        println!("Invalid price_data.last_price: {}", price_data.last_price);
-        return 0; // Hold Signal
+        return SignalExplanation::invalid(price_data, format!("Invalid price_data.last_price: {}", price_data.last_price));
     }
-    
-    // Ungültige Indikatoren prüfen
-    if let Some(bollinger_bands) = indicators.ohlc_bollinger_bands {
+
+    // Signals are scored from the OHLC indicators of the registry
+    let bollinger_bands = indicators.values.bands(IndicatorSource::Ohlc, BOLLINGER_BANDS);
+    let rsi = indicators.values.value(IndicatorSource::Ohlc, RSI);
+    let ma = indicators.values.value(IndicatorSource::Ohlc, MA);
+    let ema = indicators.values.value(IndicatorSource::Ohlc, EMA);
+    let atr = indicators.atr();
+
+    // Check for invalid indicators
+    if let Some(bollinger_bands) = bollinger_bands {
         let (lower, middle, upper) = bollinger_bands;
         if lower < 0.0 || middle < 0.0 || upper < 0.0 {
-            println!("Invalid Bollinger Bands values: {:?}", bollinger_bands);
-            return 0; // Hold Signal
+            return SignalExplanation::invalid(price_data, format!("Invalid Bollinger Bands values: {:?}", bollinger_bands));
         }
     }
-    
-    if let Some(rsi) = indicators.ohlc_rsi {
-        if rsi < 0.0 || rsi > 100.0 {
-            println!("Invalid RSI value: {}", rsi);
-            return 0; // Hold Signal
+
+    if let Some(rsi) = rsi {
+        if !(0.0..=100.0).contains(&rsi) {
+            return SignalExplanation::invalid(price_data, format!("Invalid RSI value: {}", rsi));
         }
     }
-    
-    if let Some(ma) = indicators.ohlc_ma {
+
+    if let Some(ma) = ma {
         if ma < 0.0 {
-            println!("Invalid MA value: {}", ma);
-            return 0; // Hold Signal
+            return SignalExplanation::invalid(price_data, format!("Invalid MA value: {}", ma));
         }
     }
-    
-    if let Some(ema) = indicators.ohlc_ema {
+
+    if let Some(ema) = ema {
         if ema < 0.0 {
-            println!("Invalid EMA value: {}", ema);
-            return 0; // Hold Signal
+            return SignalExplanation::invalid(price_data, format!("Invalid EMA value: {}", ema));
         }
     }
-    
-    if let Some(atr) = indicators.atr {
+
+    if let Some(atr) = atr {
         if atr < 0.0 {
-            println!("Invalid ATR value: {}", atr);
-            return 0; // Hold Signal
+            return SignalExplanation::invalid(price_data, format!("Invalid ATR value: {}", atr));
         }
     }
 
-    let settings = load_signal_settings().await;
+    let weights = settings.weights_for(indicators.regime);
+    let price = price_data.last_price;
+    let mut contributions = Vec::new();
 
     // Bollinger Bands Check
-    if let Some(bollinger_bands) = indicators.ohlc_bollinger_bands {
-        let (lower, _, upper) = bollinger_bands;
-        if price_data.last_price > upper + settings.gap_value {  // Strong Sell Condition
-            signal += (settings.bollinger_weight * -2.0) as f64;  // Strong Sell Signal
-        } else if price_data.last_price < lower - settings.gap_value {  // Strong Buy Condition
-            signal += (settings.bollinger_weight * 2.0) as f64;  // Strong Buy Signal
-        } else if price_data.last_price > upper {
-            signal += (settings.bollinger_weight * -1.0) as f64;  // Sell Signal
-        } else if price_data.last_price < lower {
-            signal += (settings.bollinger_weight * 1.0) as f64;  // Buy Signal
+    if let Some(bollinger_bands) = bollinger_bands {
+        let (lower, middle, upper) = bollinger_bands;
+        let weight = weights.bollinger_weight;
+        if price > upper + settings.gap_value {  // Strong Sell Condition
+            add_contribution(&mut contributions, "bollinger_bands", middle, weight, -2.0, Some(upper + settings.gap_value), "price > upper band + gap");
+        } else if price < lower - settings.gap_value {  // Strong Buy Condition
+            add_contribution(&mut contributions, "bollinger_bands", middle, weight, 2.0, Some(lower - settings.gap_value), "price < lower band - gap");
+        } else if price > upper {  // Sell Signal
+            add_contribution(&mut contributions, "bollinger_bands", middle, weight, -1.0, Some(upper), "price > upper band");
+        } else if price < lower {  // Buy Signal
+            add_contribution(&mut contributions, "bollinger_bands", middle, weight, 1.0, Some(lower), "price < lower band");
+        } else {
+            add_contribution(&mut contributions, "bollinger_bands", middle, weight, 0.0, None, "price within bands");
         }
     }
 
-    //println!("Signal after Bollinger: {}", signal);
-
     // RSI Check
-    if let Some(rsi) = indicators.ohlc_rsi {
+    if let Some(rsi) = rsi {
+        let weight = weights.rsi_weight;
         if rsi > 80.0 {  // Strong Sell
-            signal += (settings.rsi_weight * -2.0) as f64;  // Strong Sell Signal
+            add_contribution(&mut contributions, "rsi", rsi, weight, -2.0, Some(80.0), "rsi > 80");
         } else if rsi > 70.0 {  // Normal Sell
-            signal += (settings.rsi_weight * -1.0) as f64;  // Sell Signal
+            add_contribution(&mut contributions, "rsi", rsi, weight, -1.0, Some(70.0), "rsi > 70");
         } else if rsi < 20.0 {  // Strong Buy
-            signal += (settings.rsi_weight * 2.0) as f64;  // Strong Buy Signal
+            add_contribution(&mut contributions, "rsi", rsi, weight, 2.0, Some(20.0), "rsi < 20");
         } else if rsi < 30.0 {  // Normal Buy
-            signal += (settings.rsi_weight * 1.0) as f64;  // Buy Signal
-        }
-    }
-
-    //println!("Signal after RSI: {}", signal);
-
-    // MA Check
-    if let Some(ma) = indicators.ohlc_ma {
-        if price_data.last_price > ma + settings.gap_value { 
-            signal += (settings.ma_ema_weight * -2.0) as f64;  // Strong Sell Signal
-        } else if price_data.last_price > ma {
-            signal += (settings.ma_ema_weight * -1.0) as f64;  // Sell Signal
-        } else if price_data.last_price < ma - settings.gap_value {
-            signal += (settings.ma_ema_weight * 2.0) as f64;  // Strong Buy Signal
-        } else if price_data.last_price < ma {
-            signal += (settings.ma_ema_weight * 1.0) as f64;  // Buy Signal
+            add_contribution(&mut contributions, "rsi", rsi, weight, 1.0, Some(30.0), "rsi < 30");
         } else {
-            signal += 0.0;  // Hold Signal
+            add_contribution(&mut contributions, "rsi", rsi, weight, 0.0, None, "rsi neutral");
         }
     }
 
-    //println!("Signal after MA: {}", signal);
-
-    // EMA Check
-    if let Some(ema) = indicators.ohlc_ema {
-        if price_data.last_price > ema + settings.gap_value {  
-            signal += (settings.ma_ema_weight * -2.0) as f64;  // Strong Sell Signal
-        } else if price_data.last_price > ema {
-            signal += (settings.ma_ema_weight * -1.0) as f64;  // Sell Signal
-        } else if price_data.last_price < ema - settings.gap_value {  
-            signal += (settings.ma_ema_weight * 2.0) as f64;  // Strong Buy Signal
-        } else if price_data.last_price < ema {
-            signal += (settings.ma_ema_weight * 1.0) as f64;  // Buy Signal
-        } else {
-            signal += 0.0;  // Hold Signal
+    // MA and EMA Check (mean reversion, or trend following in trending markets: price above the average is bullish)
+    let (direction, mode) = if weights.trend_following { (-1.0, " (trend)") } else { (1.0, "") };
+    for (indicator, average) in [("ma", ma), ("ema", ema)] {
+        if let Some(average) = average {
+            let weight = weights.ma_ema_weight;
+            if price > average + settings.gap_value {  // Strong Sell Signal
+                add_contribution(&mut contributions, indicator, average, weight, -2.0 * direction, Some(average + settings.gap_value), &format!("price > average + gap{}", mode));
+            } else if price > average {  // Sell Signal
+                add_contribution(&mut contributions, indicator, average, weight, -direction, Some(average), &format!("price > average{}", mode));
+            } else if price < average - settings.gap_value {  // Strong Buy Signal
+                add_contribution(&mut contributions, indicator, average, weight, 2.0 * direction, Some(average - settings.gap_value), &format!("price < average - gap{}", mode));
+            } else if price < average {  // Buy Signal
+                add_contribution(&mut contributions, indicator, average, weight, direction, Some(average), &format!("price < average{}", mode));
+            } else {
+                add_contribution(&mut contributions, indicator, average, weight, 0.0, None, "price at average");
+            }
         }
     }
 
-    //println!("Signal after EMA: {}", signal);
-
-
     // ATR Check
-    if let Some(atr) = indicators.atr {
-        let high_volatility_threshold = price_data.last_price * 0.005; // 0.5% of the spot price
+    if let Some(atr) = atr {
+        let weight = weights.atr_weight;
+        let high_volatility_threshold = price * 0.005; // 0.5% of the spot price
         let strong_buy_threshold = high_volatility_threshold * 1.5; // e.g., 1.5x volatility
         let strong_sell_threshold = high_volatility_threshold * 1.75; // e.g., 1.75x volatility
-    
+
         // Strong Sell Condition
-        if atr > strong_sell_threshold && price_data.last_price > atr + strong_sell_threshold {
-            signal += (settings.atr_weight * -2.0) as f64;
+        if atr > strong_sell_threshold && price > atr + strong_sell_threshold {
+            add_contribution(&mut contributions, "atr", atr, weight, -2.0, Some(strong_sell_threshold), "atr > 1.75x volatility threshold");
         }
         // Strong Buy Condition
-        else if atr > strong_sell_threshold && price_data.last_price < atr - strong_buy_threshold {
-            signal += (settings.atr_weight * 2.0) as f64;
+        else if atr > strong_sell_threshold && price < atr - strong_buy_threshold {
+            add_contribution(&mut contributions, "atr", atr, weight, 2.0, Some(strong_sell_threshold), "atr > 1.75x volatility threshold");
         }
         // Sell Condition
-        else if atr > high_volatility_threshold && price_data.last_price > atr {
-            signal += (settings.atr_weight * -1.0) as f64;
+        else if atr > high_volatility_threshold && price > atr {
+            add_contribution(&mut contributions, "atr", atr, weight, -1.0, Some(high_volatility_threshold), "atr > volatility threshold");
         }
         // Buy Condition
-        else if atr > high_volatility_threshold && price_data.last_price < atr {
-            signal += (settings.atr_weight * 1.0) as f64;
+        else if atr > high_volatility_threshold && price < atr {
+            add_contribution(&mut contributions, "atr", atr, weight, 1.0, Some(high_volatility_threshold), "atr > volatility threshold");
         }
         // Hold Condition
         else {
-            signal += 0.0; // Hold signal
+            add_contribution(&mut contributions, "atr", atr, weight, 0.0, None, "low volatility");
         }
     }
-    
-    //println!("Signal after ATR: {}", signal);
 
-    println!("{}", format!(" - Singal Value: {}", signal).dimmed());
+    // Basis Check (futures - index): a basis far above its mean is expected to revert (futures rich = sell)
+    if let Some(basis) = indicators.basis.filter(|_| settings.basis_weight > 0.0) {
+        let weight = settings.basis_weight;
+        let z_score = basis.z_score_at(price);
+        if z_score > settings.basis_strong_z_threshold {  // Strong Sell
+            add_contribution(&mut contributions, "basis", z_score, weight, -2.0, Some(settings.basis_strong_z_threshold), "basis z-score > strong threshold");
+        } else if z_score > settings.basis_z_threshold {  // Sell
+            add_contribution(&mut contributions, "basis", z_score, weight, -1.0, Some(settings.basis_z_threshold), "basis z-score > threshold");
+        } else if z_score < -settings.basis_strong_z_threshold {  // Strong Buy
+            add_contribution(&mut contributions, "basis", z_score, weight, 2.0, Some(-settings.basis_strong_z_threshold), "basis z-score < -strong threshold");
+        } else if z_score < -settings.basis_z_threshold {  // Buy
+            add_contribution(&mut contributions, "basis", z_score, weight, 1.0, Some(-settings.basis_z_threshold), "basis z-score < -threshold");
+        } else {
+            add_contribution(&mut contributions, "basis", z_score, weight, 0.0, None, "basis near mean");
+        }
+    }
+
+    let score: f64 = contributions.iter().map(|c| c.contribution).sum();
+
+    SignalExplanation {
+        price,
+        time: price_data.time,
+        contributions,
+        score,
+        bucket: get_signal_bucket(score),
+        regime: indicators.regime,
+        invalid_reason: None,
+    }
+}
 
-    // Final Signal Determination
+// Final Signal Determination: maps the weighted score to a signal value (-2 to 2)
+pub fn get_signal_bucket(signal: f64) -> i32 {
     if signal >= 1.55 {  // Strong Buy Condition
-        return 2;  // Strong Buy Signal
+        2  // Strong Buy Signal
     } else if signal > 0.2 {
-        return 1;  // Buy Signal
+        1  // Buy Signal
     } else if signal <= -1.55 {  // Strong Sell Condition
-        return -2;  // Strong Sell Signal
+        -2  // Strong Sell Signal
     } else if signal < -0.2 {
-        return -1;  // Sell Signal
+        -1  // Sell Signal
     } else {
-        return 0;  // Hold Signal
+        0  // Hold Signal
     }
 }
@@ -0,0 +1,293 @@
+// src/math/indicator_set.rs
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::futures::get_index_history::IndexHistoryEntry;
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use crate::futures::get_price_history::PriceHistoryEntry;
+use crate::math::price_indicators::{
+    calculate_atr, calculate_bollinger_bands, calculate_exponential_moving_average, calculate_moving_average, calculate_rsi,
+};
+
+// IDs of the default indicators, the signals are scored from the OHLC ones
+pub const MA: &str = "ma";
+pub const EMA: &str = "ema";
+pub const BOLLINGER_BANDS: &str = "bb";
+pub const RSI: &str = "rsi";
+pub const ATR: &str = "atr";
+
+/// Data an indicator is calculated from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorSource {
+    Ohlc,                                // Closes (highs and lows for the ATR) of the OHLCs
+    Price,                               // Price history
+    Index,                               // Index history
+}
+
+impl IndicatorSource {
+    pub const ALL: [IndicatorSource; 3] = [IndicatorSource::Ohlc, IndicatorSource::Price, IndicatorSource::Index];
+}
+
+impl fmt::Display for IndicatorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndicatorSource::Ohlc => write!(f, "ohlc"),
+            IndicatorSource::Price => write!(f, "price"),
+            IndicatorSource::Index => write!(f, "index"),
+        }
+    }
+}
+
+impl FromStr for IndicatorSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ohlc" => Ok(IndicatorSource::Ohlc),
+            "price" => Ok(IndicatorSource::Price),
+            "index" => Ok(IndicatorSource::Index),
+            _ => Err(format!("Unknown indicator source: {} (ohlc, price or index)", s)),
+        }
+    }
+}
+
+/// Type and parameters of an indicator.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum IndicatorKind {
+    Ma { period: usize },                                  // Moving average
+    Ema { period: usize },                                 // Exponential moving average
+    Bb { period: usize, std_dev_multiplier: f64 },         // Bollinger Bands
+    Rsi { period: usize },                                 // Relative strength index
+    Atr { period: usize },                                 // Average true range (OHLC only)
+}
+
+impl IndicatorKind {
+    /// Default ID: the name of the type and its period (e.g. `ema_50`).
+    pub fn default_id(&self) -> String {
+        match self {
+            IndicatorKind::Ma { period } => format!("{}_{}", MA, period),
+            IndicatorKind::Ema { period } => format!("{}_{}", EMA, period),
+            IndicatorKind::Bb { period, .. } => format!("{}_{}", BOLLINGER_BANDS, period),
+            IndicatorKind::Rsi { period } => format!("{}_{}", RSI, period),
+            IndicatorKind::Atr { period } => format!("{}_{}", ATR, period),
+        }
+    }
+}
+
+/// An indicator of the registry, calculated from `source` and stored under `id`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndicatorSpec {
+    pub id: String,                      // ID within the source (e.g. "rsi" or "ema_50")
+    pub source: IndicatorSource,         // Data the indicator is calculated from
+    pub kind: IndicatorKind,             // Type and parameters
+}
+
+/// Parses `[<id>=]<source>:<type>:<period>[:<std_dev_multiplier>]`, e.g. `ohlc:ema:50`,
+/// `price:rsi:7` or `wide_bb=ohlc:bb:20:2.5`. The ID defaults to the type and period (`ema_50`).
+impl FromStr for IndicatorSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, definition) = match s.split_once('=') {
+            Some((id, definition)) => (Some(id.trim().to_string()), definition),
+            None => (None, s),
+        };
+        let parts: Vec<&str> = definition.split(':').map(str::trim).collect();
+        let period = |index: usize| match parts.get(index).map(|value| value.parse::<usize>()) {
+            Some(Ok(period)) if period > 0 => Ok(period),
+            _ => Err(format!("Invalid indicator period: {}", s)),
+        };
+
+        let source: IndicatorSource = parts[0].parse()?;
+        let kind = match parts.get(1).map(|kind| kind.to_lowercase()).as_deref() {
+            Some("ma") => IndicatorKind::Ma { period: period(2)? },
+            Some("ema") => IndicatorKind::Ema { period: period(2)? },
+            Some("rsi") => IndicatorKind::Rsi { period: period(2)? },
+            Some("atr") if source == IndicatorSource::Ohlc => IndicatorKind::Atr { period: period(2)? },
+            Some("atr") => return Err(format!("The ATR requires OHLC data: {}", s)),
+            Some("bb") => {
+                let std_dev_multiplier = match parts.get(3).map(|value| value.parse::<f64>()) {
+                    None => 2.0,
+                    Some(Ok(multiplier)) if multiplier > 0.0 => multiplier,
+                    Some(_) => return Err(format!("Invalid Bollinger Bands multiplier: {}", s)),
+                };
+                IndicatorKind::Bb { period: period(2)?, std_dev_multiplier }
+            }
+            _ => return Err(format!("Unknown indicator type: {} (ma, ema, bb, rsi or atr)", s)),
+        };
+        if parts.len() > if matches!(kind, IndicatorKind::Bb { .. }) { 4 } else { 3 } {
+            return Err(format!("Too many indicator parameters: {}", s));
+        }
+
+        let id = match id {
+            Some(id) if id.is_empty() => return Err(format!("Empty indicator ID: {}", s)),
+            Some(id) => id,
+            None => kind.default_id(),
+        };
+        Ok(IndicatorSpec { id, source, kind })
+    }
+}
+
+/// The default registry: MA, EMA, Bollinger Bands and RSI of every source and the ATR of the OHLCs,
+/// stored under the plain type names (`ma`, `ema`, `bb`, `rsi` and `atr`).
+pub fn default_indicator_registry(
+    ma_period: usize,
+    ema_period: usize,
+    bb_period: usize,
+    bb_std_dev_multiplier: f64,
+    rsi_period: usize,
+    atr_period: usize,
+) -> Vec<IndicatorSpec> {
+    let mut registry = Vec::new();
+    for source in IndicatorSource::ALL {
+        let mut kinds = vec![
+            (MA, IndicatorKind::Ma { period: ma_period }),
+            (EMA, IndicatorKind::Ema { period: ema_period }),
+            (BOLLINGER_BANDS, IndicatorKind::Bb { period: bb_period, std_dev_multiplier: bb_std_dev_multiplier }),
+            (RSI, IndicatorKind::Rsi { period: rsi_period }),
+        ];
+        if source == IndicatorSource::Ohlc {
+            kinds.push((ATR, IndicatorKind::Atr { period: atr_period }));
+        }
+        registry.extend(kinds.into_iter().map(|(id, kind)| IndicatorSpec { id: id.to_string(), source, kind }));
+    }
+    registry
+}
+
+/// Value of an indicator.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum IndicatorValue {
+    Single(f64),                                           // MA, EMA, RSI and ATR
+    Bands { lower: f64, middle: f64, upper: f64 },         // Bollinger Bands
+}
+
+impl IndicatorValue {
+    // Values in log and metric order, with the suffix of their name
+    pub fn components(&self) -> Vec<(&'static str, f64)> {
+        match *self {
+            IndicatorValue::Single(value) => vec![("", value)],
+            IndicatorValue::Bands { lower, middle, upper } => vec![("_lower", lower), ("_middle", middle), ("_upper", upper)],
+        }
+    }
+}
+
+impl fmt::Display for IndicatorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndicatorValue::Single(value) => write!(f, "{}", value),
+            IndicatorValue::Bands { lower, middle, upper } => write!(f, "Lower: {}, Middle: {}, Upper: {}", lower, middle, upper),
+        }
+    }
+}
+
+/// Indicator values keyed by source and indicator ID.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct IndicatorSet(BTreeMap<IndicatorSource, BTreeMap<String, IndicatorValue>>);
+
+impl IndicatorSet {
+    /// Calculates the indicators of `registry`. Indicators of a missing source and indicators with too
+    /// little data are left out.
+    ///
+    /// # Parameters
+    /// - `registry`: The indicators to calculate.
+    /// - `ohlc_data`: The OHLC history (oldest first).
+    /// - `price_data`: The price history (oldest first), `None` if price data is not included.
+    /// - `index_data`: The index history (oldest first), `None` if index data is not included.
+    ///
+    /// # Returns
+    /// - The calculated indicator values.
+    pub fn calculate(
+        registry: &[IndicatorSpec],
+        ohlc_data: &[OhlcHistoryEntry],
+        price_data: Option<&[PriceHistoryEntry]>,
+        index_data: Option<&[IndexHistoryEntry]>,
+    ) -> Self {
+        let closes: Vec<f64> = ohlc_data.iter().map(|entry| entry.close).collect();
+        let prices: Option<Vec<f64>> = price_data.map(|data| data.iter().map(|entry| entry.value).collect());
+        let index: Option<Vec<f64>> = index_data.map(|data| data.iter().map(|entry| entry.value).collect());
+
+        let mut set = IndicatorSet::default();
+        for spec in registry {
+            let values = match spec.source {
+                IndicatorSource::Ohlc => &closes,
+                IndicatorSource::Price => match &prices {
+                    Some(prices) => prices,
+                    None => continue,
+                },
+                IndicatorSource::Index => match &index {
+                    Some(index) => index,
+                    None => continue,
+                },
+            };
+            let value = match spec.kind {
+                IndicatorKind::Ma { period } => calculate_moving_average(values, period).map(IndicatorValue::Single),
+                IndicatorKind::Ema { period } => calculate_exponential_moving_average(values, period).map(IndicatorValue::Single),
+                IndicatorKind::Rsi { period } => calculate_rsi(values, period).map(IndicatorValue::Single),
+                IndicatorKind::Bb { period, std_dev_multiplier } => calculate_bollinger_bands(values, period, std_dev_multiplier)
+                    .map(|(lower, middle, upper)| IndicatorValue::Bands { lower, middle, upper }),
+                // Only OHLC specs are ATRs (see `IndicatorSpec::from_str`)
+                IndicatorKind::Atr { period } => {
+                    let highs: Vec<f64> = ohlc_data.iter().map(|entry| entry.high).collect();
+                    let lows: Vec<f64> = ohlc_data.iter().map(|entry| entry.low).collect();
+                    calculate_atr(&highs, &lows, &closes, period).map(IndicatorValue::Single)
+                }
+            };
+            if let Some(value) = value {
+                set.insert(spec.source, &spec.id, value);
+            }
+        }
+        set
+    }
+
+    pub fn insert(&mut self, source: IndicatorSource, id: &str, value: IndicatorValue) {
+        self.0.entry(source).or_default().insert(id.to_string(), value);
+    }
+
+    pub fn get(&self, source: IndicatorSource, id: &str) -> Option<IndicatorValue> {
+        self.0.get(&source).and_then(|values| values.get(id)).copied()
+    }
+
+    /// Value of a single-valued indicator (MA, EMA, RSI or ATR).
+    pub fn value(&self, source: IndicatorSource, id: &str) -> Option<f64> {
+        match self.get(source, id)? {
+            IndicatorValue::Single(value) => Some(value),
+            IndicatorValue::Bands { .. } => None,
+        }
+    }
+
+    /// Lower, middle and upper band of a Bollinger Bands indicator.
+    pub fn bands(&self, source: IndicatorSource, id: &str) -> Option<(f64, f64, f64)> {
+        match self.get(source, id)? {
+            IndicatorValue::Bands { lower, middle, upper } => Some((lower, middle, upper)),
+            IndicatorValue::Single(_) => None,
+        }
+    }
+
+    /// The values of `source` by ID.
+    pub fn source(&self, source: IndicatorSource) -> impl Iterator<Item = (&str, IndicatorValue)> {
+        self.0.get(&source).into_iter().flatten().map(|(id, value)| (id.as_str(), *value))
+    }
+
+    /// All values by source and ID.
+    pub fn iter(&self) -> impl Iterator<Item = (IndicatorSource, &str, IndicatorValue)> {
+        self.0.iter().flat_map(|(source, values)| values.iter().map(move |(id, value)| (*source, id.as_str(), *value)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(|values| values.is_empty())
+    }
+
+    /// Applies the values of an update: the OHLC values are replaced, the price and index values are
+    /// only overwritten where the update has one (their data can be missing in an update).
+    pub fn merge(&mut self, update: IndicatorSet) {
+        self.0.remove(&IndicatorSource::Ohlc);
+        for (source, values) in update.0 {
+            self.0.entry(source).or_default().extend(values);
+        }
+    }
+}
@@ -1,9 +1,15 @@
 // src/math/mod.rs
 
 pub mod get_indicators;
+pub mod indicator_set;
 pub mod price_indicators;
 pub mod get_signals;
 pub mod create_trade_from_signal;
 pub mod get_trade_quantity;
 pub mod get_stoploss_takeprofit;
-pub mod calculate_trade;
\ No newline at end of file
+pub mod calculate_trade;
+pub mod get_scale_out_targets;
+pub mod get_margin_policy;
+pub mod get_market_regime;
+pub mod get_basis;
+pub mod resample_ohlcs;
@@ -1,5 +1,6 @@
 // src/math/price_indicators.rs
 
+use tracing::info;
 use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
 
 /// Calculates the moving average (MA) for a given period from price data.
@@ -71,7 +72,7 @@ pub fn calculate_bollinger_bands(
     let upper_band = middle_band + std_dev_multiplier * std_dev;
     let lower_band = middle_band - std_dev_multiplier * std_dev;
 
-    // println!("LowerBAND: {} __ MIDDLE: {} __ UP: {}", lower_band, middle_band, upper_band);
+    // info!("LowerBAND: {} __ MIDDLE: {} __ UP: {}", lower_band, middle_band, upper_band);
     Some((lower_band, middle_band, upper_band))
 }
 
@@ -86,7 +87,6 @@ pub fn calculate_bollinger_bands(
 /// 
 /// # Returns:
 /// - An `Option<f64>` containing the RSI value, or `None` if insufficient data.
-/// Calculates the Relative Strength Index (RSI) for a given period.
 pub fn calculate_rsi(prices: &[f64], period: usize) -> Option<f64> {
     if prices.len() < period {
         return None;
@@ -167,6 +167,83 @@ pub fn calculate_atr(
 }
 
 
+/// Calculates the Average Directional Index (ADX, Wilder) for a given period.
+/// The ADX measures trend strength (not direction): values above ~25 indicate a trend.
+/// 
+/// # Parameters:
+/// - `highs`: A vector of f64 representing the high prices (oldest first).
+/// - `lows`: A vector of f64 representing the low prices (oldest first).
+/// - `closes`: A vector of f64 representing the closing prices (oldest first).
+/// - `period`: The number of periods for the smoothing.
+/// 
+/// # Returns:
+/// - An `Option<f64>` containing the ADX value (0-100), or `None` if insufficient data (`2 * period` entries needed).
+pub fn calculate_adx(
+    highs: &[f64],
+    lows: &[f64],
+    closes: &[f64],
+    period: usize,
+) -> Option<f64> {
+    let len = highs.len().min(lows.len()).min(closes.len());
+    if period == 0 || len < 2 * period {
+        return None;
+    }
+
+    let mut true_ranges = Vec::with_capacity(len - 1);
+    let mut plus_dms = Vec::with_capacity(len - 1);
+    let mut minus_dms = Vec::with_capacity(len - 1);
+
+    for i in 1..len {
+        let high_low = highs[i] - lows[i];
+        let high_close = (highs[i] - closes[i - 1]).abs();
+        let low_close = (lows[i] - closes[i - 1]).abs();
+        true_ranges.push(high_low.max(high_close).max(low_close));
+
+        let up_move = highs[i] - highs[i - 1];
+        let down_move = lows[i - 1] - lows[i];
+        plus_dms.push(if up_move > down_move && up_move > 0.0 { up_move } else { 0.0 });
+        minus_dms.push(if down_move > up_move && down_move > 0.0 { down_move } else { 0.0 });
+    }
+
+    // Wilder smoothing, seeded with the sum of the first period
+    let mut smoothed_tr = true_ranges.iter().take(period).sum::<f64>();
+    let mut smoothed_plus_dm = plus_dms.iter().take(period).sum::<f64>();
+    let mut smoothed_minus_dm = minus_dms.iter().take(period).sum::<f64>();
+
+    let directional_index = |tr: f64, plus_dm: f64, minus_dm: f64| -> f64 {
+        if tr == 0.0 {
+            return 0.0;
+        }
+        let plus_di = 100.0 * plus_dm / tr;
+        let minus_di = 100.0 * minus_dm / tr;
+        if plus_di + minus_di == 0.0 {
+            0.0
+        } else {
+            100.0 * (plus_di - minus_di).abs() / (plus_di + minus_di)
+        }
+    };
+
+    let mut dx_values = vec![directional_index(smoothed_tr, smoothed_plus_dm, smoothed_minus_dm)];
+    for i in period..true_ranges.len() {
+        smoothed_tr = smoothed_tr - smoothed_tr / period as f64 + true_ranges[i];
+        smoothed_plus_dm = smoothed_plus_dm - smoothed_plus_dm / period as f64 + plus_dms[i];
+        smoothed_minus_dm = smoothed_minus_dm - smoothed_minus_dm / period as f64 + minus_dms[i];
+        dx_values.push(directional_index(smoothed_tr, smoothed_plus_dm, smoothed_minus_dm));
+    }
+
+    if dx_values.len() < period {
+        return None;
+    }
+
+    let mut adx = dx_values.iter().take(period).sum::<f64>() / period as f64;
+    for dx in dx_values.iter().skip(period) {
+        adx = (adx * (period as f64 - 1.0) + dx) / period as f64;
+    }
+
+    Some(adx)
+}
+
+
 pub fn calculate_moving_average_ohlc(ohlcs: &[OhlcHistoryEntry], period: usize) -> Option<f64> {
     let closes: Vec<f64> = ohlcs.iter().map(|entry| entry.close).collect();
     calculate_moving_average(&closes, period)
@@ -186,14 +263,14 @@ pub fn calculate_bollinger_bands_ohlc(
     std_dev_multiplier: f64,
 ) -> Option<(f64, f64, f64)> {
     if ohlcs.len() < period {
-        println!("Insufficient OHLC data: got {}, need {}", ohlcs.len(), period);
+        info!(got = ohlcs.len(), need = period, "Insufficient OHLC data");
         return None;
     }
 
     let closes: Vec<f64> = ohlcs.iter().map(|entry| entry.close).collect();
 
     if closes.iter().any(|&price| !price.is_finite() || price < 0.0) {
-        println!("Invalid close prices detected: {:?}", closes);
+        info!(?closes, "Invalid close prices detected");
         return None;
     }
 
@@ -204,3 +281,10 @@ pub fn calculate_rsi_ohlc(ohlcs: &[OhlcHistoryEntry], period: usize) -> Option<f
     let closes: Vec<f64> = ohlcs.iter().map(|entry| entry.close).collect();
     calculate_rsi(&closes, period)
 }
+
+pub fn calculate_adx_ohlc(ohlcs: &[OhlcHistoryEntry], period: usize) -> Option<f64> {
+    let highs: Vec<f64> = ohlcs.iter().map(|entry| entry.high).collect();
+    let lows: Vec<f64> = ohlcs.iter().map(|entry| entry.low).collect();
+    let closes: Vec<f64> = ohlcs.iter().map(|entry| entry.close).collect();
+    calculate_adx(&highs, &lows, &closes, period)
+}
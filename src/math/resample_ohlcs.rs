@@ -0,0 +1,32 @@
+// src/math/resample_ohlcs.rs
+
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use crate::utils::timeframe::Timeframe;
+
+/// Aggregates a finer OHLC series into candles of `timeframe`: the open of the first candle, the
+/// highest high, the lowest low, the close of the last candle and the summed volume. The candles are
+/// timed at the open of their timeframe candle (see [`Timeframe::align`]).
+///
+/// # Parameters
+/// - `ohlc_data`: The finer candles (oldest first), the timeframe must be a multiple of theirs.
+/// - `timeframe`: The timeframe of the resampled candles.
+///
+/// # Returns
+/// - The resampled candles (oldest first). The last one is partial if the finer candles end within it.
+pub fn resample_ohlcs(ohlc_data: &[OhlcHistoryEntry], timeframe: Timeframe) -> Vec<OhlcHistoryEntry> {
+    let mut resampled: Vec<OhlcHistoryEntry> = Vec::new();
+    for entry in ohlc_data {
+        let time = timeframe.align(entry.time);
+        match resampled.last_mut() {
+            Some(candle) if candle.time == time => {
+                candle.high = candle.high.max(entry.high);
+                candle.low = candle.low.min(entry.low);
+                candle.close = entry.close;
+                candle.volume += entry.volume;
+            }
+            _ => resampled.push(OhlcHistoryEntry { time, ..entry.clone() }),
+        }
+    }
+    resampled
+}
+
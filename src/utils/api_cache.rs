@@ -0,0 +1,101 @@
+// src/utils/api_cache.rs
+
+use tracing::warn;
+use std::future::Future;
+use std::sync::OnceLock;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use crate::futures::ticker::{get_futures_ticker, FuturesTicker};
+use crate::utils::get_user::{get_user, User};
+
+/// TTLs of the cached API data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheSettings {
+    pub ticker_ttl: Duration,            // Futures ticker (bid/ask for new trades)
+    pub user_ttl: Duration,              // User data (balance for new trades)
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self { ticker_ttl: Duration::from_secs(1), user_ttl: Duration::from_secs(5) }
+    }
+}
+
+/// Value that is fetched again after its TTL. Concurrent callers wait for a single fetch.
+#[derive(Debug)]
+pub struct TtlCache<T> {
+    ttl: Duration,
+    entry: Mutex<Option<(Instant, T)>>,  // Fetch time and value
+}
+
+impl<T: Clone> TtlCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entry: Mutex::new(None) }
+    }
+
+    /// Returns the cached value if it's younger than the TTL, otherwise fetches and caches it.
+    /// Errors are not cached.
+    ///
+    /// # Parameters
+    /// - `fetch`: Fetches the value.
+    pub async fn get_or_fetch<F, Fut>(&self, fetch: F) -> Result<T, String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let mut entry = self.entry.lock().await;
+        if let Some((fetched, value)) = entry.as_ref() {
+            if fetched.elapsed() < self.ttl {
+                return Ok(value.clone());
+            }
+        }
+        let value = fetch().await?;
+        *entry = Some((Instant::now(), value.clone()));
+        Ok(value)
+    }
+
+    /// Drops the cached value, the next call fetches it.
+    pub async fn invalidate(&self) {
+        *self.entry.lock().await = None;
+    }
+}
+
+/// Cached ticker and user data of the API.
+pub struct ApiCache {
+    pub ticker: TtlCache<FuturesTicker>,
+    pub user: TtlCache<User>,
+}
+
+static API_CACHE: OnceLock<ApiCache> = OnceLock::new();
+
+/// Sets the TTLs of the shared cache. Has no effect after the first access.
+pub fn init_api_cache(settings: CacheSettings) {
+    let cache = ApiCache { ticker: TtlCache::new(settings.ticker_ttl), user: TtlCache::new(settings.user_ttl) };
+    if API_CACHE.set(cache).is_err() {
+        warn!("API cache already initialized");
+    }
+}
+
+/// Shared cache of the API data (default TTLs if not initialized).
+pub fn api_cache() -> &'static ApiCache {
+    API_CACHE.get_or_init(|| {
+        let settings = CacheSettings::default();
+        ApiCache { ticker: TtlCache::new(settings.ticker_ttl), user: TtlCache::new(settings.user_ttl) }
+    })
+}
+
+/// Futures ticker, fetched at most once per ticker TTL.
+pub async fn get_futures_ticker_cached(api_url: &str) -> Result<FuturesTicker, String> {
+    api_cache()
+        .ticker
+        .get_or_fetch(|| async { get_futures_ticker(api_url).await.map_err(|e| e.to_string()) })
+        .await
+}
+
+/// User data, fetched at most once per user TTL.
+pub async fn get_user_cached(api_url: &str) -> Result<User, String> {
+    api_cache()
+        .user
+        .get_or_fetch(|| async { get_user(api_url).await.map_err(|e| e.to_string()) })
+        .await
+}
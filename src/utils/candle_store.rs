@@ -0,0 +1,520 @@
+// src/utils/candle_store.rs
+
+use tracing::info;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use parquet::basic::Compression as ParquetCompression;
+use parquet::column::writer::ColumnWriter;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use crate::futures::get_index_history::{get_index_history, IndexHistoryEntry};
+use crate::futures::get_ohlcs_history::{get_ohlcs_history, GetOhlcsParams, OhlcHistoryEntry};
+use crate::futures::get_price_history::{get_price_history, PriceHistoryEntry};
+use crate::utils::get_timestamps::{format_timestamp, get_current_time_ms};
+use crate::utils::timeframe::Timeframe;
+
+// Span of one download request, stored before the next one so an interrupted download resumes
+const OHLC_CHUNK_CANDLES: i64 = 1000;
+const POINT_CHUNK_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// History series of the store, one file per series.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HistorySeries {
+    Ohlc(Timeframe),                     // OHLCs of a timeframe
+    Price,                               // Price history
+    Index,                               // Index history
+}
+
+impl HistorySeries {
+    fn name(&self) -> String {
+        match self {
+            HistorySeries::Ohlc(timeframe) => format!("ohlc_{}", timeframe),
+            HistorySeries::Price => "price".to_string(),
+            HistorySeries::Index => "index".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for HistorySeries {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistorySeries::Ohlc(timeframe) => write!(f, "OHLCs ({})", timeframe),
+            HistorySeries::Price => write!(f, "price history"),
+            HistorySeries::Index => write!(f, "index history"),
+        }
+    }
+}
+
+/// Entry of a stored series: the time and its values as columns.
+pub trait StoredEntry: Sized + Clone {
+    const COLUMNS: &'static [&'static str]; // Value columns after `time`
+
+    fn time(&self) -> i64;
+    fn values(&self) -> Vec<f64>;
+    fn from_values(time: i64, values: &[f64]) -> Option<Self>;
+}
+
+impl StoredEntry for OhlcHistoryEntry {
+    const COLUMNS: &'static [&'static str] = &["open", "high", "low", "close", "volume"];
+
+    fn time(&self) -> i64 {
+        self.time
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.open, self.high, self.low, self.close, self.volume]
+    }
+
+    fn from_values(time: i64, values: &[f64]) -> Option<Self> {
+        match *values {
+            [open, high, low, close, volume] => Some(OhlcHistoryEntry { time, open, high, low, close, volume }),
+            _ => None,
+        }
+    }
+}
+
+impl StoredEntry for PriceHistoryEntry {
+    const COLUMNS: &'static [&'static str] = &["value"];
+
+    fn time(&self) -> i64 {
+        self.time
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.value]
+    }
+
+    fn from_values(time: i64, values: &[f64]) -> Option<Self> {
+        match *values {
+            [value] => Some(PriceHistoryEntry { time, value }),
+            _ => None,
+        }
+    }
+}
+
+impl StoredEntry for IndexHistoryEntry {
+    const COLUMNS: &'static [&'static str] = &["value"];
+
+    fn time(&self) -> i64 {
+        self.time
+    }
+
+    fn values(&self) -> Vec<f64> {
+        vec![self.value]
+    }
+
+    fn from_values(time: i64, values: &[f64]) -> Option<Self> {
+        match *values {
+            [value] => Some(IndexHistoryEntry { time, value }),
+            _ => None,
+        }
+    }
+}
+
+/// Local store of the history data: one gzip compressed CSV file per series (`<dir>/<series>.csv.gz`),
+/// sorted by time without duplicates. Entries newer or older than the stored ones are appended or
+/// prepended as gzip members, only overlapping entries are merged by rewriting the file. The
+/// coverage of a series is kept in memory and read from the file again if its size changed.
+#[derive(Debug, Clone)]
+pub struct CandleStore {
+    dir: PathBuf,
+    coverage: Arc<Mutex<HashMap<PathBuf, (u64, Coverage)>>>, // Coverage per file and the file size it is valid for
+}
+
+// First and last stored time of a series
+type Coverage = Option<(i64, i64)>;
+
+// Size of the file, 0 if it does not exist
+fn file_size(path: &Path) -> io::Result<u64> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(metadata.len()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e),
+    }
+}
+
+impl CandleStore {
+    /// Opens the store in `dir`, the directory is created if needed.
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(CandleStore { dir: dir.as_ref().to_path_buf(), coverage: Arc::default() })
+    }
+
+    /// File of the series.
+    pub fn path(&self, series: &HistorySeries) -> PathBuf {
+        self.dir.join(format!("{}.csv.gz", series.name()))
+    }
+
+    /// Stored entries of the series between `from` and `to` (inclusive), oldest first.
+    pub fn load<T: StoredEntry>(&self, series: &HistorySeries, from: i64, to: i64) -> Result<Vec<T>, Box<dyn Error>> {
+        let mut entries = self.load_all::<T>(series)?;
+        entries.retain(|entry| entry.time() >= from && entry.time() <= to);
+        Ok(entries)
+    }
+
+    /// Time of the first and the last stored entry of the series.
+    pub fn coverage<T: StoredEntry>(&self, series: &HistorySeries) -> Result<Option<(i64, i64)>, Box<dyn Error>> {
+        let path = self.path(series);
+        let size = file_size(&path)?;
+        let cached = self.coverage.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).get(&path).copied();
+        if let Some((_, coverage)) = cached.filter(|(cached_size, _)| *cached_size == size) {
+            return Ok(coverage);
+        }
+
+        let entries = self.load_all::<T>(series)?;
+        let coverage = entries.first().zip(entries.last()).map(|(first, last)| (first.time(), last.time()));
+        self.set_coverage(&path, coverage)?;
+        Ok(coverage)
+    }
+
+    /// Adds entries to the series, entries of stored times replace the stored ones.
+    ///
+    /// # Returns
+    /// - The number of entries that were not stored before.
+    pub fn append<T: StoredEntry>(&self, series: &HistorySeries, entries: Vec<T>) -> Result<usize, Box<dyn Error>> {
+        let mut new_entries: BTreeMap<i64, T> = entries.into_iter().map(|entry| (entry.time(), entry)).collect();
+        let (Some(&first_new), Some(&last_new)) = (new_entries.keys().next(), new_entries.keys().next_back()) else {
+            return Ok(0);
+        };
+        let coverage = self.coverage::<T>(series)?;
+        let path = self.path(series);
+
+        match coverage {
+            // Newer than the stored entries: appended as a new gzip member
+            None => {
+                let added = new_entries.len();
+                write_rows(OpenOptions::new().create(true).append(true).open(&path)?, new_entries.values())?;
+                self.set_coverage(&path, Some((first_new, last_new)))?;
+                Ok(added)
+            }
+            Some((first, last)) if first_new > last => {
+                let added = new_entries.len();
+                write_rows(OpenOptions::new().append(true).open(&path)?, new_entries.values())?;
+                self.set_coverage(&path, Some((first, last_new)))?;
+                Ok(added)
+            }
+            // Older than the stored entries: a new gzip member followed by the stored members
+            Some((first, last)) if last_new < first => {
+                let added = new_entries.len();
+                let tmp_path = path.with_extension("gz.tmp");
+                write_rows(File::create(&tmp_path)?, new_entries.values())?;
+                let mut tmp = OpenOptions::new().append(true).open(&tmp_path)?;
+                io::copy(&mut File::open(&path)?, &mut tmp)?;
+                fs::rename(&tmp_path, &path)?;
+                self.set_coverage(&path, Some((first_new, last)))?;
+                Ok(added)
+            }
+            // Otherwise merged and rewritten
+            Some(_) => {
+                let stored = self.load_all::<T>(series)?;
+                let stored_times = stored.len();
+                for entry in stored {
+                    new_entries.entry(entry.time()).or_insert(entry);
+                }
+                let added = new_entries.len() - stored_times;
+                let tmp_path = path.with_extension("gz.tmp");
+                write_rows(File::create(&tmp_path)?, new_entries.values())?;
+                fs::rename(&tmp_path, &path)?;
+                let coverage = new_entries.keys().next().copied().zip(new_entries.keys().next_back().copied());
+                self.set_coverage(&path, coverage)?;
+                Ok(added)
+            }
+        }
+    }
+
+    // Remembers the coverage of the file at its current size
+    fn set_coverage(&self, path: &Path, coverage: Option<(i64, i64)>) -> io::Result<()> {
+        let size = file_size(path)?;
+        self.coverage.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(path.to_path_buf(), (size, coverage));
+        Ok(())
+    }
+
+    fn load_all<T: StoredEntry>(&self, series: &HistorySeries) -> Result<Vec<T>, Box<dyn Error>> {
+        let path = self.path(series);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut reader = csv::ReaderBuilder::new().has_headers(false).from_reader(MultiGzDecoder::new(file));
+        let mut entries = Vec::new();
+        for (number, record) in reader.records().enumerate() {
+            let record = record?;
+            let invalid = || format!("Invalid entry on line {} of {}", number + 1, path.display());
+            let time: i64 = record.get(0).and_then(|time| time.parse().ok()).ok_or_else(invalid)?;
+            let values = record.iter().skip(1).map(|value| value.parse()).collect::<Result<Vec<f64>, _>>().map_err(|_| invalid())?;
+            entries.push(T::from_values(time, &values).ok_or_else(invalid)?);
+        }
+        Ok(entries)
+    }
+}
+
+// Writes the entries as one gzip member of CSV rows
+fn write_rows<'a, T: StoredEntry + 'a>(file: File, entries: impl Iterator<Item = &'a T>) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(GzEncoder::new(file, Compression::default()));
+    for entry in entries {
+        let mut row = vec![entry.time().to_string()];
+        row.extend(entry.values().iter().map(|value| value.to_string()));
+        writer.write_record(&row)?;
+    }
+    writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+    Ok(())
+}
+
+static CANDLE_STORE: OnceLock<CandleStore> = OnceLock::new();
+
+/// Opens the store the history data is read from first.
+pub fn init_candle_store(dir: &str) -> io::Result<()> {
+    let store = CandleStore::open(dir)?;
+    let _ = CANDLE_STORE.set(store);
+    Ok(())
+}
+
+/// The store of the history data, `None` if the history is only fetched from the API.
+pub fn candle_store() -> Option<&'static CandleStore> {
+    CANDLE_STORE.get()
+}
+
+/// Spans of `from..=to` that are not covered by the stored entries, each split into chunks of
+/// `chunk_ms`. Spans before the stored entries are ordered newest first, so the stored entries stay
+/// contiguous if the download is interrupted.
+pub fn missing_chunks(coverage: Option<(i64, i64)>, from: i64, to: i64, chunk_ms: i64) -> Vec<(i64, i64)> {
+    let chunk_ms = chunk_ms.max(1);
+    let chunks = |from: i64, to: i64| {
+        let mut chunks = Vec::new();
+        let mut chunk_from = from;
+        while chunk_from <= to {
+            let chunk_to = chunk_from.saturating_add(chunk_ms - 1).min(to);
+            chunks.push((chunk_from, chunk_to));
+            chunk_from = chunk_to + 1;
+        }
+        chunks
+    };
+
+    match coverage {
+        None => chunks(from, to),
+        Some((first, last)) => {
+            let mut missing: Vec<(i64, i64)> = chunks(from, to.min(first - 1)).into_iter().rev().collect();
+            missing.extend(chunks(from.max(last + 1), to));
+            missing
+        }
+    }
+}
+
+/// Outcome of the download of a series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DownloadSummary {
+    pub series: HistorySeries,           // Downloaded series
+    pub requests: usize,                 // Chunks requested from the API
+    pub added: usize,                    // Entries added to the store
+    pub coverage: Option<(i64, i64)>,    // First and last stored entry after the download
+}
+
+// Downloads the chunks of the series missing in the store, the entries of a chunk are stored
+// before the next one is requested
+async fn download_series<T, F, Fut>(
+    store: &CandleStore,
+    series: HistorySeries,
+    from: i64,
+    to: i64,
+    chunk_ms: i64,
+    mut fetch: F,
+) -> Result<DownloadSummary, Box<dyn Error>>
+where
+    T: StoredEntry,
+    F: FnMut(i64, i64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, Box<dyn Error>>>,
+{
+    let chunks = missing_chunks(store.coverage::<T>(&series)?, from, to, chunk_ms);
+    let (mut requests, mut added) = (0, 0);
+    if !chunks.is_empty() {
+        info!(%series, from = %format_timestamp(from), to = %format_timestamp(to), requests = chunks.len(), "Download");
+    }
+
+    for (chunk_from, chunk_to) in chunks {
+        let mut entries = fetch(chunk_from, chunk_to).await?;
+        entries.retain(|entry| entry.time() >= chunk_from && entry.time() <= chunk_to);
+        requests += 1;
+        added += store.append(&series, entries)?;
+    }
+
+    let coverage = store.coverage::<T>(&series)?;
+    Ok(DownloadSummary { series, requests, added, coverage })
+}
+
+/// Downloads the closed OHLCs of the timeframe between `from` and `to` that are missing in the store.
+pub async fn download_ohlcs(api_url: &str, store: &CandleStore, timeframe: Timeframe, from: i64, to: i64) -> Result<DownloadSummary, Box<dyn Error>> {
+    let interval_ms = timeframe.duration().as_millis() as i64;
+    // The running candle changes until it is closed, the last closed one opened before it
+    let to = to.min(timeframe.align(timeframe.align(get_current_time_ms()) - 1));
+    download_series(store, HistorySeries::Ohlc(timeframe), from, to, OHLC_CHUNK_CANDLES * interval_ms, |from, to| {
+        let params = GetOhlcsParams { range: timeframe, from, to, limit: Some(1000), debug: false };
+        get_ohlcs_history(api_url, params)
+    })
+    .await
+}
+
+/// Downloads the price history between `from` and `to` that is missing in the store.
+pub async fn download_price_history(api_url: &str, store: &CandleStore, from: i64, to: i64) -> Result<DownloadSummary, Box<dyn Error>> {
+    download_series(store, HistorySeries::Price, from, to, POINT_CHUNK_MS, |from, to| get_price_history(api_url, Some(from), Some(to), None)).await
+}
+
+/// Downloads the index history between `from` and `to` that is missing in the store.
+pub async fn download_index_history(api_url: &str, store: &CandleStore, from: i64, to: i64) -> Result<DownloadSummary, Box<dyn Error>> {
+    download_series(store, HistorySeries::Index, from, to, POINT_CHUNK_MS, |from, to| get_index_history(api_url, Some(from), Some(to), None)).await
+}
+
+/// OHLC history, read from the store first: missing candles are downloaded into the store, the
+/// running candle is fetched from the API. Without a store, the history is fetched from the API.
+pub async fn load_ohlcs_history(api_url: &str, params: GetOhlcsParams) -> Result<Vec<OhlcHistoryEntry>, Box<dyn Error>> {
+    let Some(store) = candle_store() else {
+        return get_ohlcs_history(api_url, params).await;
+    };
+
+    download_ohlcs(api_url, store, params.range, params.from, params.to).await?;
+    let mut ohlc_data: Vec<OhlcHistoryEntry> = store.load(&HistorySeries::Ohlc(params.range), params.from, params.to)?;
+    let tail_from = ohlc_data.last().map(|entry| entry.time + 1).unwrap_or(params.from);
+    if tail_from < params.to {
+        let tail = get_ohlcs_history(api_url, GetOhlcsParams { from: tail_from, ..params }).await?;
+        ohlc_data.extend(tail.into_iter().filter(|entry| entry.time >= tail_from));
+    }
+    Ok(ohlc_data)
+}
+
+/// Price history, read from the store first (missing entries are downloaded into the store).
+pub async fn load_price_history(api_url: &str, from: i64, to: i64) -> Result<Vec<PriceHistoryEntry>, Box<dyn Error>> {
+    let Some(store) = candle_store() else {
+        return get_price_history(api_url, Some(from), Some(to), None).await;
+    };
+    download_price_history(api_url, store, from, to).await?;
+    store.load(&HistorySeries::Price, from, to)
+}
+
+/// Index history, read from the store first (missing entries are downloaded into the store).
+pub async fn load_index_history(api_url: &str, from: i64, to: i64) -> Result<Vec<IndexHistoryEntry>, Box<dyn Error>> {
+    let Some(store) = candle_store() else {
+        return get_index_history(api_url, Some(from), Some(to), None).await;
+    };
+    download_index_history(api_url, store, from, to).await?;
+    store.load(&HistorySeries::Index, from, to)
+}
+
+/// Series, span and exports of the download tool.
+#[derive(Debug, Clone)]
+pub struct DownloadSettings {
+    pub store_dir: String,               // Directory of the store
+    pub timeframes: Vec<Timeframe>,      // OHLC timeframes to download
+    pub include_price_data: bool,        // Whether the price history is downloaded
+    pub include_index_data: bool,        // Whether the index history is downloaded
+    pub from: i64,                       // Start of the span (ms)
+    pub to: i64,                         // End of the span (ms)
+    pub export_dir: Option<String>,      // Directory of the exports, None disables them
+    pub export_formats: Vec<ExportFormat>, // Formats of the exports
+}
+
+/// Export format of the stored series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,                                 // CSV with a header row
+    Parquet,                             // Parquet, snappy compressed
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            _ => Err(format!("Unknown export format: {} (csv, parquet)", s)),
+        }
+    }
+}
+
+/// Writes the entries as CSV with a header row (`time,<columns>`).
+pub fn export_csv<T: StoredEntry>(path: impl AsRef<Path>, entries: &[T]) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_path(path)?;
+    let mut header = vec!["time"];
+    header.extend_from_slice(T::COLUMNS);
+    writer.write_record(&header)?;
+    for entry in entries {
+        let mut row = vec![entry.time().to_string()];
+        row.extend(entry.values().iter().map(|value| value.to_string()));
+        writer.write_record(&row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes the entries as Parquet: `time` as timestamp (ms, UTC) and the value columns as doubles.
+pub fn export_parquet<T: StoredEntry>(path: impl AsRef<Path>, entries: &[T]) -> Result<(), Box<dyn Error>> {
+    let columns: String = T::COLUMNS.iter().map(|column| format!("REQUIRED DOUBLE {}; ", column)).collect();
+    let schema = parse_message_type(&format!("message history {{ REQUIRED INT64 time (TIMESTAMP(MILLIS, true)); {}}}", columns))?;
+    let properties = WriterProperties::builder().set_compression(ParquetCompression::SNAPPY).build();
+    let mut writer = SerializedFileWriter::new(File::create(path)?, Arc::new(schema), Arc::new(properties))?;
+
+    let times: Vec<i64> = entries.iter().map(|entry| entry.time()).collect();
+    let rows: Vec<Vec<f64>> = entries.iter().map(|entry| entry.values()).collect();
+    let mut row_group = writer.next_row_group()?;
+    let mut column = 0;
+    while let Some(mut column_writer) = row_group.next_column()? {
+        match column_writer.untyped() {
+            ColumnWriter::Int64ColumnWriter(writer) => {
+                writer.write_batch(&times, None, None)?;
+            }
+            ColumnWriter::DoubleColumnWriter(writer) => {
+                let values: Vec<f64> = rows.iter().map(|row| row[column - 1]).collect();
+                writer.write_batch(&values, None, None)?;
+            }
+            _ => return Err("Unexpected column type".into()),
+        }
+        column_writer.close()?;
+        column += 1;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Exports the stored series between `from` and `to` to `<dir>/<series>.<format>`.
+///
+/// # Returns
+/// - The path of the export and the number of exported entries.
+pub fn export_series<T: StoredEntry>(
+    store: &CandleStore,
+    series: &HistorySeries,
+    from: i64,
+    to: i64,
+    dir: impl AsRef<Path>,
+    format: ExportFormat,
+) -> Result<(PathBuf, usize), Box<dyn Error>> {
+    fs::create_dir_all(dir.as_ref())?;
+    let entries: Vec<T> = store.load(series, from, to)?;
+    let path = dir.as_ref().join(format!("{}.{}", series.name(), format.extension()));
+    match format {
+        ExportFormat::Csv => export_csv(&path, &entries)?,
+        ExportFormat::Parquet => export_parquet(&path, &entries)?,
+    }
+    Ok((path, entries.len()))
+}
@@ -1,6 +1,7 @@
 // src/utils/connect_ws.rs
 
-use serde::Deserialize;
+use tracing::{error, info, warn, Instrument};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use futures_util::{StreamExt, SinkExt};
@@ -8,9 +9,10 @@ use tokio::sync::{mpsc, Mutex};
 use uuid::Uuid;
 use tokio::time::{Instant, Duration};
 use std::sync::Arc;
-use colored::*; // Für farbige Ausgaben
+use crate::utils::metrics::metrics;
+use crate::utils::session_recorder::{record, RecordedEvent};
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PriceData {
     pub last_price: f64,
@@ -27,18 +29,24 @@ pub async fn ws_price_feed(
     price_tx: mpsc::Sender<PriceData>, // Channel to transmit price data
 ) -> Result<(), Box<dyn std::error::Error>> {
     let price_tx = Arc::new(Mutex::new(price_tx)); // Wrap the sender in Arc<Mutex>
+    let mut connected_before = false;
 
     loop {
         let (ws_stream, _) = match connect_async(ws_endpoint).await {
             Ok(ws) => ws,
             Err(_e) => {
-                eprintln!("{}", "Error connecting to WebSocket. Retrying...".red());
+                warn!(endpoint = ws_endpoint, "Error connecting to WebSocket, retrying");
                 tokio::time::sleep(Duration::from_secs(5)).await;
                 continue;
             }
         };
 
-        println!("Connected to {}", ws_endpoint.purple());
+        info!(endpoint = ws_endpoint, "Connected to WebSocket");
+        metrics().ws_connected.set(1);
+        if connected_before {
+            metrics().ws_reconnects.inc();
+        }
+        connected_before = true;
         let (mut write, mut read) = ws_stream.split(); // Split the WebSocket stream into read and write parts
 
         // Subscribe to the price channel
@@ -52,10 +60,11 @@ pub async fn ws_price_feed(
 
         // Send the subscription request
         if write.send(Message::Text(subscription_request.to_string())).await.is_err() {
-            eprintln!("{}", "Error subscribing to channel.".red());
-            continue; // Falls das Senden der Nachricht fehlschlägt, Verbindung erneut aufbauen
+            error!(channel, "Error subscribing to channel");
+            metrics().ws_connected.set(0);
+            continue; // Reconnect if the subscription could not be sent
         }
-        println!("Subscribed to: {}", channel.blue());
+        info!(channel, "Subscribed to channel");
 
         // Track the time of the last received message
         let last_received = Arc::new(Mutex::new(Instant::now()));
@@ -73,9 +82,10 @@ pub async fn ws_price_feed(
                                 if let Some(params) = parsed.get("params") {
                                     if let Some(data) = params.get("data") {
                                         if let Ok(price_data) = serde_json::from_value::<PriceData>(data.clone()) {
+                                            record(RecordedEvent::Price { data: price_data.clone() });
                                             let price_tx = price_tx.lock().await;
                                             if price_tx.send(price_data).await.is_err() {
-                                                eprintln!("Failed to send price data.");
+                                                error!("Failed to send price data.");
                                                 break;
                                             }
                                         }
@@ -88,13 +98,14 @@ pub async fn ws_price_feed(
                             *last_received = Instant::now();
                         }
                         Err(e) => {
-                            eprintln!("Error receiving message: {}", e);
+                            error!(error = %e, "Error receiving message");
                             break;
                         }
                         _ => {}
                     }
                 }
             }
+            .in_current_span()
         });
 
         // Heartbeat mechanism and shutdown handling
@@ -107,7 +118,8 @@ pub async fn ws_price_feed(
                     if Instant::now().duration_since(*last_received) >= Duration::from_secs(5) {
                         if let Err(_e) = write.send(Message::Ping(vec![])).await {
                             // Log error in red and bold when ping fails
-                            eprintln!("{}", "WEBSOCKET CONNECTION: LOST".red().bold());
+                            error!("WebSocket connection lost");
+                            metrics().ws_connected.set(0);
                             break;
                         }
                         // Do not log anything if ping is successful
@@ -115,18 +127,19 @@ pub async fn ws_price_feed(
                 }
                 _ = shutdown_rx.recv() => {
                     // Handle shutdown signal
-                    println!("Closing WebSocket connection...");
+                    info!("Closing WebSocket connection...");
                     match write.send(Message::Close(None)).await {
-                        Ok(_) => println!("WebSocket connection closed successfully."),
-                        Err(e) => eprintln!("Error closing WebSocket connection: {}", e),
+                        Ok(_) => info!("WebSocket connection closed successfully."),
+                        Err(e) => error!(error = %e, "Error closing WebSocket connection"),
                     }
+                    metrics().ws_connected.set(0);
                     break; // Exit the inner loop after closing the WebSocket
                 }
             }
         }
 
         // Here we exit the outer loop once the shutdown signal is received
-        println!("Price feed stopped.");
+        info!("Price feed stopped.");
         break; // Exit the outer loop to stop reconnecting
     }
 
@@ -0,0 +1,425 @@
+// src/utils/control_api.rs
+
+use tracing::{error, info, warn};
+use std::sync::Arc;
+use axum::extract::{Path, Request, State};
+use axum::http::{header::AUTHORIZATION, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::{mpsc, Mutex};
+use crate::backtest::execution_model::ExecutionModel;
+use crate::futures::close_trade::close_trade;
+use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Side, Trade, TradeStatus};
+use crate::math::get_indicators::Indicators;
+use crate::math::indicator_set::IndicatorSet;
+use crate::math::get_market_regime::MarketRegime;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::manage_positions::ClosedTradeEvent;
+use crate::utils::paper_exchange::{log_paper_events, PaperEvent, PaperExchange};
+use crate::utils::position_group::PositionGroup;
+use crate::utils::reconcile_state::own_trades;
+use crate::utils::runtime_settings::{RiskUpdate, RuntimeSettings, SignalRecord, SignalWeightsUpdate};
+use crate::utils::session_replay::is_replaying;
+
+/// Shared state of the control API.
+#[derive(Clone)]
+pub struct ControlState {
+    pub api_url: Arc<str>,
+    pub bot_params: Arc<Mutex<BotParams>>,
+    pub token: Arc<str>,                 // Bearer token required on every request
+    pub execution_model: ExecutionModel, // Execution model of a paper exchange created by switching on dry run
+    pub paper_fee_rate: f64,
+    pub closed_trade_tx: mpsc::Sender<ClosedTradeEvent>, // Closed paper trades (cooldowns and realized P&L)
+}
+
+/// Snapshot of the bot for `GET /status`.
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub runtime: RuntimeSettings,
+    pub balance_sats: Option<f64>,       // Balance of the last user data update
+    pub max_trades: Option<u64>,         // `limits.count.max` of the futures market
+    pub regime: Option<MarketRegime>,
+    pub position_groups: Vec<PositionGroup>,
+    pub paper: Option<PaperStatus>,      // Paper exchange of dry runs
+    pub last_signal: Option<SignalRecord>,
+}
+
+/// Summary of the paper exchange.
+#[derive(Debug, Serialize)]
+pub struct PaperStatus {
+    pub balance_sats: f64,
+    pub pending_orders: usize,
+    pub open_positions: usize,
+    pub closed_trades: usize,
+    pub realized_pl_sats: f64,           // Net P&L of the closed paper trades
+}
+
+/// Current indicator values for `GET /indicators`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IndicatorsResponse {
+    pub values: IndicatorSet,            // Values of the indicator registry by source and ID
+    pub adx: Option<f64>,
+    pub ema_slope: Option<f64>,
+    pub regime: Option<MarketRegime>,
+    pub basis_z_score: Option<f64>,
+    pub last_candle_time: Option<i64>,
+}
+
+impl From<&Indicators> for IndicatorsResponse {
+    fn from(indicators: &Indicators) -> Self {
+        Self {
+            values: indicators.values.clone(),
+            adx: indicators.regime_indicators.adx,
+            ema_slope: indicators.regime_indicators.ema_slope,
+            regime: indicators.regime,
+            basis_z_score: indicators.basis.map(|basis| basis.z_score),
+            last_candle_time: indicators.ohlc_data.last().map(|candle| candle.time),
+        }
+    }
+}
+
+/// Running trade for `GET /trades` (LN Markets trade or paper position).
+#[derive(Debug, Serialize)]
+pub struct RunningTrade {
+    pub id: String,
+    pub side: Side,                      // Buy or sell
+    pub quantity: f64,                   // Quantity in USD
+    pub entry_price: f64,
+    pub stoploss: f64,
+    pub takeprofit: f64,
+    pub margin: Option<f64>,             // Margin in sats (LN Markets trades)
+    pub pl: Option<f64>,                 // Unrealized P&L in sats (LN Markets trades)
+}
+
+impl From<&Trade> for RunningTrade {
+    fn from(trade: &Trade) -> Self {
+        Self {
+            id: trade.id.clone(),
+            side: trade.side,
+            quantity: trade.quantity,
+            entry_price: trade.entry_price.unwrap_or(trade.price),
+            stoploss: trade.stoploss,
+            takeprofit: trade.takeprofit,
+            margin: Some(trade.margin),
+            pl: Some(trade.pl),
+        }
+    }
+}
+
+/// Request body of `POST /dry-run`.
+#[derive(Debug, Deserialize)]
+pub struct DryRunRequest {
+    pub enabled: bool,
+}
+
+/// Error response with a JSON body `{"error": ...}`.
+pub struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+/// Checks the `Authorization: Bearer <token>` header (constant time comparison).
+pub fn is_authorized(headers: &HeaderMap, token: &str) -> bool {
+    let Some(provided) = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    provided.len() == token.len()
+        && provided.bytes().zip(token.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn require_token(State(state): State<ControlState>, request: Request, next: Next) -> Response {
+    if !is_authorized(request.headers(), &state.token) {
+        return ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()).into_response();
+    }
+    next.run(request).await
+}
+
+async fn status(State(state): State<ControlState>) -> Json<StatusResponse> {
+    let bot_params = state.bot_params.lock().await;
+    Json(StatusResponse {
+        runtime: bot_params.runtime.clone(),
+        balance_sats: bot_params.user_data.as_ref().map(|user| user.balance),
+        max_trades: bot_params.market_data.as_ref().map(|market| market.limits.count.max),
+        regime: bot_params.indicators.as_ref().and_then(|indicators| indicators.regime),
+        position_groups: bot_params.position_groups.clone(),
+        paper: bot_params.paper_exchange.as_ref().map(|exchange| PaperStatus {
+            balance_sats: exchange.balance,
+            pending_orders: exchange.orders.len(),
+            open_positions: exchange.positions.len(),
+            closed_trades: exchange.closed_trades.len(),
+            realized_pl_sats: exchange.closed_trades.iter().map(|trade| trade.net_pl()).sum(),
+        }),
+        last_signal: bot_params.last_signals.back().cloned(),
+    })
+}
+
+async fn indicators(State(state): State<ControlState>) -> Result<Json<IndicatorsResponse>, ApiError> {
+    let bot_params = state.bot_params.lock().await;
+    let indicators = bot_params
+        .indicators
+        .as_ref()
+        .ok_or_else(|| ApiError(StatusCode::SERVICE_UNAVAILABLE, "Indicators not initialized".to_string()))?;
+    Ok(Json(IndicatorsResponse::from(indicators)))
+}
+
+async fn signals(State(state): State<ControlState>) -> Json<Vec<SignalRecord>> {
+    Json(state.bot_params.lock().await.last_signals.iter().cloned().collect())
+}
+
+async fn running_trades(State(state): State<ControlState>) -> Result<Json<Vec<RunningTrade>>, ApiError> {
+    {
+        let bot_params = state.bot_params.lock().await;
+        if bot_params.runtime.dry_run {
+            let positions = bot_params.paper_exchange.as_ref().map(|exchange| {
+                exchange
+                    .positions
+                    .iter()
+                    .map(|position| RunningTrade {
+                        id: position.id.clone(),
+                        side: Side::from_is_buy(position.is_buy),
+                        quantity: position.quantity,
+                        entry_price: position.entry_fill.fill_price,
+                        stoploss: position.stoploss,
+                        takeprofit: position.takeprofit,
+                        margin: None,
+                        pl: None,
+                    })
+                    .collect()
+            });
+            return Ok(Json(positions.unwrap_or_default()));
+        }
+    }
+
+    let trades = own_running_trades(&state.api_url, &state.bot_params)
+        .await
+        .map_err(|e| ApiError(StatusCode::BAD_GATEWAY, e))?;
+    Ok(Json(trades.iter().map(RunningTrade::from).collect()))
+}
+
+// Running trades of the bot on LN Markets, external trades that are ignored are left out
+async fn own_running_trades(api_url: &str, bot_params: &Arc<Mutex<BotParams>>) -> Result<Vec<Trade>, String> {
+    let params = GetTradesParams {
+        r#type: TradeStatus::Running,
+        from: None,
+        to: None,
+        limit: None,
+    };
+    let running = get_trades(api_url, Some(params)).await.map_err(|e| format!("Error fetching running trades: {}", e))?;
+    Ok(own_trades(bot_params, running).await)
+}
+
+async fn set_paused(state: &ControlState, paused: bool) -> Json<RuntimeSettings> {
+    let mut bot_params = state.bot_params.lock().await;
+    bot_params.runtime.paused = paused;
+    warn!(paused, "Control API: trading {}", if paused { "paused" } else { "resumed" });
+    Json(bot_params.runtime.clone())
+}
+
+async fn pause(State(state): State<ControlState>) -> Json<RuntimeSettings> {
+    set_paused(&state, true).await
+}
+
+async fn resume(State(state): State<ControlState>) -> Json<RuntimeSettings> {
+    set_paused(&state, false).await
+}
+
+async fn dry_run(State(state): State<ControlState>, Json(request): Json<DryRunRequest>) -> Result<Json<RuntimeSettings>, ApiError> {
+    // Replayed prices must never create real orders
+    if !request.enabled && is_replaying() {
+        return Err(ApiError(StatusCode::CONFLICT, "Dry run cannot be switched off while a session is replayed".to_string()));
+    }
+    let mut bot_params = state.bot_params.lock().await;
+    if request.enabled && bot_params.paper_exchange.is_none() {
+        let balance = bot_params.user_data.as_ref().map(|user| user.balance).unwrap_or_default();
+        bot_params.paper_exchange = Some(PaperExchange::new(state.execution_model, balance, state.paper_fee_rate));
+        info!(balance, "Dry run: paper trading");
+    }
+    bot_params.runtime.dry_run = request.enabled;
+    warn!(dry_run = request.enabled, "Control API: dry run {}", if request.enabled { "on" } else { "off" });
+    Ok(Json(bot_params.runtime.clone()))
+}
+
+async fn update_risk(State(state): State<ControlState>, Json(update): Json<RiskUpdate>) -> Result<Json<RuntimeSettings>, ApiError> {
+    let mut bot_params = state.bot_params.lock().await;
+    bot_params
+        .runtime
+        .apply_risk_update(&update)
+        .map_err(|e| ApiError(StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    warn!(?update, "Control API: risk parameters updated");
+    Ok(Json(bot_params.runtime.clone()))
+}
+
+async fn update_signal_weights(
+    State(state): State<ControlState>,
+    Json(update): Json<SignalWeightsUpdate>,
+) -> Result<Json<RuntimeSettings>, ApiError> {
+    let mut bot_params = state.bot_params.lock().await;
+    bot_params
+        .runtime
+        .apply_signal_weights_update(&update)
+        .map_err(|e| ApiError(StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    warn!(?update, "Control API: signal weights updated");
+    Ok(Json(bot_params.runtime.clone()))
+}
+
+// Sends the closed paper trades to the signal processing (cooldowns and realized P&L)
+async fn forward_paper_events(closed_trade_tx: &mpsc::Sender<ClosedTradeEvent>, events: Vec<PaperEvent>) -> Vec<String> {
+    let mut closed = Vec::new();
+    for event in events {
+        if let PaperEvent::Closed { id, trade, .. } = event {
+            let closed_trade = ClosedTradeEvent {
+                trade_id: id.clone(),
+                side: trade.side,
+                pl: trade.net_pl(),
+                closed_ts: trade.exit_time,
+            };
+            let _ = closed_trade_tx.send(closed_trade).await;
+            closed.push(id);
+        }
+    }
+    closed
+}
+
+async fn close_one(State(state): State<ControlState>, Path(id): Path<String>) -> Result<Json<serde_json::Value>, ApiError> {
+    let paper_events = {
+        let mut bot_params = state.bot_params.lock().await;
+        if bot_params.runtime.dry_run {
+            let exchange = bot_params
+                .paper_exchange
+                .as_mut()
+                .ok_or_else(|| ApiError(StatusCode::CONFLICT, "Paper exchange is not initialized".to_string()))?;
+            let event = exchange
+                .close_at_market(&id)
+                .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("No paper position {}", id)))?;
+            log_paper_events(std::slice::from_ref(&event), exchange.balance);
+            Some(vec![event])
+        } else {
+            None
+        }
+    };
+    if let Some(events) = paper_events {
+        let closed = forward_paper_events(&state.closed_trade_tx, events).await;
+        return Ok(Json(json!({ "closed": closed })));
+    }
+
+    // Only trades of the bot can be closed
+    let running = own_running_trades(&state.api_url, &state.bot_params)
+        .await
+        .map_err(|e| ApiError(StatusCode::BAD_GATEWAY, e))?;
+    if !running.iter().any(|trade| trade.id == id) {
+        return Err(ApiError(StatusCode::NOT_FOUND, format!("No running trade {} of the bot", id)));
+    }
+
+    match close_trade(&state.api_url, &id).await {
+        Ok(trade) => {
+            warn!(trade_id = %trade.id, pl = trade.pl, "Control API: trade closed");
+            Ok(Json(json!({ "closed": [trade.id], "pl": trade.pl })))
+        }
+        Err(e) => Err(ApiError(StatusCode::BAD_GATEWAY, format!("Error closing trade {}: {}", id, e))),
+    }
+}
+
+/// Closes all positions: the paper positions in dry runs (pending paper orders are canceled),
+/// otherwise the running trades of the bot on LN Markets, one by one. External trades that are
+/// ignored are left open.
+///
+/// # Parameters
+/// - `api_url`: The API base URL.
+/// - `bot_params`: Shared bot params holding the runtime settings and the paper exchange.
+/// - `closed_trade_tx`: Channel for the closed paper trades (cooldowns and realized P&L).
+///
+/// # Returns
+/// - The IDs of the closed trades and their P&L in sats (net P&L for paper trades).
+pub async fn close_all_positions(
+    api_url: &str,
+    bot_params: &Arc<Mutex<BotParams>>,
+    closed_trade_tx: &mpsc::Sender<ClosedTradeEvent>,
+) -> Result<(Vec<String>, f64), String> {
+    let paper_events = {
+        let mut bot_params = bot_params.lock().await;
+        let dry_run = bot_params.runtime.dry_run;
+        match bot_params.paper_exchange.as_mut().filter(|_| dry_run) {
+            Some(exchange) => {
+                let events = exchange.close_all_at_market();
+                log_paper_events(&events, exchange.balance);
+                Some(events)
+            }
+            None => None,
+        }
+    };
+    if let Some(events) = paper_events {
+        let pl = events
+            .iter()
+            .map(|event| match event {
+                PaperEvent::Closed { trade, .. } => trade.net_pl(),
+                PaperEvent::Filled { .. } => 0.0,
+            })
+            .sum();
+        return Ok((forward_paper_events(closed_trade_tx, events).await, pl));
+    }
+
+    let running = own_running_trades(api_url, bot_params).await?;
+
+    let mut closed = Vec::new();
+    let mut pl = 0.0;
+    let mut errors = Vec::new();
+    for trade in running {
+        match close_trade(api_url, &trade.id).await {
+            Ok(trade) => {
+                pl += trade.pl;
+                closed.push(trade.id);
+            }
+            Err(e) => {
+                error!(trade_id = %trade.id, error = %e, "Error closing trade");
+                errors.push(format!("{}: {}", trade.id, e));
+            }
+        }
+    }
+    if closed.is_empty() && !errors.is_empty() {
+        return Err(format!("Error closing trades: {}", errors.join(", ")));
+    }
+    Ok((closed, pl))
+}
+
+async fn close_all(State(state): State<ControlState>) -> Result<Json<serde_json::Value>, ApiError> {
+    let (closed, pl) = close_all_positions(&state.api_url, &state.bot_params, &state.closed_trade_tx)
+        .await
+        .map_err(|e| ApiError(StatusCode::BAD_GATEWAY, e))?;
+    warn!(trades = closed.len(), pl, "Control API: trades closed");
+    Ok(Json(json!({ "closed": closed, "pl": pl })))
+}
+
+/// Router of the control API, every route requires the bearer token of the state.
+///
+/// - `GET /status`, `GET /indicators`, `GET /signals`, `GET /trades`
+/// - `POST /pause`, `POST /resume`, `POST /dry-run` (`{"enabled": bool}`)
+/// - `POST /trades/{id}/close`, `POST /trades/close-all`
+/// - `PUT /risk`, `PUT /signal-weights` (partial updates)
+pub fn control_router(state: ControlState) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/indicators", get(indicators))
+        .route("/signals", get(signals))
+        .route("/trades", get(running_trades))
+        .route("/trades/close-all", post(close_all))
+        .route("/trades/:id/close", post(close_one))
+        .route("/pause", post(pause))
+        .route("/resume", post(resume))
+        .route("/dry-run", post(dry_run))
+        .route("/risk", put(update_risk))
+        .route("/signal-weights", put(update_signal_weights))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
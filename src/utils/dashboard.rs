@@ -0,0 +1,487 @@
+// src/utils/dashboard.rs
+
+use tracing::{error, warn};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::canvas::{Canvas, Line as CanvasLine};
+use ratatui::widgets::{Block, Borders, Cell, List, ListItem, Paragraph, Row, Sparkline, Table};
+use ratatui::Frame;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::Duration;
+use crate::futures::get_ohlcs_history::OhlcHistoryEntry;
+use crate::math::indicator_set::{IndicatorSource, ATR, BOLLINGER_BANDS, EMA, MA, RSI};
+use crate::utils::control_api::{close_all_positions, IndicatorsResponse};
+use crate::utils::get_timestamps::format_timestamp;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::manage_positions::ClosedTradeEvent;
+use crate::utils::metrics::metrics;
+use crate::utils::runtime_settings::SignalRecord;
+
+// Refresh interval of the dashboard
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+// Price samples of the sparkline
+const MAX_PRICE_SAMPLES: usize = 240;
+// Candles of the candle chart
+const MAX_CANDLES: usize = 60;
+// Signals of the signal list
+const MAX_SIGNALS: usize = 8;
+
+/// Running trade as shown in the trades table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashboardTrade {
+    pub id: String,
+    pub is_buy: bool,
+    pub quantity: f64,                   // Quantity in USD
+    pub entry_price: f64,
+    pub liquidation: Option<f64>,        // Liquidation price (LN Markets trades)
+    pub unrealized_pl: f64,              // P&L in sats at the last price
+}
+
+/// Everything the dashboard draws, captured once per refresh.
+#[derive(Debug, Clone, Default)]
+pub struct DashboardSnapshot {
+    pub last_price: Option<f64>,
+    pub prices: Vec<f64>,                // Sampled last prices, oldest first
+    pub candles: Vec<OhlcHistoryEntry>,  // Most recent candles, oldest first
+    pub indicators: Option<IndicatorsResponse>,
+    pub signals: Vec<SignalRecord>,      // Most recent signals, newest first
+    pub trades: Vec<DashboardTrade>,
+    pub paused: bool,
+    pub dry_run: bool,
+    pub ws_connected: bool,
+    pub ws_reconnects: u64,
+    pub tick_rate: f64,                  // Price updates per second
+    pub balance_sats: Option<f64>,
+    pub realized_pl_sats: f64,
+    pub status_message: Option<String>,  // Confirmation prompt or result of the last hotkey
+}
+
+/// P&L of an inverse futures position in sats.
+///
+/// # Parameters
+/// - `is_buy`: Whether the position is long.
+/// - `quantity`: Quantity in USD.
+/// - `entry_price`: Entry price of the position.
+/// - `price`: Current price.
+pub fn calculate_unrealized_pl(is_buy: bool, quantity: f64, entry_price: f64, price: f64) -> f64 {
+    if entry_price <= 0.0 || price <= 0.0 {
+        return 0.0;
+    }
+    let direction = if is_buy { 1.0 } else { -1.0 };
+    direction * quantity * (1.0 / entry_price - 1.0 / price) * 100_000_000.0
+}
+
+/// Distance of the price to the liquidation price, relative to the price (e.g. 0.05 for 5%).
+pub fn calculate_liquidation_distance(price: f64, liquidation: f64) -> f64 {
+    if price <= 0.0 {
+        return 0.0;
+    }
+    (price - liquidation).abs() / price
+}
+
+impl DashboardSnapshot {
+    /// Captures the state of the bot and the metrics.
+    ///
+    /// # Parameters
+    /// - `bot_params`: The bot params (indicators, signals, trades, paper exchange, runtime settings).
+    /// - `prices`: Sampled last prices, oldest first.
+    pub fn capture(bot_params: &BotParams, prices: &VecDeque<f64>) -> Self {
+        let metrics = metrics();
+        let last_price = prices.back().copied();
+        let price = last_price.unwrap_or_default();
+
+        let trades = if bot_params.runtime.dry_run {
+            bot_params
+                .paper_exchange
+                .iter()
+                .flat_map(|exchange| exchange.positions.iter())
+                .map(|position| DashboardTrade {
+                    id: position.id.clone(),
+                    is_buy: position.is_buy,
+                    quantity: position.quantity,
+                    entry_price: position.entry_fill.fill_price,
+                    liquidation: None,
+                    unrealized_pl: calculate_unrealized_pl(position.is_buy, position.quantity, position.entry_fill.fill_price, price),
+                })
+                .collect()
+        } else {
+            bot_params
+                .running_trades
+                .iter()
+                .map(|trade| {
+                    let entry_price = trade.entry_price.unwrap_or(trade.price);
+                    let is_buy = trade.side.is_buy();
+                    DashboardTrade {
+                        id: trade.id.clone(),
+                        is_buy,
+                        quantity: trade.quantity,
+                        entry_price,
+                        liquidation: Some(trade.liquidation),
+                        unrealized_pl: if last_price.is_some() {
+                            calculate_unrealized_pl(is_buy, trade.quantity, entry_price, price)
+                        } else {
+                            trade.pl
+                        },
+                    }
+                })
+                .collect()
+        };
+
+        let candles = bot_params
+            .indicators
+            .as_ref()
+            .map(|indicators| {
+                let skip = indicators.ohlc_data.len().saturating_sub(MAX_CANDLES);
+                indicators.ohlc_data[skip..].to_vec()
+            })
+            .unwrap_or_default();
+
+        Self {
+            last_price,
+            prices: prices.iter().copied().collect(),
+            candles,
+            indicators: bot_params.indicators.as_ref().map(IndicatorsResponse::from),
+            signals: bot_params.last_signals.iter().rev().take(MAX_SIGNALS).cloned().collect(),
+            trades,
+            paused: bot_params.runtime.paused,
+            dry_run: bot_params.runtime.dry_run,
+            ws_connected: metrics.ws_connected.get() == 1,
+            ws_reconnects: metrics.ws_reconnects.get(),
+            tick_rate: metrics.tick_rate.get(),
+            balance_sats: match &bot_params.paper_exchange {
+                Some(exchange) if bot_params.runtime.dry_run => Some(exchange.balance),
+                _ => bot_params.user_data.as_ref().map(|user| user.balance),
+            },
+            realized_pl_sats: metrics.realized_pl_sats.get(),
+            status_message: None,
+        }
+    }
+}
+
+fn pl_style(value: f64) -> Style {
+    if value >= 0.0 {
+        Style::default().fg(Color::Green)
+    } else {
+        Style::default().fg(Color::Red)
+    }
+}
+
+fn format_option(value: Option<f64>, decimals: usize) -> String {
+    value.map(|value| format!("{:.*}", decimals, value)).unwrap_or("-".to_string())
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let connection = if snapshot.ws_connected {
+        Span::styled("● connected", Style::default().fg(Color::Green))
+    } else {
+        Span::styled("● disconnected", Style::default().fg(Color::Red))
+    };
+    let mode = if snapshot.dry_run {
+        Span::styled(" DRY RUN ", Style::default().fg(Color::Black).bg(Color::Magenta))
+    } else {
+        Span::styled(" LIVE ", Style::default().fg(Color::Black).bg(Color::Cyan))
+    };
+    let trading = if snapshot.paused {
+        Span::styled(" PAUSED ", Style::default().fg(Color::Black).bg(Color::Yellow))
+    } else {
+        Span::styled(" TRADING ", Style::default().fg(Color::Black).bg(Color::Green))
+    };
+    let unrealized_pl: f64 = snapshot.trades.iter().map(|trade| trade.unrealized_pl).sum();
+
+    let line = Line::from(vec![
+        Span::styled(format!("{}$ ", format_option(snapshot.last_price, 2)), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("{:.2} ticks/s  ", snapshot.tick_rate)),
+        connection,
+        Span::raw(format!(" ({} reconnects)  ", snapshot.ws_reconnects)),
+        mode,
+        Span::raw(" "),
+        trading,
+        Span::raw(format!("  Balance {} sats  Realized ", format_option(snapshot.balance_sats, 0))),
+        Span::styled(format!("{:.0}", snapshot.realized_pl_sats), pl_style(snapshot.realized_pl_sats)),
+        Span::raw("  Unrealized "),
+        Span::styled(format!("{:.0}", unrealized_pl), pl_style(unrealized_pl)),
+    ]);
+    frame.render_widget(Paragraph::new(line).block(Block::default().borders(Borders::ALL).title("LN Markets Bot")), area);
+}
+
+fn draw_sparkline(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    // Sparklines are drawn from zero, the prices are shifted to their minimum
+    let min = snapshot.prices.iter().copied().fold(f64::INFINITY, f64::min);
+    let data: Vec<u64> = snapshot.prices.iter().map(|price| ((price - min) * 100.0).round() as u64 + 1).collect();
+    let skip = data.len().saturating_sub(area.width.saturating_sub(2) as usize);
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title("Live price"))
+            .data(&data[skip..])
+            .style(Style::default().fg(Color::Cyan)),
+        area,
+    );
+}
+
+fn draw_candles(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let low = snapshot.candles.iter().map(|candle| candle.low).fold(f64::INFINITY, f64::min);
+    let high = snapshot.candles.iter().map(|candle| candle.high).fold(f64::NEG_INFINITY, f64::max);
+    let (low, high) = if low.is_finite() && high > low { (low, high) } else { (0.0, 1.0) };
+    let title = format!("Candles ({:.0}$ - {:.0}$)", low, high);
+
+    let canvas = Canvas::default()
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .x_bounds([0.0, MAX_CANDLES as f64])
+        .y_bounds([low, high])
+        .paint(|ctx| {
+            let offset = MAX_CANDLES.saturating_sub(snapshot.candles.len()) as f64;
+            for (i, candle) in snapshot.candles.iter().enumerate() {
+                let x = offset + i as f64 + 0.5;
+                let color = if candle.close >= candle.open { Color::Green } else { Color::Red };
+                ctx.draw(&CanvasLine::new(x, candle.low, x, candle.high, color));
+                // Body
+                for dx in [-0.25, 0.0, 0.25] {
+                    ctx.draw(&CanvasLine::new(x + dx, candle.open, x + dx, candle.close, color));
+                }
+            }
+        });
+    frame.render_widget(canvas, area);
+}
+
+fn draw_indicators(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let lines = match &snapshot.indicators {
+        Some(indicators) => {
+            let values = &indicators.values;
+            let (bb_lower, bb_middle, bb_upper) = match values.bands(IndicatorSource::Ohlc, BOLLINGER_BANDS) {
+                Some((lower, middle, upper)) => (Some(lower), Some(middle), Some(upper)),
+                None => (None, None, None),
+            };
+            vec![
+                Line::from(format!("RSI        {}", format_option(values.value(IndicatorSource::Ohlc, RSI), 2))),
+                Line::from(format!("ATR        {}", format_option(values.value(IndicatorSource::Ohlc, ATR), 2))),
+                Line::from(format!("BB upper   {}", format_option(bb_upper, 2))),
+                Line::from(format!("BB middle  {}", format_option(bb_middle, 2))),
+                Line::from(format!("BB lower   {}", format_option(bb_lower, 2))),
+                Line::from(format!(
+                    "MA / EMA   {} / {}",
+                    format_option(values.value(IndicatorSource::Ohlc, MA), 2),
+                    format_option(values.value(IndicatorSource::Ohlc, EMA), 2)
+                )),
+                Line::from(format!("ADX        {}", format_option(indicators.adx, 2))),
+                Line::from(format!(
+                    "Regime     {}",
+                    indicators.regime.map(|regime| format!("{:?}", regime)).unwrap_or("-".to_string())
+                )),
+                Line::from(format!("Basis z    {}", format_option(indicators.basis_z_score, 2))),
+            ]
+        }
+        None => vec![Line::from("Indicators not initialized")],
+    };
+    frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Indicators")), area);
+}
+
+fn draw_signals(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let items: Vec<ListItem> = snapshot
+        .signals
+        .iter()
+        .map(|record| {
+            let explanation = &record.explanation;
+            let breakdown = explanation
+                .contributions
+                .iter()
+                .map(|contribution| format!("{} {:+.2}", contribution.indicator, contribution.contribution))
+                .collect::<Vec<String>>()
+                .join("  ");
+            ListItem::new(vec![
+                Line::from(vec![
+                    Span::raw(format!("{} ", format_timestamp(explanation.time))),
+                    Span::styled(
+                        format!("{:<10} {:+.2}", record.signal, explanation.score),
+                        pl_style(explanation.score).add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::from(Span::styled(format!("  {}", breakdown), Style::default().fg(Color::DarkGray))),
+            ])
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title("Signals")), area);
+}
+
+fn draw_trades(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let price = snapshot.last_price.unwrap_or_default();
+    let rows = snapshot.trades.iter().map(|trade| {
+        let liquidation_distance = trade
+            .liquidation
+            .map(|liquidation| format!("{:.2}%", calculate_liquidation_distance(price, liquidation) * 100.0))
+            .unwrap_or("-".to_string());
+        Row::new(vec![
+            Cell::from(trade.id.chars().take(8).collect::<String>()),
+            Cell::from(if trade.is_buy { "buy" } else { "sell" }),
+            Cell::from(format!("{:.0}$", trade.quantity)),
+            Cell::from(format!("{:.2}", trade.entry_price)),
+            Cell::from(format_option(trade.liquidation, 2)),
+            Cell::from(liquidation_distance),
+            Cell::from(Span::styled(format!("{:.0}", trade.unrealized_pl), pl_style(trade.unrealized_pl))),
+        ])
+    });
+    let widths = [
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Length(10),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(10),
+        Constraint::Min(10),
+    ];
+    let header = Row::new(vec!["ID", "Side", "Quantity", "Entry", "Liquidation", "Distance", "P&L (sats)"])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+    let title = format!("Running trades ({})", snapshot.trades.len());
+    frame.render_widget(
+        Table::new(rows, widths).header(header).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, snapshot: &DashboardSnapshot) {
+    let mut spans = vec![Span::styled(
+        "p pause/resume  c close all  q quit",
+        Style::default().fg(Color::DarkGray),
+    )];
+    if let Some(message) = &snapshot.status_message {
+        spans.push(Span::styled(format!("   {}", message), Style::default().fg(Color::Yellow)));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Draws the dashboard: status header, price sparkline and candle chart, indicators, signals with
+/// their score breakdown, running trades and the hotkeys.
+pub fn draw_dashboard(frame: &mut Frame, snapshot: &DashboardSnapshot) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(12), Constraint::Length(8), Constraint::Length(1)])
+        .split(frame.area());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(rows[1]);
+    let charts = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(6), Constraint::Min(6)])
+        .split(columns[0]);
+    let panels = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(11), Constraint::Min(4)])
+        .split(columns[1]);
+
+    draw_header(frame, rows[0], snapshot);
+    draw_sparkline(frame, charts[0], snapshot);
+    draw_candles(frame, charts[1], snapshot);
+    draw_indicators(frame, panels[0], snapshot);
+    draw_signals(frame, panels[1], snapshot);
+    draw_trades(frame, rows[2], snapshot);
+    draw_footer(frame, rows[3], snapshot);
+}
+
+/// Runs the dashboard on the terminal until `q` or Ctrl+C is pressed. Blocking, run it with
+/// `spawn_blocking`. Hotkeys: `p` pauses or resumes trading, `c` closes all trades after
+/// confirming with `y`.
+///
+/// # Parameters
+/// - `api_url`: The API base URL.
+/// - `bot_params`: Shared bot params.
+/// - `closed_trade_tx`: Channel for closed paper trades.
+/// - `handle`: Runtime handle for closing the trades.
+/// - `stop`: Set on shutdown to leave the dashboard.
+pub fn run_dashboard(
+    api_url: Arc<str>,
+    bot_params: Arc<Mutex<BotParams>>,
+    closed_trade_tx: mpsc::Sender<ClosedTradeEvent>,
+    handle: Handle,
+    stop: Arc<AtomicBool>,
+) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut prices: VecDeque<f64> = VecDeque::new();
+    let mut status_message: Option<String> = None;
+    let mut confirm_close_all = false;
+    let (result_tx, result_rx) = std::sync::mpsc::channel::<String>();
+
+    let result = loop {
+        if stop.load(Ordering::Relaxed) {
+            break Ok(());
+        }
+        let price = metrics().last_price.get();
+        if price > 0.0 {
+            prices.push_back(price);
+            if prices.len() > MAX_PRICE_SAMPLES {
+                prices.pop_front();
+            }
+        }
+        if let Ok(message) = result_rx.try_recv() {
+            status_message = Some(message);
+        }
+
+        let mut snapshot = DashboardSnapshot::capture(&bot_params.blocking_lock(), &prices);
+        snapshot.status_message = status_message.clone();
+        if let Err(e) = terminal.draw(|frame| draw_dashboard(frame, &snapshot)) {
+            break Err(e);
+        }
+
+        match event::poll(REFRESH_INTERVAL) {
+            Ok(false) => continue,
+            Ok(true) => {}
+            Err(e) => break Err(e),
+        }
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e),
+        };
+        if confirm_close_all && key.code != KeyCode::Char('y') {
+            confirm_close_all = false;
+            status_message = Some("Close all canceled.".to_string());
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') => break Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok(()),
+            KeyCode::Char('p') => {
+                let mut bot_params = bot_params.blocking_lock();
+                bot_params.runtime.paused = !bot_params.runtime.paused;
+                let message = if bot_params.runtime.paused { "Trading paused." } else { "Trading resumed." };
+                warn!(%message, "Dashboard");
+                status_message = Some(message.to_string());
+            }
+            KeyCode::Char('c') => {
+                confirm_close_all = true;
+                status_message = Some("Close all trades? Press y to confirm.".to_string());
+            }
+            KeyCode::Char('y') if confirm_close_all => {
+                confirm_close_all = false;
+                status_message = Some("Closing all trades...".to_string());
+                let api_url = Arc::clone(&api_url);
+                let bot_params = Arc::clone(&bot_params);
+                let closed_trade_tx = closed_trade_tx.clone();
+                let result_tx = result_tx.clone();
+                handle.spawn(async move {
+                    let message = match close_all_positions(&api_url, &bot_params, &closed_trade_tx).await {
+                        Ok((closed, pl)) => {
+                            warn!(trades = closed.len(), pl, "Dashboard: trades closed");
+                            format!("{} trades closed ({:.0} sats).", closed.len(), pl)
+                        }
+                        Err(e) => {
+                            error!(error = %e, "Dashboard");
+                            e
+                        }
+                    };
+                    let _ = result_tx.send(message);
+                });
+            }
+            _ => {}
+        }
+    };
+
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,288 @@
+// src/utils/filter_signals.rs
+
+use std::fmt;
+use crate::config::SignalConditioning;
+use crate::futures::trade::Side;
+use crate::math::get_signals::Signal;
+
+/// Trade direction of a signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Long,
+    Short,
+}
+
+impl Direction {
+    pub fn from_signal(signal: Signal) -> Option<Self> {
+        match signal {
+            Signal::Buy | Signal::StrongBuy => Some(Direction::Long),
+            Signal::Sell | Signal::StrongSell => Some(Direction::Short),
+            Signal::Hold | Signal::Undefined => None,
+        }
+    }
+
+    pub fn from_side(side: Side) -> Self {
+        match side {
+            Side::Buy => Direction::Long,
+            Side::Sell => Direction::Short,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Direction::Long => 0,
+            Direction::Short => 1,
+        }
+    }
+
+    // Score seen from this direction (positive means in favour)
+    fn directed_score(self, score: f64) -> f64 {
+        match self {
+            Direction::Long => score,
+            Direction::Short => -score,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::Long => write!(f, "Long"),
+            Direction::Short => write!(f, "Short"),
+        }
+    }
+}
+
+/// Why a signal did not open a trade (suppressed by the filter or skipped by the trade creation).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    Paused,                                                   // Trading paused from the control API
+    EntryThreshold { direction: Direction, score: f64, threshold: f64 },
+    Hysteresis { direction: Direction, score: f64, threshold: f64 }, // Direction still entered (exit threshold)
+    Confirmation { direction: Direction, ticks: u32, required_ticks: u32, seconds: f64, required_seconds: u64 },
+    Cooldown { direction: Direction, seconds_left: i64 },     // Cooldown after a losing trade
+    DuplicateCandle(Direction),                               // Same direction already traded within the candle
+    TradeGap { seconds: i64 },                                // Min gap between two trades not reached
+    OrderInFlight,                                            // The order of an earlier signal is not done yet
+    TradeLimit { max: u64 },                                  // Running trades would exceed the market limit
+    Balance,                                                  // Balance does not cover the margin
+    MissingIndicators,                                        // Indicators (ATR) not available yet, e.g. during warm-up
+    InvalidSignal(Signal),                                    // Hold or undefined signal on the trade creation
+    Error(String),                                            // Request or calculation failed
+}
+
+impl SkipReason {
+    /// Label of the `trades_skipped` metric, a fixed set of values.
+    pub fn label(&self) -> &'static str {
+        match self {
+            SkipReason::Paused => "paused",
+            SkipReason::EntryThreshold { .. } => "entry_threshold",
+            SkipReason::Hysteresis { .. } => "hysteresis",
+            SkipReason::Confirmation { .. } => "confirmation",
+            SkipReason::Cooldown { .. } => "cooldown",
+            SkipReason::DuplicateCandle(_) => "duplicate_candle",
+            SkipReason::TradeGap { .. } => "trade_gap",
+            SkipReason::OrderInFlight => "order_in_flight",
+            SkipReason::TradeLimit { .. } => "trade_limit",
+            SkipReason::Balance => "balance",
+            SkipReason::MissingIndicators => "missing_indicators",
+            SkipReason::InvalidSignal(_) => "other",
+            SkipReason::Error(_) => "error",
+        }
+    }
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Paused => write!(f, "trading paused"),
+            SkipReason::EntryThreshold { direction, score, threshold } => {
+                write!(f, "{} score {:.2} below entry threshold {:.2}", direction, score, threshold)
+            }
+            SkipReason::Hysteresis { direction, score, threshold } => write!(
+                f,
+                "{} already entered, score {:.2} has not fallen below exit threshold {:.2}",
+                direction, score, threshold
+            ),
+            SkipReason::Confirmation { direction, ticks, required_ticks, seconds, required_seconds } => write!(
+                f,
+                "{} awaiting confirmation ({}/{} ticks, {:.1}/{}s)",
+                direction, ticks, required_ticks, seconds, required_seconds
+            ),
+            SkipReason::Cooldown { direction, seconds_left } => {
+                write!(f, "{} cooldown after losing trade ({}s left)", direction, seconds_left)
+            }
+            SkipReason::DuplicateCandle(direction) => write!(f, "duplicate {} signal within the same candle", direction),
+            SkipReason::TradeGap { seconds } => write!(f, "trade gap of {}s not reached", seconds),
+            SkipReason::OrderInFlight => write!(f, "order of an earlier signal in flight"),
+            SkipReason::TradeLimit { max } => write!(f, "limit of {} running trades reached", max),
+            SkipReason::Balance => write!(f, "insufficient balance for creating a trade"),
+            SkipReason::MissingIndicators => write!(f, "indicators (ATR) not available yet"),
+            SkipReason::InvalidSignal(signal) => write!(f, "no valid trading signal ({})", signal),
+            SkipReason::Error(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Result of the signal conditioning for a single signal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterDecision {
+    Actionable(Direction),   // The signal may open a trade
+    Suppressed(SkipReason),  // The signal was suppressed, with the reason
+    NoSignal,                // Hold or undefined signal, nothing to act on
+}
+
+/// Conditions raw signals before they may open a trade:
+/// - a signal must persist for `confirmation_ticks` ticks or `confirmation_seconds` seconds
+/// - entry and exit thresholds are separate (hysteresis): once a direction was entered, it can only
+///   be entered again after its score fell below the exit threshold
+/// - cooldowns are per direction and start after a losing trade
+/// - duplicate signals of the same direction within one candle are suppressed
+/// - a global minimum gap between two trades
+///
+/// Only created trades enter a direction, start the trade gap and mark the candle (`record_trade`).
+pub struct SignalFilter {
+    settings: SignalConditioning,
+    trade_gap_ms: i64,
+    candle_ms: i64,
+    active: Option<Direction>,               // Direction entered last (hysteresis)
+    pending: Option<(Direction, i64, u32)>,  // Direction awaiting confirmation: (direction, first seen, ticks)
+    cooldown_until: [Option<i64>; 2],        // End of the loss cooldown per direction
+    last_candle: [Option<i64>; 2],           // Candle of the last created trade per direction
+    last_trade_ms: Option<i64>,              // Time of the last created trade
+    in_flight: bool,                         // An actionable signal awaits its order
+}
+
+impl SignalFilter {
+    /// Creates a new filter.
+    ///
+    /// # Parameters
+    /// - `settings`: Thresholds, confirmation window and cooldowns.
+    /// - `trade_gap_seconds`: Minimum gap between two actionable signals.
+    /// - `candle_seconds`: Candle length used to suppress duplicate signals.
+    pub fn new(settings: SignalConditioning, trade_gap_seconds: u64, candle_seconds: u64) -> Self {
+        Self {
+            settings,
+            trade_gap_ms: trade_gap_seconds as i64 * 1000,
+            candle_ms: (candle_seconds as i64 * 1000).max(1),
+            active: None,
+            pending: None,
+            cooldown_until: [None, None],
+            last_candle: [None, None],
+            last_trade_ms: None,
+            in_flight: false,
+        }
+    }
+
+    /// Evaluates a signal at `time_ms` (timestamp of the price the signal was derived from). An
+    /// actionable signal suppresses the next ones until its order is done (`record_trade` or
+    /// `release_trade`).
+    pub fn evaluate(&mut self, signal: Signal, score: f64, time_ms: i64) -> FilterDecision {
+        // Hysteresis: release the active direction once its score fell below the exit threshold
+        if let Some(active) = self.active {
+            if active.directed_score(score) < self.settings.exit_threshold {
+                self.active = None;
+            }
+        }
+
+        let Some(direction) = Direction::from_signal(signal) else {
+            self.pending = None;
+            return FilterDecision::NoSignal;
+        };
+
+        if direction.directed_score(score) < self.settings.entry_threshold {
+            self.pending = None;
+            return FilterDecision::Suppressed(SkipReason::EntryThreshold {
+                direction,
+                score,
+                threshold: self.settings.entry_threshold,
+            });
+        }
+
+        if self.active == Some(direction) {
+            return FilterDecision::Suppressed(SkipReason::Hysteresis {
+                direction,
+                score,
+                threshold: self.settings.exit_threshold,
+            });
+        }
+
+        // Confirmation window
+        let (first_seen, ticks) = match self.pending {
+            Some((pending, first_seen, ticks)) if pending == direction => (first_seen, ticks + 1),
+            _ => (time_ms, 1),
+        };
+        self.pending = Some((direction, first_seen, ticks));
+
+        let persisted_ms = time_ms - first_seen;
+        let confirmation_ms = self.settings.confirmation_seconds as i64 * 1000;
+        if ticks < self.settings.confirmation_ticks && persisted_ms < confirmation_ms {
+            return FilterDecision::Suppressed(SkipReason::Confirmation {
+                direction,
+                ticks,
+                required_ticks: self.settings.confirmation_ticks,
+                seconds: persisted_ms as f64 / 1000.0,
+                required_seconds: self.settings.confirmation_seconds,
+            });
+        }
+
+        // Cooldown after a losing trade
+        if let Some(until) = self.cooldown_until[direction.index()] {
+            if time_ms < until {
+                return FilterDecision::Suppressed(SkipReason::Cooldown {
+                    direction,
+                    seconds_left: (until - time_ms + 999) / 1000,
+                });
+            }
+        }
+
+        // Duplicate signal within the same candle
+        let candle = time_ms.div_euclid(self.candle_ms);
+        if self.last_candle[direction.index()] == Some(candle) {
+            return FilterDecision::Suppressed(SkipReason::DuplicateCandle(direction));
+        }
+
+        // Global gap between two trades
+        if let Some(last_trade_ms) = self.last_trade_ms {
+            if time_ms - last_trade_ms < self.trade_gap_ms {
+                return FilterDecision::Suppressed(SkipReason::TradeGap { seconds: self.trade_gap_ms / 1000 });
+            }
+        }
+
+        if self.in_flight {
+            return FilterDecision::Suppressed(SkipReason::OrderInFlight);
+        }
+        self.in_flight = true;
+
+        FilterDecision::Actionable(direction)
+    }
+
+    /// Records the trade created from an actionable signal at `time_ms`: enters the direction
+    /// (hysteresis), marks the candle and starts the trade gap.
+    pub fn record_trade(&mut self, direction: Direction, time_ms: i64) {
+        self.in_flight = false;
+        self.active = Some(direction);
+        self.pending = None;
+        self.last_candle[direction.index()] = Some(time_ms.div_euclid(self.candle_ms));
+        self.last_trade_ms = Some(time_ms);
+    }
+
+    /// Releases an actionable signal that created no trade, the filter state is left as it was.
+    pub fn release_trade(&mut self) {
+        self.in_flight = false;
+    }
+
+    /// Starts the cooldown of `direction` after a losing trade closed at `closed_ms`.
+    ///
+    /// # Returns
+    /// - The end of the cooldown (ms), `None` if cooldowns are disabled.
+    pub fn record_loss(&mut self, direction: Direction, closed_ms: i64) -> Option<i64> {
+        if self.settings.loss_cooldown_seconds == 0 {
+            return None;
+        }
+        let until = closed_ms + self.settings.loss_cooldown_seconds as i64 * 1000;
+        let cooldown = &mut self.cooldown_until[direction.index()];
+        *cooldown = Some(cooldown.map_or(until, |current| current.max(until)));
+        *cooldown
+    }
+}
@@ -1,6 +1,7 @@
 // src/utils/get_headers.rs
 
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::Request;
 use chrono::Utc;
 use crate::utils::get_signature::generate_signature;
 use std::error::Error;
@@ -26,23 +27,52 @@ pub fn get_headers(endpoint: &str, method: &str, data: Option<&str>,) -> Result<
     let api_secret = env::var("LN_API_SECRET").expect("LN_API_SECRET not set");
     let passphrase = env::var("LN_API_PASSPHRASE").expect("LN_API_PASSPHRASE not set");
 
-    let timestamp = Utc::now().timestamp_millis();
-    
     let mut headers = HeaderMap::new();
     headers.insert("LNM-ACCESS-KEY", HeaderValue::from_str(&api_key)?);
     headers.insert("LNM-ACCESS-PASSPHRASE", HeaderValue::from_str(&passphrase)?);
-    headers.insert("LNM-ACCESS-TIMESTAMP", HeaderValue::from_str(&timestamp.to_string())?);
+    insert_signature(&mut headers, &api_secret, endpoint, method, data)?;
 
-    // Generate the signature for the request
-    let signature = generate_signature(&api_secret, timestamp, method, endpoint, data);
-    headers.insert("LNM-ACCESS-SIGNATURE", HeaderValue::from_str(&signature)?);
-    
     // Add the User-Agent header
     headers.insert(USER_AGENT, HeaderValue::from_static(USER_AGENT_VALUE));
     
     Ok(headers)
 }
 
+// Adds the timestamp and the signature of the request data at the current time
+fn insert_signature(headers: &mut HeaderMap, api_secret: &str, endpoint: &str, method: &str, data: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let timestamp = Utc::now().timestamp_millis();
+    headers.insert("LNM-ACCESS-TIMESTAMP", HeaderValue::from_str(&timestamp.to_string())?);
+
+    // Generate the signature for the request
+    let signature = generate_signature(api_secret, timestamp, method, endpoint, data);
+    headers.insert("LNM-ACCESS-SIGNATURE", HeaderValue::from_str(&signature)?);
+    Ok(())
+}
+
+/// Signs a built request again with the current time. LN Markets rejects old timestamps, requests
+/// that waited for the rate limiter or a `Retry-After` are signed right before they are sent.
+/// Requests without a signature (webhooks) are left unchanged.
+///
+/// # Parameters:
+/// - `request`: The request, signed with the path and the query (GET, DELETE) or the body.
+pub fn refresh_signature(request: &mut Request) -> Result<(), Box<dyn Error>> {
+    if !request.headers().contains_key("LNM-ACCESS-SIGNATURE") {
+        return Ok(());
+    }
+    let api_secret = env::var("LN_API_SECRET")?;
+
+    let method = request.method().as_str().to_string();
+    let endpoint = request.url().path().to_string();
+    let data = match method.as_str() {
+        "POST" | "PUT" => request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| String::from_utf8_lossy(body).to_string()),
+        _ => request.url().query().map(str::to_string),
+    };
+    insert_signature(request.headers_mut(), &api_secret, &endpoint, &method, data.as_deref())
+}
+
 /// Encodes query parameters into a URL query string.
 /// 
 /// # Parameters
@@ -2,9 +2,12 @@ use reqwest::header::HeaderMap;
 use serde::Deserialize;
 use std::error::Error;
 use crate::utils::get_headers::get_headers;
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::session_recorder::record_response;
+use crate::utils::session_replay::replayed_response;
 
 /// Struct representing the user data received from the API.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[allow(dead_code)]
 pub struct User {
     pub uid: String,                   // Unique identifier for the user.
@@ -41,18 +44,25 @@ pub struct User {
 pub async fn get_user(
     api_url: &str,
 ) -> Result<User, Box<dyn Error>> {
+    // Replay: recorded response of the session
+    if let Some(body) = replayed_response("user") {
+        return Ok(serde_json::from_str(&body)?);
+    }
+
     let headers: HeaderMap = get_headers("/v2/user", "GET", None)?;
 
-    let client = reqwest::Client::new();
+    let client = http_client();
     
     let response = client
         .get(format!("{}/user", api_url)) 
         .headers(headers)                     
-        .send()           
+        .send_metered("user")           
         .await?;
 
     if response.status().is_success() {
-        let user_data = response.json::<User>().await?;
+        let body = response.text().await?;
+        record_response("user", &body);
+        let user_data: User = serde_json::from_str(&body)?;
         Ok(user_data)
     } else {
         let error: Box<dyn Error> = Box::new(response.error_for_status().unwrap_err());
@@ -0,0 +1,93 @@
+// src/utils/http_client.rs
+
+use tracing::warn;
+use std::future::Future;
+use std::sync::OnceLock;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use tokio::time::{sleep, Instant};
+use crate::utils::get_headers::refresh_signature;
+use crate::utils::metrics::metrics;
+use crate::utils::rate_limiter::{endpoint_weight, rate_limiter, retry_after, WEBHOOK_ENDPOINT};
+
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Shared HTTP client of the REST functions (connection pool is reused between requests).
+pub fn http_client() -> &'static Client {
+    CLIENT.get_or_init(Client::new)
+}
+
+/// Sends REST requests through the shared rate limiter and metrics (latency, errors and rate-limit
+/// hits per endpoint).
+pub trait MeteredRequest {
+    /// Waits for the request budget, signs the request (LN Markets) and sends it, its metrics are
+    /// recorded under `endpoint`. A 429 response of LN Markets blocks all LN Markets requests for its
+    /// `Retry-After`, a 429 of a webhook only delays the webhook. The request is retried (if its body
+    /// can be cloned) up to the configured retries and signed again before every retry.
+    ///
+    /// # Parameters
+    /// - `endpoint`: Low-cardinality endpoint name for the metrics and the weight (e.g. "futures/ticker").
+    ///
+    /// # Returns
+    /// - The response (also for unsuccessful status codes), or the transport error.
+    fn send_metered(self, endpoint: &'static str) -> impl Future<Output = Result<Response, reqwest::Error>> + Send;
+}
+
+impl MeteredRequest for RequestBuilder {
+    async fn send_metered(self, endpoint: &'static str) -> Result<Response, reqwest::Error> {
+        let metrics = metrics();
+        let limiter = rate_limiter();
+        let weight = endpoint_weight(endpoint);
+        // Webhooks have their own limits and must not block the LN Markets requests
+        let shared = endpoint != WEBHOOK_ENDPOINT;
+        let mut request = self;
+        let mut retries = 0;
+
+        loop {
+            let retry = request.try_clone();
+            limiter.acquire(weight).await;
+
+            let (client, built) = request.build_split();
+            let start = Instant::now();
+            let result = match built {
+                Ok(mut built) => {
+                    if let Err(e) = refresh_signature(&mut built) {
+                        warn!(endpoint, error = %e, "Error signing request");
+                    }
+                    client.execute(built).await
+                }
+                Err(e) => Err(e),
+            };
+            metrics
+                .rest_request_duration
+                .with_label_values(&[endpoint])
+                .observe(start.elapsed().as_secs_f64());
+
+            match &result {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    metrics.rest_rate_limit_hits.with_label_values(&[endpoint]).inc();
+                    metrics.rest_errors.with_label_values(&[endpoint]).inc();
+
+                    let wait = retry_after(response.headers());
+                    if shared {
+                        limiter.block_until(Instant::now() + wait);
+                    }
+                    if let Some(next) = retry.filter(|_| retries < limiter.settings().max_retries) {
+                        retries += 1;
+                        warn!(endpoint, "Rate limited, retrying in {:.1}s ({}/{})", wait.as_secs_f64(), retries, limiter.settings().max_retries);
+                        if !shared {
+                            sleep(wait).await;
+                        }
+                        request = next;
+                        continue;
+                    }
+                }
+                Ok(response) if !response.status().is_success() => {
+                    metrics.rest_errors.with_label_values(&[endpoint]).inc();
+                }
+                Ok(_) => {}
+                Err(_) => metrics.rest_errors.with_label_values(&[endpoint]).inc(),
+            }
+            return result;
+        }
+    }
+}
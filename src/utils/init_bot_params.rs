@@ -1,10 +1,17 @@
 // src/math/init_bot_params.rs
 
+use tracing::error;
 use crate::utils::get_user::{get_user, User};
 use crate::futures::ticker::{get_futures_ticker, FuturesTicker};
 use crate::futures::get_market::{get_market, FuturesMarket};
-use crate::math::get_indicators::{get_indicators, Indicators};
-use crate::futures::get_trades::{get_trades, GetTradesParams, TradeEntry};
+use crate::math::get_indicators::{get_indicators, IndicatorParams, Indicators};
+use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Trade, TradeStatus};
+use crate::utils::position_group::PositionGroup;
+use std::collections::{HashSet, VecDeque};
+use crate::utils::paper_exchange::PaperExchange;
+use crate::utils::reconcile_state::TradeOwnership;
+use crate::utils::runtime_settings::{RuntimeSettings, SignalRecord};
 
 /// Struct to hold all initialized parameters.
 #[allow(dead_code)]
@@ -13,7 +20,14 @@ pub struct BotParams {
     pub ticker_data: Option<FuturesTicker>,
     pub market_data: Option<FuturesMarket>,
     pub indicators: Option<Indicators>,
-    pub trades: Option<Vec<TradeEntry>>,
+    pub trades: Option<Vec<Trade>>,
+    pub running_trades: Vec<Trade>,       // Running trades of the last position check
+    pub position_groups: Vec<PositionGroup>,
+    pub paper_exchange: Option<PaperExchange>, // Simulated exchange of dry runs
+    pub runtime: RuntimeSettings,              // Settings changed at runtime through the control API
+    pub last_signals: VecDeque<SignalRecord>,  // Most recent signals, oldest first
+    pub ignored_trade_ids: HashSet<String>,    // External trades the bot leaves alone
+    pub trade_ownership: TradeOwnership,       // Tells the trades of the bot from external trades
 }
 
 /// Initialize bot parameters by fetching user data, market data, ticker data,
@@ -21,33 +35,23 @@ pub struct BotParams {
 ///
 /// # Parameters:
 /// - `api_url`: The API base URL.
-/// - `range`: The range parameter for the OHLC data (e.g., "1D" for daily).
-/// - `ma_period`, `ema_period`, `bb_period`, `rsi_period`, `atr_period`: Indicator parameters.
-/// - `bb_std_dev_multiplier`: Multiplier for Bollinger Bands.
-/// - `trade_type`: Type of trades to fetch (e.g., "running", "open", "closed").
+/// - `indicator_params`: Timeframe, data range, indicator registry and market regime periods.
+/// - `trade_type`: Status of the trades to fetch (open, running or closed).
+/// - `runtime`: Initial runtime settings (pause, dry run, risk and signal weights).
 ///
 /// # Returns:
 /// - A `BotParams` struct containing the initialized values.
 pub async fn init_bot_params(
     api_url: &str,
-    range: &str,
-    from: Option<i64>,
-    to: Option<i64>,
-    ma_period: usize,
-    ema_period: usize,
-    bb_period: usize,
-    bb_std_dev_multiplier: f64,
-    rsi_period: usize,
-    atr_period: usize,
-    trade_type: &str,
-    include_price_data: bool, // Flag for including price data
-    include_index_data: bool, // Flag for including index data
+    indicator_params: &IndicatorParams<'_>,
+    trade_type: TradeStatus,
+    runtime: RuntimeSettings,
 ) -> Result<BotParams, Box<dyn std::error::Error>> {
     // Initialize user data
     let user_data = match get_user(api_url).await {
         Ok(user) => Some(user),
         Err(e) => {
-            eprintln!("Error fetching user data: {}", e);
+            error!(error = %e, "Error fetching user data");
             None
         }
     };
@@ -56,7 +60,7 @@ pub async fn init_bot_params(
     let ticker_data = match get_futures_ticker(api_url).await {
         Ok(ticker) => Some(ticker),
         Err(e) => {
-            eprintln!("Error fetching futures ticker: {}", e);
+            error!(error = %e, "Error fetching futures ticker");
             None
         }
     };
@@ -65,29 +69,16 @@ pub async fn init_bot_params(
     let market_data = match get_market(api_url).await {
         Ok(market) => Some(market),
         Err(e) => {
-            eprintln!("Error fetching market data: {}", e);
+            error!(error = %e, "Error fetching market data");
             None
         }
     };
 
     // Initialize indicators
-    let indicators = match get_indicators(
-        api_url,
-        range,
-        from,
-        to,
-        ma_period,
-        ema_period,
-        bb_period,
-        bb_std_dev_multiplier,
-        rsi_period,
-        atr_period,
-        include_price_data,
-        include_index_data
-    ).await {
+    let indicators = match get_indicators(api_url, indicator_params).await {
         Ok(indicators) => Some(indicators),
         Err(e) => {
-            eprintln!("Error fetching indicators: {}", e);
+            error!(error = %e, "Error fetching indicators");
             None
         }
     };
@@ -103,7 +94,7 @@ pub async fn init_bot_params(
     let trades = match get_trades(api_url, Some(trade_params)).await {
         Ok(trades) => Some(trades),
         Err(e) => {
-            eprintln!("Error fetching trades: {}", e);
+            error!(error = %e, "Error fetching trades");
             None
         }
     };
@@ -114,5 +105,12 @@ pub async fn init_bot_params(
         market_data,
         indicators,
         trades,
+        running_trades: Vec::new(),
+        position_groups: Vec::new(),
+        paper_exchange: None,
+        runtime,
+        last_signals: VecDeque::new(),
+        ignored_trade_ids: HashSet::new(),
+        trade_ownership: TradeOwnership::default(),
     })
 }
@@ -0,0 +1,177 @@
+// src/utils/logging.rs
+
+use std::error::Error;
+use std::str::FromStr;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::fmt::format::{self, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::{FmtContext, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+/// Output format of a log sink.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,                              // Human readable, colored on the console
+    Json,                                // One JSON object per event (for ingestion)
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pretty" | "text" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Unknown log format: {} (pretty, json)", s)),
+        }
+    }
+}
+
+/// Rotation of the log files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl FromStr for LogRotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hourly" => Ok(LogRotation::Hourly),
+            "daily" => Ok(LogRotation::Daily),
+            "never" => Ok(LogRotation::Never),
+            _ => Err(format!("Unknown log rotation: {} (hourly, daily, never)", s)),
+        }
+    }
+}
+
+/// Configuration of the log sinks.
+#[derive(Debug, Clone)]
+pub struct LoggingSettings {
+    pub filter: String,                  // Level per module, e.g. "info,trading_backend::utils::connect_ws=debug"
+    pub console: bool,                   // Whether logs are written to the console (off while the dashboard draws the terminal)
+    pub console_format: LogFormat,
+    pub log_dir: Option<String>,         // Directory of the rolling log files, `None` disables them
+    pub file_prefix: String,             // File name prefix of the log files
+    pub file_format: LogFormat,
+    pub rotation: LogRotation,
+}
+
+/// Console format of the pretty sinks: time, level, spans, the message colored by level and
+/// the fields of the event as `key=value`. Colors are only written if the sink supports ANSI.
+pub struct ConsoleFormat;
+
+impl<S, N> FormatEvent<S, N> for ConsoleFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: format::Writer<'_>, event: &Event<'_>) -> std::fmt::Result {
+        let ansi = writer.has_ansi_escapes();
+        let level = *event.metadata().level();
+        let color = match level {
+            Level::ERROR => "31",                                  // Red
+            Level::WARN => "33",                                   // Yellow
+            Level::INFO => "32",                                   // Green
+            Level::DEBUG => "34",                                  // Blue
+            Level::TRACE => "35",                                  // Magenta
+        };
+
+        write!(writer, "{} ", paint(&chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f").to_string(), "2", ansi))?;
+        write!(writer, "{} ", paint(&format!("{:>5}", level), &format!("1;{}", color), ansi))?;
+
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                write!(writer, "{}", paint(span.name(), "1", ansi))?;
+                if let Some(fields) = span.extensions().get::<FormattedFields<N>>().filter(|fields| !fields.is_empty()) {
+                    write!(writer, "{{{}}}", fields)?;
+                }
+                write!(writer, ": ")?;
+            }
+        }
+
+        let mut visitor = ConsoleVisitor::default();
+        event.record(&mut visitor);
+        write!(writer, "{}", paint(&visitor.message, color, ansi))?;
+        for (name, value) in &visitor.fields {
+            write!(writer, " {}{}", paint(&format!("{}=", name), "2;3", ansi), value)?;
+        }
+        writeln!(writer)
+    }
+}
+
+// Wraps the text in the ANSI escape sequence of the style
+fn paint(text: &str, style: &str, ansi: bool) -> String {
+    if ansi {
+        format!("\x1b[{}m{}\x1b[0m", style, text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Collects the message and the remaining fields of an event
+#[derive(Default)]
+struct ConsoleVisitor {
+    message: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Visit for ConsoleVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name(), format!("{:?}", value)));
+        }
+    }
+}
+
+/// Installs the global tracing subscriber: console output (colored or JSON) and optional rolling log files.
+///
+/// # Returns
+/// - The guard of the file writer, which must be kept alive to flush the log files.
+pub fn init_logging(settings: &LoggingSettings) -> Result<Option<WorkerGuard>, Box<dyn Error>> {
+    let filter = EnvFilter::try_new(&settings.filter)?;
+
+    let console = settings.console.then(|| match settings.console_format {
+        LogFormat::Pretty => fmt::layer().event_format(ConsoleFormat).boxed(),
+        LogFormat::Json => fmt::layer().json().with_ansi(false).boxed(),
+    });
+
+    let (file, guard) = match &settings.log_dir {
+        Some(log_dir) => {
+            let rotation = match settings.rotation {
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Daily => Rotation::DAILY,
+                LogRotation::Never => Rotation::NEVER,
+            };
+            let appender = RollingFileAppender::new(rotation, log_dir, &settings.file_prefix);
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = match settings.file_format {
+                LogFormat::Pretty => fmt::layer().event_format(ConsoleFormat).with_ansi(false).with_writer(writer).boxed(),
+                LogFormat::Json => fmt::layer().json().with_ansi(false).with_writer(writer).boxed(),
+            };
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry().with(filter).with(console).with(file).try_init()?;
+    Ok(guard)
+}
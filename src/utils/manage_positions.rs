@@ -0,0 +1,324 @@
+// src/utils/manage_positions.rs
+
+use tracing::{error, info, warn};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{self, Duration};
+use crate::config::MarginPolicy;
+use crate::futures::add_margin::add_margin;
+use crate::futures::cash_in::cash_in;
+use crate::futures::close_trade::close_trade;
+use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Side, Trade, TradeStatus};
+use crate::futures::ticker::get_futures_ticker;
+use crate::math::get_margin_policy::{calculate_cash_in_amount, calculate_margin_topup};
+use crate::utils::get_user::get_user;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::log_bot_params::log_position_group;
+use crate::utils::metrics::metrics;
+use crate::utils::reconcile_state::own_trades;
+use crate::utils::shutdown::Shutdown;
+
+/// A trade that closed since the last position check.
+#[derive(Debug, Clone)]
+pub struct ClosedTradeEvent {
+    pub trade_id: String,      // LN Markets trade ID
+    pub side: Side,            // Buy or sell
+    pub pl: f64,               // Net P&L in sats (fees deducted)
+    pub closed_ts: i64,        // Timestamp (ms) when the trade was closed
+}
+
+/// Revalidates running trades on interval and keeps the position groups in sync with the API.
+/// Every running trade is classified against the trade journal and the state, external trades
+/// opened at any time are adopted or ignored according to the policy of the reconciliation.
+/// Child trades that closed on the server (takeprofit, stoploss or liquidation) are updated with their
+/// final P&L. For groups with emulated partial closes, children are closed when their target is hit.
+/// The margin policy tops up trades close to liquidation and cashes in profit above the threshold.
+/// Every trade that closed since the last check is sent to `closed_trade_tx`. Running trades, margin,
+/// unrealized P&L and balance are recorded in the metrics (by the price loop when paper trading).
+///
+/// # Parameters:
+/// - `api_url`: The API base URL.
+/// - `bot_params`: Shared bot params holding the position groups.
+/// - `interval`: Interval for the position checks.
+/// - `margin_policy`: Margin top up and cash-in rules for running trades.
+/// - `closed_trade_tx`: Channel for trades that closed since the last check.
+/// - `shutdown`: Stops the checks.
+pub async fn manage_positions(
+    api_url: Arc<str>,
+    bot_params: Arc<Mutex<BotParams>>,
+    interval: Duration,
+    margin_policy: MarginPolicy,
+    closed_trade_tx: mpsc::Sender<ClosedTradeEvent>,
+    mut shutdown: Shutdown,
+) {
+    let mut interval_timer = time::interval(interval);
+    let policy_enabled = margin_policy.enable_margin_topup || margin_policy.enable_cash_in;
+
+    // Running trades of the last check: (id, creation timestamp)
+    let mut known_running: Vec<(String, u64)> = Vec::new();
+
+    loop {
+        // Stop between checks, never in the middle of a close or margin update
+        tokio::select! {
+            _ = interval_timer.tick() => {}
+            _ = shutdown.wait() => break,
+        }
+
+        let running_params = GetTradesParams {
+            r#type: TradeStatus::Running,
+            from: None,
+            to: None,
+            limit: None,
+        };
+        let running_trades = match get_trades(&api_url, Some(running_params)).await {
+            Ok(trades) => trades,
+            Err(e) => {
+                error!(error = %e, "Error fetching running trades");
+                continue;
+            }
+        };
+
+        // Trades of the bot (journal and state), external trades are adopted or left alone
+        let running_trades = own_trades(&bot_params, running_trades).await;
+        let paper_trading = bot_params.lock().await.runtime.dry_run;
+
+        // Account metrics (the price loop records them for the paper exchange)
+        let user_data = if paper_trading {
+            None
+        } else {
+            metrics().record_running_trades(&running_trades);
+            match get_user(&api_url).await {
+                Ok(user) => Some(user),
+                Err(e) => {
+                    error!(error = %e, "Error fetching user data");
+                    None
+                }
+            }
+        };
+
+        // The lock is only held to read and apply state, never across requests
+        let (disappeared, emulating) = {
+            let mut bot_params = bot_params.lock().await;
+            if let Some(user) = user_data {
+                metrics().balance_sats.set(user.balance);
+                bot_params.user_data = Some(user);
+            }
+
+            // Update running children (unrealized P&L)
+            for group in bot_params.position_groups.iter_mut() {
+                for trade in &running_trades {
+                    group.update_child(trade);
+                }
+            }
+
+            // Trades and children that are no longer running were closed on the server
+            let mut disappeared: Vec<(String, u64)> = known_running
+                .iter()
+                .filter(|(id, _)| !running_trades.iter().any(|trade| &trade.id == id))
+                .cloned()
+                .collect();
+            for group in &bot_params.position_groups {
+                for child in group.children.iter().filter(|child| child.running) {
+                    if !running_trades.iter().any(|trade| trade.id == child.trade_id)
+                        && !disappeared.iter().any(|(id, _)| id == &child.trade_id)
+                    {
+                        disappeared.push((child.trade_id.clone(), group.creation_ts as u64));
+                    }
+                }
+            }
+
+            let emulating = bot_params.position_groups.iter().any(|group| group.emulate_closes && !group.is_closed());
+            (disappeared, emulating)
+        };
+
+        if !disappeared.is_empty() {
+            let oldest_ts = disappeared.iter().map(|(_, creation_ts)| *creation_ts).min().unwrap_or_default();
+
+            let closed_params = GetTradesParams {
+                r#type: TradeStatus::Closed,
+                from: Some(oldest_ts),
+                to: None,
+                limit: Some(1000),
+            };
+            let closed_trades = match get_trades(&api_url, Some(closed_params)).await {
+                Ok(trades) => trades,
+                Err(e) => {
+                    error!(error = %e, "Error fetching closed trades");
+                    Vec::new()
+                }
+            };
+            {
+                let mut bot_params = bot_params.lock().await;
+                for group in bot_params.position_groups.iter_mut() {
+                    for trade in &closed_trades {
+                        group.update_child(trade);
+                    }
+                }
+            }
+
+            for trade in closed_trades.iter().filter(|trade| disappeared.iter().any(|(id, _)| id == &trade.id)) {
+                let event = ClosedTradeEvent {
+                    trade_id: trade.id.clone(),
+                    side: trade.side,
+                    pl: trade.pl - (trade.opening_fee + trade.closing_fee + trade.sum_carry_fees),
+                    closed_ts: trade.closed_ts.unwrap_or(trade.last_update_ts) as i64,
+                };
+                if closed_trade_tx.send(event).await.is_err() {
+                    error!("Error sending closed trade event");
+                }
+            }
+        }
+        known_running = running_trades.iter().map(|trade| (trade.id.clone(), trade.creation_ts)).collect();
+
+        // Last price for emulated partial closes and the margin policy
+        let last_price = if emulating || (policy_enabled && !running_trades.is_empty()) {
+            match get_futures_ticker(&api_url).await {
+                Ok(ticker) => Some(ticker.last_price),
+                Err(e) => {
+                    error!(error = %e, "Error fetching futures ticker");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Emulated partial closes: close children whose target is hit
+        let mut closed_by_bot: Vec<String> = Vec::new();
+        if let Some(last_price) = last_price.filter(|_| emulating) {
+            let targets_hit: Vec<String> = {
+                let bot_params = bot_params.lock().await;
+                bot_params
+                    .position_groups
+                    .iter()
+                    .filter(|group| group.emulate_closes)
+                    .flat_map(|group| group.targets_hit(last_price))
+                    .collect()
+            };
+
+            let mut closed_children: Vec<(String, f64, f64)> = Vec::new();
+            for trade_id in targets_hit {
+                let closed = match close_trade(&api_url, &trade_id).await {
+                    Ok(closed) => closed,
+                    Err(e) => {
+                        error!(%trade_id, error = %e, "Error closing child trade");
+                        continue;
+                    }
+                };
+                info!(%trade_id, price = last_price, pl = closed.pl, "Target hit, child trade closed");
+                let fees = closed.opening_fee + closed.closing_fee + closed.sum_carry_fees;
+                closed_children.push((trade_id.clone(), closed.pl, fees));
+                closed_by_bot.push(trade_id);
+            }
+
+            let mut bot_params = bot_params.lock().await;
+            for (trade_id, pl, fees) in &closed_children {
+                for group in bot_params.position_groups.iter_mut() {
+                    group.close_child(trade_id, *pl, *fees);
+                }
+            }
+        }
+
+        // Margin policy: rescue trades close to liquidation, de-risk trades in profit (never in dry
+        // runs, the account is not traded)
+        if let Some(last_price) = last_price.filter(|_| policy_enabled && !paper_trading) {
+            let trades: Vec<&Trade> = running_trades
+                .iter()
+                .filter(|trade| !closed_by_bot.contains(&trade.id))
+                .collect();
+            apply_margin_policy(&api_url, &trades, last_price, &margin_policy).await;
+        }
+
+        let mut bot_params = bot_params.lock().await;
+        for group in &bot_params.position_groups {
+            log_position_group(group);
+        }
+
+        // Drop groups without running children
+        bot_params.position_groups.retain(|group| {
+            if group.is_closed() {
+                info!(position = &group.id[..8], "Position closed");
+            }
+            !group.is_closed()
+        });
+        bot_params.running_trades = running_trades;
+    }
+}
+
+/// Applies the margin policy to running trades: adds margin to trades that come within the
+/// configured distance of their liquidation price (if the balance allows) and cashes in
+/// unrealized profit above the threshold.
+async fn apply_margin_policy(
+    api_url: &str,
+    running_trades: &[&Trade],
+    last_price: f64,
+    margin_policy: &MarginPolicy,
+) {
+    if margin_policy.enable_margin_topup {
+        // Balance is only fetched if any trade needs a top up
+        let needs_topup = running_trades.iter().any(|trade| {
+            calculate_margin_topup(
+                last_price,
+                trade.liquidation,
+                trade.side.is_buy(),
+                margin_policy.topup_distance_percent,
+                margin_policy.topup_sats,
+                f64::MAX,
+            )
+            .is_some()
+        });
+
+        if needs_topup {
+            let mut balance = match get_user(api_url).await {
+                Ok(user) => user.balance,
+                Err(e) => {
+                    error!(error = %e, "Error fetching user data");
+                    0.0
+                }
+            };
+
+            for trade in running_trades {
+                let Some(amount) = calculate_margin_topup(
+                    last_price,
+                    trade.liquidation,
+                    trade.side.is_buy(),
+                    margin_policy.topup_distance_percent,
+                    margin_policy.topup_sats,
+                    balance,
+                ) else {
+                    continue;
+                };
+
+                let updated = match add_margin(api_url, &trade.id, amount).await {
+                    Ok(updated) => updated,
+                    Err(e) => {
+                        error!(trade_id = %trade.id, error = %e, "Error adding margin");
+                        continue;
+                    }
+                };
+                balance -= amount as f64;
+                warn!(
+                    trade_id = %trade.id,
+                    amount,
+                    liquidation = trade.liquidation,
+                    new_liquidation = updated.liquidation,
+                    "Margin top up"
+                );
+            }
+        }
+    }
+
+    if margin_policy.enable_cash_in {
+        for trade in running_trades {
+            let Some(amount) = calculate_cash_in_amount(trade.pl, margin_policy.cash_in_threshold_sats) else {
+                continue;
+            };
+
+            match cash_in(api_url, &trade.id, amount).await {
+                Ok(_) => info!(trade_id = %trade.id, amount, "Cash-in"),
+                Err(e) => error!(trade_id = %trade.id, error = %e, "Error cashing in"),
+            }
+        }
+    }
+}
@@ -0,0 +1,216 @@
+// src/utils/metrics.rs
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::{Mutex, OnceLock};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use prometheus::core::Collector;
+use prometheus::{
+    Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use tokio::net::TcpListener;
+use tokio::time::{Duration, Instant};
+use crate::futures::trade::Trade;
+use crate::math::get_indicators::Indicators;
+use crate::math::indicator_set::IndicatorSource;
+use crate::utils::filter_signals::SkipReason;
+use crate::utils::rate_limiter::WEBHOOK_ENDPOINT;
+
+// Window of the tick rate
+const TICK_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Prometheus metrics of the bot, exposed on `/metrics`.
+pub struct Metrics {
+    pub registry: Registry,
+    pub last_price: Gauge,                     // Last price of the websocket feed
+    pub price_ticks: IntCounter,               // Price updates received
+    pub tick_rate: Gauge,                      // Price updates per second over the last minute
+    pub ws_connected: IntGauge,                // 1 while the websocket is connected
+    pub ws_reconnects: IntCounter,             // Connections after the first one
+    pub rest_request_duration: HistogramVec,   // REST latency in seconds, by endpoint
+    pub rest_errors: IntCounterVec,            // Failed REST requests (transport or status), by endpoint
+    pub rest_rate_limit_hits: IntCounterVec,   // 429 responses, by endpoint
+    pub indicator: GaugeVec,                   // Indicator values driving the signal, by name
+    pub signal_score: Gauge,                   // Score of the last signal
+    pub running_trades: IntGauge,
+    pub max_trades: IntGauge,                  // `limits.count.max` of the futures market
+    pub margin_used_sats: Gauge,               // Margin of the running trades
+    pub balance_sats: Gauge,
+    pub realized_pl_sats: Gauge,               // Net P&L of the trades closed since the start
+    pub unrealized_pl_sats: Gauge,             // P&L of the running trades
+    pub trades_opened: IntCounter,
+    pub trades_skipped: IntCounterVec,         // Actionable or suppressed signals without a trade, by reason
+    ticks: Mutex<VecDeque<Instant>>,           // Tick times within the tick rate window
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+/// Global metrics of the bot.
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(|| Metrics::new().expect("Error registering metrics"))
+}
+
+impl Metrics {
+    /// Creates the metrics on their own registry.
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new_custom(Some("ln_bot".to_string()), None)?;
+
+        let last_price = Gauge::new("last_price", "Last price of the websocket feed in USD")?;
+        let price_ticks = IntCounter::new("price_ticks_total", "Price updates received")?;
+        let tick_rate = Gauge::new("tick_rate", "Price updates per second over the last minute")?;
+        let ws_connected = IntGauge::new("ws_connected", "1 while the websocket is connected")?;
+        let ws_reconnects = IntCounter::new("ws_reconnects_total", "Websocket reconnects")?;
+        let rest_request_duration = HistogramVec::new(
+            HistogramOpts::new("rest_request_duration_seconds", "REST request latency")
+                .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            &["endpoint"],
+        )?;
+        let rest_errors = IntCounterVec::new(Opts::new("rest_errors_total", "Failed REST requests"), &["endpoint"])?;
+        let rest_rate_limit_hits =
+            IntCounterVec::new(Opts::new("rest_rate_limit_hits_total", "REST responses with status 429"), &["endpoint"])?;
+        let indicator = GaugeVec::new(Opts::new("indicator", "Indicator values of the registry"), &["name"])?;
+        let signal_score = Gauge::new("signal_score", "Score of the last signal")?;
+        let running_trades = IntGauge::new("running_trades", "Running trades")?;
+        let max_trades = IntGauge::new("max_trades", "Max running trades of the futures market")?;
+        let margin_used_sats = Gauge::new("margin_used_sats", "Margin of the running trades in sats")?;
+        let balance_sats = Gauge::new("balance_sats", "Balance in sats")?;
+        let realized_pl_sats = Gauge::new("realized_pl_sats", "Net P&L of the trades closed since the start in sats")?;
+        let unrealized_pl_sats = Gauge::new("unrealized_pl_sats", "P&L of the running trades in sats")?;
+        let trades_opened = IntCounter::new("trades_opened_total", "Trades opened")?;
+        let trades_skipped =
+            IntCounterVec::new(Opts::new("trades_skipped_total", "Signals without a trade"), &["reason"])?;
+
+        registry.register(Box::new(last_price.clone()))?;
+        registry.register(Box::new(price_ticks.clone()))?;
+        registry.register(Box::new(tick_rate.clone()))?;
+        registry.register(Box::new(ws_connected.clone()))?;
+        registry.register(Box::new(ws_reconnects.clone()))?;
+        registry.register(Box::new(rest_request_duration.clone()))?;
+        registry.register(Box::new(rest_errors.clone()))?;
+        registry.register(Box::new(rest_rate_limit_hits.clone()))?;
+        registry.register(Box::new(indicator.clone()))?;
+        registry.register(Box::new(signal_score.clone()))?;
+        registry.register(Box::new(running_trades.clone()))?;
+        registry.register(Box::new(max_trades.clone()))?;
+        registry.register(Box::new(margin_used_sats.clone()))?;
+        registry.register(Box::new(balance_sats.clone()))?;
+        registry.register(Box::new(realized_pl_sats.clone()))?;
+        registry.register(Box::new(unrealized_pl_sats.clone()))?;
+        registry.register(Box::new(trades_opened.clone()))?;
+        registry.register(Box::new(trades_skipped.clone()))?;
+
+        Ok(Self {
+            registry,
+            last_price,
+            price_ticks,
+            tick_rate,
+            ws_connected,
+            ws_reconnects,
+            rest_request_duration,
+            rest_errors,
+            rest_rate_limit_hits,
+            indicator,
+            signal_score,
+            running_trades,
+            max_trades,
+            margin_used_sats,
+            balance_sats,
+            realized_pl_sats,
+            unrealized_pl_sats,
+            trades_opened,
+            trades_skipped,
+            ticks: Mutex::new(VecDeque::new()),
+        })
+    }
+
+    /// Records a price update of the websocket feed and updates the tick rate.
+    pub fn record_price_tick(&self, price: f64) {
+        self.last_price.set(price);
+        self.price_ticks.inc();
+
+        let now = Instant::now();
+        let mut ticks = self.ticks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        ticks.push_back(now);
+        while ticks.front().is_some_and(|tick| now.duration_since(*tick) > TICK_RATE_WINDOW) {
+            ticks.pop_front();
+        }
+        self.tick_rate.set(ticks.len() as f64 / TICK_RATE_WINDOW.as_secs_f64());
+    }
+
+    /// Seconds since the last price update, None before the first one.
+    pub fn seconds_since_last_tick(&self) -> Option<f64> {
+        let ticks = self.ticks.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        ticks.back().map(|tick| tick.elapsed().as_secs_f64())
+    }
+
+    /// Failed REST requests of the LN Markets endpoints. Webhooks are left out, a broken webhook
+    /// would otherwise report its own errors to itself.
+    pub fn total_rest_errors(&self) -> u64 {
+        self.rest_errors
+            .collect()
+            .iter()
+            .flat_map(|family| family.get_metric())
+            .filter(|metric| !metric.get_label().iter().any(|label| label.get_value() == WEBHOOK_ENDPOINT))
+            .map(|metric| metric.get_counter().get_value() as u64)
+            .sum()
+    }
+
+    /// Records the values of the indicator registry, named by ID (`rsi`, `bb_lower`, ...) for the OHLC
+    /// indicators that drive the signal and prefixed with the source (`price_rsi`) for the others.
+    pub fn record_indicators(&self, indicators: &Indicators) {
+        for (source, id, value) in indicators.values.iter() {
+            let prefix = match source {
+                IndicatorSource::Ohlc => String::new(),
+                source => format!("{}_", source),
+            };
+            for (suffix, value) in value.components() {
+                self.indicator.with_label_values(&[&format!("{}{}{}", prefix, id, suffix)]).set(value);
+            }
+        }
+    }
+
+    /// Records the running trades: count, margin and unrealized P&L.
+    pub fn record_running_trades(&self, running_trades: &[Trade]) {
+        self.running_trades.set(running_trades.len() as i64);
+        self.margin_used_sats.set(running_trades.iter().map(|trade| trade.margin).sum());
+        self.unrealized_pl_sats.set(running_trades.iter().map(|trade| trade.pl).sum());
+    }
+
+    /// Records a signal that did not open a trade.
+    pub fn record_skipped_trade(&self, reason: &SkipReason) {
+        self.trades_skipped.with_label_values(&[reason.label()]).inc();
+    }
+
+    /// Encodes all metrics in the Prometheus text format.
+    pub fn encode(&self) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    match metrics().encode() {
+        Ok(body) => ([(CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        Err(e) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Router with the `GET /metrics` endpoint of the global metrics.
+pub fn metrics_router() -> Router {
+    Router::new().route("/metrics", get(metrics_handler))
+}
+
+/// Serves the router on the address until the task is dropped.
+///
+/// # Parameters
+/// - `addr`: Listen address (e.g. "127.0.0.1:9100").
+/// - `router`: The routes to serve.
+pub async fn serve_http(addr: &str, router: Router) -> Result<(), std::io::Error> {
+    let listener = TcpListener::bind(addr).await?;
+    axum::serve(listener, router).await
+}
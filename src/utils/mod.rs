@@ -9,4 +9,25 @@ pub mod log_bot_params;
 pub mod set_updated_indicators;
 pub mod process_signals;
 pub mod init_bot_params;
-pub mod update_history_data;
\ No newline at end of file
+pub mod update_history_data;
+pub mod position_group;
+pub mod manage_positions;
+pub mod filter_signals;
+pub mod trade_journal;
+pub mod paper_exchange;
+pub mod logging;
+pub mod http_client;
+pub mod metrics;
+pub mod runtime_settings;
+pub mod control_api;
+pub mod dashboard;
+pub mod notifier;
+pub mod state_store;
+pub mod shutdown;
+pub mod reconcile_state;
+pub mod rate_limiter;
+pub mod api_cache;
+pub mod session_recorder;
+pub mod session_replay;
+pub mod candle_store;
+pub mod timeframe;
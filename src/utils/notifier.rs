@@ -0,0 +1,458 @@
+// src/utils/notifier.rs
+
+use tracing::{info, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Instant};
+use crate::futures::trade::Side;
+use crate::utils::get_timestamps::{format_timestamp, get_current_time_ms};
+use crate::utils::http_client::{http_client, MeteredRequest};
+use crate::utils::metrics::metrics;
+use crate::utils::rate_limiter::WEBHOOK_ENDPOINT;
+
+// Pending notifications before new ones are dropped
+const NOTIFICATION_QUEUE_SIZE: usize = 100;
+// Window of the notification rate limit
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+// Check interval of the health watchdog
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Kind of a notification event, used for the event filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    TradeOpened,
+    TradeClosed,
+    CircuitBreaker,
+    WebsocketStale,
+    ApiErrors,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 5] = [
+        EventKind::TradeOpened,
+        EventKind::TradeClosed,
+        EventKind::CircuitBreaker,
+        EventKind::WebsocketStale,
+        EventKind::ApiErrors,
+    ];
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            EventKind::TradeOpened => "trade_opened",
+            EventKind::TradeClosed => "trade_closed",
+            EventKind::CircuitBreaker => "circuit_breaker",
+            EventKind::WebsocketStale => "websocket_stale",
+            EventKind::ApiErrors => "api_errors",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s.trim().to_lowercase())
+            .ok_or_else(|| format!("Unknown notification event: {} (trade_opened, trade_closed, circuit_breaker, websocket_stale, api_errors)", s))
+    }
+}
+
+/// Event the bot notifies about.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    TradeOpened {
+        id: Option<String>,              // Trade ID (paper order ID in dry runs)
+        signal: String,                  // Signal bucket (e.g. "StrongBuy")
+        score: f64,                      // Signal score
+        price: f64,                      // Price the signal was derived from
+        dry_run: bool,
+    },
+    TradeClosed {
+        id: String,
+        side: Side,                      // Buy or sell
+        pl_sats: f64,                    // Net P&L in sats (fees deducted)
+    },
+    CircuitBreaker {
+        reason: String,                  // Why trading was stopped
+    },
+    WebsocketStale {
+        seconds_since_last_tick: f64,    // Seconds since the last price update
+    },
+    ApiErrors {
+        errors: u64,                     // Failed REST requests within the window
+        window_seconds: u64,
+    },
+}
+
+impl NotificationEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            NotificationEvent::TradeOpened { .. } => EventKind::TradeOpened,
+            NotificationEvent::TradeClosed { .. } => EventKind::TradeClosed,
+            NotificationEvent::CircuitBreaker { .. } => EventKind::CircuitBreaker,
+            NotificationEvent::WebsocketStale { .. } => EventKind::WebsocketStale,
+            NotificationEvent::ApiErrors { .. } => EventKind::ApiErrors,
+        }
+    }
+
+    /// Key of the deduplication, events with the same key are sent once per dedup window.
+    pub fn dedup_key(&self) -> String {
+        match self {
+            NotificationEvent::TradeOpened { id: Some(id), .. } => format!("trade_opened:{}", id),
+            NotificationEvent::TradeOpened { id: None, signal, price, .. } => format!("trade_opened:{}:{}", signal, price),
+            NotificationEvent::TradeClosed { id, .. } => format!("trade_closed:{}", id),
+            NotificationEvent::CircuitBreaker { reason } => format!("circuit_breaker:{}", reason),
+            NotificationEvent::WebsocketStale { .. } => "websocket_stale".to_string(),
+            NotificationEvent::ApiErrors { .. } => "api_errors".to_string(),
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            NotificationEvent::TradeOpened { .. } => "Trade opened",
+            NotificationEvent::TradeClosed { .. } => "Trade closed",
+            NotificationEvent::CircuitBreaker { .. } => "Circuit breaker tripped",
+            NotificationEvent::WebsocketStale { .. } => "Websocket stale",
+            NotificationEvent::ApiErrors { .. } => "API errors",
+        }
+    }
+
+    /// Human readable description of the event.
+    pub fn message(&self) -> String {
+        match self {
+            NotificationEvent::TradeOpened { id, signal, score, price, dry_run } => format!(
+                "{}{} at {:.2}$ (score {:+.2}){}",
+                signal,
+                id.as_ref().map(|id| format!(" trade {}", id)).unwrap_or_default(),
+                price,
+                score,
+                if *dry_run { " [dry run]" } else { "" }
+            ),
+            NotificationEvent::TradeClosed { id, side, pl_sats } => format!(
+                "{} trade {} closed with {:+.0} sats",
+                side,
+                id,
+                pl_sats
+            ),
+            NotificationEvent::CircuitBreaker { reason } => format!("Trading stopped: {}", reason),
+            NotificationEvent::WebsocketStale { seconds_since_last_tick } => {
+                format!("No price update for {:.0} seconds", seconds_since_last_tick)
+            }
+            NotificationEvent::ApiErrors { errors, window_seconds } => {
+                format!("{} failed REST requests within {} seconds", errors, window_seconds)
+            }
+        }
+    }
+}
+
+/// Payload format of a webhook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    Json,                                // Generic JSON with the event data
+    Telegram,                            // Bot API `sendMessage`
+    Discord,                             // Discord webhook with an embed
+    Slack,                               // Slack incoming webhook
+}
+
+impl FromStr for PayloadFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Ok(PayloadFormat::Json),
+            "telegram" => Ok(PayloadFormat::Telegram),
+            "discord" => Ok(PayloadFormat::Discord),
+            "slack" => Ok(PayloadFormat::Slack),
+            _ => Err(format!("Unknown webhook format: {} (json, telegram, discord, slack)", s)),
+        }
+    }
+}
+
+/// Webhook target of the notifications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookSettings {
+    pub url: String,
+    pub format: PayloadFormat,
+    pub chat_id: Option<String>,         // Chat of Telegram messages
+}
+
+impl FromStr for WebhookSettings {
+    type Err = String;
+
+    /// Parses `<format>=<url>` or a plain URL (generic JSON).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (format, url) = match s.split_once('=') {
+            Some((format, url)) if format.parse::<PayloadFormat>().is_ok() => (format.parse()?, url.trim()),
+            _ => (PayloadFormat::Json, s),
+        };
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("Invalid webhook URL: {}", url));
+        }
+        Ok(WebhookSettings { url: url.to_string(), format, chat_id: None })
+    }
+}
+
+/// Settings of the notifications.
+#[derive(Debug, Clone)]
+pub struct NotificationSettings {
+    pub webhooks: Vec<WebhookSettings>,
+    pub events: HashSet<EventKind>,      // Events that are sent, the others are dropped
+    pub max_per_minute: usize,           // Rate limit over all events
+    pub dedup_seconds: u64,              // Events with the same key are sent once within this window
+    pub stale_ws_seconds: u64,           // Seconds without a price update until the websocket is reported stale
+    pub api_error_threshold: u64,        // Failed REST requests within the window that are reported
+    pub api_error_window_seconds: u64,
+}
+
+/// Builds the payload of an event for a webhook format.
+///
+/// # Parameters
+/// - `format`: The payload format of the webhook.
+/// - `event`: The event to send.
+/// - `chat_id`: Chat of Telegram messages.
+pub fn format_payload(format: PayloadFormat, event: &NotificationEvent, chat_id: Option<&str>) -> Value {
+    let text = format!("{}: {}", event.title(), event.message());
+    match format {
+        PayloadFormat::Json => json!({
+            "title": event.title(),
+            "message": event.message(),
+            "timestamp": format_timestamp(get_current_time_ms()),
+            "data": event,
+        }),
+        PayloadFormat::Telegram => json!({
+            "chat_id": chat_id.unwrap_or_default(),
+            "text": text,
+            "disable_web_page_preview": true,
+        }),
+        PayloadFormat::Discord => {
+            let color = match event {
+                NotificationEvent::TradeOpened { .. } => 0x3498db,
+                NotificationEvent::TradeClosed { pl_sats, .. } if *pl_sats >= 0.0 => 0x2ecc71,
+                _ => 0xe74c3c,
+            };
+            json!({
+                "username": "LN Markets Bot",
+                "embeds": [{
+                    "title": event.title(),
+                    "description": event.message(),
+                    "color": color,
+                }],
+            })
+        }
+        PayloadFormat::Slack => json!({
+            "text": text,
+            "blocks": [{
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", event.title(), event.message()) },
+            }],
+        }),
+    }
+}
+
+/// Future returned by a notifier.
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Target the notifications are sent to.
+pub trait Notifier: Send + Sync {
+    /// Name for the logs.
+    fn name(&self) -> String;
+
+    /// Sends an event.
+    fn notify<'a>(&'a self, event: &'a NotificationEvent) -> NotifyFuture<'a>;
+}
+
+/// Notifier posting the events to a webhook.
+pub struct WebhookNotifier {
+    pub settings: WebhookSettings,
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> String {
+        format!("{:?} webhook", self.settings.format)
+    }
+
+    fn notify<'a>(&'a self, event: &'a NotificationEvent) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let payload = format_payload(self.settings.format, event, self.settings.chat_id.as_deref());
+            let response = http_client()
+                .post(&self.settings.url)
+                .json(&payload)
+                .send_metered(WEBHOOK_ENDPOINT)
+                .await
+                .map_err(|e| format!("Error sending webhook: {}", e))?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("Webhook returned {}: {}", status, body));
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Event filter, rate limit and deduplication of the notifications.
+pub struct NotificationFilter {
+    events: HashSet<EventKind>,
+    max_per_minute: usize,
+    dedup_window: Duration,
+    sent: VecDeque<Instant>,             // Send times within the rate limit window
+    last_sent: HashMap<String, Instant>, // Last send time per dedup key
+}
+
+impl NotificationFilter {
+    pub fn new(events: HashSet<EventKind>, max_per_minute: usize, dedup_window: Duration) -> Self {
+        Self { events, max_per_minute, dedup_window, sent: VecDeque::new(), last_sent: HashMap::new() }
+    }
+
+    /// Checks whether an event is sent and records it if so.
+    ///
+    /// # Parameters
+    /// - `event`: The event to check.
+    /// - `now`: Current time.
+    ///
+    /// # Returns
+    /// - The reason if the event is dropped.
+    pub fn check(&mut self, event: &NotificationEvent, now: Instant) -> Result<(), String> {
+        if !self.events.contains(&event.kind()) {
+            return Err(format!("{} events are disabled", event.kind()));
+        }
+
+        let key = event.dedup_key();
+        if self.last_sent.get(&key).is_some_and(|sent| now.duration_since(*sent) < self.dedup_window) {
+            return Err(format!("duplicate of {}", key));
+        }
+
+        while self.sent.front().is_some_and(|sent| now.duration_since(*sent) >= RATE_LIMIT_WINDOW) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= self.max_per_minute {
+            return Err(format!("rate limit of {} per minute reached", self.max_per_minute));
+        }
+
+        self.sent.push_back(now);
+        self.last_sent.retain(|_, sent| now.duration_since(*sent) < self.dedup_window);
+        self.last_sent.insert(key, now);
+        Ok(())
+    }
+}
+
+/// Sends the events that pass the filter to all notifiers.
+pub struct NotificationDispatcher {
+    pub notifiers: Vec<Box<dyn Notifier>>,
+    pub filter: NotificationFilter,
+}
+
+impl NotificationDispatcher {
+    /// Creates webhook notifiers for the settings.
+    pub fn from_settings(settings: &NotificationSettings) -> Self {
+        Self {
+            notifiers: settings
+                .webhooks
+                .iter()
+                .map(|webhook| Box::new(WebhookNotifier { settings: webhook.clone() }) as Box<dyn Notifier>)
+                .collect(),
+            filter: NotificationFilter::new(
+                settings.events.clone(),
+                settings.max_per_minute,
+                Duration::from_secs(settings.dedup_seconds),
+            ),
+        }
+    }
+
+    /// Sends an event to all notifiers unless it's filtered. Errors of a notifier are logged.
+    pub async fn dispatch(&mut self, event: &NotificationEvent) {
+        if let Err(reason) = self.filter.check(event, Instant::now()) {
+            info!(%reason, "Notification dropped");
+            return;
+        }
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(event).await {
+                warn!(notifier = notifier.name(), error = %e, "Error notifying");
+            }
+        }
+    }
+}
+
+static NOTIFICATIONS: OnceLock<mpsc::Sender<NotificationEvent>> = OnceLock::new();
+
+/// Starts sending the events passed to `notify`. Only the first call has an effect.
+///
+/// # Returns
+/// - The task sending the notifications.
+pub fn start_notifications(mut dispatcher: NotificationDispatcher) -> JoinHandle<()> {
+    let (tx, mut rx) = mpsc::channel::<NotificationEvent>(NOTIFICATION_QUEUE_SIZE);
+    if NOTIFICATIONS.set(tx).is_err() {
+        warn!("Notifications already started.");
+    }
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            dispatcher.dispatch(&event).await;
+        }
+    })
+}
+
+/// Queues an event for the notifiers. Does nothing if the notifications aren't started.
+pub fn notify(event: NotificationEvent) {
+    if let Some(tx) = NOTIFICATIONS.get() {
+        if let Err(e) = tx.try_send(event) {
+            warn!(error = %e, "Notification dropped");
+        }
+    }
+}
+
+/// Reports a stale websocket and piling up REST errors from the metrics. A stale websocket is
+/// reported again after price updates resumed. Until the first price update the time is measured
+/// from the start of the watchdog, so a websocket that never delivers is reported too.
+///
+/// # Parameters
+/// - `settings`: The thresholds of the notification settings.
+pub async fn health_watchdog(settings: NotificationSettings) {
+    let mut check = interval(WATCHDOG_INTERVAL);
+    let error_window = Duration::from_secs(settings.api_error_window_seconds);
+    let mut error_samples: VecDeque<(Instant, u64)> = VecDeque::new();
+    let mut stale = false;
+    let started = Instant::now();
+
+    loop {
+        check.tick().await;
+        let now = Instant::now();
+
+        // Websocket: time since the last price update (or the start without any update)
+        let seconds = metrics()
+            .seconds_since_last_tick()
+            .unwrap_or_else(|| now.duration_since(started).as_secs_f64());
+        if seconds < settings.stale_ws_seconds as f64 {
+            stale = false;
+        } else if !stale {
+            stale = true;
+            warn!(seconds, "No price update");
+            notify(NotificationEvent::WebsocketStale { seconds_since_last_tick: seconds });
+        }
+
+        // REST errors within the window
+        let total_errors = metrics().total_rest_errors();
+        error_samples.push_back((now, total_errors));
+        while error_samples.front().is_some_and(|(time, _)| now.duration_since(*time) > error_window) {
+            error_samples.pop_front();
+        }
+        let errors = total_errors - error_samples.front().map(|(_, errors)| *errors).unwrap_or(total_errors);
+        if errors >= settings.api_error_threshold {
+            notify(NotificationEvent::ApiErrors { errors, window_seconds: settings.api_error_window_seconds });
+        }
+    }
+}
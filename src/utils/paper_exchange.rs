@@ -0,0 +1,395 @@
+// src/utils/paper_exchange.rs
+
+use tracing::{error, info};
+use std::sync::Arc;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::analytics::performance_report::ClosedTrade;
+use crate::backtest::execution_model::{ExecutionModel, Fill};
+use crate::futures::trade::Side;
+use crate::math::create_trade_from_signal::TradeSettings;
+use crate::math::get_indicators::Indicators;
+use crate::math::get_signals::{Signal, SignalExplanation};
+use crate::math::get_stoploss_takeprofit::calculate_stoploss_takeprofit;
+use crate::math::get_trade_quantity::calculate_trade_quantity;
+use crate::utils::filter_signals::SkipReason;
+use crate::utils::get_timestamps::get_current_time_ms;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::trade_journal::{append_trade_journal, TradeJournalEntry};
+
+/// Market order placed on the paper exchange.
+#[derive(Debug, Clone, Copy)]
+pub struct MarketOrder {
+    pub is_buy: bool,
+    pub quantity: f64,                   // Quantity in USD
+    pub signal_price: f64,               // Price at signal time
+    pub stoploss: f64,
+    pub takeprofit: f64,
+    pub atr: Option<f64>,                // ATR at signal time (ATR-proportional spread)
+}
+
+/// Market order waiting for its latency to pass.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperOrder {
+    pub id: String,
+    pub is_buy: bool,
+    pub quantity: f64,                   // Quantity in USD
+    pub signal_price: f64,               // Price at signal time
+    pub stoploss: f64,
+    pub takeprofit: f64,
+    pub atr: Option<f64>,                // ATR at signal time (ATR-proportional spread)
+    pub ticks_remaining: usize,          // Price updates until the order is filled
+}
+
+/// Open paper position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperPosition {
+    pub id: String,
+    pub is_buy: bool,
+    pub quantity: f64,
+    pub entry_time: i64,
+    pub stoploss: f64,
+    pub takeprofit: f64,
+    pub atr: Option<f64>,
+    pub entry_fill: Fill,
+}
+
+/// Result of a price update of the paper exchange.
+#[derive(Debug, Clone)]
+pub enum PaperEvent {
+    Filled { id: String, is_buy: bool, fill: Fill },
+    Closed { id: String, exit_reason: String, trade: ClosedTrade },
+}
+
+/// Persisted state of the paper exchange.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperState {
+    pub balance: f64,
+    pub orders: Vec<PaperOrder>,
+    pub positions: Vec<PaperPosition>,
+    pub closed_trades: Vec<ClosedTrade>,
+}
+
+/// Simulated exchange for dry runs. Orders are filled through the execution model on the live
+/// price feed, stoploss and takeprofit are checked on every price update.
+pub struct PaperExchange {
+    pub balance: f64,                    // Balance in sats
+    pub fee_rate: f64,                   // Trading fee rate per side
+    pub orders: Vec<PaperOrder>,
+    pub positions: Vec<PaperPosition>,
+    pub closed_trades: Vec<ClosedTrade>,
+    pub last_price: Option<(f64, i64)>,  // Last price update (price, timestamp in ms)
+    model: ExecutionModel,
+    rng: StdRng,
+}
+
+impl PaperExchange {
+    pub fn new(model: ExecutionModel, balance: f64, fee_rate: f64) -> Self {
+        Self {
+            balance,
+            fee_rate,
+            orders: Vec::new(),
+            positions: Vec::new(),
+            closed_trades: Vec::new(),
+            last_price: None,
+            rng: StdRng::seed_from_u64(model.seed),
+            model,
+        }
+    }
+
+    /// Places a market order that is filled after `latency_ticks` price updates (immediately at the
+    /// signal price without latency).
+    ///
+    /// # Returns
+    /// - The ID of the order (and of the position once filled) and the fill event without latency.
+    pub fn submit_market_order(&mut self, order: MarketOrder, time: i64) -> (String, Option<PaperEvent>) {
+        let MarketOrder { is_buy, quantity, signal_price, stoploss, takeprofit, atr } = order;
+        let order = PaperOrder {
+            id: Uuid::new_v4().to_string(),
+            is_buy,
+            quantity,
+            signal_price,
+            stoploss,
+            takeprofit,
+            atr,
+            ticks_remaining: self.model.latency_ticks,
+        };
+        let id = order.id.clone();
+        if order.ticks_remaining == 0 {
+            return (id, Some(self.fill_order(order, signal_price, time)));
+        }
+        self.orders.push(order);
+        (id, None)
+    }
+
+    // Fills the order at the mid price and opens its position
+    fn fill_order(&mut self, order: PaperOrder, price: f64, time: i64) -> PaperEvent {
+        let fill = self.model.fill(order.signal_price, price, order.is_buy, order.atr, &mut self.rng);
+        self.balance -= order.quantity / fill.fill_price * self.fee_rate * 100_000_000.0;
+        self.positions.push(PaperPosition {
+            id: order.id.clone(),
+            is_buy: order.is_buy,
+            quantity: order.quantity,
+            entry_time: time,
+            stoploss: order.stoploss,
+            takeprofit: order.takeprofit,
+            atr: order.atr,
+            entry_fill: fill,
+        });
+        PaperEvent::Filled { id: order.id, is_buy: order.is_buy, fill }
+    }
+
+    /// Processes a price update: fills the orders whose latency passed and closes the positions
+    /// whose stoploss (market order) or takeprofit (limit order, filled at its level) was hit.
+    pub fn on_price(&mut self, price: f64, time: i64) -> Vec<PaperEvent> {
+        let mut events = Vec::new();
+        self.last_price = Some((price, time));
+
+        // Exits of the positions opened before this update
+        let mut i = 0;
+        while i < self.positions.len() {
+            let position = &self.positions[i];
+            let exit = if position.is_buy {
+                if price <= position.stoploss {
+                    Some("stoploss")
+                } else if price >= position.takeprofit {
+                    Some("takeprofit")
+                } else {
+                    None
+                }
+            } else if price >= position.stoploss {
+                Some("stoploss")
+            } else if price <= position.takeprofit {
+                Some("takeprofit")
+            } else {
+                None
+            };
+
+            match exit {
+                Some(exit_reason) => {
+                    let position = self.positions.remove(i);
+                    let exit_price = if exit_reason == "stoploss" {
+                        self.model.fill(price, price, !position.is_buy, position.atr, &mut self.rng).fill_price
+                    } else {
+                        position.takeprofit
+                    };
+                    let trade = self.close_position(&position, exit_price, time);
+                    events.push(PaperEvent::Closed { id: position.id, exit_reason: exit_reason.to_string(), trade });
+                }
+                None => i += 1,
+            }
+        }
+
+        // Orders that reached the market
+        for order in self.orders.iter_mut() {
+            order.ticks_remaining = order.ticks_remaining.saturating_sub(1);
+        }
+        let (ready, waiting): (Vec<PaperOrder>, Vec<PaperOrder>) =
+            self.orders.drain(..).partition(|order| order.ticks_remaining == 0);
+        self.orders = waiting;
+
+        for order in ready {
+            let event = self.fill_order(order, price, time);
+            events.push(event);
+        }
+
+        events
+    }
+
+    /// Closes a position with a market order at the last price. A pending order with the ID is canceled.
+    ///
+    /// # Returns
+    /// - The close event, `None` if there is no position with the ID or no price yet.
+    pub fn close_at_market(&mut self, id: &str) -> Option<PaperEvent> {
+        self.orders.retain(|order| order.id != id);
+        let (price, time) = self.last_price?;
+        let index = self.positions.iter().position(|position| position.id == id)?;
+        let position = self.positions.remove(index);
+        let exit_price = self.model.fill(price, price, !position.is_buy, position.atr, &mut self.rng).fill_price;
+        let trade = self.close_position(&position, exit_price, time);
+        Some(PaperEvent::Closed { id: position.id, exit_reason: "manual".to_string(), trade })
+    }
+
+    /// Cancels the pending orders.
+    ///
+    /// # Returns
+    /// - The IDs of the canceled orders.
+    pub fn cancel_orders(&mut self) -> Vec<String> {
+        self.orders.drain(..).map(|order| order.id).collect()
+    }
+
+    /// Restores a paper exchange from a persisted state.
+    pub fn restore(model: ExecutionModel, fee_rate: f64, state: PaperState) -> Self {
+        let mut exchange = Self::new(model, state.balance, fee_rate);
+        exchange.orders = state.orders;
+        exchange.positions = state.positions;
+        exchange.closed_trades = state.closed_trades;
+        exchange
+    }
+
+    /// Snapshot of the balance, orders, positions and closed trades.
+    pub fn state(&self) -> PaperState {
+        PaperState {
+            balance: self.balance,
+            orders: self.orders.clone(),
+            positions: self.positions.clone(),
+            closed_trades: self.closed_trades.clone(),
+        }
+    }
+
+    /// Cancels the pending orders and closes all positions with market orders at the last price.
+    pub fn close_all_at_market(&mut self) -> Vec<PaperEvent> {
+        self.orders.clear();
+        let ids: Vec<String> = self.positions.iter().map(|position| position.id.clone()).collect();
+        ids.iter().filter_map(|id| self.close_at_market(id)).collect()
+    }
+
+    /// Unrealized P&L of the open positions in sats at the price.
+    pub fn unrealized_pl(&self, price: f64) -> f64 {
+        self.positions
+            .iter()
+            .map(|position| {
+                let entry_price = position.entry_fill.fill_price;
+                let direction = if position.is_buy { 1.0 } else { -1.0 };
+                direction * position.quantity * (1.0 / entry_price - 1.0 / price) * 100_000_000.0
+            })
+            .sum()
+    }
+
+    // P&L of an inverse futures contract (quantity in USD, P&L in sats), the opening fee was paid on the fill
+    fn close_position(&mut self, position: &PaperPosition, exit_price: f64, time: i64) -> ClosedTrade {
+        let entry_price = position.entry_fill.fill_price;
+        let pl = if position.is_buy {
+            position.quantity * (1.0 / entry_price - 1.0 / exit_price)
+        } else {
+            position.quantity * (1.0 / exit_price - 1.0 / entry_price)
+        } * 100_000_000.0;
+        let closing_fee = position.quantity / exit_price * self.fee_rate * 100_000_000.0;
+
+        let trade = ClosedTrade {
+            side: Side::from_is_buy(position.is_buy),
+            quantity: position.quantity,
+            entry_price,
+            exit_price,
+            entry_time: position.entry_time,
+            exit_time: time,
+            pl,
+            opening_fee: position.quantity / entry_price * self.fee_rate * 100_000_000.0,
+            closing_fee,
+            carry_fees: 0.0,
+        };
+        self.balance += pl - closing_fee;
+        self.closed_trades.push(trade.clone());
+        trade
+    }
+}
+
+/// Creates a paper order for the signal (dry run counterpart of `create_trade_from_signal`).
+/// Quantity, stoploss and takeprofit are calculated from the signal price like for live trades.
+pub async fn create_paper_trade_from_signal(
+    signal: Signal,
+    explanation: SignalExplanation,
+    bot_params: Arc<Mutex<BotParams>>,
+    indicators: Option<Indicators>,
+    settings: &TradeSettings,
+) -> Result<String, SkipReason> {
+    let TradeSettings { leverage, risk_per_trade_percent, risk_to_reward_ratio, risk_to_loss_ratio, ref trade_journal_path, .. } = *settings;
+    let is_buy = match signal {
+        Signal::Buy | Signal::StrongBuy => true,
+        Signal::Sell | Signal::StrongSell => false,
+        Signal::Hold | Signal::Undefined => return Err(SkipReason::InvalidSignal(signal)),
+    };
+    // The ATR sizes the stoploss, no order before the indicators are warmed up
+    let atr = indicators
+        .as_ref()
+        .and_then(|indicators| indicators.atr())
+        .ok_or(SkipReason::MissingIndicators)?;
+    let signal_price = explanation.price;
+
+    let mut bot_params = bot_params.lock().await;
+    let BotParams { market_data, paper_exchange, .. } = &mut *bot_params;
+    let futures_market = market_data
+        .as_ref()
+        .ok_or_else(|| SkipReason::Error("Market data is not available".to_string()))?;
+    let max_trades = futures_market.limits.count.max;
+    let exchange = paper_exchange
+        .as_mut()
+        .ok_or_else(|| SkipReason::Error("Paper exchange is not initialized".to_string()))?;
+
+    if exchange.positions.len() + exchange.orders.len() >= max_trades as usize {
+        return Err(SkipReason::TradeLimit { max: max_trades });
+    }
+
+    let quantity = calculate_trade_quantity(
+        exchange.balance.max(0.0) as u64,
+        signal_price,
+        risk_per_trade_percent,
+        max_trades,
+        leverage as f64,
+        Some(atr),
+        futures_market,
+    )
+    .map_err(|e| SkipReason::Error(format!("Error calculating trade quantity: {}", e)))?;
+    let (takeprofit, stoploss) = calculate_stoploss_takeprofit(
+        signal_price,
+        atr,
+        leverage as f64,
+        is_buy,
+        risk_to_reward_ratio,
+        risk_to_loss_ratio,
+    )
+    .map_err(|e| SkipReason::Error(format!("Error calculating stoploss/takeprofit: {}", e)))?;
+
+    let order = MarketOrder { is_buy, quantity: quantity.floor(), signal_price, stoploss, takeprofit, atr: Some(atr) };
+    let (id, fill) = exchange.submit_market_order(order, explanation.time);
+    if let Some(fill) = fill {
+        log_paper_events(&[fill], exchange.balance);
+    }
+
+    let entry = TradeJournalEntry {
+        time: get_current_time_ms(),
+        trade_ids: vec![id.clone()],
+        group_id: None,
+        side: Side::from_is_buy(is_buy),
+        quantity: quantity.floor() as u64,
+        leverage,
+        entry_price: signal_price,
+        takeprofit: Some(takeprofit as u64),
+        stoploss: Some(stoploss as u64),
+        explanation: Some(explanation),
+    };
+    if let Err(e) = append_trade_journal(trade_journal_path, &entry) {
+        error!(error = %e, "Error writing trade journal");
+    }
+    Ok(id)
+}
+
+/// Logs the fills and closed positions of the paper exchange.
+pub fn log_paper_events(events: &[PaperEvent], balance: f64) {
+    for event in events {
+        match event {
+            PaperEvent::Filled { id, is_buy, fill } => info!(
+                trade_id = %id,
+                side = if *is_buy { "buy" } else { "sell" },
+                price = fill.fill_price,
+                signal_price = fill.signal_price,
+                difference = fill.price_difference(),
+                adverse_percent = fill.adverse_difference_percent(*is_buy) * 100.0,
+                half_spread = fill.half_spread,
+                slippage = fill.slippage,
+                "Paper order filled"
+            ),
+            PaperEvent::Closed { id, exit_reason, trade } => info!(
+                trade_id = %id,
+                %exit_reason,
+                price = trade.exit_price,
+                pl = trade.net_pl(),
+                balance,
+                "Paper position closed"
+            ),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// src/utils/position_group.rs
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::futures::trade::{Side, Trade};
+use crate::utils::get_timestamps::get_current_time_ms;
+
+/// A child trade of a scaled-out position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionChild {
+    pub trade_id: String,      // LN Markets trade ID
+    pub quantity: u64,         // Quantity (USD) of the child trade
+    pub takeprofit: f64,       // Take-profit price of the child trade
+    pub r_multiple: f64,       // Target in multiples of R
+    pub running: bool,         // Whether the child trade is still running
+    pub pl: f64,               // Profit or loss in sats (unrealized while running)
+    pub fees: f64,             // Opening, closing and carry fees in sats
+}
+
+/// A group of child trades that are tracked as one logical position.
+/// All children share the entry side and the stoploss, but close at staggered take-profits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionGroup {
+    pub id: String,            // Local group ID
+    pub side: Side,            // Buy or sell
+    pub entry_price: f64,      // Entry price at creation of the group
+    pub stoploss: f64,         // Shared stoploss price
+    pub emulate_closes: bool,  // Children are closed by the bot when their target is hit (no server-side takeprofit)
+    pub creation_ts: i64,      // Timestamp (ms) when the group was created
+    pub children: Vec<PositionChild>,
+}
+
+impl PositionGroup {
+    pub fn new(side: Side, entry_price: f64, stoploss: f64, emulate_closes: bool) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            side,
+            entry_price,
+            stoploss,
+            emulate_closes,
+            creation_ts: get_current_time_ms(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn is_buy(&self) -> bool {
+        self.side.is_buy()
+    }
+
+    /// Total quantity (USD) of the group.
+    pub fn quantity(&self) -> u64 {
+        self.children.iter().map(|child| child.quantity).sum()
+    }
+
+    /// Quantity (USD) of the children that are still running.
+    pub fn open_quantity(&self) -> u64 {
+        self.children.iter().filter(|child| child.running).map(|child| child.quantity).sum()
+    }
+
+    /// Net P&L in sats of all closed children (fees deducted).
+    pub fn realized_pl(&self) -> f64 {
+        self.children.iter().filter(|child| !child.running).map(|child| child.pl - child.fees).sum()
+    }
+
+    /// Unrealized P&L in sats of all running children.
+    pub fn unrealized_pl(&self) -> f64 {
+        self.children.iter().filter(|child| child.running).map(|child| child.pl).sum()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.children.iter().all(|child| !child.running)
+    }
+
+    /// Running children whose take-profit was reached at `last_price`.
+    pub fn targets_hit(&self, last_price: f64) -> Vec<String> {
+        self.children
+            .iter()
+            .filter(|child| child.running)
+            .filter(|child| if self.is_buy() { last_price >= child.takeprofit } else { last_price <= child.takeprofit })
+            .map(|child| child.trade_id.clone())
+            .collect()
+    }
+
+    /// Updates a child from the latest trade entry of the API.
+    pub fn update_child(&mut self, trade: &Trade) {
+        if let Some(child) = self.children.iter_mut().find(|child| child.trade_id == trade.id) {
+            child.running = trade.running;
+            child.pl = trade.pl;
+            child.fees = trade.opening_fee + trade.closing_fee + trade.sum_carry_fees;
+        }
+    }
+
+    /// Marks a child as closed with its final P&L and fees (sats).
+    pub fn close_child(&mut self, trade_id: &str, pl: f64, fees: f64) {
+        if let Some(child) = self.children.iter_mut().find(|child| child.trade_id == trade_id) {
+            child.running = false;
+            child.pl = pl;
+            child.fees = fees;
+        }
+    }
+}
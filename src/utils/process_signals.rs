@@ -1,73 +1,217 @@
 // src/utils/process_signals.rs
 
+use tracing::{error, info, warn};
 use tokio::sync::mpsc::Receiver;
-use tokio::time::Duration;
-use std::io::{self, Write};
+use tokio::task::JoinSet;
+use tokio::time::{timeout, Duration};
 use std::sync::Arc;
-use colored::Colorize;
-use crate::math::create_trade_from_signal::{create_trade_from_signal, CreateTradeResult};
+use crate::config::SignalConditioning;
+use crate::math::create_trade_from_signal::{create_trade_from_signal, CreateTradeResult, TradeSettings};
+use crate::utils::filter_signals::{Direction, FilterDecision, SignalFilter, SkipReason};
+use crate::utils::get_timestamps::format_timestamp;
 use crate::utils::init_bot_params::BotParams;
+use crate::utils::manage_positions::ClosedTradeEvent;
+use crate::utils::metrics::metrics;
+use crate::utils::notifier::{notify, NotificationEvent};
+use crate::utils::paper_exchange::create_paper_trade_from_signal;
+use crate::utils::runtime_settings::record_signal;
+use crate::utils::shutdown::Shutdown;
 use crate::math::get_signals::SignalResponse;
 
+/// Settings of the signal processing, fixed for the lifetime of the bot.
+#[derive(Debug, Clone)]
+pub struct SignalProcessingSettings {
+    pub trade_gap_seconds: u64,          // Min gap between opening two trades in seconds
+    pub scale_out_targets: Vec<f64>,     // Take-profit targets in multiples of the stoploss distance (R)
+    pub emulate_partial_closes: bool,    // Close child trades from the bot instead of server-side takeprofits
+    pub signal_conditioning: SignalConditioning, // Rules that decide when a signal is actionable
+    pub candle_seconds: u64,             // Length of a candle (duplicate signals within one candle are suppressed)
+    pub trade_journal_path: String,      // File the created trades are persisted to
+    pub order_timeout: Duration,         // Max wait for order requests in flight on shutdown
+}
+
+/// Logs the signals and creates trades (paper orders in dry runs) for the actionable ones. On
+/// shutdown no further signals are accepted and the order requests in flight are awaited.
+///
+/// # Returns
+/// - The number of order requests aborted after `order_timeout`.
 pub async fn process_signals(
     mut signal_result_rx: Receiver<SignalResponse>,
+    mut closed_trade_rx: Receiver<ClosedTradeEvent>,
     api_url: Arc<str>,
     bot_params: Arc<tokio::sync::Mutex<BotParams>>,
-    trade_gap_seconds: u64,
-    risk_per_trade_percent: f64,
-    risk_to_reward_ratio: f64,
-    risk_to_loss_ratio: f64,
-) {
-    let mut last_trade_time = tokio::time::Instant::now();
+    settings: SignalProcessingSettings,
+    mut shutdown: Shutdown,
+) -> usize {
+    let mut signal_filter = SignalFilter::new(settings.signal_conditioning, settings.trade_gap_seconds, settings.candle_seconds);
+    // Order requests in flight, they return the direction and signal time of a created trade
+    let mut orders: JoinSet<Option<(Direction, i64)>> = JoinSet::new();
+
+    loop {
+        let signal_response = tokio::select! {
+            _ = shutdown.wait() => break,
+            Some(order) = orders.join_next(), if !orders.is_empty() => {
+                // Only created trades enter the direction and start the trade gap
+                match order {
+                    Ok(Some((direction, time_ms))) => signal_filter.record_trade(direction, time_ms),
+                    _ => signal_filter.release_trade(),
+                }
+                continue;
+            }
+            Some(closed_trade) = closed_trade_rx.recv() => {
+                metrics().realized_pl_sats.add(closed_trade.pl);
+                notify(NotificationEvent::TradeClosed {
+                    id: closed_trade.trade_id.clone(),
+                    side: closed_trade.side,
+                    pl_sats: closed_trade.pl,
+                });
+
+                // Losing trades start the cooldown of their direction
+                if closed_trade.pl < 0.0 {
+                    let direction = Direction::from_side(closed_trade.side);
+                    warn!(%direction, pl = closed_trade.pl, trade_id = %closed_trade.trade_id, "Losing trade closed");
+                    // The cooldown stops new trades of the direction (circuit breaker)
+                    if let Some(until) = signal_filter.record_loss(direction, closed_trade.closed_ts) {
+                        warn!(%direction, until = %format_timestamp(until), "Cooldown started");
+                        notify(NotificationEvent::CircuitBreaker {
+                            reason: format!("{} trades paused until {} after a losing trade", direction, format_timestamp(until)),
+                        });
+                    }
+                }
+                continue;
+            }
+            signal_response = signal_result_rx.recv() => match signal_response {
+                Some(signal_response) => signal_response,
+                None => break,
+            },
+        };
 
-    while let Some(signal_response) = signal_result_rx.recv().await {
         let signal = signal_response.signal;
+        let explanation = signal_response.explanation;
         let indicators = signal_response.indicators;
 
         // Log the signal
-        println!(" - {}", signal.to_string());
-        io::stdout().flush().unwrap();
+        info!(%signal, score = explanation.score, price = explanation.price, "Signal");
+        metrics().signal_score.set(explanation.score);
 
-        // Check if enough time has passed for a new trade
-        if last_trade_time.elapsed() >= Duration::new(trade_gap_seconds, 0) {
-            last_trade_time = tokio::time::Instant::now();
+        // Runtime settings of the control API
+        let runtime = {
+            let mut bot_params = bot_params.lock().await;
+            record_signal(&mut bot_params.last_signals, signal, &explanation);
+            bot_params.runtime.clone()
+        };
+        let trade_settings = TradeSettings {
+            leverage: 20,
+            risk_per_trade_percent: runtime.risk_per_trade_percent,
+            risk_to_reward_ratio: runtime.risk_to_reward_ratio,
+            risk_to_loss_ratio: runtime.risk_to_loss_ratio,
+            scale_out_targets: settings.scale_out_targets.clone(),
+            emulate_partial_closes: settings.emulate_partial_closes,
+            trade_journal_path: settings.trade_journal_path.clone(),
+        };
 
-            let bot_params = Arc::clone(&bot_params);
-            let api_url = Arc::clone(&api_url);
+        // Paused: the signal is not evaluated, so it leaves no hysteresis or trade gap behind
+        if runtime.paused {
+            if Direction::from_signal(signal).is_some() {
+                metrics().record_skipped_trade(&SkipReason::Paused);
+                info!(%signal, "Trading paused, no trade created");
+            }
+            continue;
+        }
 
-            tokio::spawn(async move {
-                match create_trade_from_signal(
-                    signal,
-                    &api_url,
-                    bot_params,
-                    indicators,
-                    None,
-                    risk_per_trade_percent,
-                    risk_to_reward_ratio,
-                    risk_to_loss_ratio,
-                )
-                .await
-                {
-                    Ok(CreateTradeResult::TradeCreated) => {
-                        println!(
-                            "{}",
-                            format!(
-                                "Trade successfully created for signal: {}",
-                                signal.to_string()
-                            )
-                            .green()
-                        );
-                    }
-                    Ok(CreateTradeResult::NoTradeCreated(reason)) => {
-                        println!("{}", format!("No trade created: {}", reason).yellow());
-                    }
-                    Err(e) => {
-                        eprintln!("{}", format!("Error creating trade: {}", e).red());
-                    }
+        // Check if the signal is actionable (confirmation, hysteresis, cooldowns, trade gap)
+        match signal_filter.evaluate(signal, explanation.score, explanation.time) {
+            FilterDecision::Actionable(direction) => {
+                let bot_params = Arc::clone(&bot_params);
+                let api_url = Arc::clone(&api_url);
+                let (score, price, time_ms) = (explanation.score, explanation.price, explanation.time);
+
+                // Dry run: paper order on the simulated exchange
+                if runtime.dry_run {
+                    orders.spawn(async move {
+                        match create_paper_trade_from_signal(
+                            signal,
+                            explanation,
+                            bot_params,
+                            indicators,
+                            &trade_settings,
+                        )
+                        .await
+                        {
+                            Ok(id) => {
+                                metrics().trades_opened.inc();
+                                notify(NotificationEvent::TradeOpened {
+                                    id: Some(id.clone()),
+                                    signal: format!("{:?}", signal),
+                                    score,
+                                    price,
+                                    dry_run: true,
+                                });
+                                info!(trade_id = %id, %signal, price, "Paper order created");
+                                Some((direction, time_ms))
+                            }
+                            Err(reason) => {
+                                metrics().record_skipped_trade(&reason);
+                                warn!(%signal, %reason, "No paper trade created");
+                                None
+                            }
+                        }
+                    });
+                    continue;
                 }
-            });
-        } else {
-            println!("{}", "...skipped.".cyan());
+
+                orders.spawn(async move {
+                    match create_trade_from_signal(
+                        signal,
+                        Some(explanation),
+                        &api_url,
+                        bot_params,
+                        indicators,
+                        &trade_settings,
+                    )
+                    .await
+                    {
+                        Ok(CreateTradeResult::TradeCreated) => {
+                            metrics().trades_opened.inc();
+                            notify(NotificationEvent::TradeOpened {
+                                id: None,
+                                signal: format!("{:?}", signal),
+                                score,
+                                price,
+                                dry_run: false,
+                            });
+                            info!(%signal, price, "Trade created");
+                            Some((direction, time_ms))
+                        }
+                        Ok(CreateTradeResult::NoTradeCreated(reason)) => {
+                            metrics().record_skipped_trade(&reason);
+                            warn!(%signal, %reason, "No trade created");
+                            None
+                        }
+                        Err(e) => {
+                            error!(%signal, error = %e, "Error creating trade");
+                            metrics().record_skipped_trade(&SkipReason::Error(e));
+                            None
+                        }
+                    }
+                });
+            }
+            FilterDecision::Suppressed(reason) => {
+                metrics().record_skipped_trade(&reason);
+                info!(%signal, %reason, "Signal suppressed");
+            }
+            FilterDecision::NoSignal => {}
+        }
+    }
+
+    // Wait for the order requests in flight
+    if !orders.is_empty() {
+        info!(orders = orders.len(), "Waiting for order requests");
+        if timeout(settings.order_timeout, async { while orders.join_next().await.is_some() {} }).await.is_err() {
+            warn!(orders = orders.len(), "Order requests still in flight, aborted");
         }
     }
+    let aborted = orders.len();
+    orders.shutdown().await;
+    aborted
 }
@@ -0,0 +1,140 @@
+// src/utils/rate_limiter.rs
+
+use tracing::warn;
+use std::sync::{Mutex, OnceLock};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use tokio::time::{sleep_until, Duration, Instant};
+
+// Wait after a 429 response without a usable `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+// Longest wait accepted from a `Retry-After` header
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Request budget of the LN Markets REST API.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitSettings {
+    pub requests_per_second: f64,        // Refill rate of the token bucket (weight units per second)
+    pub burst: f64,                      // Capacity of the token bucket
+    pub max_retries: u32,                // Retries of a request after a 429 response
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self { requests_per_second: 1.0, burst: 10.0, max_retries: 2 }
+    }
+}
+
+/// Token bucket: refills continuously up to its capacity, every request takes its weight.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+    blocked_until: Option<Instant>,      // Set by 429 responses, no request is sent before
+}
+
+/// Token bucket rate limiter shared by all REST requests.
+#[derive(Debug)]
+pub struct RateLimiter {
+    settings: RateLimitSettings,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings,
+            bucket: Mutex::new(TokenBucket { tokens: settings.burst, last_refill: Instant::now(), blocked_until: None }),
+        }
+    }
+
+    pub fn settings(&self) -> RateLimitSettings {
+        self.settings
+    }
+
+    /// Takes `weight` tokens if available.
+    ///
+    /// # Returns
+    /// - `None` if the tokens were taken, otherwise the time to try again.
+    pub fn try_acquire(&self, weight: f64, now: Instant) -> Option<Instant> {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(blocked_until) = bucket.blocked_until.filter(|until| *until > now) {
+            return Some(blocked_until);
+        }
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.settings.requests_per_second).min(self.settings.burst);
+        bucket.last_refill = now;
+
+        // Requests heavier than the bucket wait for a full bucket
+        let weight = weight.min(self.settings.burst);
+        if bucket.tokens >= weight {
+            bucket.tokens -= weight;
+            return None;
+        }
+        let missing = weight - bucket.tokens;
+        Some(now + Duration::from_secs_f64(missing / self.settings.requests_per_second.max(f64::EPSILON)))
+    }
+
+    /// Waits until `weight` tokens are available and takes them.
+    pub async fn acquire(&self, weight: f64) {
+        if weight <= 0.0 {
+            return;
+        }
+        while let Some(retry_at) = self.try_acquire(weight, Instant::now()) {
+            sleep_until(retry_at).await;
+        }
+    }
+
+    /// Blocks all requests until `until` (429 responses) and empties the bucket.
+    pub fn block_until(&self, until: Instant) {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if bucket.blocked_until.is_none_or(|blocked_until| blocked_until < until) {
+            bucket.blocked_until = Some(until);
+        }
+        bucket.tokens = 0.0;
+        bucket.last_refill = until;
+    }
+}
+
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+/// Sets the request budget of the shared rate limiter. Has no effect after the first request.
+pub fn init_rate_limiter(settings: RateLimitSettings) {
+    if RATE_LIMITER.set(RateLimiter::new(settings)).is_err() {
+        warn!("Rate limiter already initialized");
+    }
+}
+
+/// Rate limiter shared by all REST requests (default budget if not initialized).
+pub fn rate_limiter() -> &'static RateLimiter {
+    RATE_LIMITER.get_or_init(|| RateLimiter::new(RateLimitSettings::default()))
+}
+
+/// Endpoint label of the webhook notifications (not a LN Markets endpoint).
+pub const WEBHOOK_ENDPOINT: &str = "webhook";
+
+/// Weight of a request to an endpoint. Paged history endpoints are heavier, requests that don't go
+/// to LN Markets (webhooks) are free.
+pub fn endpoint_weight(endpoint: &str) -> f64 {
+    match endpoint {
+        WEBHOOK_ENDPOINT => 0.0,
+        "futures/ohlcs" | "futures/price_history" | "futures/index_history" => 2.0,
+        "futures/create_trade" | "futures/close_all" | "futures/cancel_all" => 2.0,
+        _ => 1.0,
+    }
+}
+
+/// Wait requested by a 429 response: `Retry-After` in seconds or as HTTP date, capped at one minute.
+pub fn retry_after(headers: &HeaderMap) -> Duration {
+    let Some(value) = headers.get(RETRY_AFTER).and_then(|value| value.to_str().ok()) else {
+        return DEFAULT_RETRY_AFTER;
+    };
+    let wait = match value.trim().parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Duration::from_secs_f64(seconds),
+        _ => match chrono::DateTime::parse_from_rfc2822(value.trim()) {
+            Ok(date) => (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default(),
+            Err(_) => DEFAULT_RETRY_AFTER,
+        },
+    };
+    wait.min(MAX_RETRY_AFTER)
+}
@@ -0,0 +1,348 @@
+// src/utils/reconcile_state.rs
+
+use tracing::{info, warn};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Trade, TradeStatus};
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::manage_positions::ClosedTradeEvent;
+use crate::utils::position_group::{PositionChild, PositionGroup};
+use crate::utils::state_store::BotState;
+use crate::utils::trade_journal::{read_trade_journal, TradeJournalEntry};
+
+/// Handling of trades on the account that the bot did not create.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExternalTradePolicy {
+    Adopt,                               // Managed like the bot's own trades (margin policy, close all, dashboard)
+    #[default]
+    Ignore,                              // Left alone by the position checks
+}
+
+impl fmt::Display for ExternalTradePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExternalTradePolicy::Adopt => write!(f, "adopt"),
+            ExternalTradePolicy::Ignore => write!(f, "ignore"),
+        }
+    }
+}
+
+impl FromStr for ExternalTradePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "adopt" => Ok(ExternalTradePolicy::Adopt),
+            "ignore" => Ok(ExternalTradePolicy::Ignore),
+            _ => Err(format!("Unknown external trade policy: {} (adopt, ignore)", s)),
+        }
+    }
+}
+
+/// Tells the trades of the bot from external trades on the position checks, close all and the exit
+/// policy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeOwnership {
+    pub known_trade_ids: HashSet<String>, // Trades created by the bot: the trade journal and the state on startup, then every created trade
+    pub policy: ExternalTradePolicy,     // Handling of all other trades
+    pub pending_orders: usize,           // Orders in flight, their trades are not known yet
+}
+
+impl TradeOwnership {
+    /// Marks an order as in flight, unknown trades are not ignored for good until it is recorded.
+    pub fn begin_order(&mut self) {
+        self.pending_orders += 1;
+    }
+
+    /// Records the trades of an order that is not in flight anymore (none if it failed).
+    pub fn end_order(&mut self, trade_ids: impl IntoIterator<Item = String>) {
+        self.pending_orders = self.pending_orders.saturating_sub(1);
+        self.known_trade_ids.extend(trade_ids);
+    }
+}
+
+/// Outcome of the reconciliation.
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileReport {
+    pub restored_groups: usize,          // Position groups restored from the state or rebuilt from the journal
+    pub running: Vec<String>,            // Running trades of the bot
+    pub open_orders: Vec<String>,        // Open (unfilled) limit orders of the bot
+    pub adopted: Vec<String>,            // External trades adopted
+    pub ignored: Vec<String>,            // External trades ignored
+    pub closed_while_down: Vec<ClosedTradeEvent>, // Trades of the bot that closed while it was down
+    pub missing: Vec<String>,            // Trades of the bot that were running but are not found anymore
+}
+
+/// Reconciled state of the position manager.
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    pub position_groups: Vec<PositionGroup>,
+    pub running_trades: Vec<Trade>, // Running trades of the bot and adopted external trades
+    pub ignored_trade_ids: HashSet<String>,
+    pub report: ReconcileReport,
+}
+
+/// Net P&L of a closed trade in sats (fees deducted).
+fn closed_trade_event(trade: &Trade) -> ClosedTradeEvent {
+    ClosedTradeEvent {
+        trade_id: trade.id.clone(),
+        side: trade.side,
+        pl: trade.pl - (trade.opening_fee + trade.closing_fee + trade.sum_carry_fees),
+        closed_ts: trade.closed_ts.unwrap_or(trade.last_update_ts) as i64,
+    }
+}
+
+/// Rebuilds a scaled-out position from its journal entry. Targets of emulated partial closes are
+/// not journaled, the children keep the takeprofit of the server.
+fn rebuild_group(entry: &TradeJournalEntry, group_id: &str, running: &[Trade], closed: &[Trade]) -> Option<PositionGroup> {
+    let stoploss = entry.stoploss.unwrap_or_default() as f64;
+    let mut group = PositionGroup::new(entry.side, entry.entry_price, stoploss, false);
+    group.id = group_id.to_string();
+    group.creation_ts = entry.time;
+
+    for trade_id in &entry.trade_ids {
+        let Some(trade) = running.iter().chain(closed.iter()).find(|trade| &trade.id == trade_id) else {
+            continue;
+        };
+        let risk = (entry.entry_price - stoploss).abs();
+        group.children.push(PositionChild {
+            trade_id: trade.id.clone(),
+            quantity: trade.quantity as u64,
+            takeprofit: trade.takeprofit,
+            r_multiple: if risk > 0.0 && trade.takeprofit > 0.0 { (trade.takeprofit - entry.entry_price).abs() / risk } else { 0.0 },
+            running: trade.running,
+            pl: trade.pl,
+            fees: trade.opening_fee + trade.closing_fee + trade.sum_carry_fees,
+        });
+    }
+    (!group.is_closed()).then_some(group)
+}
+
+/// Trades created by the bot: the ones in the trade journal and the running trades of the state.
+pub fn known_trade_ids(state: Option<&BotState>, journal: &[TradeJournalEntry]) -> HashSet<String> {
+    let mut known: HashSet<String> = journal.iter().flat_map(|entry| entry.trade_ids.iter().cloned()).collect();
+    if let Some(state) = state {
+        known.extend(state.running_trade_ids.iter().cloned());
+        for group in &state.position_groups {
+            known.extend(group.children.iter().map(|child| child.trade_id.clone()));
+        }
+    }
+    known
+}
+
+/// Reconciles the local state with the trades of the account.
+///
+/// Trades of the bot are the ones in the trade journal or the state. Trades that were running when
+/// the state was saved and are closed now are reported with their net P&L. Position groups are
+/// restored from the state (or rebuilt from the journal) and updated from the API. Running and open
+/// trades the bot did not create are adopted or ignored according to the policy.
+///
+/// # Parameters
+/// - `state`: The persisted state, `None` on the first start.
+/// - `journal`: The trade journal entries.
+/// - `running`: Running trades of the account.
+/// - `open`: Open (unfilled limit) trades of the account.
+/// - `closed`: Recently closed trades of the account.
+/// - `policy`: Handling of external trades.
+pub fn reconcile_trades(
+    state: Option<&BotState>,
+    journal: &[TradeJournalEntry],
+    running: &[Trade],
+    open: &[Trade],
+    closed: &[Trade],
+    policy: ExternalTradePolicy,
+) -> Reconciliation {
+    let mut reconciliation = Reconciliation::default();
+    let report = &mut reconciliation.report;
+
+    // Trades created by the bot
+    let known = known_trade_ids(state, journal);
+    let mut expected_running: HashSet<&str> = HashSet::new();
+    if let Some(state) = state {
+        expected_running.extend(state.running_trade_ids.iter().map(String::as_str));
+        for group in &state.position_groups {
+            expected_running.extend(group.children.iter().filter(|child| child.running).map(|child| child.trade_id.as_str()));
+        }
+    }
+
+    // Trades that closed while the bot was down
+    let mut expected_running: Vec<&str> = expected_running.into_iter().collect();
+    expected_running.sort_unstable();
+    for trade_id in expected_running {
+        if running.iter().any(|trade| trade.id == trade_id) {
+            continue;
+        }
+        match closed.iter().find(|trade| trade.id == trade_id) {
+            Some(trade) => report.closed_while_down.push(closed_trade_event(trade)),
+            None => report.missing.push(trade_id.to_string()),
+        }
+    }
+
+    // Position groups of the state, updated with the latest trades
+    let mut groups: Vec<PositionGroup> = state.map(|state| state.position_groups.clone()).unwrap_or_default();
+    for group in groups.iter_mut() {
+        for trade in running.iter().chain(closed.iter()) {
+            group.update_child(trade);
+        }
+        for trade_id in &report.missing {
+            group.close_child(trade_id, 0.0, 0.0);
+        }
+    }
+
+    // Scaled-out positions of the journal that are not in the state
+    for entry in journal {
+        let Some(group_id) = &entry.group_id else { continue };
+        let still_running = entry.trade_ids.iter().any(|id| running.iter().any(|trade| &trade.id == id));
+        if !still_running || groups.iter().any(|group| &group.id == group_id) {
+            continue;
+        }
+        if let Some(group) = rebuild_group(entry, group_id, running, closed) {
+            groups.push(group);
+        }
+    }
+    groups.retain(|group| !group.is_closed());
+    report.restored_groups = groups.len();
+    reconciliation.position_groups = groups;
+
+    // Running and open trades: own, adopted or ignored
+    for trade in running.iter().chain(open.iter()) {
+        if known.contains(&trade.id) {
+            if trade.running {
+                report.running.push(trade.id.clone());
+            } else {
+                report.open_orders.push(trade.id.clone());
+            }
+        } else if policy == ExternalTradePolicy::Adopt {
+            report.adopted.push(trade.id.clone());
+        } else {
+            report.ignored.push(trade.id.clone());
+            reconciliation.ignored_trade_ids.insert(trade.id.clone());
+        }
+    }
+    reconciliation.running_trades = running
+        .iter()
+        .filter(|trade| !reconciliation.ignored_trade_ids.contains(&trade.id))
+        .cloned()
+        .collect();
+
+    reconciliation
+}
+
+/// Trades of the bot among `trades`: the known trades (trade journal, created trades, position groups,
+/// running trades of the last check) and, if the policy adopts them, external trades. External trades
+/// that are ignored are added to `ignored_trade_ids` and stay ignored. While orders are in flight,
+/// unknown trades are only left out of this check, they may be the trades of these orders.
+///
+/// # Parameters
+/// - `trades`: Running or open trades of the account.
+/// - `bot_params`: Bot params holding the state, the known trades, the policy and the ignored trades.
+///
+/// # Returns
+/// - The trades the bot manages.
+pub fn classify_trades(trades: Vec<Trade>, bot_params: &mut BotParams) -> Vec<Trade> {
+    let TradeOwnership { known_trade_ids, policy, pending_orders } = &bot_params.trade_ownership;
+    let mut ignored: Vec<String> = Vec::new();
+    let own = {
+        let mut known: HashSet<&str> = known_trade_ids.iter().map(String::as_str).collect();
+        known.extend(bot_params.running_trades.iter().map(|trade| trade.id.as_str()));
+        for group in &bot_params.position_groups {
+            known.extend(group.children.iter().map(|child| child.trade_id.as_str()));
+        }
+
+        trades
+            .into_iter()
+            .filter(|trade| {
+                if bot_params.ignored_trade_ids.contains(&trade.id) {
+                    return false;
+                }
+                if known.contains(trade.id.as_str()) || *policy == ExternalTradePolicy::Adopt {
+                    return true;
+                }
+                if *pending_orders == 0 {
+                    ignored.push(trade.id.clone());
+                }
+                false
+            })
+            .collect()
+    };
+
+    for trade_id in ignored {
+        info!(%trade_id, "External trade ignored");
+        bot_params.ignored_trade_ids.insert(trade_id);
+    }
+    own
+}
+
+/// Returns the trades of the bot among `trades` (see `classify_trades`).
+///
+/// # Parameters
+/// - `bot_params`: Shared bot params holding the state, the known trades, the policy and the ignored trades.
+/// - `trades`: Running or open trades of the account.
+pub async fn own_trades(bot_params: &Arc<Mutex<BotParams>>, trades: Vec<Trade>) -> Vec<Trade> {
+    classify_trades(trades, &mut *bot_params.lock().await)
+}
+
+/// Fetches the running, open and closed trades, reconciles them with the state and the trade
+/// journal and applies the result to the bot params.
+///
+/// # Parameters
+/// - `api_url`: The API base URL.
+/// - `bot_params`: Shared bot params, receives the known trades, position groups, running and
+///   ignored trades.
+/// - `state`: The persisted state, `None` on the first start.
+/// - `trade_journal_path`: Path of the trade journal.
+/// - `policy`: Handling of external trades.
+///
+/// # Returns
+/// - The reconciliation report.
+pub async fn reconcile_on_startup(
+    api_url: &str,
+    bot_params: &Arc<Mutex<BotParams>>,
+    state: Option<&BotState>,
+    trade_journal_path: &str,
+    policy: ExternalTradePolicy,
+) -> Result<ReconcileReport, String> {
+    // Set first, the position checks classify trades even if the reconciliation fails
+    let journal = read_trade_journal(trade_journal_path);
+    let known_trade_ids = known_trade_ids(state, journal.as_deref().unwrap_or_default());
+    bot_params.lock().await.trade_ownership = TradeOwnership { known_trade_ids, policy, pending_orders: 0 };
+
+    let journal = journal.map_err(|e| format!("Error reading trade journal: {}", e))?;
+
+    let mut trades: Vec<Vec<Trade>> = Vec::new();
+    for status in TradeStatus::ALL {
+        let params = GetTradesParams { r#type: status, from: None, to: None, limit: Some(1000) };
+        let fetched = get_trades(api_url, Some(params)).await.map_err(|e| format!("Error fetching {} trades: {}", status, e))?;
+        trades.push(fetched);
+    }
+
+    let reconciliation = reconcile_trades(state, &journal, &trades[0], &trades[1], &trades[2], policy);
+    let mut bot_params = bot_params.lock().await;
+    bot_params.position_groups = reconciliation.position_groups;
+    bot_params.running_trades = reconciliation.running_trades;
+    bot_params.ignored_trade_ids = reconciliation.ignored_trade_ids;
+    Ok(reconciliation.report)
+}
+
+/// Logs the reconciliation report.
+pub fn log_reconcile_report(report: &ReconcileReport, policy: ExternalTradePolicy) {
+    info!(
+        running = report.running.len(),
+        open_orders = report.open_orders.len(),
+        restored_groups = report.restored_groups,
+        "State reconciliation"
+    );
+    if !report.adopted.is_empty() || !report.ignored.is_empty() {
+        info!(%policy, adopted = report.adopted.len(), ignored = report.ignored.len(), "External trades");
+    }
+    for closed in &report.closed_while_down {
+        info!(trade_id = %closed.trade_id, pl = closed.pl, "Trade closed while the bot was down");
+    }
+    for trade_id in &report.missing {
+        warn!(%trade_id, "Trade was running but is not found anymore");
+    }
+}
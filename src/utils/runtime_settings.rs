@@ -0,0 +1,139 @@
+// src/utils/runtime_settings.rs
+
+use std::collections::VecDeque;
+use serde::{Deserialize, Serialize};
+use crate::config::{SignalSettings, StrategyWeights};
+use crate::math::get_signals::{Signal, SignalExplanation};
+
+// Number of signals kept for the control API
+const MAX_SIGNAL_RECORDS: usize = 50;
+
+/// Settings that can be changed while the bot is running (control API).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    pub paused: bool,                    // No trades are created while paused, signals are still evaluated
+    pub dry_run: bool,                   // Paper trade instead of creating trades on LN Markets
+    pub risk_per_trade_percent: f64,     // Risk handling for trade quantity
+    pub risk_to_reward_ratio: f64,       // Risk handling for takeprofit
+    pub risk_to_loss_ratio: f64,         // Risk handling for stoploss
+    pub signal_settings: SignalSettings, // Indicator weights of the signal score
+}
+
+/// Partial update of the risk parameters, missing values are kept.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskUpdate {
+    pub risk_per_trade_percent: Option<f64>,
+    pub risk_to_reward_ratio: Option<f64>,
+    pub risk_to_loss_ratio: Option<f64>,
+}
+
+/// Partial update of the base signal weights (ranging markets), missing values are kept.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignalWeightsUpdate {
+    pub bollinger_weight: Option<f64>,
+    pub rsi_weight: Option<f64>,
+    pub ma_ema_weight: Option<f64>,
+    pub atr_weight: Option<f64>,
+    pub basis_weight: Option<f64>,
+}
+
+/// A signal evaluated by the bot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalRecord {
+    pub signal: String,                  // Signal bucket (e.g. "StrongBuy")
+    pub explanation: SignalExplanation,
+}
+
+impl RuntimeSettings {
+    /// Restores the settings of the last run (state). A dry run of the config is kept, the bot never
+    /// trades live because of a saved state.
+    ///
+    /// # Parameters
+    /// - `saved`: The settings of the persisted state.
+    pub fn restore(&mut self, saved: &RuntimeSettings) {
+        let dry_run = self.dry_run || saved.dry_run;
+        *self = RuntimeSettings { dry_run, ..saved.clone() };
+    }
+
+    /// Applies a risk update. Nothing is changed if a value is out of range.
+    ///
+    /// # Parameters
+    /// - `update`: The values to change.
+    ///
+    /// # Returns
+    /// - An error naming the invalid value.
+    pub fn apply_risk_update(&mut self, update: &RiskUpdate) -> Result<(), String> {
+        if let Some(risk) = update.risk_per_trade_percent {
+            if risk.is_nan() || risk <= 0.0 || risk > 1.0 {
+                return Err(format!("risk_per_trade_percent must be in (0, 1], got {}", risk));
+            }
+        }
+        for (name, value) in [
+            ("risk_to_reward_ratio", update.risk_to_reward_ratio),
+            ("risk_to_loss_ratio", update.risk_to_loss_ratio),
+        ] {
+            if let Some(value) = value.filter(|value| value.is_nan() || *value <= 0.0) {
+                return Err(format!("{} must be positive, got {}", name, value));
+            }
+        }
+
+        if let Some(risk) = update.risk_per_trade_percent {
+            self.risk_per_trade_percent = risk;
+        }
+        if let Some(ratio) = update.risk_to_reward_ratio {
+            self.risk_to_reward_ratio = ratio;
+        }
+        if let Some(ratio) = update.risk_to_loss_ratio {
+            self.risk_to_loss_ratio = ratio;
+        }
+        Ok(())
+    }
+
+    /// Applies a signal weight update. The trend and high volatility weights follow the base weights
+    /// like on a restart with tuned parameters (see `SignalSettings::with_base_weights`). Nothing is
+    /// changed if a weight is negative.
+    ///
+    /// # Parameters
+    /// - `update`: The weights to change.
+    ///
+    /// # Returns
+    /// - An error naming the invalid weight.
+    pub fn apply_signal_weights_update(&mut self, update: &SignalWeightsUpdate) -> Result<(), String> {
+        for (name, value) in [
+            ("bollinger_weight", update.bollinger_weight),
+            ("rsi_weight", update.rsi_weight),
+            ("ma_ema_weight", update.ma_ema_weight),
+            ("atr_weight", update.atr_weight),
+            ("basis_weight", update.basis_weight),
+        ] {
+            if let Some(value) = value.filter(|value| value.is_nan() || *value < 0.0) {
+                return Err(format!("{} must not be negative, got {}", name, value));
+            }
+        }
+
+        let settings = &self.signal_settings;
+        let weights = StrategyWeights {
+            bollinger_weight: update.bollinger_weight.unwrap_or(settings.bollinger_weight),
+            rsi_weight: update.rsi_weight.unwrap_or(settings.rsi_weight),
+            ma_ema_weight: update.ma_ema_weight.unwrap_or(settings.ma_ema_weight),
+            atr_weight: update.atr_weight.unwrap_or(settings.atr_weight),
+            trend_following: false,
+        };
+        self.signal_settings = SignalSettings {
+            basis_weight: update.basis_weight.unwrap_or(settings.basis_weight),
+            ..settings.with_base_weights(weights)
+        };
+        Ok(())
+    }
+}
+
+/// Appends a signal to the records and drops the oldest beyond the limit.
+pub fn record_signal(records: &mut VecDeque<SignalRecord>, signal: Signal, explanation: &SignalExplanation) {
+    records.push_back(SignalRecord {
+        signal: format!("{:?}", signal),
+        explanation: explanation.clone(),
+    });
+    while records.len() > MAX_SIGNAL_RECORDS {
+        records.pop_front();
+    }
+}
@@ -0,0 +1,154 @@
+// src/utils/session_recorder.rs
+
+use tracing::{error, warn};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::sync::OnceLock;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use crate::utils::connect_ws::PriceData;
+use crate::utils::get_timestamps::get_current_time_ms;
+use crate::utils::shutdown::Shutdown;
+
+// Frames buffered for the writer, frames beyond are dropped
+const RECORDER_CAPACITY: usize = 10_000;
+
+/// Market data message of a recorded session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedEvent {
+    Price { data: PriceData },           // Websocket price message
+    Rest { endpoint: String, body: Value }, // REST response body (endpoint as in the REST metrics, e.g. "futures/ohlcs")
+}
+
+/// Line of a session file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub ts: i64,                         // Time (ms) the message was received
+    #[serde(flatten)]
+    pub event: RecordedEvent,
+}
+
+static RECORDER: OnceLock<mpsc::Sender<RecordedFrame>> = OnceLock::new();
+
+// Session file writer, gzip compressed for `.gz` paths
+enum SessionWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl SessionWriter {
+    fn create(path: &str) -> io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(if path.ends_with(".gz") {
+            SessionWriter::Gzip(GzEncoder::new(file, Compression::default()))
+        } else {
+            SessionWriter::Plain(file)
+        })
+    }
+
+    fn write_frame(&mut self, frame: &RecordedFrame) -> io::Result<()> {
+        let line = serde_json::to_string(frame)?;
+        match self {
+            SessionWriter::Plain(writer) => writeln!(writer, "{}", line),
+            SessionWriter::Gzip(writer) => writeln!(writer, "{}", line),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            SessionWriter::Plain(mut writer) => writer.flush(),
+            SessionWriter::Gzip(writer) => writer.finish()?.flush(),
+        }
+    }
+}
+
+/// Starts recording the session to `path` (JSONL, gzip compressed if it ends with `.gz`). The
+/// frames are written until the shutdown, the file is complete once the task finished.
+///
+/// # Returns
+/// - The writer task, resolves to the number of recorded frames.
+pub fn start_recording(path: &str, mut shutdown: Shutdown) -> io::Result<JoinHandle<usize>> {
+    let mut writer = SessionWriter::create(path)?;
+    let (tx, mut rx) = mpsc::channel::<RecordedFrame>(RECORDER_CAPACITY);
+    if RECORDER.set(tx).is_err() {
+        return Err(io::Error::other("Session recording already started"));
+    }
+
+    let path = path.to_string();
+    Ok(tokio::spawn(async move {
+        let mut written = 0;
+        let mut write = |frame: RecordedFrame| match writer.write_frame(&frame) {
+            Ok(()) => written += 1,
+            Err(e) => error!(%path, error = %e, "Error writing session"),
+        };
+        loop {
+            tokio::select! {
+                frame = rx.recv() => match frame {
+                    Some(frame) => write(frame),
+                    None => break,
+                },
+                _ = shutdown.wait() => {
+                    while let Ok(frame) = rx.try_recv() {
+                        write(frame);
+                    }
+                    break;
+                }
+            }
+        }
+        if let Err(e) = writer.finish() {
+            error!(%path, error = %e, "Error finishing session file");
+        }
+        written
+    }))
+}
+
+/// Whether the session is recorded.
+pub fn is_recording() -> bool {
+    RECORDER.get().is_some()
+}
+
+/// Records a market data message, without effect if the session is not recorded.
+pub fn record(event: RecordedEvent) {
+    let Some(tx) = RECORDER.get() else { return };
+    let frame = RecordedFrame { ts: get_current_time_ms(), event };
+    if let Err(mpsc::error::TrySendError::Full(_)) = tx.try_send(frame) {
+        warn!("Session recorder is falling behind, frame dropped");
+    }
+}
+
+/// Records the body of a REST response. Bodies that are not JSON are kept as string.
+pub fn record_response(endpoint: &str, body: &str) {
+    if !is_recording() {
+        return;
+    }
+    let body = serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()));
+    record(RecordedEvent::Rest { endpoint: endpoint.to_string(), body });
+}
+
+/// Reads a session file (JSONL, gzip compressed if it ends with `.gz`).
+pub fn read_session(path: &str) -> Result<Vec<RecordedFrame>, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn BufRead> = if path.ends_with(".gz") {
+        Box::new(BufReader::new(GzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+
+    let mut frames = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let frame = serde_json::from_str(&line).map_err(|e| format!("Invalid frame on line {}: {}", number + 1, e))?;
+        frames.push(frame);
+    }
+    Ok(frames)
+}
@@ -0,0 +1,190 @@
+// src/utils/session_replay.rs
+
+use tracing::info;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration, Instant};
+use crate::utils::connect_ws::PriceData;
+use crate::utils::session_recorder::{RecordedEvent, RecordedFrame};
+
+// Max wait for a triggered history update to request its OHLCs
+const HISTORY_UPDATE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Session file and speed of a replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaySettings {
+    pub path: String,                    // Recorded session (JSONL, gzip compressed if it ends with `.gz`)
+    pub speed: f64,                      // 1.0 original speed, 10.0 ten times faster, 0 as fast as possible
+}
+
+#[derive(Debug, Default)]
+struct ReplayQueue {
+    pending: VecDeque<String>,           // Recorded bodies not served yet
+    last: Option<String>,                // Last served body
+}
+
+/// Recorded REST responses, served in the recorded order per endpoint.
+#[derive(Debug, Default)]
+pub struct ReplayStore {
+    queues: Mutex<HashMap<String, ReplayQueue>>,
+}
+
+impl ReplayStore {
+    pub fn push(&self, endpoint: &str, body: &Value) {
+        let body = match body {
+            Value::String(body) => body.clone(),
+            body => body.to_string(),
+        };
+        let mut queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        queues.entry(endpoint.to_string()).or_default().pending.push_back(body);
+    }
+
+    /// Next recorded body of the endpoint. Once all are served, paged endpoints (arrays) return an
+    /// empty page and the others repeat the last body.
+    ///
+    /// # Returns
+    /// - `None` if the endpoint was not recorded.
+    pub fn next(&self, endpoint: &str) -> Option<String> {
+        let mut queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let queue = queues.get_mut(endpoint)?;
+        match queue.pending.pop_front() {
+            Some(body) => {
+                queue.last = Some(body.clone());
+                Some(body)
+            }
+            None => queue.last.as_ref().map(|last| if last.trim_start().starts_with('[') { "[]".to_string() } else { last.clone() }),
+        }
+    }
+
+    /// Number of recorded bodies of the endpoint not served yet.
+    pub fn pending(&self, endpoint: &str) -> usize {
+        let queues = self.queues.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        queues.get(endpoint).map(|queue| queue.pending.len()).unwrap_or_default()
+    }
+}
+
+static REPLAY: OnceLock<ReplayStore> = OnceLock::new();
+
+fn replay_store() -> &'static ReplayStore {
+    REPLAY.get_or_init(ReplayStore::default)
+}
+
+/// Switches the REST functions to the recorded responses and queues the ones of the startup.
+pub fn init_replay(startup: &[RecordedFrame]) {
+    let store = replay_store();
+    for frame in startup {
+        if let RecordedEvent::Rest { endpoint, body } = &frame.event {
+            store.push(endpoint, body);
+        }
+    }
+}
+
+/// Whether a session is replayed.
+pub fn is_replaying() -> bool {
+    REPLAY.get().is_some()
+}
+
+/// Recorded response of the endpoint if a session is replayed.
+///
+/// # Returns
+/// - `None` if no session is replayed or the endpoint was not recorded (the request goes to the API).
+pub fn replayed_response(endpoint: &str) -> Option<String> {
+    REPLAY.get()?.next(endpoint)
+}
+
+/// Splits a session into the startup (REST responses before the first price) and the stream.
+pub fn split_session(mut frames: Vec<RecordedFrame>) -> (Vec<RecordedFrame>, Vec<RecordedFrame>) {
+    let first_price = frames
+        .iter()
+        .position(|frame| matches!(frame.event, RecordedEvent::Price { .. }))
+        .unwrap_or(frames.len());
+    let stream = frames.split_off(first_price);
+    (frames, stream)
+}
+
+/// Wait between two frames at the replay speed (0 = no wait).
+pub fn replay_delay(previous_ts: i64, ts: i64, speed: f64) -> Duration {
+    if speed <= 0.0 || ts <= previous_ts {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64((ts - previous_ts) as f64 / 1000.0 / speed)
+}
+
+/// Outcome of a replay.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplaySummary {
+    pub prices: usize,                   // Price messages sent
+    pub responses: usize,                // REST responses queued
+    pub history_updates: usize,          // History updates triggered
+}
+
+/// Feeds the stream of a session through the channels of the live feed: prices to `price_tx` and
+/// REST responses to the replay store. A history update is triggered at every recorded OHLC
+/// response, the replay continues once the update requested it.
+///
+/// # Parameters
+/// - `stream`: The frames after the startup.
+/// - `speed`: The replay speed (1.0 original, 0 as fast as possible).
+/// - `price_tx`: Channel of the price feed.
+/// - `history_tx`: Triggers a history update.
+/// - `shutdown_rx`: Stops the replay.
+pub async fn replay_session(
+    stream: Vec<RecordedFrame>,
+    speed: f64,
+    price_tx: mpsc::Sender<PriceData>,
+    history_tx: mpsc::Sender<()>,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) -> ReplaySummary {
+    let store = replay_store();
+    let mut summary = ReplaySummary::default();
+    let mut previous_ts = stream.first().map(|frame| frame.ts).unwrap_or_default();
+    let mut frames = stream.into_iter().peekable();
+    info!(speed, "Replaying session");
+
+    while let Some(frame) = frames.next() {
+        tokio::select! {
+            _ = sleep(replay_delay(previous_ts, frame.ts, speed)) => {}
+            _ = shutdown_rx.recv() => break,
+        }
+        previous_ts = frame.ts;
+
+        match frame.event {
+            RecordedEvent::Price { data } => {
+                if price_tx.send(data).await.is_err() {
+                    break;
+                }
+                summary.prices += 1;
+            }
+            RecordedEvent::Rest { endpoint, body } => {
+                // Responses of one history update are recorded back to back
+                let mut history_update = endpoint == "futures/ohlcs";
+                store.push(&endpoint, &body);
+                summary.responses += 1;
+                let is_rest = |frame: &RecordedFrame| matches!(frame.event, RecordedEvent::Rest { .. });
+                while let Some(RecordedFrame { event: RecordedEvent::Rest { endpoint, body }, .. }) = frames.next_if(is_rest) {
+                    history_update |= endpoint == "futures/ohlcs";
+                    store.push(&endpoint, &body);
+                    summary.responses += 1;
+                }
+
+                if history_update && history_tx.send(()).await.is_ok() {
+                    summary.history_updates += 1;
+                    let deadline = Instant::now() + HISTORY_UPDATE_TIMEOUT;
+                    while store.pending("futures/ohlcs") > 0 && Instant::now() < deadline {
+                        sleep(Duration::from_millis(5)).await;
+                    }
+                }
+            }
+        }
+    }
+
+    info!(
+        prices = summary.prices,
+        responses = summary.responses,
+        history_updates = summary.history_updates,
+        "Replay finished"
+    );
+    summary
+}
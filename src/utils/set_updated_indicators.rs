@@ -1,69 +1,44 @@
 // src/utils/set_updated_indicators.rs
 
-// TO DO: implmennt logic for price and index derived params as well (ref to get_indicators -> update_price_indicators)
+use tracing::error;
+use crate::{futures::{get_index_history::IndexHistoryEntry, get_ohlcs_history::OhlcHistoryEntry, get_price_history::PriceHistoryEntry}, math::{get_basis::BasisStats, get_market_regime::{MarketRegime, RegimeIndicators}, indicator_set::IndicatorSet}, utils::init_bot_params::BotParams};
 
-use crate::{futures::get_ohlcs_history::OhlcHistoryEntry, utils::init_bot_params::BotParams};
+/// Indicators recalculated from the refreshed history data.
+pub struct IndicatorUpdate {
+    pub ohlc_data: Vec<OhlcHistoryEntry>,
+    pub values: IndicatorSet,
+    pub regime_indicators: RegimeIndicators,
+    pub regime: Option<MarketRegime>,
+    pub price_data: Option<Vec<PriceHistoryEntry>>, // None if price data is not included
+    pub index_data: Option<Vec<IndexHistoryEntry>>, // None if index data is not included
+    pub basis: Option<BasisStats>,
+}
+
+pub fn set_updated_indicators(bot_params: &mut BotParams, update: IndicatorUpdate) {
+    let IndicatorUpdate { ohlc_data, values, regime_indicators, regime, price_data, index_data, basis } = update;
 
-pub fn set_updated_indicators(
-    bot_params: &mut BotParams,
-    ohlc_data: Vec<OhlcHistoryEntry>,
-    ma: Option<f64>,
-    ema: Option<f64>,
-    bollinger_bands: Option<(f64, f64, f64)>,
-    rsi: Option<f64>,
-    atr: Option<f64>,
-    price_ma: Option<f64>,
-    price_ema: Option<f64>,
-    price_bollinger_bands: Option<(f64, f64, f64)>,
-    price_rsi: Option<f64>,
-    index_ma: Option<f64>,
-    index_ema: Option<f64>,
-    index_bollinger_bands: Option<(f64, f64, f64)>,
-    index_rsi: Option<f64>,
-) {
     // Update OHLC data and indicators in bot_params
     if let Some(ref mut indicators) = bot_params.indicators {
 
         indicators.ohlc_data = ohlc_data;
 
-        indicators.ohlc_ma = ma;
-        indicators.ohlc_ema = ema;
-        indicators.ohlc_bollinger_bands = bollinger_bands;
-        indicators.ohlc_rsi = rsi;
-        indicators.atr = atr;
+        // OHLC indicators are replaced, price and index indicators only where the update has a value
+        indicators.values.merge(values);
+
+        indicators.regime_indicators = regime_indicators;
+        indicators.regime = regime;
 
-        // Update price indicators only if values are not None
-        if price_ma.is_some() || price_ema.is_some() || price_bollinger_bands.is_some() || price_rsi.is_some() {
-            if let Some(price_ma) = price_ma {
-                indicators.ma = Some(price_ma);
-            }
-            if let Some(price_ema) = price_ema {
-                indicators.ema = Some(price_ema);
-            }
-            if let Some(price_bollinger_bands) = price_bollinger_bands {
-                indicators.bollinger_bands = Some(price_bollinger_bands);
-            }
-            if let Some(price_rsi) = price_rsi {
-                indicators.rsi = Some(price_rsi);
-            }
+        // Update price data only if price data is included
+        if let Some(price_data) = price_data {
+            indicators.price_data = price_data;
         }
 
-        // Update index indicators only if values are not None
-        if index_ma.is_some() || index_ema.is_some() || index_bollinger_bands.is_some() || index_rsi.is_some() {
-            if let Some(index_ma) = index_ma {
-                indicators.i_ma = Some(index_ma);
-            }
-            if let Some(index_ema) = index_ema {
-                indicators.i_ema = Some(index_ema);
-            }
-            if let Some(index_bollinger_bands) = index_bollinger_bands {
-                indicators.i_bollinger_bands = Some(index_bollinger_bands);
-            }
-            if let Some(index_rsi) = index_rsi {
-                indicators.i_rsi = Some(index_rsi);
-            }
+        // Update index data and basis only if index data is included
+        if let Some(index_data) = index_data {
+            indicators.index_price_data = index_data;
+            indicators.basis = basis;
         }
     } else {
-        eprintln!("No indicators available in bot_params.");
+        error!("No indicators available in bot_params.");
     }
 }
@@ -0,0 +1,207 @@
+// src/utils/shutdown.rs
+
+use tracing::{info, warn};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use serde::Serialize;
+use tokio::sync::{mpsc, watch, Mutex};
+use crate::futures::cancel_trade::cancel_trade;
+use crate::futures::get_trades::{get_trades, GetTradesParams};
+use crate::futures::trade::{Trade, TradeStatus};
+use crate::utils::control_api::close_all_positions;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::manage_positions::ClosedTradeEvent;
+use crate::utils::reconcile_state::own_trades;
+
+/// What happens to the open orders and running trades on shutdown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitPolicy {
+    #[default]
+    Leave,                               // Orders and trades keep running (server-side stoploss and takeprofit)
+    CancelOrders,                        // Open limit orders of the bot (pending paper orders) are canceled
+    CloseAll,                            // Open orders are canceled and the running trades of the bot are closed
+}
+
+impl fmt::Display for ExitPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ExitPolicy::Leave => "leave",
+            ExitPolicy::CancelOrders => "cancel_orders",
+            ExitPolicy::CloseAll => "close_all",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ExitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "leave" => Ok(ExitPolicy::Leave),
+            "cancel_orders" | "cancel" => Ok(ExitPolicy::CancelOrders),
+            "close_all" | "flatten" => Ok(ExitPolicy::CloseAll),
+            _ => Err(format!("Unknown exit policy: {} (leave, cancel_orders, close_all)", s)),
+        }
+    }
+}
+
+/// Shutdown signal shared by the tasks of the bot.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    rx: watch::Receiver<bool>,
+}
+
+/// Sender of the shutdown signal.
+#[derive(Debug)]
+pub struct ShutdownTrigger {
+    tx: watch::Sender<bool>,
+}
+
+/// Creates the shutdown signal.
+pub fn shutdown_channel() -> (ShutdownTrigger, Shutdown) {
+    let (tx, rx) = watch::channel(false);
+    (ShutdownTrigger { tx }, Shutdown { rx })
+}
+
+impl ShutdownTrigger {
+    /// Signals the shutdown to all receivers.
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+}
+
+impl Shutdown {
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Waits for the shutdown. Returns immediately if it was already signaled or the trigger is gone.
+    pub async fn wait(&mut self) {
+        let _ = self.rx.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// Runs a task until it finishes or the shutdown is signaled (the task is dropped).
+pub async fn until_shutdown<F: Future<Output = ()>>(mut shutdown: Shutdown, task: F) {
+    tokio::select! {
+        _ = task => {}
+        _ = shutdown.wait() => {}
+    }
+}
+
+/// Outcome of the exit policy.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExitPolicyResult {
+    pub canceled: Vec<String>,           // IDs of the canceled orders
+    pub closed: Vec<String>,             // IDs of the closed trades
+    pub closed_pl: f64,                  // P&L of the closed trades in sats
+    pub errors: Vec<String>,
+}
+
+/// Applies the exit policy, to the paper exchange in dry runs and to LN Markets otherwise. On LN
+/// Markets only the orders and trades of the bot are canceled and closed, external trades that are
+/// ignored are left alone.
+///
+/// # Parameters
+/// - `policy`: The exit policy.
+/// - `api_url`: The API base URL.
+/// - `bot_params`: Shared bot params holding the runtime settings and the paper exchange.
+/// - `closed_trade_tx`: Channel for the closed paper trades.
+pub async fn apply_exit_policy(
+    policy: ExitPolicy,
+    api_url: &str,
+    bot_params: &Arc<Mutex<BotParams>>,
+    closed_trade_tx: &mpsc::Sender<ClosedTradeEvent>,
+) -> ExitPolicyResult {
+    let mut result = ExitPolicyResult::default();
+    if policy == ExitPolicy::Leave {
+        return result;
+    }
+
+    // Open orders
+    let paper_orders = {
+        let mut bot_params = bot_params.lock().await;
+        let dry_run = bot_params.runtime.dry_run;
+        bot_params.paper_exchange.as_mut().filter(|_| dry_run).map(|exchange| exchange.cancel_orders())
+    };
+    match paper_orders {
+        Some(canceled) => result.canceled = canceled,
+        None => match open_orders(api_url, bot_params).await {
+            Ok(orders) => {
+                for order in orders {
+                    match cancel_trade(api_url, &order.id).await {
+                        Ok(trade) => result.canceled.push(trade.id),
+                        Err(e) => result.errors.push(format!("Error canceling open order {}: {}", order.id, e)),
+                    }
+                }
+            }
+            Err(e) => result.errors.push(format!("Error canceling open orders: {}", e)),
+        },
+    }
+
+    // Running trades
+    if policy == ExitPolicy::CloseAll {
+        match close_all_positions(api_url, bot_params, closed_trade_tx).await {
+            Ok((closed, pl)) => {
+                let mut bot_params = bot_params.lock().await;
+                bot_params.running_trades.retain(|trade| !closed.contains(&trade.id));
+                result.closed = closed;
+                result.closed_pl = pl;
+            }
+            Err(e) => result.errors.push(e),
+        }
+    }
+    result
+}
+
+// Open (unfilled limit) orders of the bot
+async fn open_orders(api_url: &str, bot_params: &Arc<Mutex<BotParams>>) -> Result<Vec<Trade>, String> {
+    let params = GetTradesParams {
+        r#type: TradeStatus::Open,
+        from: None,
+        to: None,
+        limit: None,
+    };
+    let open = get_trades(api_url, Some(params)).await.map_err(|e| e.to_string())?;
+    Ok(own_trades(bot_params, open).await)
+}
+
+/// Final report of a shutdown.
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownSummary {
+    pub uptime_seconds: u64,
+    pub trades_opened: u64,
+    pub realized_pl_sats: f64,           // Net P&L of the trades closed while running, including the exit policy
+    pub aborted_orders: usize,           // Order requests still in flight after the timeout
+    pub exit_policy: ExitPolicy,
+    pub canceled_orders: usize,
+    pub closed_trades: usize,
+    pub open_positions: usize,           // Running trades (paper positions) left open
+    pub state_saved: bool,
+    pub errors: Vec<String>,
+}
+
+/// Logs the shutdown summary.
+pub fn log_shutdown_summary(summary: &ShutdownSummary) {
+    info!(
+        uptime_seconds = summary.uptime_seconds,
+        trades_opened = summary.trades_opened,
+        realized_pl_sats = summary.realized_pl_sats,
+        exit_policy = %summary.exit_policy,
+        canceled_orders = summary.canceled_orders,
+        closed_trades = summary.closed_trades,
+        open_positions = summary.open_positions,
+        state_saved = summary.state_saved,
+        "Shutdown summary"
+    );
+    if summary.aborted_orders > 0 {
+        warn!(aborted_orders = summary.aborted_orders, "Order requests aborted");
+    }
+    for error in &summary.errors {
+        warn!(%error, "Shutdown error");
+    }
+}
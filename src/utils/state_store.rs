@@ -0,0 +1,77 @@
+// src/utils/state_store.rs
+
+use tracing::error;
+use std::error::Error;
+use std::fs;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::{self, Duration};
+use crate::utils::get_timestamps::get_current_time_ms;
+use crate::utils::init_bot_params::BotParams;
+use crate::utils::paper_exchange::PaperState;
+use crate::utils::position_group::PositionGroup;
+use crate::utils::runtime_settings::RuntimeSettings;
+
+/// State of the bot that is persisted between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotState {
+    pub saved_at: i64,                   // Timestamp (ms) when the state was saved
+    pub runtime: RuntimeSettings,        // Runtime settings (pause, dry run, risk, signal weights)
+    pub position_groups: Vec<PositionGroup>,
+    pub running_trade_ids: Vec<String>,  // LN Markets trades running at the last position check
+    pub paper: Option<PaperState>,       // Paper exchange of dry runs
+}
+
+impl BotState {
+    /// Captures the persisted part of the bot params.
+    pub fn capture(bot_params: &BotParams) -> Self {
+        Self {
+            saved_at: get_current_time_ms(),
+            runtime: bot_params.runtime.clone(),
+            position_groups: bot_params.position_groups.clone(),
+            running_trade_ids: bot_params.running_trades.iter().map(|trade| trade.id.clone()).collect(),
+            paper: bot_params.paper_exchange.as_ref().map(|exchange| exchange.state()),
+        }
+    }
+}
+
+/// Saves the state as JSON. The file is written next to the path and renamed, so an interrupted
+/// save keeps the previous state.
+///
+/// # Parameters
+/// - `path`: Path of the state file.
+/// - `state`: The state to save.
+pub fn save_state(path: &str, state: &BotState) -> Result<(), Box<dyn Error>> {
+    let json = serde_json::to_string_pretty(state)?;
+    let temp_path = format!("{}.tmp", path);
+    fs::write(&temp_path, json)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Loads the state. A missing file results in `None`.
+pub fn load_state(path: &str) -> Result<Option<BotState>, Box<dyn Error>> {
+    match fs::read_to_string(path) {
+        Ok(json) => Ok(Some(serde_json::from_str(&json)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Saves the state on interval, so a restart after a crash can reconcile with it.
+///
+/// # Parameters
+/// - `bot_params`: Shared bot params.
+/// - `path`: Path of the state file.
+/// - `interval`: Interval of the saves.
+pub async fn persist_state(bot_params: Arc<Mutex<BotParams>>, path: String, interval: Duration) {
+    let mut interval_timer = time::interval(interval);
+    loop {
+        interval_timer.tick().await;
+        let state = BotState::capture(&*bot_params.lock().await);
+        if let Err(e) = save_state(&path, &state) {
+            error!(%path, error = %e, "Error saving state");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+// src/utils/timeframe.rs
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::time::Duration;
+use chrono::{Datelike, TimeZone, Utc};
+use serde::Serializer;
+
+const MINUTE_MS: i64 = 60_000;
+const DAY_MINUTES: u32 = 24 * 60;
+const WEEK_MINUTES: u32 = 7 * DAY_MINUTES;
+const DAY_MS: i64 = DAY_MINUTES as i64 * MINUTE_MS;
+// First Monday after the epoch (1970-01-05), weekly candles open on Mondays
+const WEEK_ANCHOR_MS: i64 = 4 * DAY_MS;
+
+// Candle lengths served by the LN Markets OHLC endpoint (in minutes) and their `range` values
+const NATIVE_RANGES: [(u32, &str); 13] = [
+    (1, "1"),
+    (3, "3"),
+    (5, "5"),
+    (10, "10"),
+    (15, "15"),
+    (30, "30"),
+    (45, "45"),
+    (60, "60"),
+    (120, "120"),
+    (180, "180"),
+    (240, "240"),
+    (DAY_MINUTES, "1D"),
+    (WEEK_MINUTES, "1W"),
+];
+
+/// Length of a candle. Timeframes LN Markets does not serve (e.g. 6h or 7m) are resampled from a
+/// finer native one.
+///
+/// Intraday candles are aligned to the UTC day (a 7h candle opens at 00:00, 07:00, 14:00 and 21:00),
+/// daily candles to the epoch, weekly candles to Mondays and monthly candles to calendar months.
+#[derive(Debug, Clone, Copy, Eq)]
+pub enum Timeframe {
+    Minutes(u32),                        // N-minute candles
+    Hours(u32),                          // N-hour candles
+    Days(u32),                           // N-day candles
+    Weeks(u32),                          // N-week candles
+    Months(u32),                         // N-month candles (calendar months, 30 days as interval)
+}
+
+impl Timeframe {
+    /// Timeframe of `minutes`, in the largest unit that divides it.
+    pub fn from_minutes(minutes: u32) -> Self {
+        if minutes.is_multiple_of(WEEK_MINUTES) {
+            Timeframe::Weeks(minutes / WEEK_MINUTES)
+        } else if minutes.is_multiple_of(DAY_MINUTES) {
+            Timeframe::Days(minutes / DAY_MINUTES)
+        } else if minutes.is_multiple_of(60) {
+            Timeframe::Hours(minutes / 60)
+        } else {
+            Timeframe::Minutes(minutes)
+        }
+    }
+
+    /// Length in minutes (months count as 30 days).
+    pub fn minutes(&self) -> u32 {
+        match *self {
+            Timeframe::Minutes(minutes) => minutes,
+            Timeframe::Hours(hours) => hours * 60,
+            Timeframe::Days(days) => days * DAY_MINUTES,
+            Timeframe::Weeks(weeks) => weeks * WEEK_MINUTES,
+            Timeframe::Months(months) => months * 30 * DAY_MINUTES,
+        }
+    }
+
+    /// Length of a candle, the refresh interval of the history data.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.minutes() as u64 * 60)
+    }
+
+    /// The `range` of the LN Markets OHLC endpoint, `None` if the timeframe is resampled.
+    pub fn lnm_range(&self) -> Option<&'static str> {
+        match *self {
+            Timeframe::Months(1) => Some("1M"),
+            Timeframe::Months(3) => Some("3M"),
+            Timeframe::Months(_) => None,
+            _ => NATIVE_RANGES.iter().find(|(minutes, _)| *minutes == self.minutes()).map(|(_, range)| *range),
+        }
+    }
+
+    /// The native timeframe the candles are fetched in: the timeframe itself or the longest native
+    /// one that divides it.
+    pub fn source(&self) -> Timeframe {
+        if self.lnm_range().is_some() {
+            return *self;
+        }
+        if let Timeframe::Months(months) = *self {
+            return Timeframe::Months(if months.is_multiple_of(3) { 3 } else { 1 });
+        }
+        let minutes = self.minutes();
+        let native = NATIVE_RANGES
+            .iter()
+            .rev()
+            .map(|(native, _)| *native)
+            .find(|native| minutes.is_multiple_of(*native))
+            .unwrap_or(1);
+        Timeframe::from_minutes(native)
+    }
+
+    /// Open time (ms) of the candle containing `time`.
+    pub fn align(&self, time: i64) -> i64 {
+        if let Timeframe::Months(months) = *self {
+            let index = month_index(time).div_euclid(months as i64) * months as i64;
+            return month_start(index);
+        }
+        let length = self.minutes() as i64 * MINUTE_MS;
+        match self.minutes() {
+            minutes if minutes < DAY_MINUTES => {
+                let day = time.div_euclid(DAY_MS) * DAY_MS;
+                day + (time - day).div_euclid(length) * length
+            }
+            minutes if minutes.is_multiple_of(WEEK_MINUTES) => WEEK_ANCHOR_MS + (time - WEEK_ANCHOR_MS).div_euclid(length) * length,
+            _ => time.div_euclid(length) * length,
+        }
+    }
+
+    /// Open time (ms) of the candle after the one containing `time`.
+    pub fn next_open(&self, time: i64) -> i64 {
+        let open = self.align(time);
+        if let Timeframe::Months(months) = *self {
+            return month_start(month_index(open) + months as i64);
+        }
+        let next = open + self.minutes() as i64 * MINUTE_MS;
+        if self.minutes() < DAY_MINUTES {
+            // The last candle of a day ends with the day
+            next.min(time.div_euclid(DAY_MS) * DAY_MS + DAY_MS)
+        } else {
+            next
+        }
+    }
+}
+
+// Months since 0000-01 of the UTC time
+fn month_index(time: i64) -> i64 {
+    let date = Utc.timestamp_millis_opt(time).single().unwrap_or_default();
+    date.year() as i64 * 12 + date.month0() as i64
+}
+
+fn month_start(index: i64) -> i64 {
+    let (year, month0) = (index.div_euclid(12) as i32, index.rem_euclid(12) as u32);
+    Utc.with_ymd_and_hms(year, month0 + 1, 1, 0, 0, 0).single().map(|date| date.timestamp_millis()).unwrap_or_default()
+}
+
+impl PartialEq for Timeframe {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Timeframe::Months(a), Timeframe::Months(b)) => a == b,
+            (Timeframe::Months(_), _) | (_, Timeframe::Months(_)) => false,
+            _ => self.minutes() == other.minutes(),
+        }
+    }
+}
+
+impl Hash for Timeframe {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        matches!(self, Timeframe::Months(_)).hash(state);
+        self.minutes().hash(state);
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes = self.minutes();
+        match *self {
+            Timeframe::Months(months) => write!(f, "{}M", months),
+            _ if minutes.is_multiple_of(WEEK_MINUTES) => write!(f, "{}w", minutes / WEEK_MINUTES),
+            _ if minutes.is_multiple_of(DAY_MINUTES) => write!(f, "{}d", minutes / DAY_MINUTES),
+            _ if minutes.is_multiple_of(60) => write!(f, "{}h", minutes / 60),
+            _ => write!(f, "{}m", minutes),
+        }
+    }
+}
+
+/// Parses `30`, `45m`, `2h`, `6H`, `1D`, `2w` or `3M`: a plain number counts minutes (LN Markets
+/// ranges), `m`/`min` minutes, `h` hours, `d` days, `w` weeks and `M` months.
+impl FromStr for Timeframe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let (count, unit) = value.split_at(split);
+        let count: u32 = match count.parse() {
+            Ok(count) if count > 0 => count,
+            _ => return Err(format!("Invalid timeframe: {} (e.g. 30, 45m, 2h, 1D, 1W, 1M)", s)),
+        };
+        let minutes = match unit {
+            "M" => return Ok(Timeframe::Months(count)),
+            "" | "m" | "min" => Some(count),
+            unit => match unit.to_lowercase().as_str() {
+                "h" => count.checked_mul(60),
+                "d" => count.checked_mul(DAY_MINUTES),
+                "w" => count.checked_mul(WEEK_MINUTES),
+                _ => return Err(format!("Unknown timeframe unit: {} (m, h, d, w, M)", s)),
+            },
+        };
+        minutes.map(Timeframe::from_minutes).ok_or_else(|| format!("Timeframe too long: {}", s))
+    }
+}
+
+/// Serializes the `range` of the OHLC endpoint (the native source of resampled timeframes).
+pub fn serialize_lnm_range<S: Serializer>(timeframe: &Timeframe, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(timeframe.source().lnm_range().unwrap_or("1"))
+}
@@ -0,0 +1,59 @@
+// src/utils/trade_journal.rs
+
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use crate::futures::trade::Side;
+use crate::math::get_signals::SignalExplanation;
+
+/// A trade created by the bot, persisted with the signal it was created from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeJournalEntry {
+    pub time: i64,                       // Timestamp (ms) when the trade was created
+    pub trade_ids: Vec<String>,          // LN Markets trade IDs (several for scaled-out positions)
+    pub group_id: Option<String>,        // Position group ID of scaled-out positions
+    pub side: Side,                      // Buy or sell
+    pub quantity: u64,                   // Quantity in USD
+    pub leverage: u64,                   // Leverage of the trade
+    pub entry_price: f64,                // Expected entry price (ask/bid at signal time)
+    pub takeprofit: Option<u64>,         // Take-profit price (nearest target for scaled-out positions)
+    pub stoploss: Option<u64>,           // Stop-loss price
+    pub explanation: Option<SignalExplanation>, // Signal breakdown the trade was created from
+}
+
+/// Appends an entry to the trade journal (one JSON object per line).
+///
+/// # Parameters
+/// - `path`: Path of the journal file, created if it does not exist.
+/// - `entry`: The entry to append.
+pub fn append_trade_journal(path: &str, entry: &TradeJournalEntry) -> Result<(), Box<dyn Error>> {
+    let line = serde_json::to_string(entry)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
+
+/// Reads all entries of the trade journal. A missing file results in an empty journal, lines that
+/// can not be parsed are skipped with a warning.
+pub fn read_trade_journal(path: &str) -> Result<Vec<TradeJournalEntry>, Box<dyn Error>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(path, line = number + 1, error = %e, "Skipping invalid trade journal entry"),
+        }
+    }
+    Ok(entries)
+}
@@ -1,102 +1,194 @@
-// src/math/update_data.rs
+// src/utils/update_history_data.rs
 
-//TO DO: add logic for update price and index data (history) on option
-
-use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
 use std::{sync::Arc, error::Error};
 use tokio::sync::{Mutex, mpsc};
 use tokio::time::{self, Duration};
+use crate::futures::get_index_history::{get_index_history, IndexHistoryEntry};
 use crate::futures::get_ohlcs_history::{get_ohlcs_history, GetOhlcsParams, OhlcHistoryEntry};
+use crate::futures::get_price_history::{get_price_history, PriceHistoryEntry};
+use crate::futures::ticker::get_futures_ticker;
 use crate::utils::get_timestamps::get_current_time_ms;
+use crate::utils::timeframe::Timeframe;
+
+/// Refreshed history data, sent after every interval with new OHLCs and every index refresh.
+#[derive(Debug, Clone)]
+pub struct HistoryUpdate {
+    pub ohlc_data: Vec<OhlcHistoryEntry>,               // OHLC history (oldest first)
+    pub price_data: Option<Vec<PriceHistoryEntry>>,     // Price history (oldest first), if price data is included
+    pub index_data: Option<Vec<IndexHistoryEntry>>,     // Index history (oldest first), if index data is included
+    pub ticker_index: Option<f64>,                      // Latest index value of the futures ticker, if index data is included
+}
 
+/// Shared history buffers (oldest first) refreshed by `update_history_data`.
+pub struct HistoryBuffers {
+    pub ohlc_data: Arc<Mutex<Vec<OhlcHistoryEntry>>>,
+    pub price_data: Option<Arc<Mutex<Vec<PriceHistoryEntry>>>>, // None if price data is not included
+    pub index_data: Option<Arc<Mutex<Vec<IndexHistoryEntry>>>>, // None if index data is not included
+}
+
+/// Refreshes the history data on interval (aligned to the candles of the timeframe) and sends it to `tx`.
+/// The index history is refreshed on its own interval as well, so the basis follows the index between
+/// candles. The buffers are bounded to their initial length, empty buffers are filled from `from`.
+///
+/// # Parameters:
+/// - `api_url`: The API base URL.
+/// - `timeframe`: The timeframe of the OHLCs, a candle is the refresh interval.
+/// - `from`: Start timestamp (ms) of the data fetched into empty buffers.
+/// - `buffers`: The OHLC, price and index history buffers.
+/// - `index_interval`: Refresh interval of the index history (not used for replays).
+/// - `tx`: Channel for the refreshed history data.
+/// - `ticks`: Triggers the updates instead of the interval (replays), `None` for the interval.
 pub async fn update_history_data(
     api_url: &str,
-    interval: Duration,
-    ohlc_data: Arc<Mutex<Vec<OhlcHistoryEntry>>>,
-    range: &str,
-    tx: mpsc::Sender<Vec<OhlcHistoryEntry>>,
+    timeframe: Timeframe,
+    from: i64,
+    buffers: HistoryBuffers,
+    index_interval: Duration,
+    tx: mpsc::Sender<HistoryUpdate>,
+    mut ticks: Option<mpsc::Receiver<()>>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let initial_delay = calculate_initial_delay(interval);
-    tokio::time::sleep(initial_delay).await;
-    let mut interval_timer = time::interval(interval);
+    let HistoryBuffers { ohlc_data, price_data, index_data } = buffers;
+
+    // Replays follow the recorded responses, the index is only refreshed with the OHLCs
+    let refresh_index = index_data.is_some() && ticks.is_none();
+    let mut index_timer = time::interval_at(time::Instant::now() + index_interval, index_interval);
+    index_timer.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+
+    if ticks.is_none() {
+        let initial_delay = calculate_initial_delay(timeframe);
+        tokio::time::sleep(initial_delay).await;
+    }
+    let mut interval_timer = time::interval(timeframe.duration());
 
     loop {
-        interval_timer.tick().await;
+        let candle = tokio::select! {
+            tick = next_candle(&mut ticks, &mut interval_timer) => match tick {
+                Some(()) => true,
+                None => return Ok(()),
+            },
+            _ = index_timer.tick(), if refresh_index => false,
+        };
 
-        let mut ohlc_data_lock = ohlc_data.lock().await;
+        let ohlc_data_update = if candle {
+            let mut ohlc_data_lock = ohlc_data.lock().await;
 
-        let data_length = ohlc_data_lock.len();
+            let data_length = ohlc_data_lock.len();
 
-        let from = ohlc_data_lock.last().map(|entry| entry.time).unwrap_or(0);
+            let from = ohlc_data_lock.last().map(|entry| entry.time).unwrap_or(from);
 
-        let ohlc_params = GetOhlcsParams {
-            range,
-            from,
-            to: get_current_time_ms(),
-            limit: Some(1000),
-            debug: false
-        };
+            let ohlc_params = GetOhlcsParams {
+                range: timeframe,
+                from,
+                to: get_current_time_ms(),
+                limit: Some(1000),
+                debug: false
+            };
 
-        match get_ohlcs_history(api_url, ohlc_params).await {
-            Ok(mut new_data) => {
-                new_data.retain(|entry| entry.time > from);
+            let mut new_data = match get_ohlcs_history(api_url, ohlc_params).await {
+                Ok(new_data) => new_data,
+                Err(e) => {
+                    error!(error = %e, "Error updating OHLC data");
+                    continue;
+                }
+            };
 
-                if !new_data.is_empty() {
-                    ohlc_data_lock.extend(new_data);
+            new_data.retain(|entry| entry.time > from);
+            if new_data.is_empty() {
+                continue;
+            }
 
-                    if ohlc_data_lock.len() > data_length {
-                        ohlc_data_lock.reverse();
-                        ohlc_data_lock.truncate(data_length);
-                        ohlc_data_lock.reverse();
-                    }
+            ohlc_data_lock.extend(new_data);
 
-                    let tx_clone = tx.clone();
-                    let ohlc_data_clone = ohlc_data.clone();
+            if data_length > 0 && ohlc_data_lock.len() > data_length {
+                ohlc_data_lock.reverse();
+                ohlc_data_lock.truncate(data_length);
+                ohlc_data_lock.reverse();
+            }
+            ohlc_data_lock.clone()
+        } else {
+            ohlc_data.lock().await.clone()
+        };
 
-                    tokio::spawn(async move {
-                        let ohlc_data_lock = ohlc_data_clone.lock().await;
-                        if let Err(e) = tx_clone.send(ohlc_data_lock.clone()).await {
-                            eprintln!("Error sending updated OHLC data: {}", e);
-                        }
-                    });
+        // Price history (with the OHLCs only)
+        let price_data_update = match &price_data {
+            Some(price_data) => {
+                let mut price_data_lock = price_data.lock().await;
+                if candle {
+                    let from = price_data_lock.last().map(|entry| entry.time + 1).unwrap_or(from);
+                    match get_price_history(api_url, Some(from), Some(get_current_time_ms()), None).await {
+                        Ok(new_data) => merge_history(&mut price_data_lock, new_data, |entry| entry.time),
+                        Err(e) => error!(error = %e, "Error updating price data"),
+                    }
                 }
+                Some(price_data_lock.clone())
             }
-            Err(e) => {
-                let error_message = format!("Error updating OHLC data: {}", e);
-                eprintln!("{}", error_message);
+            None => None,
+        };
+
+        // Index history and latest index of the ticker (basis)
+        let (index_data_update, ticker_index) = match &index_data {
+            Some(index_data) => {
+                let mut index_data_lock = index_data.lock().await;
+                let from = index_data_lock.last().map(|entry| entry.time + 1).unwrap_or(from);
+                match get_index_history(api_url, Some(from), Some(get_current_time_ms()), None).await {
+                    Ok(new_data) => merge_history(&mut index_data_lock, new_data, |entry| entry.time),
+                    Err(e) => error!(error = %e, "Error updating index data"),
+                }
+
+                let ticker_index = match get_futures_ticker(api_url).await {
+                    Ok(ticker) => Some(ticker.index),
+                    Err(e) => {
+                        error!(error = %e, "Error fetching futures ticker");
+                        None
+                    }
+                };
+                (Some(index_data_lock.clone()), ticker_index)
             }
+            None => (None, None),
+        };
+
+        let history_update = HistoryUpdate {
+            ohlc_data: ohlc_data_update,
+            price_data: price_data_update,
+            index_data: index_data_update,
+            ticker_index,
+        };
+        if let Err(e) = tx.send(history_update).await {
+            error!(error = %e, "Error sending updated history data");
         }
     }
 }
 
-fn calculate_initial_delay(interval: Duration) -> Duration {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("System time should be valid");
-    let now_secs = now.as_secs();
+// Waits for the next candle (or the next tick of a replay), None once the ticks ended
+async fn next_candle(ticks: &mut Option<mpsc::Receiver<()>>, interval_timer: &mut time::Interval) -> Option<()> {
+    match ticks {
+        Some(ticks) => ticks.recv().await,
+        None => {
+            interval_timer.tick().await;
+            Some(())
+        }
+    }
+}
 
-    let interval_secs = interval.as_secs();
+/// Appends the entries newer than the last entry of the buffer (oldest first) and keeps the buffer
+/// at its initial length by dropping the oldest entries.
+pub fn merge_history<T>(buffer: &mut Vec<T>, mut new_data: Vec<T>, time: fn(&T) -> i64) {
+    let data_length = buffer.len();
+    let from = buffer.last().map(time).unwrap_or(i64::MIN);
 
-    let next_aligned_time = match interval_secs {
-        60 | 180 | 300 | 600 | 900 | 1800 | 2700 | 3600 => {
-            ((now_secs / interval_secs) + 1) * interval_secs
-        }
-        86400 => {
-            let days_since_epoch = now_secs / 86400;
-            (days_since_epoch + 1) * 86400
-        }
-        2592000 => {
-            let months_since_epoch = now_secs / 2592000;
-            (months_since_epoch + 1) * 2592000
-        }
-        7776000 => {
-            let quarters_since_epoch = now_secs / 7776000;
-            (quarters_since_epoch + 1) * 7776000
-        }
-        _ => {
-            ((now_secs / interval_secs) + 1) * interval_secs
-        }
-    };
+    new_data.retain(|entry| time(entry) > from);
+    new_data.sort_by_key(time); // The price and index history APIs return the newest entries first
+    buffer.extend(new_data);
+
+    if data_length > 0 && buffer.len() > data_length {
+        let excess = buffer.len() - data_length;
+        buffer.drain(..excess);
+    }
+}
 
-    Duration::from_secs(next_aligned_time - now_secs) + Duration::from_millis(1000)
+// Wait until a second after the next candle opens
+fn calculate_initial_delay(timeframe: Timeframe) -> Duration {
+    let now = get_current_time_ms();
+    Duration::from_millis((timeframe.next_open(now) - now) as u64) + Duration::from_millis(1000)
 }
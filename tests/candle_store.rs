@@ -0,0 +1,199 @@
+// src/tests/candle_store.rs
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+use common::candles::candle;
+use common::mock_lnm::{MockLnm, MockResponse};
+use trading_backend::futures::get_ohlcs_history::{GetOhlcsParams, OhlcHistoryEntry};
+use trading_backend::futures::get_price_history::PriceHistoryEntry;
+use trading_backend::utils::candle_store::{
+    download_ohlcs, export_csv, export_parquet, init_candle_store, load_ohlcs_history, missing_chunks, CandleStore, ExportFormat, HistorySeries,
+};
+use trading_backend::utils::get_timestamps::get_current_time_ms;
+use trading_backend::utils::timeframe::Timeframe;
+
+const MINUTE: i64 = 60_000;
+const DAY: i64 = 24 * 60 * MINUTE;
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("candle_store_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+// Closed one minute candles, the first one `count` minutes before the last full hour
+fn history_start(count: i64) -> i64 {
+    (get_current_time_ms() / (60 * MINUTE) - 1) * 60 * MINUTE - count * MINUTE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_load_series() {
+        let dir = temp_dir("append");
+        let store = CandleStore::open(&dir).unwrap();
+        let series = HistorySeries::Ohlc(Timeframe::Minutes(1));
+        assert_eq!(store.coverage::<OhlcHistoryEntry>(&series).unwrap(), None);
+
+        // Newer entries are appended, unordered input is sorted
+        assert_eq!(store.append(&series, vec![
+            candle(3 * MINUTE, 102.0, 105.0, 101.0, 103.0, 0.5),
+            candle(2 * MINUTE, 101.0, 104.0, 100.0, 102.0, 0.5),
+        ]).unwrap(), 2);
+        assert_eq!(store.append(&series, vec![candle(4 * MINUTE, 103.0, 106.0, 102.0, 104.0, 0.5)]).unwrap(), 1);
+        // Older and overlapping entries are merged without duplicates
+        assert_eq!(store.append(&series, vec![
+            candle(MINUTE, 100.0, 103.0, 99.0, 101.0, 0.5),
+            candle(3 * MINUTE, 202.0, 205.0, 201.0, 203.0, 0.5),
+        ]).unwrap(), 1);
+
+        let entries: Vec<OhlcHistoryEntry> = store.load(&series, 0, i64::MAX).unwrap();
+        let closes: Vec<(i64, f64)> = entries.iter().map(|entry| (entry.time, entry.close)).collect();
+        assert_eq!(closes, vec![(MINUTE, 101.0), (2 * MINUTE, 102.0), (3 * MINUTE, 203.0), (4 * MINUTE, 104.0)]);
+        assert_eq!(entries[0].volume, 0.5);
+        assert_eq!(store.coverage::<OhlcHistoryEntry>(&series).unwrap(), Some((MINUTE, 4 * MINUTE)));
+        assert_eq!(store.load::<OhlcHistoryEntry>(&series, 2 * MINUTE, 3 * MINUTE).unwrap().len(), 2);
+
+        // One file per series
+        let prices = vec![PriceHistoryEntry { time: 1_000, value: 100_000.5 }];
+        store.append(&HistorySeries::Price, prices).unwrap();
+        assert!(store.path(&HistorySeries::Price).exists());
+        assert_eq!(store.load::<OhlcHistoryEntry>(&series, 0, i64::MAX).unwrap().len(), 4);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backfill_keeps_coverage() {
+        let dir = temp_dir("backfill");
+        let store = CandleStore::open(&dir).unwrap();
+        let series = HistorySeries::Ohlc(Timeframe::Minutes(1));
+
+        // Newest chunk first, older chunks are prepended
+        store.append(&series, vec![
+            candle(5 * MINUTE, 104.0, 107.0, 103.0, 105.0, 0.5),
+            candle(6 * MINUTE, 105.0, 108.0, 104.0, 106.0, 0.5),
+        ]).unwrap();
+        assert_eq!(store.append(&series, vec![
+            candle(3 * MINUTE, 102.0, 105.0, 101.0, 103.0, 0.5),
+            candle(4 * MINUTE, 103.0, 106.0, 102.0, 104.0, 0.5),
+        ]).unwrap(), 2);
+        assert_eq!(store.append(&series, vec![candle(MINUTE, 100.0, 103.0, 99.0, 101.0, 0.5)]).unwrap(), 1);
+        assert_eq!(store.coverage::<OhlcHistoryEntry>(&series).unwrap(), Some((MINUTE, 6 * MINUTE)));
+
+        let entries: Vec<OhlcHistoryEntry> = store.load(&series, 0, i64::MAX).unwrap();
+        let times: Vec<i64> = entries.iter().map(|entry| entry.time / MINUTE).collect();
+        assert_eq!(times, vec![1, 3, 4, 5, 6]);
+
+        // Another store on the same directory sees the coverage of the file
+        let other = CandleStore::open(&dir).unwrap();
+        other.append(&series, vec![candle(7 * MINUTE, 106.0, 109.0, 105.0, 107.0, 0.5)]).unwrap();
+        assert_eq!(store.coverage::<OhlcHistoryEntry>(&series).unwrap(), Some((MINUTE, 7 * MINUTE)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_chunks() {
+        // Empty store: the whole span
+        assert_eq!(missing_chunks(None, 0, 249, 100), vec![(0, 99), (100, 199), (200, 249)]);
+        // Covered span: nothing
+        assert!(missing_chunks(Some((0, 500)), 100, 400, 100).is_empty());
+        // Before (newest first) and after the stored entries
+        assert_eq!(
+            missing_chunks(Some((200, 300)), 0, 450, 100),
+            vec![(100, 199), (0, 99), (301, 400), (401, 450)]
+        );
+    }
+
+    #[test]
+    fn test_export_csv_and_parquet() {
+        let dir = temp_dir("export");
+        std::fs::create_dir_all(&dir).unwrap();
+        let candles = vec![
+            candle(MINUTE, 100.0, 103.0, 99.0, 101.0, 0.5),
+            candle(2 * MINUTE, 101.5, 104.5, 100.5, 102.5, 0.5),
+        ];
+
+        let csv_path = dir.join("ohlc.csv");
+        export_csv(&csv_path, &candles).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv, "time,open,high,low,close,volume\n60000,100,103,99,101,0.5\n120000,101.5,104.5,100.5,102.5,0.5\n");
+
+        let parquet_path = dir.join("ohlc.parquet");
+        export_parquet(&parquet_path, &candles).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&parquet_path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let columns: Vec<String> = reader.metadata().file_metadata().schema_descr().columns().iter().map(|column| column.name().to_string()).collect();
+        assert_eq!(columns, vec!["time", "open", "high", "low", "close", "volume"]);
+        let rows: Vec<(i64, f64)> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                let row = row.unwrap();
+                (row.get_timestamp_millis(0).unwrap(), row.get_double(4).unwrap())
+            })
+            .collect();
+        assert_eq!(rows, vec![(MINUTE, 101.0), (2 * MINUTE, 102.5)]);
+
+        assert_eq!("Parquet".parse::<ExportFormat>(), Ok(ExportFormat::Parquet));
+        assert!("xlsx".parse::<ExportFormat>().is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_from_store() {
+        let mock = MockLnm::start().await;
+        let start = history_start(2500);
+        let closes: Vec<f64> = (0..2500).map(|i| 100_000.0 + i as f64).collect();
+        mock.set_candles(start, MINUTE, &closes).await;
+        let store = CandleStore::open(temp_dir("download")).unwrap();
+        let series = HistorySeries::Ohlc(Timeframe::Minutes(1));
+        let ohlc_requests = || mock.requests_to(Method::GET, "/v2/futures/ohlcs");
+
+        // 1000 candles per request, stored chunk by chunk
+        let first = download_ohlcs(&mock.api_url, &store, Timeframe::Minutes(1), start + 1000 * MINUTE, start + 2499 * MINUTE).await.unwrap();
+        assert_eq!((first.requests, first.added), (2, 1500));
+        assert_eq!(first.coverage, Some((start + 1000 * MINUTE, start + 2499 * MINUTE)));
+        let requests = ohlc_requests().await.len();
+
+        // Nothing missing: no request
+        let again = download_ohlcs(&mock.api_url, &store, Timeframe::Minutes(1), start + 1000 * MINUTE, start + 2499 * MINUTE).await.unwrap();
+        assert_eq!((again.requests, again.added), (0, 0));
+        assert_eq!(ohlc_requests().await.len(), requests);
+
+        // A failed request keeps the store, the next download continues
+        mock.script(Method::GET, "/v2/futures/ohlcs", vec![MockResponse::Status(StatusCode::INTERNAL_SERVER_ERROR)]).await;
+        assert!(download_ohlcs(&mock.api_url, &store, Timeframe::Minutes(1), start, start + 2499 * MINUTE).await.is_err());
+        let earlier = download_ohlcs(&mock.api_url, &store, Timeframe::Minutes(1), start, start + 2499 * MINUTE).await.unwrap();
+        assert_eq!((earlier.requests, earlier.added), (1, 1000));
+
+        let candles: Vec<OhlcHistoryEntry> = store.load(&series, start, start + 2499 * MINUTE).unwrap();
+        assert_eq!(candles.len(), 2500);
+        assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == MINUTE));
+        assert_eq!(candles.last().unwrap().close, 102_499.0);
+    }
+
+    #[tokio::test]
+    async fn test_load_ohlcs_history_reads_store_first() {
+        let mock = MockLnm::start().await;
+        // Daily candles up to the last closed one
+        let start = (get_current_time_ms() / DAY - 300) * DAY;
+        let closes: Vec<f64> = (0..300).map(|i| 100_000.0 + i as f64).collect();
+        mock.set_candles(start, DAY, &closes).await;
+        init_candle_store(temp_dir("load").to_str().unwrap()).unwrap();
+
+        let params = || GetOhlcsParams { range: Timeframe::Days(1), from: start, to: get_current_time_ms(), limit: Some(1000), debug: false };
+        let candles = load_ohlcs_history(&mock.api_url, params()).await.unwrap();
+        assert_eq!(candles.len(), 300);
+        let requests = mock.requests_to(Method::GET, "/v2/futures/ohlcs").await.len();
+
+        // Second run: the stored candles are not downloaded again, only the running candle is fetched
+        let candles = load_ohlcs_history(&mock.api_url, params()).await.unwrap();
+        assert_eq!(candles.len(), 300);
+        assert_eq!(mock.requests_to(Method::GET, "/v2/futures/ohlcs").await.len(), requests + 1);
+    }
+}
@@ -0,0 +1,21 @@
+// src/tests/common/candles.rs
+
+use serde_json::{json, Value};
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+
+/// Creates an OHLC candle.
+pub fn candle(time: i64, open: f64, high: f64, low: f64, close: f64, volume: f64) -> OhlcHistoryEntry {
+    OhlcHistoryEntry { time, open, high, low, close, volume }
+}
+
+/// Converts a candle to the JSON of the LN Markets OHLCs endpoint.
+pub fn candle_json(candle: &OhlcHistoryEntry) -> Value {
+    json!({
+        "time": candle.time,
+        "open": candle.open,
+        "high": candle.high,
+        "low": candle.low,
+        "close": candle.close,
+        "volume": candle.volume,
+    })
+}
@@ -0,0 +1,378 @@
+// src/tests/common/mock_lnm.rs
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Once};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::time::Duration;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use trading_backend::utils::api_cache::{init_api_cache, CacheSettings};
+use trading_backend::utils::get_signature::generate_signature;
+use trading_backend::utils::rate_limiter::{init_rate_limiter, RateLimitSettings};
+
+// Credentials the mock accepts, exported to the environment of the test process
+pub const API_KEY: &str = "mock-key";
+pub const API_SECRET: &str = "mock-secret";
+pub const API_PASSPHRASE: &str = "mock-passphrase";
+
+// Channel of the price feed
+pub const PRICE_CHANNEL: &str = "futures:btc_usd:last-price";
+
+// Recorded LN Markets responses
+const USER: &str = include_str!("../fixtures/lnm/user.json");
+const TICKER: &str = include_str!("../fixtures/lnm/ticker.json");
+const MARKET: &str = include_str!("../fixtures/lnm/market.json");
+const TRADE: &str = include_str!("../fixtures/lnm/trade.json");
+
+/// Scripted response, served once for its method and path before the default handler.
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    Json(StatusCode, Value),
+    Status(StatusCode),                  // Error status with an LN Markets style message
+    RateLimited(u64),                    // 429 with `Retry-After` in seconds
+    Malformed,                           // 200 with a truncated JSON body
+}
+
+/// Request received by the REST mock.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,                    // Path including the `/v2` prefix, as signed
+    pub query: Option<String>,
+    pub body: String,
+    pub timestamp: Option<i64>,          // `LNM-ACCESS-TIMESTAMP` the request was signed at
+    pub signature_valid: bool,           // `LNM-ACCESS-*` headers match `generate_signature`
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    scripted: HashMap<(Method, String), VecDeque<MockResponse>>,
+    requests: Vec<RecordedRequest>,
+    candles: Vec<Value>,                 // Served by `/futures/ohlcs`, ascending by time
+    trades: Vec<Value>,                  // Created by `POST /futures`, listed by `GET /futures`, closed and canceled
+    ws_frames: VecDeque<String>,         // Sent to the next websocket subscriber
+    ws_subscriptions: Vec<Value>,        // JSON-RPC subscription requests
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+/// Local LN Markets REST API and websocket price feed.
+pub struct MockLnm {
+    pub api_url: String,                 // Base URL of the REST API (`http://127.0.0.1:<port>/v2`)
+    pub ws_url: String,                  // URL of the JSON-RPC websocket
+    state: SharedState,
+}
+
+static INIT: Once = Once::new();
+
+/// Exports the mock credentials and lifts the request budget and the cache TTLs, so tests don't
+/// wait on the limiter or see data of another mock.
+pub fn init_test_env() {
+    INIT.call_once(|| {
+        std::env::set_var("LN_API_KEY", API_KEY);
+        std::env::set_var("LN_API_SECRET", API_SECRET);
+        std::env::set_var("LN_API_PASSPHRASE", API_PASSPHRASE);
+        init_rate_limiter(RateLimitSettings { requests_per_second: 1_000.0, burst: 1_000.0, max_retries: 2 });
+        init_api_cache(CacheSettings { ticker_ttl: Duration::ZERO, user_ttl: Duration::ZERO });
+    });
+}
+
+impl MockLnm {
+    /// Starts the REST and websocket servers on free local ports.
+    pub async fn start() -> Self {
+        init_test_env();
+        let state = SharedState::default();
+
+        let router = Router::new().fallback(handle_rest).with_state(Arc::clone(&state));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}/v2", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let ws_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", ws_listener.local_addr().unwrap());
+        tokio::spawn(serve_ws(ws_listener, Arc::clone(&state)));
+
+        Self { api_url, ws_url, state }
+    }
+
+    /// Queues responses for a method and path (e.g. `/v2/futures/ticker`), served in order.
+    pub async fn script(&self, method: Method, path: &str, responses: Vec<MockResponse>) {
+        let mut state = self.state.lock().await;
+        state.scripted.entry((method, path.to_string())).or_default().extend(responses);
+    }
+
+    /// Candles of `/futures/ohlcs`, one per close price, `step_ms` apart.
+    pub async fn set_candles(&self, start_time: i64, step_ms: i64, closes: &[f64]) {
+        let mut open = closes.first().copied().unwrap_or_default();
+        let candles = closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let candle = json!({
+                    "time": start_time + i as i64 * step_ms,
+                    "open": open,
+                    "high": open.max(close) + 5.0,
+                    "low": open.min(close) - 5.0,
+                    "close": close,
+                    "volume": 1_000.0,
+                });
+                open = close;
+                candle
+            })
+            .collect();
+        self.state.lock().await.candles = candles;
+    }
+
+    /// Queues frames for the next websocket subscriber, sent after the subscription reply.
+    pub async fn script_ws(&self, frames: Vec<String>) {
+        self.state.lock().await.ws_frames.extend(frames);
+    }
+
+    pub async fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().await.requests.clone()
+    }
+
+    /// Requests received for a method and path.
+    pub async fn requests_to(&self, method: Method, path: &str) -> Vec<RecordedRequest> {
+        self.requests().await.into_iter().filter(|request| request.method == method && request.path == path).collect()
+    }
+
+    /// Trades created through the mock.
+    pub async fn trades(&self) -> Vec<Value> {
+        self.state.lock().await.trades.clone()
+    }
+
+    pub async fn ws_subscriptions(&self) -> Vec<Value> {
+        self.state.lock().await.ws_subscriptions.clone()
+    }
+}
+
+/// Price notification of the websocket feed.
+pub fn price_frame(price: f64, time: i64) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "subscription",
+        "params": {
+            "channel": PRICE_CHANNEL,
+            "data": { "lastPrice": price, "lastTickDirection": "PlusTick", "time": time },
+        },
+    })
+    .to_string()
+}
+
+fn fixture(raw: &str) -> Value {
+    serde_json::from_str(raw).expect("invalid fixture")
+}
+
+fn query_param<'a>(query: Option<&'a str>, key: &str) -> Option<&'a str> {
+    query?.split('&').filter_map(|pair| pair.split_once('=')).find(|(name, _)| *name == key).map(|(_, value)| value)
+}
+
+// Query values are percent-encoded by the client (`-` as `%2D`)
+fn percent_decode(value: &str) -> String {
+    let mut decoded = Vec::new();
+    let mut bytes = value.bytes();
+    while let Some(byte) = bytes.next() {
+        let hex = (byte == b'%').then(|| bytes.clone().take(2).collect::<Vec<u8>>());
+        match hex.and_then(|hex| u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()) {
+            Some(value) => {
+                decoded.push(value);
+                bytes.nth(1);
+            }
+            None => decoded.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// Same check as LN Markets: HMAC of timestamp, method, path and query (GET, DELETE) or body
+fn verify_signature(headers: &HeaderMap, method: &Method, path: &str, data: Option<&str>) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let Some(timestamp) = header("LNM-ACCESS-TIMESTAMP").and_then(|value| value.parse::<i64>().ok()) else {
+        return false;
+    };
+    let expected = generate_signature(API_SECRET, timestamp, method.as_str(), path, data);
+    header("LNM-ACCESS-KEY") == Some(API_KEY)
+        && header("LNM-ACCESS-PASSPHRASE") == Some(API_PASSPHRASE)
+        && header("LNM-ACCESS-SIGNATURE") == Some(expected.as_str())
+}
+
+async fn handle_rest(State(state): State<SharedState>, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+    let path = uri.path().to_string();
+    let query = uri.query().map(str::to_string);
+    let body = String::from_utf8_lossy(&body).to_string();
+    let data = if method == Method::POST || method == Method::PUT { Some(body.as_str()) } else { query.as_deref() };
+    let signature_valid = verify_signature(&headers, &method, &path, data);
+    let timestamp = headers.get("LNM-ACCESS-TIMESTAMP").and_then(|value| value.to_str().ok()?.parse().ok());
+
+    let mut state = state.lock().await;
+    state.requests.push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        query: query.clone(),
+        body: body.clone(),
+        timestamp,
+        signature_valid,
+    });
+    if !signature_valid {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "message": "Invalid signature" }))).into_response();
+    }
+
+    let scripted = state.scripted.get_mut(&(method.clone(), path.clone())).and_then(VecDeque::pop_front);
+    match scripted {
+        Some(response) => scripted_response(response),
+        None => state.default_response(&method, &path, query.as_deref(), &body),
+    }
+}
+
+fn scripted_response(response: MockResponse) -> Response {
+    match response {
+        MockResponse::Json(status, body) => (status, Json(body)).into_response(),
+        MockResponse::Status(status) => {
+            let message = status.canonical_reason().unwrap_or("Error");
+            (status, Json(json!({ "message": message }))).into_response()
+        }
+        MockResponse::RateLimited(seconds) => (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, seconds.to_string())],
+            Json(json!({ "message": "Too many requests" })),
+        )
+            .into_response(),
+        MockResponse::Malformed => {
+            (StatusCode::OK, [(CONTENT_TYPE, "application/json")], "{\"lastPrice\": 100000,").into_response()
+        }
+    }
+}
+
+impl MockState {
+    fn default_response(&mut self, method: &Method, path: &str, query: Option<&str>, body: &str) -> Response {
+        let ok = |value: Value| (StatusCode::OK, Json(value)).into_response();
+        match (method.as_str(), path) {
+            ("GET", "/v2/user") => ok(fixture(USER)),
+            ("GET", "/v2/futures/ticker") => ok(fixture(TICKER)),
+            ("GET", "/v2/futures/market") => ok(fixture(MARKET)),
+            ("GET", "/v2/futures/ohlcs") => ok(self.ohlcs_page(query)),
+            ("GET", "/v2/futures") => ok(self.list_trades(query)),
+            ("POST", "/v2/futures") => match self.create_trade(body) {
+                Ok(trade) => ok(trade),
+                Err(message) => (StatusCode::BAD_REQUEST, Json(json!({ "message": message }))).into_response(),
+            },
+            ("DELETE", "/v2/futures") => match self.update_trade(query_param(query, "id").map(percent_decode).as_deref(), "running", "closed") {
+                Some(trade) => ok(trade),
+                None => (StatusCode::NOT_FOUND, Json(json!({ "message": "Trade not found" }))).into_response(),
+            },
+            ("POST", "/v2/futures/cancel") => {
+                let params: Value = serde_json::from_str(body).unwrap_or_default();
+                match self.update_trade(params["id"].as_str(), "open", "canceled") {
+                    Some(trade) => ok(trade),
+                    None => (StatusCode::NOT_FOUND, Json(json!({ "message": "Trade not found" }))).into_response(),
+                }
+            }
+            _ => (StatusCode::NOT_FOUND, Json(json!({ "message": "Not found" }))).into_response(),
+        }
+    }
+
+    // Moves a trade from one state to another (running to closed, open to canceled)
+    fn update_trade(&mut self, id: Option<&str>, from: &str, to: &str) -> Option<Value> {
+        let trade = self.trades.iter_mut().find(|trade| trade["id"].as_str() == id && trade[from] == true)?;
+        trade[from] = json!(false);
+        trade[to] = json!(true);
+        if to == "closed" {
+            trade["closed_ts"] = trade["creation_ts"].clone();
+            trade["exit_price"] = trade["price"].clone();
+        }
+        Some(trade.clone())
+    }
+
+    // Candles within `from..=to`, at most `limit` (LN Markets default 100)
+    fn ohlcs_page(&self, query: Option<&str>) -> Value {
+        let param = |key: &str| query_param(query, key).and_then(|value| value.parse::<i64>().ok());
+        let (from, to) = (param("from").unwrap_or(i64::MIN), param("to").unwrap_or(i64::MAX));
+        let limit = param("limit").unwrap_or(100) as usize;
+        let page: Vec<Value> = self
+            .candles
+            .iter()
+            .filter(|candle| candle["time"].as_i64().is_some_and(|time| time >= from && time <= to))
+            .take(limit)
+            .cloned()
+            .collect();
+        Value::Array(page)
+    }
+
+    fn list_trades(&self, query: Option<&str>) -> Value {
+        let state = query_param(query, "type").unwrap_or("open");
+        let limit = query_param(query, "limit").and_then(|value| value.parse().ok()).unwrap_or(100);
+        let trades: Vec<Value> = self.trades.iter().filter(|trade| trade[state] == true).take(limit).cloned().collect();
+        Value::Array(trades)
+    }
+
+    // Market orders fill at the ask (buy) or bid (sell) of the ticker fixture, limit orders stay open
+    fn create_trade(&mut self, body: &str) -> Result<Value, String> {
+        let params: Value = serde_json::from_str(body).map_err(|e| format!("Invalid body: {}", e))?;
+        let side = params["side"].as_str().filter(|side| *side == "b" || *side == "s").ok_or("Invalid side")?;
+        let r#type = params["type"].as_str().filter(|r#type| *r#type == "m" || *r#type == "l").ok_or("Invalid type")?;
+        let quantity = params["quantity"].as_u64().ok_or("Quantity is required")?;
+        let ticker = fixture(TICKER);
+        let price = match (r#type, side) {
+            ("l", _) => params["price"].as_f64().ok_or("Price is required for limit orders")?,
+            (_, "b") => ticker["askPrice"].as_f64().unwrap_or_default(),
+            _ => ticker["bidPrice"].as_f64().unwrap_or_default(),
+        };
+
+        let mut trade = fixture(TRADE);
+        trade["id"] = json!(format!("mock-trade-{}", self.trades.len() + 1));
+        trade["type"] = json!(r#type);
+        trade["side"] = json!(side);
+        trade["quantity"] = json!(quantity);
+        trade["leverage"] = params["leverage"].clone();
+        trade["price"] = json!(price);
+        trade["entry_price"] = json!(price);
+        trade["takeprofit"] = json!(params["takeprofit"].as_u64().unwrap_or_default());
+        trade["stoploss"] = json!(params["stoploss"].as_u64().unwrap_or_default());
+        trade["open"] = json!(r#type == "l");
+        trade["running"] = json!(r#type == "m");
+        self.trades.push(trade.clone());
+        Ok(trade)
+    }
+}
+
+// JSON-RPC websocket: replies to the subscription, sends the scripted frames and keeps the
+// connection open until the client closes it
+async fn serve_ws(listener: TcpListener, state: SharedState) {
+    while let Ok((stream, _)) = listener.accept().await {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let Ok(ws_stream) = accept_async(stream).await else { return };
+            let (mut write, mut read) = ws_stream.split();
+
+            let Some(Ok(Message::Text(text))) = read.next().await else { return };
+            let request: Value = serde_json::from_str(&text).unwrap_or_default();
+            let reply = json!({ "jsonrpc": "2.0", "id": request["id"], "result": request["params"] });
+            let frames: Vec<String> = {
+                let mut state = state.lock().await;
+                state.ws_subscriptions.push(request);
+                state.ws_frames.drain(..).collect()
+            };
+
+            for frame in std::iter::once(reply.to_string()).chain(frames) {
+                if write.send(Message::Text(frame)).await.is_err() {
+                    return;
+                }
+            }
+            while let Some(Ok(message)) = read.next().await {
+                if let Message::Close(_) = message {
+                    break;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,7 @@
+// src/tests/common/mod.rs
+
+// Shared by several test binaries, each uses a part of it
+#![allow(dead_code)]
+
+pub mod candles;
+pub mod mock_lnm;
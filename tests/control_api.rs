@@ -0,0 +1,174 @@
+// src/tests/control_api.rs
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use axum::http::{header::AUTHORIZATION, HeaderMap, HeaderValue};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+use trading_backend::backtest::execution_model::ExecutionModel;
+use trading_backend::config::{load_signal_settings, StrategyWeights};
+use trading_backend::utils::control_api::{control_router, is_authorized, ControlState};
+use trading_backend::utils::init_bot_params::BotParams;
+use trading_backend::utils::reconcile_state::TradeOwnership;
+use trading_backend::utils::runtime_settings::{RiskUpdate, RuntimeSettings, SignalWeightsUpdate};
+
+async fn runtime_settings() -> RuntimeSettings {
+    RuntimeSettings {
+        paused: false,
+        dry_run: false,
+        risk_per_trade_percent: 0.01,
+        risk_to_reward_ratio: 0.25,
+        risk_to_loss_ratio: 0.25,
+        signal_settings: load_signal_settings().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_apply_risk_update() {
+        let mut runtime = runtime_settings().await;
+        let update = RiskUpdate { risk_per_trade_percent: Some(0.02), ..Default::default() };
+        runtime.apply_risk_update(&update).unwrap();
+        assert_eq!(runtime.risk_per_trade_percent, 0.02);
+        assert_eq!(runtime.risk_to_reward_ratio, 0.25);
+
+        // An invalid value rejects the whole update
+        let update = RiskUpdate {
+            risk_per_trade_percent: Some(0.03),
+            risk_to_loss_ratio: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(runtime.apply_risk_update(&update).is_err());
+        assert_eq!(runtime.risk_per_trade_percent, 0.02);
+        assert!(runtime.apply_risk_update(&RiskUpdate { risk_per_trade_percent: Some(1.5), ..Default::default() }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_signal_weights_update() {
+        let mut runtime = runtime_settings().await;
+        let update = SignalWeightsUpdate { rsi_weight: Some(0.4), ..Default::default() };
+        runtime.apply_signal_weights_update(&update).unwrap();
+        assert_eq!(runtime.signal_settings.rsi_weight, 0.4);
+        assert!((runtime.signal_settings.high_volatility_weights.rsi_weight - 0.2).abs() < 1e-9);
+        assert_eq!(runtime.signal_settings.bollinger_weight, 0.25);
+
+        // Same regime weights as tuned parameters on a restart
+        let base = load_signal_settings().await;
+        let weights = StrategyWeights { rsi_weight: 0.4, ..base.weights_for(None) };
+        let restarted = base.with_base_weights(weights);
+        assert_eq!(runtime.signal_settings.trend_weights.rsi_weight, restarted.trend_weights.rsi_weight);
+        assert_eq!(
+            runtime.signal_settings.high_volatility_weights.rsi_weight,
+            restarted.high_volatility_weights.rsi_weight
+        );
+        assert!(runtime.signal_settings.trend_weights.rsi_weight > base.trend_weights.rsi_weight);
+
+        let update = SignalWeightsUpdate { atr_weight: Some(-0.1), ..Default::default() };
+        assert!(runtime.apply_signal_weights_update(&update).is_err());
+        assert_eq!(runtime.signal_settings.atr_weight, 0.25);
+    }
+
+    #[tokio::test]
+    async fn test_restore_runtime_settings() {
+        let mut saved = runtime_settings().await;
+        saved.paused = true;
+        saved.risk_per_trade_percent = 0.02;
+        saved.signal_settings.rsi_weight = 0.4;
+
+        let mut runtime = runtime_settings().await;
+        runtime.restore(&saved);
+        assert!(runtime.paused);
+        assert!(!runtime.dry_run);
+        assert_eq!(runtime.risk_per_trade_percent, 0.02);
+        assert_eq!(runtime.signal_settings.rsi_weight, 0.4);
+
+        // A dry run of the config is kept
+        let mut runtime = runtime_settings().await;
+        runtime.dry_run = true;
+        runtime.restore(&saved);
+        assert!(runtime.dry_run);
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer wrong!"));
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("secret"));
+        assert!(!is_authorized(&headers, "secret"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert!(is_authorized(&headers, "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_control_api_pause_and_risk() {
+        let bot_params = Arc::new(Mutex::new(BotParams {
+            user_data: None,
+            ticker_data: None,
+            market_data: None,
+            indicators: None,
+            trades: None,
+            running_trades: Vec::new(),
+            position_groups: Vec::new(),
+            paper_exchange: None,
+            runtime: runtime_settings().await,
+            last_signals: VecDeque::new(),
+            ignored_trade_ids: HashSet::new(),
+            trade_ownership: TradeOwnership::default(),
+        }));
+        let (closed_trade_tx, _closed_trade_rx) = mpsc::channel(1);
+        let state = ControlState {
+            api_url: "http://127.0.0.1:1".into(),
+            bot_params: Arc::clone(&bot_params),
+            token: "secret".into(),
+            execution_model: ExecutionModel::default(),
+            paper_fee_rate: 0.001,
+            closed_trade_tx,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, control_router(state)).await });
+
+        let client = reqwest::Client::new();
+        let response = client.post(format!("{}/pause", url)).send().await.unwrap();
+        assert_eq!(response.status(), 401);
+        assert!(!bot_params.lock().await.runtime.paused);
+
+        let response = client.post(format!("{}/pause", url)).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(bot_params.lock().await.runtime.paused);
+
+        let response = client
+            .put(format!("{}/risk", url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "risk_per_trade_percent": 0.005 }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(bot_params.lock().await.runtime.risk_per_trade_percent, 0.005);
+
+        let response = client
+            .post(format!("{}/dry-run", url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "enabled": true }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(bot_params.lock().await.paper_exchange.is_some());
+
+        let status: serde_json::Value =
+            client.get(format!("{}/status", url)).bearer_auth("secret").send().await.unwrap().json().await.unwrap();
+        assert_eq!(status["runtime"]["paused"], true);
+        assert_eq!(status["runtime"]["dry_run"], true);
+        assert_eq!(status["paper"]["open_positions"], 0);
+
+        let response = client.get(format!("{}/indicators", url)).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), 503);
+    }
+}
@@ -0,0 +1,94 @@
+// src/tests/dashboard.rs
+
+mod common;
+
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use common::candles::candle;
+use trading_backend::math::get_signals::{IndicatorContribution, SignalExplanation};
+use trading_backend::utils::dashboard::{
+    calculate_liquidation_distance, calculate_unrealized_pl, draw_dashboard, DashboardSnapshot, DashboardTrade,
+};
+use trading_backend::utils::runtime_settings::SignalRecord;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_unrealized_pl() {
+        // 100$ long from 50k to 100k: 100 * (1/50000 - 1/100000) BTC = 100_000 sats
+        assert!((calculate_unrealized_pl(true, 100.0, 50_000.0, 100_000.0) - 100_000.0).abs() < 1e-6);
+        assert!((calculate_unrealized_pl(false, 100.0, 50_000.0, 100_000.0) + 100_000.0).abs() < 1e-6);
+        assert_eq!(calculate_unrealized_pl(true, 100.0, 0.0, 100_000.0), 0.0);
+    }
+
+    #[test]
+    fn test_calculate_liquidation_distance() {
+        assert!((calculate_liquidation_distance(100_000.0, 95_000.0) - 0.05).abs() < 1e-12);
+        assert!((calculate_liquidation_distance(100_000.0, 110_000.0) - 0.1).abs() < 1e-12);
+        assert_eq!(calculate_liquidation_distance(0.0, 95_000.0), 0.0);
+    }
+
+    #[test]
+    fn test_draw_dashboard() {
+        let snapshot = DashboardSnapshot {
+            last_price: Some(100_000.0),
+            prices: vec![99_900.0, 99_950.0, 100_000.0],
+            candles: (0..20)
+                .map(|i| {
+                    let open = 99_900.0 + i as f64;
+                    candle(i * 60_000, open, open + 15.0, open - 10.0, open + 5.0, 1.0)
+                })
+                .collect(),
+            indicators: None,
+            signals: vec![SignalRecord {
+                signal: "Buy".to_string(),
+                explanation: SignalExplanation {
+                    price: 100_000.0,
+                    time: 1_700_000_000_000,
+                    contributions: vec![IndicatorContribution {
+                        indicator: "rsi".to_string(),
+                        value: 25.0,
+                        threshold: Some(30.0),
+                        condition: "rsi < 30".to_string(),
+                        weight: 0.25,
+                        contribution: 0.25,
+                    }],
+                    score: 0.25,
+                    bucket: 1,
+                    regime: None,
+                    invalid_reason: None,
+                },
+            }],
+            trades: vec![DashboardTrade {
+                id: "abcdef123456".to_string(),
+                is_buy: true,
+                quantity: 100.0,
+                entry_price: 95_000.0,
+                liquidation: Some(90_000.0),
+                unrealized_pl: 5_263.0,
+            }],
+            paused: true,
+            dry_run: false,
+            ws_connected: true,
+            ws_reconnects: 2,
+            tick_rate: 1.5,
+            balance_sats: Some(1_000_000.0),
+            realized_pl_sats: 0.0,
+            status_message: Some("Trading paused.".to_string()),
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|frame| draw_dashboard(frame, &snapshot)).unwrap();
+        let content: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(content.contains("PAUSED"));
+        assert!(content.contains("connected"));
+        assert!(content.contains("Indicators not initialized"));
+        assert!(content.contains("rsi +0.25"));
+        assert!(content.contains("abcdef12"));
+        assert!(content.contains("10.00%"));
+        assert!(content.contains("Trading paused."));
+    }
+}
@@ -0,0 +1,94 @@
+// src/tests/execution_model.rs
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use trading_backend::backtest::execution_model::{ExecutionModel, SlippageModel, SpreadModel};
+use trading_backend::utils::paper_exchange::{MarketOrder, PaperEvent, PaperExchange};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_spread() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let model = ExecutionModel { spread: SpreadModel::Fixed { spread: 10.0 }, ..ExecutionModel::default() };
+
+        let buy = model.fill(100_000.0, 100_100.0, true, None, &mut rng);
+        assert_eq!(buy.fill_price, 100_105.0);
+        assert_eq!(buy.price_difference(), 105.0);
+        let sell = model.fill(100_000.0, 100_000.0, false, None, &mut rng);
+        assert_eq!(sell.fill_price, 99_995.0);
+        assert!(sell.adverse_difference_percent(false) > 0.0);
+    }
+
+    #[test]
+    fn test_fill_atr_spread() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let model = ExecutionModel { spread: SpreadModel::AtrProportional { factor: 0.1 }, ..ExecutionModel::default() };
+
+        assert_eq!(model.fill(100_000.0, 100_000.0, true, Some(400.0), &mut rng).fill_price, 100_020.0);
+        assert_eq!(model.fill(100_000.0, 100_000.0, true, None, &mut rng).fill_price, 100_000.0);
+    }
+
+    #[test]
+    fn test_fill_slippage_is_seeded() {
+        let model = ExecutionModel { slippage: SlippageModel::Uniform { max: 0.001 }, ..ExecutionModel::default() };
+        let fills = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20).map(|_| model.fill(100_000.0, 100_000.0, true, None, &mut rng).fill_price).collect::<Vec<f64>>()
+        };
+
+        assert_eq!(fills(3), fills(3));
+        assert!(fills(3).iter().all(|price| (100_000.0..=100_100.0).contains(price)));
+    }
+
+    #[test]
+    fn test_paper_exchange_latency_and_exits() {
+        let model = ExecutionModel { spread: SpreadModel::Fixed { spread: 2.0 }, latency_ticks: 2, ..ExecutionModel::default() };
+        let mut exchange = PaperExchange::new(model, 1_000_000.0, 0.0);
+        let (id, fill) = exchange.submit_market_order(MarketOrder { is_buy: true, quantity: 100.0, signal_price: 100_000.0, stoploss: 99_000.0, takeprofit: 101_000.0, atr: None }, 0);
+        assert!(fill.is_none());
+
+        // Filled on the second price update
+        assert!(exchange.on_price(100_050.0, 1).is_empty());
+        match exchange.on_price(100_100.0, 2).as_slice() {
+            [PaperEvent::Filled { id: filled_id, fill, .. }] => {
+                assert_eq!(filled_id, &id);
+                assert_eq!(fill.signal_price, 100_000.0);
+                assert_eq!(fill.fill_price, 100_101.0);
+            }
+            events => panic!("unexpected events: {:?}", events),
+        }
+
+        // Takeprofit fills at its level
+        match exchange.on_price(101_500.0, 3).as_slice() {
+            [PaperEvent::Closed { exit_reason, trade, .. }] => {
+                assert_eq!(exit_reason, "takeprofit");
+                assert_eq!(trade.exit_price, 101_000.0);
+                assert!(trade.pl > 0.0);
+            }
+            events => panic!("unexpected events: {:?}", events),
+        }
+        assert!(exchange.positions.is_empty());
+        assert_eq!(exchange.closed_trades.len(), 1);
+        assert!((exchange.balance - (1_000_000.0 + exchange.closed_trades[0].net_pl())).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_paper_exchange_without_latency() {
+        let mut exchange = PaperExchange::new(ExecutionModel::default(), 1_000_000.0, 0.001);
+        let (_, fill) = exchange.submit_market_order(MarketOrder { is_buy: false, quantity: 100.0, signal_price: 100_000.0, stoploss: 101_000.0, takeprofit: 99_000.0, atr: None }, 0);
+        assert!(matches!(fill, Some(PaperEvent::Filled { .. })));
+        assert_eq!(exchange.positions.len(), 1);
+
+        match exchange.on_price(101_200.0, 1).as_slice() {
+            [PaperEvent::Closed { exit_reason, trade, .. }] => {
+                assert_eq!(exit_reason, "stoploss");
+                assert_eq!(trade.exit_price, 101_200.0);
+                assert!(trade.net_pl() < 0.0);
+            }
+            events => panic!("unexpected events: {:?}", events),
+        }
+    }
+}
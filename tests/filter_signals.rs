@@ -0,0 +1,127 @@
+// tests/filter_signals.rs
+
+use trading_backend::config::SignalConditioning;
+use trading_backend::math::get_signals::Signal;
+use trading_backend::utils::filter_signals::{Direction, FilterDecision, SignalFilter, SkipReason};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_filter(confirmation_ticks: u32) -> SignalFilter {
+        let settings = SignalConditioning {
+            entry_threshold: 0.2,
+            exit_threshold: 0.1,
+            confirmation_ticks,
+            confirmation_seconds: 10,
+            loss_cooldown_seconds: 60,
+        };
+        SignalFilter::new(settings, 0, 1800) // no trade gap, 30 min candles
+    }
+
+    fn is_suppressed(decision: &FilterDecision) -> bool {
+        matches!(decision, FilterDecision::Suppressed(_))
+    }
+
+    #[test]
+    fn test_confirmation_by_ticks() {
+        let mut filter = create_filter(3);
+
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.5, 1_000)));
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.5, 2_000)));
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 3_000), FilterDecision::Actionable(Direction::Long));
+    }
+
+    #[test]
+    fn test_confirmation_by_seconds() {
+        let mut filter = create_filter(100);
+
+        assert!(is_suppressed(&filter.evaluate(Signal::Sell, -0.5, 0)));
+        assert_eq!(filter.evaluate(Signal::Sell, -0.5, 10_000), FilterDecision::Actionable(Direction::Short));
+    }
+
+    #[test]
+    fn test_default_entry_threshold_suppresses_weak_signals() {
+        let settings = SignalConditioning { confirmation_ticks: 1, ..SignalConditioning::default() };
+        let mut filter = SignalFilter::new(settings, 0, 1800);
+
+        // Score 0.25 is in the Buy bucket (> 0.2) but below the default entry threshold
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.25, 0)));
+        assert!(is_suppressed(&filter.evaluate(Signal::Sell, -0.25, 1_000)));
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 2_000), FilterDecision::Actionable(Direction::Long));
+    }
+
+    #[test]
+    fn test_opposite_signal_resets_confirmation() {
+        let mut filter = create_filter(2);
+
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.5, 1_000)));
+        assert!(is_suppressed(&filter.evaluate(Signal::Sell, -0.5, 2_000)));
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.5, 3_000)));
+    }
+
+    #[test]
+    fn test_hysteresis_requires_exit_before_reentry() {
+        let mut filter = create_filter(1);
+        let candle = 1_800_000;
+
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 0), FilterDecision::Actionable(Direction::Long));
+        filter.record_trade(Direction::Long, 0);
+
+        // Score hovers above the exit threshold: no new entry, even in the next candle
+        assert_eq!(filter.evaluate(Signal::Hold, 0.15, candle), FilterDecision::NoSignal);
+        assert!(is_suppressed(&filter.evaluate(Signal::Buy, 0.5, candle + 1_000)));
+
+        // Score falls below the exit threshold, the direction is released
+        assert_eq!(filter.evaluate(Signal::Hold, 0.05, candle + 2_000), FilterDecision::NoSignal);
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, candle + 3_000), FilterDecision::Actionable(Direction::Long));
+    }
+
+    #[test]
+    fn test_duplicate_signal_within_candle() {
+        let mut filter = create_filter(1);
+
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 0), FilterDecision::Actionable(Direction::Long));
+        filter.record_trade(Direction::Long, 0);
+        filter.evaluate(Signal::Hold, 0.0, 1_000); // release hysteresis
+        assert_eq!(
+            filter.evaluate(Signal::Buy, 0.5, 2_000),
+            FilterDecision::Suppressed(SkipReason::DuplicateCandle(Direction::Long))
+        );
+    }
+
+    #[test]
+    fn test_signal_without_trade_leaves_no_state() {
+        let mut filter = create_filter(1);
+
+        // Further signals wait for the order of the actionable one
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 0), FilterDecision::Actionable(Direction::Long));
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 1_000), FilterDecision::Suppressed(SkipReason::OrderInFlight));
+
+        // No trade created: no hysteresis and no duplicate candle
+        filter.release_trade();
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 2_000), FilterDecision::Actionable(Direction::Long));
+    }
+
+    #[test]
+    fn test_cooldown_after_loss_is_per_direction() {
+        let mut filter = create_filter(1);
+        assert_eq!(filter.record_loss(Direction::Long, 0), Some(60_000));
+
+        assert_eq!(
+            filter.evaluate(Signal::Buy, 0.5, 30_000),
+            FilterDecision::Suppressed(SkipReason::Cooldown { direction: Direction::Long, seconds_left: 30 })
+        );
+        assert_eq!(filter.evaluate(Signal::Sell, -0.5, 31_000), FilterDecision::Actionable(Direction::Short));
+        filter.record_trade(Direction::Short, 31_000);
+        assert_eq!(filter.evaluate(Signal::Buy, 0.5, 61_000), FilterDecision::Actionable(Direction::Long));
+    }
+
+    #[test]
+    fn test_no_cooldown_if_disabled() {
+        let settings = SignalConditioning { loss_cooldown_seconds: 0, ..SignalConditioning::default() };
+        let mut filter = SignalFilter::new(settings, 0, 1800);
+
+        assert_eq!(filter.record_loss(Direction::Long, 0), None);
+    }
+}
@@ -0,0 +1,68 @@
+// src/tests/get_basis.rs
+
+use trading_backend::futures::get_index_history::IndexHistoryEntry;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+use trading_backend::math::get_basis::{calculate_basis_history, calculate_basis_stats};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_candle(time: i64, close: f64) -> OhlcHistoryEntry {
+        OhlcHistoryEntry {
+            time,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0.0,
+        }
+    }
+
+    fn create_index(time: i64, value: f64) -> IndexHistoryEntry {
+        IndexHistoryEntry { time, value }
+    }
+
+    #[test]
+    fn test_basis_history_matches_nearest_index() {
+        let ohlcs = vec![create_candle(1_000, 100.0), create_candle(2_000, 110.0)];
+        // Index data newest first (API order), slightly offset timestamps
+        let index = vec![create_index(2_100, 105.0), create_index(1_050, 98.0), create_index(500, 50.0)];
+
+        let history = calculate_basis_history(&ohlcs, &index);
+        assert_eq!(history, vec![2.0, 5.0]);
+    }
+
+    #[test]
+    fn test_basis_history_without_index() {
+        let ohlcs = vec![create_candle(1_000, 100.0)];
+        assert!(calculate_basis_history(&ohlcs, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_basis_stats_z_score() {
+        // Basis alternates between 1 and 3 (mean 2, std dev 1), last basis is 3
+        let ohlcs: Vec<OhlcHistoryEntry> = (0..10)
+            .map(|i| create_candle(i * 1_000, if i % 2 == 0 { 101.0 } else { 103.0 }))
+            .collect();
+        let index: Vec<IndexHistoryEntry> = (0..10).map(|i| create_index(i * 1_000, 100.0)).collect();
+
+        let stats = calculate_basis_stats(&ohlcs, &index, 10, None).unwrap();
+        assert_eq!(stats.mean, 2.0);
+        assert_eq!(stats.std_dev, 1.0);
+        assert_eq!(stats.basis, 3.0);
+        assert_eq!(stats.z_score, 1.0);
+
+        // Live price against a newer index from the ticker
+        let stats = calculate_basis_stats(&ohlcs, &index, 10, Some(99.0)).unwrap();
+        assert_eq!(stats.z_score_at(104.0), 3.0);
+    }
+
+    #[test]
+    fn test_basis_stats_constant_basis() {
+        let ohlcs: Vec<OhlcHistoryEntry> = (0..10).map(|i| create_candle(i * 1_000, 101.0)).collect();
+        let index: Vec<IndexHistoryEntry> = (0..10).map(|i| create_index(i * 1_000, 100.0)).collect();
+
+        assert_eq!(calculate_basis_stats(&ohlcs, &index, 10, None), None); // No variation, no z-score
+    }
+}
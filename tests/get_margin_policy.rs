@@ -0,0 +1,59 @@
+// tests/get_margin_policy.rs
+
+use trading_backend::math::get_margin_policy::{
+    calculate_cash_in_amount, calculate_liquidation_distance, calculate_margin_topup,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liquidation_distance() {
+        let distance = calculate_liquidation_distance(100_000.0, 98_000.0, true).unwrap();
+        assert!((distance - 0.02).abs() < 1e-9);
+        let distance = calculate_liquidation_distance(100_000.0, 102_000.0, false).unwrap();
+        assert!((distance - 0.02).abs() < 1e-9);
+
+        // Beyond liquidation: negative
+        let distance = calculate_liquidation_distance(100_000.0, 101_000.0, true).unwrap();
+        assert!((distance + 0.01).abs() < 1e-9);
+
+        assert_eq!(calculate_liquidation_distance(100_000.0, 0.0, true), None);
+    }
+
+    #[test]
+    fn test_topup_close_to_liquidation() {
+        // Price within 1.5% of liquidation, threshold 2%
+        let topup = calculate_margin_topup(100_000.0, 98_500.0, true, 0.02, 1000, 50_000.0);
+        assert_eq!(topup, Some(1000));
+        let topup = calculate_margin_topup(100_000.0, 101_500.0, false, 0.02, 1000, 50_000.0);
+        assert_eq!(topup, Some(1000));
+    }
+
+    #[test]
+    fn test_no_topup_beyond_liquidation() {
+        // Buy with the price already below its liquidation, sell with the price above
+        assert_eq!(calculate_margin_topup(100_000.0, 101_000.0, true, 0.02, 1000, 50_000.0), None);
+        assert_eq!(calculate_margin_topup(100_000.0, 99_000.0, false, 0.02, 1000, 50_000.0), None);
+    }
+
+    #[test]
+    fn test_no_topup_far_from_liquidation() {
+        let topup = calculate_margin_topup(100_000.0, 90_000.0, true, 0.02, 1000, 50_000.0);
+        assert_eq!(topup, None);
+    }
+
+    #[test]
+    fn test_no_topup_with_insufficient_balance() {
+        let topup = calculate_margin_topup(100_000.0, 98_500.0, true, 0.02, 1000, 500.0);
+        assert_eq!(topup, None);
+    }
+
+    #[test]
+    fn test_cash_in_above_threshold() {
+        assert_eq!(calculate_cash_in_amount(6_000.7, 5_000.0), Some(6_000));
+        assert_eq!(calculate_cash_in_amount(4_000.0, 5_000.0), None);
+        assert_eq!(calculate_cash_in_amount(-100.0, 0.0), None);
+    }
+}
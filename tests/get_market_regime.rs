@@ -0,0 +1,87 @@
+// src/tests/get_market_regime.rs
+
+use trading_backend::config::RegimeSettings;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+use trading_backend::math::get_market_regime::{calculate_regime_indicators, classify_market_regime, MarketRegime, RegimeIndicators};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_settings() -> RegimeSettings {
+        RegimeSettings {
+            adx_period: 14,
+            adx_trend_threshold: 25.0,
+            ema_slope_lookback: 5,
+            ema_slope_threshold: 0.0002,
+            high_volatility_atr_percent: 0.01,
+            high_volatility_bandwidth: 0.06,
+        }
+    }
+
+    // Candles (oldest first) with a constant step per candle and a fixed high/low spread
+    fn create_mock_ohlcs(start: f64, step: f64, spread: f64, count: usize) -> Vec<OhlcHistoryEntry> {
+        (0..count)
+            .map(|i| {
+                let close = start + step * i as f64;
+                OhlcHistoryEntry {
+                    time: i as i64 * 60_000,
+                    open: close - step,
+                    high: close + spread,
+                    low: close - spread,
+                    close,
+                    volume: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_high_volatility_takes_precedence() {
+        let indicators = RegimeIndicators {
+            adx: Some(40.0),
+            ema_slope: Some(0.001),
+            atr_percent: Some(0.02),
+            bb_bandwidth: Some(0.01),
+        };
+        assert_eq!(classify_market_regime(&indicators, &create_settings()), Some(MarketRegime::HighVolatility));
+    }
+
+    #[test]
+    fn test_trend_requires_adx_confirmation() {
+        let mut indicators = RegimeIndicators {
+            adx: Some(30.0),
+            ema_slope: Some(-0.001),
+            atr_percent: Some(0.002),
+            bb_bandwidth: Some(0.01),
+        };
+        assert_eq!(classify_market_regime(&indicators, &create_settings()), Some(MarketRegime::TrendingDown));
+
+        indicators.adx = Some(15.0); // Weak trend
+        assert_eq!(classify_market_regime(&indicators, &create_settings()), Some(MarketRegime::Ranging));
+    }
+
+    #[test]
+    fn test_no_data_is_undefined() {
+        assert_eq!(classify_market_regime(&RegimeIndicators::default(), &create_settings()), None);
+    }
+
+    #[test]
+    fn test_uptrend_from_ohlcs() {
+        let ohlcs = create_mock_ohlcs(100_000.0, 50.0, 30.0, 60);
+        let indicators = calculate_regime_indicators(&ohlcs, 12, 12, 2.0, 7, &create_settings());
+
+        assert!(indicators.adx.unwrap() > 25.0);
+        assert!(indicators.ema_slope.unwrap() > 0.0);
+        assert_eq!(classify_market_regime(&indicators, &create_settings()), Some(MarketRegime::TrendingUp));
+    }
+
+    #[test]
+    fn test_flat_market_is_ranging() {
+        let ohlcs = create_mock_ohlcs(100_000.0, 0.0, 30.0, 60);
+        let indicators = calculate_regime_indicators(&ohlcs, 12, 12, 2.0, 7, &create_settings());
+
+        assert_eq!(indicators.ema_slope, Some(0.0));
+        assert_eq!(classify_market_regime(&indicators, &create_settings()), Some(MarketRegime::Ranging));
+    }
+}
@@ -0,0 +1,59 @@
+// tests/get_scale_out_targets.rs
+
+use trading_backend::math::get_scale_out_targets::calculate_scale_out_targets;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buy_targets_are_staggered_above_entry() {
+        let targets = calculate_scale_out_targets(100_000.0, 99_000.0, true, 300, &[1.0, 2.0, 3.0]).unwrap();
+
+        assert_eq!(targets.len(), 3);
+        assert_eq!(targets[0].takeprofit, 101_000.0); // 1R
+        assert_eq!(targets[1].takeprofit, 102_000.0); // 2R
+        assert_eq!(targets[2].takeprofit, 103_000.0); // 3R
+        assert!(targets.iter().all(|target| target.quantity == 100));
+    }
+
+    #[test]
+    fn test_sell_targets_are_staggered_below_entry() {
+        let targets = calculate_scale_out_targets(100_000.0, 100_500.0, false, 10, &[1.0, 2.0]).unwrap();
+
+        assert_eq!(targets[0].takeprofit, 99_500.0);
+        assert_eq!(targets[1].takeprofit, 99_000.0);
+    }
+
+    #[test]
+    fn test_remainder_goes_to_nearest_target() {
+        let targets = calculate_scale_out_targets(100_000.0, 99_000.0, true, 10, &[1.0, 2.0, 3.0]).unwrap();
+
+        let quantities: Vec<u64> = targets.iter().map(|target| target.quantity).collect();
+        assert_eq!(quantities, vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn test_small_quantity_keeps_nearest_targets() {
+        let targets = calculate_scale_out_targets(100_000.0, 99_000.0, true, 2, &[1.0, 2.0, 3.0]).unwrap();
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[1].r_multiple, 2.0);
+    }
+
+    #[test]
+    fn test_unsorted_targets_are_sorted_nearest_first() {
+        let targets = calculate_scale_out_targets(100_000.0, 99_000.0, true, 2, &[3.0, 1.0, 2.0]).unwrap();
+
+        let r_multiples: Vec<f64> = targets.iter().map(|target| target.r_multiple).collect();
+        assert_eq!(r_multiples, vec![1.0, 2.0]);
+        assert_eq!(targets[0].takeprofit, 101_000.0);
+    }
+
+    #[test]
+    fn test_invalid_stoploss() {
+        // Stoploss above entry for a buy
+        let result = calculate_scale_out_targets(100_000.0, 101_000.0, true, 100, &[1.0, 2.0]);
+        assert!(result.is_err());
+    }
+}
@@ -2,6 +2,9 @@
 
 use trading_backend::utils::connect_ws::PriceData;
 use trading_backend::math::get_indicators::Indicators;
+use trading_backend::math::indicator_set::{IndicatorSet, IndicatorSource, IndicatorValue, ATR, BOLLINGER_BANDS, EMA, MA, RSI};
+use trading_backend::math::get_basis::BasisStats;
+use trading_backend::math::get_market_regime::{MarketRegime, RegimeIndicators};
 use trading_backend::math::get_signals::calculate_ohlc_with_price_signal;
 
 #[cfg(test)]
@@ -27,23 +30,24 @@ mod tests {
         ohlc_ema: Option<f64>,
         atr: Option<f64>,
     ) -> Indicators {
+        let mut values = IndicatorSet::default();
+        if let Some((lower, middle, upper)) = ohlc_bollinger_bands {
+            values.insert(IndicatorSource::Ohlc, BOLLINGER_BANDS, IndicatorValue::Bands { lower, middle, upper });
+        }
+        for (id, value) in [(RSI, ohlc_rsi), (MA, ohlc_ma), (EMA, ohlc_ema), (ATR, atr)] {
+            if let Some(value) = value {
+                values.insert(IndicatorSource::Ohlc, id, IndicatorValue::Single(value));
+            }
+        }
+
         Indicators {
             ohlc_data: vec![],             // Empty OHLC data
             price_data: vec![],            // Empty price data
             index_price_data: vec![],      // Empty index data
-            ma: None,
-            ema: None,
-            bollinger_bands: None,
-            rsi: None,
-            i_ma: None,
-            i_ema: None,
-            i_bollinger_bands: None,
-            i_rsi: None,
-            atr,
-            ohlc_ma,
-            ohlc_ema,
-            ohlc_bollinger_bands,
-            ohlc_rsi,
+            values,
+            regime_indicators: RegimeIndicators::default(),
+            regime: None,
+            basis: None,
         }
     }
 
@@ -58,7 +62,7 @@ mod tests {
             Some(30.0),                           // ATR (high volatility)
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, 2); // Strong Buy
     }
 
@@ -73,7 +77,7 @@ mod tests {
             Some(30.0),                           // ATR (high volatility)
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, -2); // Strong Sell
     }
 
@@ -88,7 +92,7 @@ mod tests {
             Some(15.0),                           // ATR (moderate volatility)
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, 1); // Buy
     }
 
@@ -103,7 +107,7 @@ mod tests {
             Some(20.0),                           // ATR (moderate volatility)
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, -1); // Sell
     }
 
@@ -118,7 +122,7 @@ mod tests {
             Some(10.0),                           // ATR (low volatility)
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, 0); // Hold
     }
 
@@ -133,7 +137,7 @@ mod tests {
             Some(-1.0),               // Invalid ATR
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, 0); // Hold, invalid data should not affect signals
     }
 
@@ -148,7 +152,73 @@ mod tests {
             None, // No ATR
         );
 
-        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let signal = calculate_ohlc_with_price_signal(&price_data, &indicators).await.bucket;
         assert_eq!(signal, 0); // Hold, no indicators available
     }
+
+    #[tokio::test]
+    async fn test_explanation_contributions() {
+        let price_data = create_mock_price_data(95_000.0);
+        let indicators = create_mock_indicators(
+            Some((97_000.0, 98_000.0, 99_000.0)),
+            Some(10.0),
+            Some(98_500.0),
+            Some(98_800.0),
+            Some(30.0),
+        );
+
+        let explanation = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let sum: f64 = explanation.contributions.iter().map(|c| c.contribution).sum();
+
+        assert!(explanation.invalid_reason.is_none());
+        assert!(!explanation.contributions.is_empty());
+        assert!((sum - explanation.score).abs() < 1e-9); // Contributions add up to the score
+        assert!(explanation.main_driver().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_explanation_invalid_reason() {
+        let price_data = create_mock_price_data(-1.0);
+        let indicators = create_mock_indicators(None, None, None, None, None);
+
+        let explanation = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+
+        assert!(explanation.invalid_reason.is_some());
+        assert!(explanation.contributions.is_empty());
+        assert!(explanation.main_driver().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_trending_regime_follows_averages() {
+        let price_data = create_mock_price_data(99_000.0); // Price above MA and EMA
+        let mut indicators = create_mock_indicators(None, None, Some(98_500.0), Some(98_800.0), None);
+
+        let ranging = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        assert!(ranging.score < 0.0); // Mean reversion: price above the averages is bearish
+
+        indicators.regime = Some(MarketRegime::TrendingUp);
+        let trending = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        assert!(trending.score > 0.0); // Trend following: price above the averages is bullish
+        assert_eq!(trending.regime, Some(MarketRegime::TrendingUp));
+    }
+
+    #[tokio::test]
+    async fn test_rich_basis_is_bearish() {
+        let price_data = create_mock_price_data(98_050.0);
+        let mut indicators = create_mock_indicators(None, None, None, None, None);
+        indicators.basis = Some(BasisStats {
+            index: 98_000.0,
+            basis: 0.0,
+            mean: 10.0,
+            std_dev: 10.0,
+            z_score: -1.0,
+            samples: 48,
+        });
+
+        // Live basis 50$ is 4 standard deviations above its mean
+        let explanation = calculate_ohlc_with_price_signal(&price_data, &indicators).await;
+        let basis = explanation.contributions.iter().find(|c| c.indicator == "basis").unwrap();
+        assert_eq!(basis.value, 4.0);
+        assert!(basis.contribution < 0.0);
+    }
 }
@@ -0,0 +1,123 @@
+// src/tests/indicator_set.rs
+
+use serde_json::json;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+use trading_backend::futures::get_price_history::PriceHistoryEntry;
+use trading_backend::math::indicator_set::{
+    default_indicator_registry, IndicatorKind, IndicatorSet, IndicatorSource, IndicatorSpec, IndicatorValue, ATR, BOLLINGER_BANDS, EMA, MA, RSI,
+};
+use trading_backend::math::price_indicators::{
+    calculate_atr, calculate_bollinger_bands, calculate_exponential_moving_average, calculate_moving_average, calculate_rsi,
+};
+
+// Oscillating closes around a rising trend, oldest first
+fn candles(count: usize) -> Vec<OhlcHistoryEntry> {
+    (0..count)
+        .map(|i| {
+            let close = 100_000.0 + i as f64 * 25.0 + if i % 3 == 0 { 120.0 } else { -80.0 };
+            OhlcHistoryEntry { time: i as i64 * 60_000, open: close - 10.0, high: close + 50.0, low: close - 60.0, close, volume: 1.0 }
+        })
+        .collect()
+}
+
+fn prices(count: usize) -> Vec<PriceHistoryEntry> {
+    (0..count).map(|i| PriceHistoryEntry { time: i as i64 * 60_000, value: 99_000.0 + (i % 5) as f64 * 40.0 }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_indicator_specs() {
+        let spec: IndicatorSpec = "ohlc:ema:50".parse().unwrap();
+        assert_eq!(spec, IndicatorSpec { id: "ema_50".to_string(), source: IndicatorSource::Ohlc, kind: IndicatorKind::Ema { period: 50 } });
+
+        let spec: IndicatorSpec = " wide_bb = price:BB:20:2.5 ".parse().unwrap();
+        assert_eq!(spec.id, "wide_bb");
+        assert_eq!(spec.source, IndicatorSource::Price);
+        assert_eq!(spec.kind, IndicatorKind::Bb { period: 20, std_dev_multiplier: 2.5 });
+        assert_eq!("index:bb:20".parse::<IndicatorSpec>().unwrap().kind, IndicatorKind::Bb { period: 20, std_dev_multiplier: 2.0 });
+
+        // Unknown sources and types, missing periods and ATRs without OHLC data are rejected
+        for invalid in ["candles:ma:14", "ohlc:macd:14", "ohlc:rsi", "ohlc:rsi:0", "ohlc:ma:14:2", "price:atr:7", "=ohlc:ma:14"] {
+            assert!(invalid.parse::<IndicatorSpec>().is_err(), "{} should be rejected", invalid);
+        }
+    }
+
+    #[test]
+    fn test_calculate_default_registry() {
+        let ohlc_data = candles(60);
+        let price_data = prices(60);
+        let registry = default_indicator_registry(14, 12, 12, 2.0, 9, 7);
+        let values = IndicatorSet::calculate(&registry, &ohlc_data, Some(&price_data), None);
+
+        // Same values as the price indicator functions
+        let closes: Vec<f64> = ohlc_data.iter().map(|entry| entry.close).collect();
+        let highs: Vec<f64> = ohlc_data.iter().map(|entry| entry.high).collect();
+        let lows: Vec<f64> = ohlc_data.iter().map(|entry| entry.low).collect();
+        assert_eq!(values.value(IndicatorSource::Ohlc, MA), calculate_moving_average(&closes, 14));
+        assert_eq!(values.value(IndicatorSource::Ohlc, EMA), calculate_exponential_moving_average(&closes, 12));
+        assert_eq!(values.bands(IndicatorSource::Ohlc, BOLLINGER_BANDS), calculate_bollinger_bands(&closes, 12, 2.0));
+        assert_eq!(values.value(IndicatorSource::Ohlc, RSI), calculate_rsi(&closes, 9));
+        assert_eq!(values.value(IndicatorSource::Ohlc, ATR), calculate_atr(&highs, &lows, &closes, 7));
+
+        let price_values: Vec<f64> = price_data.iter().map(|entry| entry.value).collect();
+        assert_eq!(values.value(IndicatorSource::Price, RSI), calculate_rsi(&price_values, 9));
+        assert_eq!(values.source(IndicatorSource::Price).count(), 4);
+
+        // No index data, no index indicators
+        assert_eq!(values.source(IndicatorSource::Index).count(), 0);
+        assert_eq!(values.iter().count(), 9);
+    }
+
+    #[test]
+    fn test_custom_indicators() {
+        let ohlc_data = candles(80);
+        let mut registry = default_indicator_registry(14, 12, 12, 2.0, 9, 7);
+        registry.push("ohlc:ema:50".parse().unwrap());
+        registry.push("slow_rsi=ohlc:rsi:21".parse().unwrap());
+        registry.push("ohlc:ma:100".parse().unwrap()); // More than the 80 candles
+        let values = IndicatorSet::calculate(&registry, &ohlc_data, None, None);
+
+        let closes: Vec<f64> = ohlc_data.iter().map(|entry| entry.close).collect();
+        assert_eq!(values.value(IndicatorSource::Ohlc, "ema_50"), calculate_exponential_moving_average(&closes, 50));
+        assert_eq!(values.value(IndicatorSource::Ohlc, "slow_rsi"), calculate_rsi(&closes, 21));
+        assert_ne!(values.value(IndicatorSource::Ohlc, EMA), values.value(IndicatorSource::Ohlc, "ema_50"));
+        assert_eq!(values.get(IndicatorSource::Ohlc, "ma_100"), None);
+
+        // Single values and bands are only returned by their accessor
+        assert_eq!(values.value(IndicatorSource::Ohlc, BOLLINGER_BANDS), None);
+        assert_eq!(values.bands(IndicatorSource::Ohlc, RSI), None);
+    }
+
+    #[test]
+    fn test_merge_and_serialize() {
+        let mut values = IndicatorSet::default();
+        values.insert(IndicatorSource::Ohlc, RSI, IndicatorValue::Single(55.0));
+        values.insert(IndicatorSource::Ohlc, "ema_50", IndicatorValue::Single(99_000.0));
+        values.insert(IndicatorSource::Price, MA, IndicatorValue::Single(98_000.0));
+        values.insert(IndicatorSource::Price, RSI, IndicatorValue::Single(40.0));
+
+        // OHLC values are replaced, price values kept unless updated
+        let mut update = IndicatorSet::default();
+        update.insert(IndicatorSource::Ohlc, RSI, IndicatorValue::Single(60.0));
+        update.insert(IndicatorSource::Price, RSI, IndicatorValue::Single(45.0));
+        values.merge(update);
+        assert_eq!(values.value(IndicatorSource::Ohlc, RSI), Some(60.0));
+        assert_eq!(values.get(IndicatorSource::Ohlc, "ema_50"), None);
+        assert_eq!(values.value(IndicatorSource::Price, MA), Some(98_000.0));
+        assert_eq!(values.value(IndicatorSource::Price, RSI), Some(45.0));
+
+        values.insert(IndicatorSource::Ohlc, BOLLINGER_BANDS, IndicatorValue::Bands { lower: 1.0, middle: 2.0, upper: 3.0 });
+        let value = serde_json::to_value(&values).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "ohlc": { "bb": { "lower": 1.0, "middle": 2.0, "upper": 3.0 }, "rsi": 60.0 },
+                "price": { "ma": 98_000.0, "rsi": 45.0 },
+            })
+        );
+        assert_eq!(serde_json::from_value::<IndicatorSet>(value).unwrap(), values);
+    }
+}
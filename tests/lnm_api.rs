@@ -0,0 +1,196 @@
+// src/tests/lnm_api.rs
+
+mod common;
+
+use axum::http::{Method, StatusCode};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+use common::mock_lnm::{price_frame, MockLnm, MockResponse, PRICE_CHANNEL};
+use trading_backend::futures::create_trade::{create_market_order, Sizing};
+use trading_backend::futures::get_market::get_market;
+use trading_backend::futures::get_ohlcs_history::{get_ohlcs_history, GetOhlcsParams};
+use trading_backend::futures::get_trades::{get_trades, GetTradesParams};
+use trading_backend::futures::ticker::get_futures_ticker;
+use trading_backend::futures::trade::{OrderType, Side, TradeStatus};
+use trading_backend::utils::connect_ws::ws_price_feed;
+use trading_backend::utils::get_headers::get_headers;
+use trading_backend::utils::get_user::get_user;
+use trading_backend::utils::http_client::http_client;
+use trading_backend::utils::timeframe::Timeframe;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_signed_requests_are_accepted() {
+        let mock = MockLnm::start().await;
+
+        let user = get_user(&mock.api_url).await.unwrap();
+        assert_eq!(user.balance, 2_500_000.0);
+        let ticker = get_futures_ticker(&mock.api_url).await.unwrap();
+        assert!(ticker.ask_price > ticker.bid_price);
+        let market = get_market(&mock.api_url).await.unwrap();
+        assert_eq!(market.limits.count.max, 50);
+
+        // Query parameters are signed in the order they are sent
+        let params = GetTradesParams { r#type: TradeStatus::Running, from: None, to: None, limit: Some(1000) };
+        assert!(get_trades(&mock.api_url, Some(params)).await.unwrap().is_empty());
+
+        let requests = mock.requests().await;
+        assert_eq!(requests.len(), 4);
+        assert!(requests.iter().all(|request| request.signature_valid), "{:?}", requests);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_signature_is_rejected() {
+        let mock = MockLnm::start().await;
+
+        // Headers signed for another path, sent without `send_metered` (which signs again)
+        let headers = get_headers("/v2/user", "GET", None).unwrap();
+        let response = http_client()
+            .get(format!("{}/futures/ticker", mock.api_url))
+            .headers(headers)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        let requests = mock.requests_to(Method::GET, "/v2/futures/ticker").await;
+        assert!(!requests[0].signature_valid);
+    }
+
+    #[tokio::test]
+    async fn test_create_trade_sends_signed_body() {
+        let mock = MockLnm::start().await;
+
+        let trade = create_market_order(&mock.api_url, Side::Buy, 20, Sizing::Quantity(10), Some(110_000), Some(95_000)).await.unwrap();
+        assert_eq!(trade.id, "mock-trade-1");
+        assert_eq!((trade.side, trade.order_type), (Side::Buy, OrderType::Market));
+        assert_eq!(trade.price, 100_000.5);
+        assert_eq!(trade.status(), TradeStatus::Running);
+
+        let requests = mock.requests_to(Method::POST, "/v2/futures").await;
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].signature_valid);
+        let body: Value = serde_json::from_str(&requests[0].body).unwrap();
+        assert_eq!(body["type"], "m");
+        assert_eq!(body["side"], "b");
+        assert_eq!(body["quantity"], 10);
+        assert_eq!(body["leverage"], 20);
+        assert_eq!(body["takeprofit"], 110_000);
+        assert_eq!(body["stoploss"], 95_000);
+        assert!(body.get("margin").is_none());
+
+        // The created trade is listed as running
+        let params = GetTradesParams { r#type: TradeStatus::Running, ..Default::default() };
+        let running = get_trades(&mock.api_url, Some(params)).await.unwrap();
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].id, "mock-trade-1");
+    }
+
+    #[tokio::test]
+    async fn test_ohlcs_history_pagination() {
+        let mock = MockLnm::start().await;
+        let (start, step) = (1_735_689_600_000, 60_000);
+        let closes: Vec<f64> = (0..250).map(|i| 100_000.0 + i as f64).collect();
+        mock.set_candles(start, step, &closes).await;
+
+        let last = start + 249 * step;
+        let params = GetOhlcsParams { range: Timeframe::Minutes(1), from: start, to: last, limit: Some(100), debug: false };
+        let candles = get_ohlcs_history(&mock.api_url, params).await.unwrap();
+
+        assert_eq!(candles.len(), 250);
+        assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == step));
+        assert_eq!(candles.last().unwrap().close, 100_249.0);
+
+        // Every page starts after the last candle of the previous one
+        let requests = mock.requests_to(Method::GET, "/v2/futures/ohlcs").await;
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.signature_valid));
+        let froms: Vec<String> = requests
+            .iter()
+            .map(|request| {
+                let query = request.query.as_deref().unwrap_or_default();
+                query.split('&').find_map(|pair| pair.strip_prefix("from=")).unwrap_or_default().to_string()
+            })
+            .collect();
+        assert_eq!(froms, vec![start.to_string(), (start + 99 * step + 1).to_string(), (start + 199 * step + 1).to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_is_signed_again() {
+        let mock = MockLnm::start().await;
+        mock.script(Method::GET, "/v2/user", vec![MockResponse::RateLimited(1)]).await;
+
+        // The retry waits for `Retry-After` and is sent with a new timestamp
+        assert!(get_user(&mock.api_url).await.is_ok());
+        let requests = mock.requests_to(Method::GET, "/v2/user").await;
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|request| request.signature_valid), "{:?}", requests);
+        let (first, retry) = (requests[0].timestamp.unwrap(), requests[1].timestamp.unwrap());
+        assert!(retry - first >= 1_000, "{} {}", first, retry);
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() {
+        let mock = MockLnm::start().await;
+        mock.script(
+            Method::GET,
+            "/v2/futures/ticker",
+            vec![
+                MockResponse::Status(StatusCode::INTERNAL_SERVER_ERROR),
+                MockResponse::Malformed,
+                MockResponse::RateLimited(0),
+            ],
+        )
+        .await;
+        mock.script(Method::POST, "/v2/futures", vec![MockResponse::Status(StatusCode::SERVICE_UNAVAILABLE)]).await;
+
+        assert!(get_futures_ticker(&mock.api_url).await.is_err());
+        assert!(get_futures_ticker(&mock.api_url).await.is_err());
+        // 429: retried after `Retry-After`
+        assert!(get_futures_ticker(&mock.api_url).await.is_ok());
+        assert_eq!(mock.requests_to(Method::GET, "/v2/futures/ticker").await.len(), 4);
+
+        let error = create_market_order(&mock.api_url, Side::Buy, 20, Sizing::Quantity(10), None, None).await.unwrap_err();
+        assert!(error.to_string().contains("503"), "{}", error);
+        assert!(mock.trades().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ws_price_feed() {
+        let mock = MockLnm::start().await;
+        mock.script_ws(vec![
+            price_frame(100_000.0, 1_000),
+            "{\"jsonrpc\": \"2.0\", \"method\": ".to_string(),
+            price_frame(100_050.5, 2_000),
+            price_frame(99_990.0, 3_000),
+        ])
+        .await;
+
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let (price_tx, mut price_rx) = mpsc::channel(16);
+        let feed = ws_price_feed(shutdown_rx, &mock.ws_url, "v1/public/subscribe", price_tx);
+        let receive = async {
+            let mut prices = Vec::new();
+            while prices.len() < 3 {
+                let price = price_rx.recv().await.expect("price feed stopped");
+                prices.push((price.last_price, price.time));
+            }
+            shutdown_tx.send(()).await.unwrap();
+            prices
+        };
+
+        let (result, prices) = timeout(Duration::from_secs(5), async { tokio::join!(feed, receive) }).await.unwrap();
+        assert!(result.is_ok());
+        // The malformed frame is skipped
+        assert_eq!(prices, vec![(100_000.0, 1_000), (100_050.5, 2_000), (99_990.0, 3_000)]);
+
+        let subscriptions = mock.ws_subscriptions().await;
+        assert_eq!(subscriptions.len(), 1);
+        assert_eq!(subscriptions[0]["method"], "v1/public/subscribe");
+        assert_eq!(subscriptions[0]["params"][0], PRICE_CHANNEL);
+    }
+}
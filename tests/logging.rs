@@ -0,0 +1,100 @@
+// src/tests/logging.rs
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use serde_json::Value;
+use tracing::{error, info};
+use tracing_subscriber::fmt::MakeWriter;
+use trading_backend::utils::logging::{ConsoleFormat, LogFormat, LogRotation};
+
+// Collects the log output of a subscriber
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Buffer {
+    type Writer = Buffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_sink_gets_fields() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt().json().with_writer(buffer.clone()).finish();
+        tracing::subscriber::with_default(subscriber, || {
+            info!(trade_id = "trade-1", price = 100_000.5, signal = "Buy", "Trade created");
+        });
+
+        let line: Value = serde_json::from_str(buffer.output().trim()).unwrap();
+        assert_eq!(line["fields"]["message"], "Trade created");
+        assert_eq!(line["fields"]["trade_id"], "trade-1");
+        assert_eq!(line["fields"]["price"], 100_000.5);
+        assert_eq!(line["fields"]["signal"], "Buy");
+        assert!(!buffer.output().contains("\\u001b"));
+    }
+
+    #[test]
+    fn test_console_format_colors_message_by_level() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(true)
+            .event_format(ConsoleFormat)
+            .with_writer(buffer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            error!(trade_id = "trade-1", "Error creating trade");
+        });
+
+        let output = buffer.output();
+        assert!(output.contains("\x1b[31mError creating trade\x1b[0m"), "{}", output);
+        assert!(output.contains("trade_id=\x1b[0mtrade-1"), "{}", output);
+    }
+
+    #[test]
+    fn test_console_format_without_ansi() {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .event_format(ConsoleFormat)
+            .with_writer(buffer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            info!(price = 100_000.5, "Signal");
+        });
+
+        let output = buffer.output();
+        assert!(!output.contains('\x1b'), "{}", output);
+        assert!(output.trim_end().ends_with("INFO Signal price=100000.5"), "{}", output);
+    }
+
+    #[test]
+    fn test_log_settings_from_str() {
+        assert_eq!("json".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert_eq!("Pretty".parse::<LogFormat>(), Ok(LogFormat::Pretty));
+        assert_eq!("hourly".parse::<LogRotation>(), Ok(LogRotation::Hourly));
+        assert!("weekly".parse::<LogRotation>().is_err());
+    }
+}
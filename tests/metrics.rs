@@ -0,0 +1,69 @@
+// src/tests/metrics.rs
+
+use trading_backend::math::get_signals::Signal;
+use trading_backend::utils::filter_signals::{Direction, SkipReason};
+use trading_backend::utils::metrics::Metrics;
+use trading_backend::utils::rate_limiter::WEBHOOK_ENDPOINT;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_reason_label() {
+        let direction = Direction::Long;
+        assert_eq!(SkipReason::EntryThreshold { direction, score: 0.15, threshold: 0.2 }.label(), "entry_threshold");
+        assert_eq!(SkipReason::Hysteresis { direction, score: 0.3, threshold: 0.1 }.label(), "hysteresis");
+        assert_eq!(
+            SkipReason::Confirmation { direction, ticks: 1, required_ticks: 3, seconds: 0.5, required_seconds: 10 }.label(),
+            "confirmation"
+        );
+        assert_eq!(SkipReason::Cooldown { direction, seconds_left: 120 }.label(), "cooldown");
+        assert_eq!(SkipReason::DuplicateCandle(direction).label(), "duplicate_candle");
+        assert_eq!(SkipReason::TradeGap { seconds: 60 }.label(), "trade_gap");
+        assert_eq!(SkipReason::OrderInFlight.label(), "order_in_flight");
+        assert_eq!(SkipReason::TradeLimit { max: 50 }.label(), "trade_limit");
+        assert_eq!(SkipReason::Balance.label(), "balance");
+        assert_eq!(SkipReason::MissingIndicators.label(), "missing_indicators");
+        assert_eq!(SkipReason::InvalidSignal(Signal::Hold).label(), "other");
+        // Free-text errors never change the label, whatever they mention
+        assert_eq!(SkipReason::Error("Error fetching user data: insufficient balance".to_string()).label(), "error");
+    }
+
+    #[test]
+    fn test_tick_rate() {
+        let metrics = Metrics::new().unwrap();
+        for price in [100_000.0, 100_010.0, 100_005.0] {
+            metrics.record_price_tick(price);
+        }
+
+        assert_eq!(metrics.last_price.get(), 100_005.0);
+        assert_eq!(metrics.price_ticks.get(), 3);
+        assert!((metrics.tick_rate.get() - 3.0 / 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_total_rest_errors_without_webhooks() {
+        let metrics = Metrics::new().unwrap();
+        metrics.rest_errors.with_label_values(&["futures/ticker"]).inc();
+        metrics.rest_errors.with_label_values(&["user"]).inc();
+        metrics.rest_errors.with_label_values(&[WEBHOOK_ENDPOINT]).inc_by(5);
+
+        assert_eq!(metrics.total_rest_errors(), 2);
+    }
+
+    #[test]
+    fn test_encode_metrics() {
+        let metrics = Metrics::new().unwrap();
+        metrics.record_price_tick(100_000.0);
+        metrics.record_skipped_trade(&SkipReason::TradeGap { seconds: 60 });
+        metrics.rest_request_duration.with_label_values(&["futures/ticker"]).observe(0.2);
+        metrics.max_trades.set(50);
+
+        let text = metrics.encode().unwrap();
+        assert!(text.contains("ln_bot_last_price 100000"));
+        assert!(text.contains("ln_bot_trades_skipped_total{reason=\"trade_gap\"} 1"));
+        assert!(text.contains("ln_bot_rest_request_duration_seconds_count{endpoint=\"futures/ticker\"} 1"));
+        assert!(text.contains("ln_bot_max_trades 50"));
+    }
+}
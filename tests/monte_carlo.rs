@@ -0,0 +1,107 @@
+// src/tests/monte_carlo.rs
+
+use trading_backend::analytics::monte_carlo::{calculate_distribution, run_monte_carlo, MonteCarloSettings, ResamplingMethod};
+use trading_backend::analytics::performance_report::ClosedTrade;
+use trading_backend::futures::trade::Side;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_trade(pl: f64) -> ClosedTrade {
+        ClosedTrade {
+            side: Side::Buy,
+            quantity: 100.0,
+            entry_price: 100_000.0,
+            exit_price: 100_000.0,
+            entry_time: 0,
+            exit_time: 0,
+            pl,
+            opening_fee: 0.0,
+            closing_fee: 0.0,
+            carry_fees: 0.0,
+        }
+    }
+
+    fn create_settings(method: ResamplingMethod) -> MonteCarloSettings {
+        MonteCarloSettings {
+            simulations: 500,
+            method,
+            seed: 7,
+            max_slippage: 0.0,
+            fee_perturbation: 0.0,
+            risk_levels: vec![0.01, 0.5],
+            ruin_drawdown: 0.5,
+        }
+    }
+
+    fn create_trades() -> Vec<ClosedTrade> {
+        vec![create_trade(2_000.0), create_trade(-1_000.0), create_trade(-1_000.0), create_trade(3_000.0), create_trade(-1_000.0)]
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let samples: Vec<f64> = (0..=100).map(|i| i as f64).collect();
+        let distribution = calculate_distribution(&samples);
+        assert_eq!(distribution.min, 0.0);
+        assert_eq!(distribution.p5, 5.0);
+        assert_eq!(distribution.median, 50.0);
+        assert_eq!(distribution.p95, 95.0);
+        assert_eq!(distribution.max, 100.0);
+        assert_eq!(distribution.mean, 50.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_shuffle_keeps_final_equity() {
+        let report = run_monte_carlo(&create_trades(), 100_000.0, &create_settings(ResamplingMethod::Shuffle)).unwrap();
+
+        // The order changes the drawdown but not the sum of the trades
+        assert_eq!(report.final_equity.min, 102_000.0);
+        assert_eq!(report.final_equity.max, 102_000.0);
+        assert_eq!(report.probability_of_loss, 0.0);
+        assert!(report.max_drawdown.max > report.max_drawdown.min);
+    }
+
+    #[test]
+    fn test_monte_carlo_is_reproducible() {
+        let trades = create_trades();
+        let mut settings = create_settings(ResamplingMethod::Bootstrap);
+        settings.max_slippage = 0.001;
+        settings.fee_perturbation = 0.2;
+
+        let first = run_monte_carlo(&trades, 100_000.0, &settings).unwrap();
+        let second = run_monte_carlo(&trades, 100_000.0, &settings).unwrap();
+        assert_eq!(first.final_equity, second.final_equity);
+        assert_eq!(first.ruin, second.ruin);
+
+        settings.seed = 8;
+        let other = run_monte_carlo(&trades, 100_000.0, &settings).unwrap();
+        assert_ne!(first.final_equity, other.final_equity);
+    }
+
+    #[test]
+    fn test_monte_carlo_slippage_lowers_equity() {
+        let trades = create_trades();
+        let mut settings = create_settings(ResamplingMethod::Shuffle);
+        settings.max_slippage = 0.001;
+
+        let report = run_monte_carlo(&trades, 100_000.0, &settings).unwrap();
+        assert!(report.final_equity.max < 102_000.0);
+    }
+
+    #[test]
+    fn test_monte_carlo_probability_of_ruin() {
+        let report = run_monte_carlo(&create_trades(), 100_000.0, &create_settings(ResamplingMethod::Bootstrap)).unwrap();
+
+        assert_eq!(report.ruin.len(), 2);
+        // 1% per trade can not lose half of the balance in 5 trades, 50% per trade can
+        assert_eq!(report.ruin[0].probability_of_ruin, 0.0);
+        assert!(report.ruin[1].probability_of_ruin > 0.0);
+        assert!(report.ruin[1].final_equity.p5 < report.ruin[0].final_equity.p5);
+    }
+
+    #[test]
+    fn test_monte_carlo_without_trades() {
+        assert!(run_monte_carlo(&[], 100_000.0, &create_settings(ResamplingMethod::Shuffle)).is_none());
+    }
+}
@@ -0,0 +1,137 @@
+// src/tests/notifier.rs
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+use trading_backend::futures::trade::Side;
+use trading_backend::utils::notifier::{
+    format_payload, EventKind, NotificationDispatcher, NotificationEvent, NotificationFilter, NotificationSettings,
+    PayloadFormat, WebhookSettings,
+};
+
+// Payloads received by the stub with the path they were posted to
+type Received = Arc<Mutex<Vec<(String, Value)>>>;
+
+fn trade_closed(id: &str) -> NotificationEvent {
+    NotificationEvent::TradeClosed { id: id.to_string(), side: Side::Buy, pl_sats: 1_250.0 }
+}
+
+// Local webhook stub recording the received payloads
+async fn start_stub() -> (String, Received) {
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    let record = |path: &'static str| {
+        move |State(received): State<Received>, Json(body): Json<Value>| async move {
+            received.lock().await.push((path.to_string(), body));
+            StatusCode::OK
+        }
+    };
+    let router = Router::new()
+        .route("/json", post(record("json")))
+        .route("/slack", post(record("slack")))
+        .route("/failing", post(|| async { StatusCode::INTERNAL_SERVER_ERROR }))
+        .with_state(Arc::clone(&received));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, router).await });
+    (url, received)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_filter() {
+        let events: HashSet<EventKind> = [EventKind::TradeClosed, EventKind::ApiErrors].into_iter().collect();
+        let mut filter = NotificationFilter::new(events, 2, Duration::from_secs(300));
+        let now = Instant::now();
+
+        // Disabled event
+        assert!(filter.check(&NotificationEvent::CircuitBreaker { reason: "test".to_string() }, now).is_err());
+
+        // Duplicates within the dedup window
+        assert!(filter.check(&trade_closed("a"), now).is_ok());
+        assert!(filter.check(&trade_closed("a"), now + Duration::from_secs(10)).is_err());
+
+        // Rate limit of 2 per minute
+        assert!(filter.check(&trade_closed("b"), now + Duration::from_secs(20)).is_ok());
+        assert!(filter.check(&trade_closed("c"), now + Duration::from_secs(30)).is_err());
+        assert!(filter.check(&trade_closed("c"), now + Duration::from_secs(81)).is_ok());
+
+        // Dedup window expired
+        assert!(filter.check(&trade_closed("a"), now + Duration::from_secs(400)).is_ok());
+    }
+
+    #[test]
+    fn test_format_payload() {
+        let event = trade_closed("abc");
+
+        let payload = format_payload(PayloadFormat::Json, &event, None);
+        assert_eq!(payload["data"]["event"], "trade_closed");
+        assert_eq!(payload["data"]["pl_sats"], 1_250.0);
+        assert_eq!(payload["title"], "Trade closed");
+
+        let payload = format_payload(PayloadFormat::Telegram, &event, Some("42"));
+        assert_eq!(payload["chat_id"], "42");
+        assert_eq!(payload["text"], "Trade closed: Buy trade abc closed with +1250 sats");
+
+        let payload = format_payload(PayloadFormat::Discord, &event, None);
+        assert_eq!(payload["embeds"][0]["title"], "Trade closed");
+        assert_eq!(payload["embeds"][0]["color"], 0x2ecc71);
+
+        let payload = format_payload(PayloadFormat::Slack, &event, None);
+        assert_eq!(payload["text"], "Trade closed: Buy trade abc closed with +1250 sats");
+    }
+
+    #[test]
+    fn test_parse_webhook_settings() {
+        let webhook: WebhookSettings = "discord=https://discord.com/api/webhooks/1/x".parse().unwrap();
+        assert_eq!(webhook.format, PayloadFormat::Discord);
+        assert_eq!(webhook.url, "https://discord.com/api/webhooks/1/x");
+
+        let webhook: WebhookSettings = "https://example.com/hook?key=value".parse().unwrap();
+        assert_eq!(webhook.format, PayloadFormat::Json);
+        assert_eq!(webhook.url, "https://example.com/hook?key=value");
+
+        assert!("slack=not-a-url".parse::<WebhookSettings>().is_err());
+        assert_eq!("websocket_stale".parse::<EventKind>(), Ok(EventKind::WebsocketStale));
+        assert!("unknown".parse::<EventKind>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_to_webhook_stub() {
+        let (url, received) = start_stub().await;
+        let settings = NotificationSettings {
+            webhooks: vec![
+                format!("json={}/json", url).parse().unwrap(),
+                format!("slack={}/slack", url).parse().unwrap(),
+                format!("{}/failing", url).parse().unwrap(),
+            ],
+            events: EventKind::ALL.into_iter().collect(),
+            max_per_minute: 10,
+            dedup_seconds: 300,
+            stale_ws_seconds: 60,
+            api_error_threshold: 5,
+            api_error_window_seconds: 300,
+        };
+        let mut dispatcher = NotificationDispatcher::from_settings(&settings);
+
+        dispatcher.dispatch(&NotificationEvent::WebsocketStale { seconds_since_last_tick: 75.0 }).await;
+        dispatcher.dispatch(&NotificationEvent::WebsocketStale { seconds_since_last_tick: 85.0 }).await;
+
+        // The failing webhook doesn't stop the others, the duplicate isn't sent
+        let received = received.lock().await;
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].0, "json");
+        assert_eq!(received[0].1["data"]["event"], "websocket_stale");
+        assert_eq!(received[1].0, "slack");
+        assert_eq!(received[1].1["text"], "Websocket stale: No price update for 75 seconds");
+    }
+}
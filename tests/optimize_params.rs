@@ -0,0 +1,152 @@
+// src/tests/optimize_params.rs
+
+use trading_backend::backtest::execution_model::ExecutionModel;
+use trading_backend::backtest::optimize_params::{optimize_params, walk_forward, OptimizationMetric, OptimizationResult, ParameterGrid, Ranking, SearchMode, WalkForwardSettings};
+use trading_backend::backtest::run_backtest::{BacktestSettings, StrategyParams};
+use trading_backend::config::load_signal_settings;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net_pl() -> Ranking {
+        Ranking { metric: OptimizationMetric::NetPl, min_trades: 0 }
+    }
+
+    fn create_mock_ohlcs(count: usize) -> Vec<OhlcHistoryEntry> {
+        (0..count)
+            .map(|i| {
+                let close = 100_000.0 + 1_500.0 * (i as f64 / 8.0).sin() + 10.0 * i as f64;
+                OhlcHistoryEntry {
+                    time: i as i64 * 1_800_000,
+                    open: close,
+                    high: close + 150.0,
+                    low: close - 150.0,
+                    close,
+                    volume: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    fn create_base() -> StrategyParams {
+        StrategyParams {
+            ma_period: 14,
+            ema_period: 12,
+            bb_period: 12,
+            bb_std_dev_multiplier: 2.0,
+            rsi_period: 9,
+            atr_period: 7,
+            gap_value: 15.0,
+            bollinger_weight: 0.25,
+            rsi_weight: 0.30,
+            ma_ema_weight: 0.20,
+            atr_weight: 0.25,
+        }
+    }
+
+    fn create_grid() -> ParameterGrid {
+        ParameterGrid {
+            ma_periods: vec![10, 14],
+            rsi_periods: vec![7, 9, 14],
+            bb_std_dev_multipliers: vec![1.5, 2.0],
+            gap_values: Vec::new(), // Keeps the base value
+            weights: Vec::new(),
+        }
+    }
+
+    fn create_settings() -> BacktestSettings {
+        BacktestSettings {
+            initial_balance_sats: 1_000_000,
+            leverage: 10.0,
+            risk_per_trade_percent: 0.01,
+            risk_to_reward_ratio: 0.25,
+            risk_to_loss_ratio: 0.25,
+            fee_rate: 0.001,
+            execution: ExecutionModel::default(),
+            regime_settings: None,
+        }
+    }
+
+    #[test]
+    fn test_grid_combinations() {
+        let combinations = create_grid().combinations(&create_base());
+        assert_eq!(combinations.len(), 12);
+        assert!(combinations.iter().all(|params| params.gap_value == 15.0));
+    }
+
+    #[test]
+    fn test_random_search_is_seeded() {
+        let grid = create_grid();
+        let first = grid.candidates(&create_base(), SearchMode::Random { samples: 5, seed: 7 });
+        let second = grid.candidates(&create_base(), SearchMode::Random { samples: 5, seed: 7 });
+
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_metric_from_str() {
+        assert_eq!("sharpe".parse::<OptimizationMetric>(), Ok(OptimizationMetric::Sharpe));
+        assert!("unknown".parse::<OptimizationMetric>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_ranking_independent_of_threads() {
+        let ohlc_data = create_mock_ohlcs(300);
+        let candidates = create_grid().combinations(&create_base());
+        let signal_settings = load_signal_settings().await;
+        let settings = create_settings();
+
+        let single = optimize_params(&ohlc_data, &candidates, &signal_settings, &settings, net_pl(), 0, 1);
+        let parallel = optimize_params(&ohlc_data, &candidates, &signal_settings, &settings, net_pl(), 0, 4);
+
+        assert_eq!(single.len(), candidates.len());
+        assert!(single.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        let scores = |results: &[OptimizationResult]| results.iter().map(|result| result.score).collect::<Vec<f64>>();
+        assert_eq!(scores(&single), scores(&parallel));
+    }
+
+    #[tokio::test]
+    async fn test_min_trades_ranks_idle_sets_last() {
+        let ohlc_data = create_mock_ohlcs(300);
+        // Zero weights never signal: no trades and no drawdown
+        let idle = StrategyParams { bollinger_weight: 0.0, rsi_weight: 0.0, ma_ema_weight: 0.0, atr_weight: 0.0, ..create_base() };
+        let candidates = vec![idle, create_base()];
+        let signal_settings = load_signal_settings().await;
+        let ranking = Ranking { metric: OptimizationMetric::MaxDrawdown, min_trades: 1 };
+
+        let results = optimize_params(&ohlc_data, &candidates, &signal_settings, &create_settings(), ranking, 0, 1);
+
+        assert!(results[0].eligible);
+        assert!(results[0].metrics.trade_count >= 1);
+        assert_eq!(results[1].metrics.trade_count, 0);
+        assert!(!results[1].eligible);
+    }
+
+    #[tokio::test]
+    async fn test_walk_forward_windows() {
+        let ohlc_data = create_mock_ohlcs(300);
+        let candidates = create_grid().combinations(&create_base());
+        let signal_settings = load_signal_settings().await;
+        let walk_forward_settings = WalkForwardSettings {
+            in_sample_candles: 150,
+            out_of_sample_candles: 50,
+        };
+
+        let windows = walk_forward(
+            &ohlc_data,
+            &candidates,
+            &signal_settings,
+            &create_settings(),
+            net_pl(),
+            &walk_forward_settings,
+            2,
+        );
+
+        assert_eq!(windows.len(), 3); // Out-of-sample windows start at 150, 200 and 250
+        assert_eq!(windows[0].out_of_sample_from, ohlc_data[150].time);
+        assert_eq!(windows[2].out_of_sample_to, ohlc_data[299].time);
+    }
+}
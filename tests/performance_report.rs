@@ -0,0 +1,111 @@
+// src/tests/performance_report.rs
+
+use trading_backend::analytics::performance_report::{calculate_performance_report, ClosedTrade};
+use trading_backend::futures::trade::Side;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+    const DAY: i64 = 86_400_000;
+
+    fn create_trade(entry_time: i64, exit_time: i64, pl: f64, fees: f64) -> ClosedTrade {
+        ClosedTrade {
+            side: Side::Buy,
+            quantity: 100.0,
+            entry_price: 100_000.0,
+            exit_price: 100_000.0,
+            entry_time,
+            exit_time,
+            pl,
+            opening_fee: fees / 2.0,
+            closing_fee: fees / 2.0,
+            carry_fees: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_performance_report_empty() {
+        let report = calculate_performance_report(&[], 1_000_000.0);
+        assert_eq!(report.trade_count, 0);
+        assert_eq!(report.final_balance_sats, 1_000_000.0);
+        assert_eq!(report.profit_factor, None);
+        assert!(report.equity_curve.is_empty());
+    }
+
+    #[test]
+    fn test_performance_report_win_rate_and_expectancy() {
+        let trades = vec![
+            create_trade(0, HOUR, 1_100.0, 100.0),
+            create_trade(2 * HOUR, 3 * HOUR, -400.0, 100.0),
+            create_trade(4 * HOUR, 5 * HOUR, 2_100.0, 100.0),
+        ];
+        let report = calculate_performance_report(&trades, 100_000.0);
+
+        assert_eq!(report.trade_count, 3);
+        assert!((report.win_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.average_win, 1_500.0);
+        assert_eq!(report.average_loss, -500.0);
+        assert_eq!(report.profit_factor, Some(6.0));
+        assert!((report.expectancy - 2_500.0 / 3.0).abs() < 1e-9);
+        assert_eq!(report.net_pl, 2_500.0);
+        assert_eq!(report.final_balance_sats, 102_500.0);
+        // 1_000 sats of P&L at 100_000$ are 1$
+        assert!((report.average_win_usd - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_performance_report_drawdown() {
+        let trades = vec![
+            create_trade(0, HOUR, 10_000.0, 0.0),
+            create_trade(HOUR, 2 * HOUR, -5_000.0, 0.0),
+            create_trade(2 * HOUR, 4 * HOUR, -6_000.0, 0.0),
+            create_trade(4 * HOUR, 6 * HOUR, 20_000.0, 0.0),
+        ];
+        let report = calculate_performance_report(&trades, 100_000.0);
+
+        assert_eq!(report.max_drawdown, 11_000.0);
+        assert!((report.max_drawdown_percent - 0.1).abs() < 1e-9);
+        // Below the peak from the first exit until the third exit
+        assert_eq!(report.max_drawdown_duration_ms, 3 * HOUR);
+    }
+
+    #[test]
+    fn test_performance_report_fees_and_exposure() {
+        let mut trades = vec![
+            create_trade(0, 2 * HOUR, 1_000.0, 100.0),
+            create_trade(HOUR, 3 * HOUR, 1_000.0, 100.0),
+            create_trade(5 * HOUR, 6 * HOUR, 2_000.0, 100.0),
+        ];
+        trades[2].carry_fees = 100.0;
+        let report = calculate_performance_report(&trades, 100_000.0);
+
+        assert_eq!(report.opening_fees, 150.0);
+        assert_eq!(report.closing_fees, 150.0);
+        assert_eq!(report.carry_fees, 100.0);
+        assert_eq!(report.total_fees, 400.0);
+        assert_eq!(report.fee_share_of_gross_pl, Some(0.1));
+        // Overlapping trades count once: 0h-3h and 5h-6h
+        assert_eq!(report.exposure_time_ms, 4 * HOUR);
+        assert!((report.exposure_percent - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(report.profit_factor, None);
+    }
+
+    #[test]
+    fn test_performance_report_daily_ratios() {
+        let trades = vec![
+            create_trade(0, HOUR, 1_000.0, 0.0),
+            create_trade(DAY, DAY + HOUR, -500.0, 0.0),
+            create_trade(2 * DAY, 2 * DAY + HOUR, 2_000.0, 0.0),
+            create_trade(4 * DAY, 4 * DAY + HOUR, 1_000.0, 0.0),
+        ];
+        let report = calculate_performance_report(&trades, 100_000.0);
+
+        // Days without trades are part of the equity curve
+        assert_eq!(report.equity_curve.len(), 5);
+        assert_eq!(report.equity_curve[3].equity, 102_500.0);
+        assert!(report.sharpe.unwrap() > 0.0);
+        assert!(report.sortino.unwrap() > report.sharpe.unwrap());
+    }
+}
@@ -2,7 +2,7 @@
 
 use trading_backend::math::price_indicators::{
     calculate_moving_average, calculate_exponential_moving_average,
-    calculate_bollinger_bands, calculate_rsi, calculate_atr,
+    calculate_bollinger_bands, calculate_rsi, calculate_atr, calculate_adx,
 };
 
 #[cfg(test)]
@@ -98,4 +98,22 @@ mod tests {
         assert!(result.is_some());
         assert!((result.unwrap() - expected_atr).abs() < 1e-4);
     }
+
+    // Test for ADX in a steady uptrend (only positive directional movement)
+    #[test]
+    fn test_adx_trend() {
+        let closes: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        let highs: Vec<f64> = closes.iter().map(|close| close + 0.5).collect();
+        let lows: Vec<f64> = closes.iter().map(|close| close - 0.5).collect();
+
+        let result = calculate_adx(&highs, &lows, &closes, 5).unwrap();
+        assert!((result - 100.0).abs() < 1e-9);
+    }
+
+    // Test for ADX with insufficient data
+    #[test]
+    fn test_adx_short_data() {
+        let prices = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(calculate_adx(&prices, &prices, &prices, 3), None); // 2 * period entries needed
+    }
 }
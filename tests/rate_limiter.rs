@@ -0,0 +1,153 @@
+// src/tests/rate_limiter.rs
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::{get, post};
+use axum::Router;
+use reqwest::header::RETRY_AFTER;
+use tokio::net::TcpListener;
+use tokio::time::{sleep, timeout, Duration, Instant};
+use trading_backend::utils::api_cache::TtlCache;
+use trading_backend::utils::http_client::{http_client, MeteredRequest};
+use trading_backend::utils::rate_limiter::{endpoint_weight, retry_after, RateLimitSettings, RateLimiter, WEBHOOK_ENDPOINT};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_burst_and_refill() {
+        let limiter = RateLimiter::new(RateLimitSettings { requests_per_second: 2.0, burst: 3.0, max_retries: 0 });
+        let now = Instant::now();
+
+        // The full bucket allows a burst
+        assert!(limiter.try_acquire(1.0, now).is_none());
+        assert!(limiter.try_acquire(2.0, now).is_none());
+
+        // Empty: one token refills in half a second
+        let retry_at = limiter.try_acquire(1.0, now).expect("bucket should be empty");
+        assert_eq!(retry_at.duration_since(now), Duration::from_millis(500));
+        assert!(limiter.try_acquire(1.0, now + Duration::from_millis(500)).is_none());
+
+        // Refill is capped at the burst
+        let later = now + Duration::from_secs(60);
+        assert!(limiter.try_acquire(3.0, later).is_none());
+        assert!(limiter.try_acquire(1.0, later).is_some());
+    }
+
+    #[test]
+    fn test_block_until_holds_requests() {
+        let limiter = RateLimiter::new(RateLimitSettings { requests_per_second: 10.0, burst: 10.0, max_retries: 0 });
+        let now = Instant::now();
+        let until = now + Duration::from_secs(2);
+        limiter.block_until(until);
+
+        assert_eq!(limiter.try_acquire(1.0, now), Some(until));
+        assert_eq!(limiter.try_acquire(1.0, now + Duration::from_secs(1)), Some(until));
+        // The bucket starts empty after the block
+        assert!(limiter.try_acquire(1.0, until).is_some());
+        assert!(limiter.try_acquire(1.0, until + Duration::from_millis(100)).is_none());
+    }
+
+    #[test]
+    fn test_retry_after_and_weights() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), Duration::from_secs(1));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Duration::from_secs(3));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(retry_after(&headers), Duration::from_secs(60));
+
+        let date = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc2822();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let wait = retry_after(&headers);
+        assert!(wait > Duration::from_secs(25) && wait <= Duration::from_secs(30), "wait: {:?}", wait);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("soon"));
+        assert_eq!(retry_after(&headers), Duration::from_secs(1));
+
+        assert_eq!(endpoint_weight("webhook"), 0.0);
+        assert_eq!(endpoint_weight("futures/ohlcs"), 2.0);
+        assert_eq!(endpoint_weight("futures/ticker"), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_cache_coalesces_and_expires() {
+        let cache = Arc::new(TtlCache::new(Duration::from_millis(200)));
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let fetch = |fetches: Arc<AtomicUsize>| async move {
+            sleep(Duration::from_millis(20)).await;
+            Ok::<usize, String>(fetches.fetch_add(1, Ordering::SeqCst) + 1)
+        };
+
+        // A burst of callers shares one fetch
+        let mut handles = Vec::new();
+        for _ in 0..5 {
+            let (cache, fetches) = (Arc::clone(&cache), Arc::clone(&fetches));
+            handles.push(tokio::spawn(async move { cache.get_or_fetch(|| fetch(fetches)).await }));
+        }
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), Ok(1));
+        }
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // Errors are not cached, invalidation and expiry fetch again
+        cache.invalidate().await;
+        assert_eq!(cache.get_or_fetch(|| async { Err("down".to_string()) }).await, Err("down".to_string()));
+        assert_eq!(cache.get_or_fetch(|| fetch(Arc::clone(&fetches))).await, Ok(2));
+        sleep(Duration::from_millis(250)).await;
+        assert_eq!(cache.get_or_fetch(|| fetch(Arc::clone(&fetches))).await, Ok(3));
+    }
+
+    #[tokio::test]
+    async fn test_send_metered_retries_after_429() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let router = Router::new().route(
+            "/ticker",
+            get(move || {
+                let counter = Arc::clone(&counter);
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "0")]).into_response()
+                    } else {
+                        StatusCode::OK.into_response()
+                    }
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/ticker", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let response = http_client().get(&url).send_metered("futures/ticker").await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_429_does_not_block_requests() {
+        let router = Router::new()
+            .route("/webhook", post(|| async { (StatusCode::TOO_MANY_REQUESTS, [(RETRY_AFTER, "30")]).into_response() }))
+            .route("/user", get(|| async { StatusCode::OK }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        // The webhook waits for its `Retry-After`, LN Markets requests are sent right away
+        let webhook = http_client().post(format!("{}/webhook", url)).send_metered(WEBHOOK_ENDPOINT);
+        let webhook = tokio::spawn(webhook);
+        sleep(Duration::from_millis(200)).await;
+        let response = timeout(Duration::from_secs(2), http_client().get(format!("{}/user", url)).send_metered("user"))
+            .await
+            .expect("request blocked by the webhook 429")
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(!webhook.is_finished());
+        webhook.abort();
+    }
+}
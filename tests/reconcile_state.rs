@@ -0,0 +1,160 @@
+// src/tests/reconcile_state.rs
+
+use std::io::Write;
+use trading_backend::config::load_signal_settings;
+use trading_backend::futures::trade::{Side, Trade};
+use trading_backend::utils::position_group::{PositionChild, PositionGroup};
+use trading_backend::utils::reconcile_state::{known_trade_ids, reconcile_trades, ExternalTradePolicy};
+use trading_backend::utils::runtime_settings::RuntimeSettings;
+use trading_backend::utils::state_store::BotState;
+use trading_backend::utils::trade_journal::{append_trade_journal, read_trade_journal, TradeJournalEntry};
+
+fn trade(id: &str, running: bool, closed: bool, pl: f64) -> Trade {
+    Trade {
+        id: id.to_string(),
+        side: Side::Buy,
+        quantity: 100.0,
+        takeprofit: 110_000.0,
+        pl,
+        opening_fee: 10.0,
+        closing_fee: if closed { 10.0 } else { 0.0 },
+        running,
+        open: !running && !closed,
+        closed,
+        closed_ts: closed.then_some(2_000),
+        ..Default::default()
+    }
+}
+
+fn journal_entry(trade_ids: &[&str], group_id: Option<&str>) -> TradeJournalEntry {
+    TradeJournalEntry {
+        time: 1_000,
+        trade_ids: trade_ids.iter().map(|id| id.to_string()).collect(),
+        group_id: group_id.map(|id| id.to_string()),
+        side: Side::Buy,
+        quantity: 200,
+        leverage: 10,
+        entry_price: 100_000.0,
+        takeprofit: Some(110_000),
+        stoploss: Some(95_000),
+        explanation: None,
+    }
+}
+
+fn child(trade_id: &str) -> PositionChild {
+    PositionChild { trade_id: trade_id.to_string(), quantity: 100, takeprofit: 110_000.0, r_multiple: 2.0, running: true, pl: 0.0, fees: 10.0 }
+}
+
+async fn state() -> BotState {
+    let mut group = PositionGroup::new(Side::Buy, 100_000.0, 95_000.0, false);
+    group.children = vec![child("g1"), child("g2")];
+    BotState {
+        saved_at: 1_500,
+        runtime: RuntimeSettings {
+            paused: false,
+            dry_run: false,
+            risk_per_trade_percent: 0.01,
+            risk_to_reward_ratio: 0.25,
+            risk_to_loss_ratio: 0.25,
+            signal_settings: load_signal_settings().await,
+        },
+        position_groups: vec![group],
+        running_trade_ids: vec!["a".to_string(), "b".to_string(), "c".to_string(), "g1".to_string(), "g2".to_string()],
+        paper: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reconcile_with_state() {
+        let state = state().await;
+        let journal = vec![journal_entry(&["limit"], None)];
+        let running = vec![trade("b", true, false, 50.0), trade("g1", true, false, 30.0), trade("ext1", true, false, 0.0)];
+        let open = vec![trade("limit", false, false, 0.0), trade("ext2", false, false, 0.0)];
+        let closed = vec![trade("a", false, true, 500.0), trade("g2", false, true, 400.0)];
+
+        let reconciliation = reconcile_trades(Some(&state), &journal, &running, &open, &closed, ExternalTradePolicy::Ignore);
+        let report = &reconciliation.report;
+
+        // Closed while down with their net P&L, "c" is gone
+        let closed_ids: Vec<&str> = report.closed_while_down.iter().map(|event| event.trade_id.as_str()).collect();
+        assert_eq!(closed_ids, vec!["a", "g2"]);
+        assert_eq!(report.closed_while_down[0].pl, 480.0);
+        assert_eq!(report.missing, vec!["c".to_string()]);
+
+        // The group keeps running with one child
+        assert_eq!(report.restored_groups, 1);
+        let group = &reconciliation.position_groups[0];
+        assert!(group.children[0].running);
+        assert_eq!(group.children[0].pl, 30.0);
+        assert!(!group.children[1].running);
+        assert_eq!(group.realized_pl(), 380.0);
+
+        // Own trades and orders, external ones ignored
+        assert_eq!(report.running, vec!["b".to_string(), "g1".to_string()]);
+        assert_eq!(report.open_orders, vec!["limit".to_string()]);
+        assert_eq!(report.ignored, vec!["ext1".to_string(), "ext2".to_string()]);
+        assert!(reconciliation.ignored_trade_ids.contains("ext1"));
+        assert_eq!(reconciliation.running_trades.len(), 2);
+
+        // Adopted external trades are managed
+        let reconciliation = reconcile_trades(Some(&state), &journal, &running, &open, &closed, ExternalTradePolicy::Adopt);
+        assert_eq!(reconciliation.report.adopted, vec!["ext1".to_string(), "ext2".to_string()]);
+        assert!(reconciliation.ignored_trade_ids.is_empty());
+        assert_eq!(reconciliation.running_trades.len(), 3);
+    }
+
+    #[test]
+    fn test_reconcile_rebuilds_groups_from_journal() {
+        let journal = vec![journal_entry(&["t1", "t2"], Some("group")), journal_entry(&["t3"], None)];
+        let running = vec![trade("t1", true, false, 20.0), trade("t3", true, false, 10.0)];
+        let closed = vec![trade("t2", false, true, 300.0)];
+
+        let reconciliation = reconcile_trades(None, &journal, &running, &[], &closed, ExternalTradePolicy::Ignore);
+
+        // Without a state nothing is known to have closed while down
+        assert!(reconciliation.report.closed_while_down.is_empty());
+        assert_eq!(reconciliation.report.running, vec!["t1".to_string(), "t3".to_string()]);
+
+        assert_eq!(reconciliation.position_groups.len(), 1);
+        let group = &reconciliation.position_groups[0];
+        assert_eq!(group.id, "group");
+        assert_eq!(group.children.len(), 2);
+        assert!(group.children[0].running);
+        assert!(!group.children[1].running);
+        assert_eq!(group.children[0].r_multiple, 2.0);
+        assert!(!group.emulate_closes);
+    }
+
+    #[tokio::test]
+    async fn test_known_trades_skip_invalid_journal_lines() {
+        let path = std::env::temp_dir().join(format!("trade_journal_invalid_{}.jsonl", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let _ = std::fs::remove_file(&path);
+        append_trade_journal(&path, &journal_entry(&["t1"], None)).unwrap();
+        let mut file = std::fs::OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"time\": 2000, \"trade_ids\": [").unwrap();
+        append_trade_journal(&path, &journal_entry(&["t2", "t3"], Some("group"))).unwrap();
+
+        // The truncated line is skipped, the entries around it are kept
+        let journal = read_trade_journal(&path).unwrap();
+        assert_eq!(journal.len(), 2);
+
+        let state = state().await;
+        let known = known_trade_ids(Some(&state), &journal);
+        for id in ["t1", "t2", "t3", "a", "c", "g1", "g2"] {
+            assert!(known.contains(id), "{}", id);
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_external_trade_policy() {
+        assert_eq!("adopt".parse::<ExternalTradePolicy>(), Ok(ExternalTradePolicy::Adopt));
+        assert_eq!(" Ignore ".parse::<ExternalTradePolicy>(), Ok(ExternalTradePolicy::Ignore));
+        assert!("close".parse::<ExternalTradePolicy>().is_err());
+    }
+}
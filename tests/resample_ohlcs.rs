@@ -0,0 +1,141 @@
+// src/tests/resample_ohlcs.rs
+
+mod common;
+
+use axum::http::Method;
+use chrono::{TimeZone, Utc};
+use common::candles::candle;
+use common::mock_lnm::MockLnm;
+use trading_backend::futures::get_ohlcs_history::{get_ohlcs_history, GetOhlcsParams};
+use trading_backend::math::resample_ohlcs::resample_ohlcs;
+use trading_backend::utils::timeframe::Timeframe;
+
+const MINUTE: i64 = 60_000;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+fn utc(year: i32, month: u32, day: u32, hour: u32) -> i64 {
+    Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display_timeframes() {
+        // LN Markets ranges
+        assert_eq!("30".parse::<Timeframe>(), Ok(Timeframe::Minutes(30)));
+        assert_eq!("60".parse::<Timeframe>(), Ok(Timeframe::Hours(1)));
+        assert_eq!("1D".parse::<Timeframe>(), Ok(Timeframe::Days(1)));
+        assert_eq!("1W".parse::<Timeframe>(), Ok(Timeframe::Weeks(1)));
+        assert_eq!("3M".parse::<Timeframe>(), Ok(Timeframe::Months(3)));
+        // Custom timeframes, `m` minutes and `M` months
+        assert_eq!("7m".parse::<Timeframe>(), Ok(Timeframe::Minutes(7)));
+        assert_eq!("6h".parse::<Timeframe>(), Ok(Timeframe::Hours(6)));
+        assert_eq!(" 12H ".parse::<Timeframe>(), Ok(Timeframe::Hours(12)));
+        assert_eq!("14d".parse::<Timeframe>(), Ok(Timeframe::Weeks(2)));
+        assert_eq!("1m".parse::<Timeframe>(), Ok(Timeframe::Minutes(1)));
+        assert_eq!("1M".parse::<Timeframe>(), Ok(Timeframe::Months(1)));
+
+        // No silent fallback
+        for invalid in ["", "0", "h", "-5", "2y", "1.5h"] {
+            assert!(invalid.parse::<Timeframe>().is_err(), "{}", invalid);
+        }
+
+        assert_eq!(Timeframe::Minutes(120), Timeframe::Hours(2));
+        assert_eq!(Timeframe::Minutes(90).to_string(), "90m");
+        assert_eq!(Timeframe::Minutes(360).to_string(), "6h");
+        assert_eq!(Timeframe::Days(7).to_string(), "1w");
+        assert_eq!(Timeframe::Months(1).to_string(), "1M");
+        assert_eq!(Timeframe::Hours(6).duration().as_secs(), 6 * 3600);
+    }
+
+    #[test]
+    fn test_native_source_of_timeframes() {
+        assert_eq!(Timeframe::Hours(4).lnm_range(), Some("240"));
+        assert_eq!(Timeframe::Days(1).lnm_range(), Some("1D"));
+        assert_eq!(Timeframe::Months(1).lnm_range(), Some("1M"));
+        assert_eq!(Timeframe::Hours(6).lnm_range(), None);
+
+        assert_eq!(Timeframe::Minutes(30).source(), Timeframe::Minutes(30));
+        assert_eq!(Timeframe::Hours(6).source(), Timeframe::Hours(3));
+        assert_eq!(Timeframe::Hours(12).source(), Timeframe::Hours(4));
+        assert_eq!(Timeframe::Minutes(7).source(), Timeframe::Minutes(1));
+        assert_eq!(Timeframe::Minutes(90).source(), Timeframe::Minutes(45));
+        assert_eq!(Timeframe::Days(2).source(), Timeframe::Days(1));
+        assert_eq!(Timeframe::Weeks(2).source(), Timeframe::Weeks(1));
+        assert_eq!(Timeframe::Months(6).source(), Timeframe::Months(3));
+        assert_eq!(Timeframe::Months(2).source(), Timeframe::Months(1));
+    }
+
+    #[test]
+    fn test_session_alignment() {
+        let time = utc(2025, 3, 12, 15) + 25 * MINUTE; // Wednesday 15:25 UTC
+
+        assert_eq!(Timeframe::Hours(6).align(time), utc(2025, 3, 12, 12));
+        assert_eq!(Timeframe::Hours(6).next_open(time), utc(2025, 3, 12, 18));
+        // Intraday candles restart with the UTC day, the last one of the day is shorter
+        assert_eq!(Timeframe::Hours(7).align(time), utc(2025, 3, 12, 14));
+        assert_eq!(Timeframe::Hours(7).next_open(utc(2025, 3, 12, 22)), utc(2025, 3, 13, 0));
+        assert_eq!(Timeframe::Minutes(7).align(time), utc(2025, 3, 12, 15) + 24 * MINUTE);
+
+        assert_eq!(Timeframe::Days(1).align(time), utc(2025, 3, 12, 0));
+        assert_eq!(Timeframe::Weeks(1).align(time), utc(2025, 3, 10, 0)); // Monday
+        assert_eq!(Timeframe::Weeks(1).next_open(time), utc(2025, 3, 17, 0));
+        assert_eq!(Timeframe::Months(1).align(time), utc(2025, 3, 1, 0));
+        assert_eq!(Timeframe::Months(1).next_open(time), utc(2025, 4, 1, 0));
+        assert_eq!(Timeframe::Months(3).align(time), utc(2025, 1, 1, 0));
+        assert_eq!(Timeframe::Months(3).next_open(utc(2025, 11, 5, 0)), utc(2026, 1, 1, 0));
+    }
+
+    #[test]
+    fn test_resample_aggregates_ohlcv() {
+        let start = utc(2025, 3, 12, 0);
+        let hourly = vec![
+            candle(start, 100.0, 105.0, 99.0, 104.0, 1.0),
+            candle(start + HOUR, 104.0, 110.0, 103.0, 108.0, 1.0),
+            candle(start + 2 * HOUR, 108.0, 109.0, 95.0, 96.0, 1.0),
+            candle(start + 3 * HOUR, 96.0, 100.0, 94.0, 99.0, 1.0),
+            // Gap: 04:00 missing
+            candle(start + 5 * HOUR, 99.0, 101.0, 98.0, 100.0, 1.0),
+        ];
+
+        let resampled = resample_ohlcs(&hourly, Timeframe::Hours(2));
+        assert_eq!(resampled.len(), 3);
+        let first = &resampled[0];
+        assert_eq!((first.time, first.open, first.high, first.low, first.close, first.volume), (start, 100.0, 110.0, 99.0, 108.0, 2.0));
+        let second = &resampled[1];
+        assert_eq!((second.time, second.open, second.high, second.low, second.close), (start + 2 * HOUR, 108.0, 109.0, 94.0, 99.0));
+        // Partial candle of the gap
+        assert_eq!((resampled[2].time, resampled[2].open, resampled[2].volume), (start + 4 * HOUR, 99.0, 1.0));
+
+        // Aligned to the day, not to the first candle
+        let resampled = resample_ohlcs(&hourly[1..], Timeframe::Hours(6));
+        assert_eq!(resampled.len(), 1);
+        assert_eq!((resampled[0].time, resampled[0].open, resampled[0].close, resampled[0].volume), (start, 104.0, 100.0, 4.0));
+        assert!(resample_ohlcs(&[], Timeframe::Days(1)).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_ohlcs_history_resamples_custom_timeframes() {
+        let mock = MockLnm::start().await;
+        // Two days of 3 hour candles
+        let start = utc(2025, 3, 10, 0);
+        let closes: Vec<f64> = (0..16).map(|i| 100.0 + i as f64).collect();
+        mock.set_candles(start, 3 * HOUR, &closes).await;
+
+        // From within the first and to within the last 6 hour candle
+        let params = GetOhlcsParams { range: Timeframe::Hours(6), from: start + HOUR, to: start + 2 * DAY - HOUR, limit: Some(1000), debug: false };
+        let candles = get_ohlcs_history(&mock.api_url, params).await.unwrap();
+
+        assert_eq!(candles.len(), 8);
+        assert!(candles.windows(2).all(|pair| pair[1].time - pair[0].time == 6 * HOUR));
+        assert_eq!(candles[0].time, start);
+        assert_eq!((candles[0].close, candles[7].close), (101.0, 115.0));
+
+        // Fetched as native 3 hour candles
+        let requests = mock.requests_to(Method::GET, "/v2/futures/ohlcs").await;
+        assert!(requests.iter().all(|request| request.signature_valid && request.query.as_deref().unwrap_or_default().contains("range=180")));
+    }
+}
@@ -0,0 +1,154 @@
+// src/tests/run_backtest.rs
+
+use trading_backend::backtest::execution_model::{ExecutionModel, SpreadModel};
+use trading_backend::backtest::run_backtest::{calculate_backtest_metrics, run_backtest, BacktestSettings, BacktestTrade, StrategyParams};
+use trading_backend::config::load_signal_settings;
+use trading_backend::futures::get_ohlcs_history::OhlcHistoryEntry;
+use trading_backend::futures::trade::Side;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Oscillating market (oldest first) that triggers mean reversion signals
+    fn create_mock_ohlcs(count: usize) -> Vec<OhlcHistoryEntry> {
+        (0..count)
+            .map(|i| {
+                let close = 100_000.0 + 1_500.0 * (i as f64 / 8.0).sin();
+                OhlcHistoryEntry {
+                    time: i as i64 * 1_800_000,
+                    open: close,
+                    high: close + 150.0,
+                    low: close - 150.0,
+                    close,
+                    volume: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    fn create_params() -> StrategyParams {
+        StrategyParams {
+            ma_period: 14,
+            ema_period: 12,
+            bb_period: 12,
+            bb_std_dev_multiplier: 2.0,
+            rsi_period: 9,
+            atr_period: 7,
+            gap_value: 15.0,
+            bollinger_weight: 0.25,
+            rsi_weight: 0.30,
+            ma_ema_weight: 0.20,
+            atr_weight: 0.25,
+        }
+    }
+
+    fn create_settings() -> BacktestSettings {
+        BacktestSettings {
+            initial_balance_sats: 1_000_000,
+            leverage: 10.0,
+            risk_per_trade_percent: 0.01,
+            risk_to_reward_ratio: 0.25,
+            risk_to_loss_ratio: 0.25,
+            fee_rate: 0.001,
+            execution: ExecutionModel::default(),
+            regime_settings: None,
+        }
+    }
+
+    fn create_trade(pl: f64, fees: f64) -> BacktestTrade {
+        BacktestTrade {
+            side: Side::Buy,
+            entry_time: 0,
+            exit_time: 1,
+            signal_price: 100_000.0,
+            entry_price: 100_000.0,
+            exit_price: 100_000.0,
+            quantity: 100.0,
+            pl,
+            opening_fee: fees / 2.0,
+            closing_fee: fees / 2.0,
+            exit_reason: "takeprofit".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_backtest_metrics() {
+        let trades = vec![create_trade(110.0, 10.0), create_trade(-40.0, 10.0), create_trade(60.0, 10.0)];
+        let metrics = calculate_backtest_metrics(&trades, 1_000.0);
+
+        assert_eq!(metrics.trade_count, 3);
+        assert_eq!(metrics.net_pl, 100.0);
+        assert!((metrics.win_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.profit_factor, 3.0); // 150 / 50
+        assert!((metrics.max_drawdown - 50.0 / 1_100.0).abs() < 1e-9); // 1100 -> 1050
+    }
+
+    #[test]
+    fn test_backtest_metrics_without_trades() {
+        let metrics = calculate_backtest_metrics(&[], 1_000.0);
+        assert_eq!(metrics.trade_count, 0);
+        assert_eq!(metrics.net_pl, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_signal_settings_derive_regime_weights() {
+        let base = load_signal_settings().await;
+        let params = StrategyParams {
+            bollinger_weight: base.bollinger_weight * 2.0,
+            rsi_weight: base.rsi_weight,
+            ..create_params()
+        };
+        let settings = params.signal_settings(&base);
+
+        // Regime weights follow the tuned weights, relative to the base weights
+        assert!((settings.trend_weights.bollinger_weight - base.trend_weights.bollinger_weight * 2.0).abs() < 1e-9);
+        assert!((settings.high_volatility_weights.bollinger_weight - params.bollinger_weight * 0.5).abs() < 1e-9);
+        assert!((settings.trend_weights.rsi_weight - base.trend_weights.rsi_weight).abs() < 1e-9);
+        assert!(settings.trend_weights.trend_following);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_balance_matches_trades() {
+        let signal_settings = load_signal_settings().await;
+        let settings = create_settings();
+        let result = run_backtest(&create_mock_ohlcs(400), &create_params(), &signal_settings, &settings, 0);
+
+        assert!(!result.trades.is_empty());
+        let net_pl: f64 = result.trades.iter().map(|trade| trade.net_pl()).sum();
+        assert!((result.final_balance_sats - (1_000_000.0 + net_pl)).abs() < 1e-6);
+        assert!(result.trades.iter().all(|trade| trade.exit_time > trade.entry_time || trade.exit_reason == "end of data"));
+    }
+
+    #[tokio::test]
+    async fn test_backtest_start_index() {
+        let ohlc_data = create_mock_ohlcs(400);
+        let signal_settings = load_signal_settings().await;
+        let result = run_backtest(&ohlc_data, &create_params(), &signal_settings, &create_settings(), 300);
+
+        assert!(result.trades.iter().all(|trade| trade.entry_time >= ohlc_data[300].time));
+    }
+
+    #[tokio::test]
+    async fn test_backtest_execution_model() {
+        let ohlc_data = create_mock_ohlcs(400);
+        let signal_settings = load_signal_settings().await;
+        let mut settings = create_settings();
+        settings.execution = ExecutionModel {
+            spread: SpreadModel::Fixed { spread: 20.0 },
+            latency_ticks: 1,
+            ..ExecutionModel::default()
+        };
+        let result = run_backtest(&ohlc_data, &create_params(), &signal_settings, &settings, 0);
+
+        assert!(!result.trades.is_empty());
+        for trade in &result.trades {
+            // Filled at the close of the next candle plus half the spread, against the trade
+            let signal_index = ohlc_data.iter().position(|candle| candle.time == trade.entry_time).unwrap() - 1;
+            assert_eq!(trade.signal_price, ohlc_data[signal_index].close);
+            let expected = if trade.side == Side::Buy { ohlc_data[signal_index + 1].close + 10.0 } else { ohlc_data[signal_index + 1].close - 10.0 };
+            assert!((trade.entry_price - expected).abs() < 1e-9);
+        }
+        assert!(result.metrics.average_entry_slippage != 0.0);
+    }
+}
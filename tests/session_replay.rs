@@ -0,0 +1,239 @@
+// src/tests/session_replay.rs
+
+mod common;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use common::candles::{candle, candle_json};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{timeout, Duration};
+use trading_backend::backtest::execution_model::ExecutionModel;
+use trading_backend::config::load_signal_settings;
+use trading_backend::futures::get_ohlcs_history::{get_ohlcs_history, GetOhlcsParams};
+use trading_backend::futures::ticker::get_futures_ticker;
+use trading_backend::utils::connect_ws::PriceData;
+use trading_backend::utils::control_api::{control_router, ControlState};
+use trading_backend::utils::init_bot_params::BotParams;
+use trading_backend::utils::reconcile_state::TradeOwnership;
+use trading_backend::utils::runtime_settings::RuntimeSettings;
+use trading_backend::utils::session_recorder::{read_session, record, record_response, start_recording, RecordedEvent, RecordedFrame};
+use trading_backend::utils::session_replay::{init_replay, replay_delay, replay_session, split_session, ReplayStore, ReplaySummary};
+use trading_backend::utils::shutdown::shutdown_channel;
+use trading_backend::utils::timeframe::Timeframe;
+
+// Nothing listens here, requests that are not replayed fail
+const UNREACHABLE_API_URL: &str = "http://127.0.0.1:9/v2";
+
+fn price(last_price: f64, time: i64) -> PriceData {
+    PriceData { last_price, last_tick_direction: "PlusTick".to_string(), time }
+}
+
+fn price_frame(ts: i64, last_price: f64) -> RecordedFrame {
+    RecordedFrame { ts, event: RecordedEvent::Price { data: price(last_price, ts) } }
+}
+
+fn rest_frame(ts: i64, endpoint: &str, body: Value) -> RecordedFrame {
+    RecordedFrame { ts, event: RecordedEvent::Rest { endpoint: endpoint.to_string(), body } }
+}
+
+fn session_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("session_{}_{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path.to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_and_read_compressed_session() {
+        let path = session_path("recorded.jsonl.gz");
+        let (shutdown_trigger, shutdown) = shutdown_channel();
+        let recorder = start_recording(&path, shutdown.clone()).unwrap();
+        // One recording per process
+        assert!(start_recording(&session_path("second.jsonl"), shutdown).is_err());
+
+        record_response("futures/ticker", r#"{"lastPrice": 100000}"#);
+        record(RecordedEvent::Price { data: price(100_000.0, 1_000) });
+        record_response("futures/market", "not json");
+        tokio::task::yield_now().await;
+        shutdown_trigger.trigger();
+        assert_eq!(recorder.await.unwrap(), 3);
+
+        // Gzip compressed JSON lines
+        let compressed = std::fs::read(&path).unwrap();
+        assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+
+        let frames = read_session(&path).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert!(frames.windows(2).all(|pair| pair[0].ts <= pair[1].ts));
+        assert_eq!(frames[0].event, RecordedEvent::Rest { endpoint: "futures/ticker".to_string(), body: json!({ "lastPrice": 100000 }) });
+        assert_eq!(frames[1].event, RecordedEvent::Price { data: price(100_000.0, 1_000) });
+        assert_eq!(frames[2].event, RecordedEvent::Rest { endpoint: "futures/market".to_string(), body: json!("not json") });
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_session_reports_invalid_lines() {
+        let path = session_path("invalid.jsonl");
+        let frame = serde_json::to_string(&price_frame(1_000, 100_000.0)).unwrap();
+        std::fs::write(&path, format!("{}\n\n{{\"ts\": 2000}}\n", frame)).unwrap();
+
+        let error = read_session(&path).unwrap_err();
+        assert!(error.to_string().contains("line 3"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_split_session_and_replay_delay() {
+        let frames = vec![
+            rest_frame(0, "user", json!({})),
+            rest_frame(10, "futures/ohlcs", json!([])),
+            price_frame(1_000, 100_000.0),
+            rest_frame(1_500, "futures/ohlcs", json!([])),
+        ];
+        let (startup, stream) = split_session(frames);
+        assert_eq!(startup.len(), 2);
+        assert_eq!(stream.len(), 2);
+        assert_eq!(stream[0].ts, 1_000);
+
+        assert_eq!(replay_delay(1_000, 3_000, 1.0), Duration::from_secs(2));
+        assert_eq!(replay_delay(1_000, 3_000, 4.0), Duration::from_millis(500));
+        assert_eq!(replay_delay(1_000, 3_000, 0.0), Duration::ZERO);
+        assert_eq!(replay_delay(3_000, 1_000, 1.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_replay_store_serves_recorded_order() {
+        let store = ReplayStore::default();
+        store.push("futures/ohlcs", &json!([candle_json(&candle(1, 1.0, 1.0, 1.0, 1.0, 1.0))]));
+        store.push("futures/ohlcs", &json!([candle_json(&candle(2, 2.0, 2.0, 2.0, 2.0, 1.0))]));
+        store.push("futures/ticker", &json!({ "lastPrice": 1 }));
+        assert_eq!(store.pending("futures/ohlcs"), 2);
+
+        assert_eq!(store.next("futures/ohlcs"), Some(json!([candle_json(&candle(1, 1.0, 1.0, 1.0, 1.0, 1.0))]).to_string()));
+        assert_eq!(store.next("futures/ohlcs"), Some(json!([candle_json(&candle(2, 2.0, 2.0, 2.0, 2.0, 1.0))]).to_string()));
+        // Exhausted: empty page for paged endpoints, last body for the others
+        assert_eq!(store.next("futures/ohlcs"), Some("[]".to_string()));
+        assert_eq!(store.next("futures/ticker"), Some(json!({ "lastPrice": 1 }).to_string()));
+        assert_eq!(store.next("futures/ticker"), Some(json!({ "lastPrice": 1 }).to_string()));
+        assert_eq!(store.next("user"), None);
+        assert_eq!(store.pending("futures/ohlcs"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_session_through_channels() {
+        let ticker: Value = serde_json::from_str(include_str!("fixtures/lnm/ticker.json")).unwrap();
+        let frames = vec![
+            rest_frame(0, "futures/ticker", ticker),
+            rest_frame(5, "futures/ohlcs", json!([candle_json(&candle(60_000, 100.0, 100.0, 100.0, 100.0, 1.0)), candle_json(&candle(120_000, 101.0, 101.0, 101.0, 101.0, 1.0))])),
+            price_frame(1_000, 100_000.0),
+            price_frame(1_200, 100_010.0),
+            // History update: pages of the OHLCs back to back
+            rest_frame(1_800_000, "futures/ohlcs", json!([candle_json(&candle(180_000, 102.0, 102.0, 102.0, 102.0, 1.0))])),
+            rest_frame(1_800_001, "futures/ohlcs", json!([])),
+            price_frame(1_800_500, 100_020.0),
+        ];
+        let (startup, stream) = split_session(frames);
+        init_replay(&startup);
+
+        // The REST functions serve the recorded responses
+        let ticker = get_futures_ticker(UNREACHABLE_API_URL).await.unwrap();
+        assert_eq!(ticker.last_price, 100_000.0);
+        let params = GetOhlcsParams { range: Timeframe::Minutes(1), from: 0, to: 1_000_000, limit: Some(1000), debug: false };
+        assert_eq!(get_ohlcs_history(UNREACHABLE_API_URL, params).await.unwrap().len(), 2);
+
+        // History updates requested on the ticks of the replay
+        let (history_tx, mut history_rx) = mpsc::channel(1);
+        let history = tokio::spawn(async move {
+            let mut candles = Vec::new();
+            while history_rx.recv().await.is_some() {
+                let params = GetOhlcsParams { range: Timeframe::Minutes(1), from: 120_001, to: 1_000_000, limit: Some(1000), debug: false };
+                candles.extend(get_ohlcs_history(UNREACHABLE_API_URL, params).await.unwrap().into_iter().map(|candle| candle.close));
+            }
+            candles
+        });
+
+        let (price_tx, mut price_rx) = mpsc::channel(16);
+        let (_shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        // As fast as possible, a 30 minute gap is not waited for
+        let summary = timeout(Duration::from_secs(5), replay_session(stream, 0.0, price_tx, history_tx, shutdown_rx)).await.unwrap();
+        assert_eq!(summary, ReplaySummary { prices: 3, responses: 2, history_updates: 1 });
+
+        let mut prices = Vec::new();
+        while let Some(price) = price_rx.recv().await {
+            prices.push((price.last_price, price.time));
+        }
+        assert_eq!(prices, vec![(100_000.0, 1_000), (100_010.0, 1_200), (100_020.0, 1_800_500)]);
+        assert_eq!(history.await.unwrap(), vec![102.0]);
+    }
+
+    #[tokio::test]
+    async fn test_replay_session_stops_on_shutdown() {
+        let stream = vec![price_frame(0, 100_000.0), price_frame(60_000, 100_010.0)];
+        let (price_tx, mut price_rx) = mpsc::channel(16);
+        let (history_tx, _history_rx) = mpsc::channel(1);
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+
+        // Original speed: the second price is a minute away
+        let replay = tokio::spawn(replay_session(stream, 1.0, price_tx, history_tx, shutdown_rx));
+        assert_eq!(price_rx.recv().await.unwrap().last_price, 100_000.0);
+        shutdown_tx.send(()).await.unwrap();
+
+        let summary = timeout(Duration::from_secs(5), replay).await.unwrap().unwrap();
+        assert_eq!(summary.prices, 1);
+        assert!(price_rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_stays_on_while_replaying() {
+        init_replay(&[]);
+        let bot_params = Arc::new(Mutex::new(BotParams {
+            user_data: None,
+            ticker_data: None,
+            market_data: None,
+            indicators: None,
+            trades: None,
+            running_trades: Vec::new(),
+            position_groups: Vec::new(),
+            paper_exchange: None,
+            runtime: RuntimeSettings {
+                paused: false,
+                dry_run: true,
+                risk_per_trade_percent: 0.01,
+                risk_to_reward_ratio: 0.25,
+                risk_to_loss_ratio: 0.25,
+                signal_settings: load_signal_settings().await,
+            },
+            last_signals: VecDeque::new(),
+            ignored_trade_ids: HashSet::new(),
+            trade_ownership: TradeOwnership::default(),
+        }));
+        let (closed_trade_tx, _closed_trade_rx) = mpsc::channel(1);
+        let state = ControlState {
+            api_url: UNREACHABLE_API_URL.into(),
+            bot_params: Arc::clone(&bot_params),
+            token: "secret".into(),
+            execution_model: ExecutionModel::default(),
+            paper_fee_rate: 0.001,
+            closed_trade_tx,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, control_router(state)).await });
+
+        // Replayed prices must not create real orders
+        let response = reqwest::Client::new()
+            .post(format!("{}/dry-run", url))
+            .bearer_auth("secret")
+            .json(&json!({ "enabled": false }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 409);
+        assert!(bot_params.lock().await.runtime.dry_run);
+    }
+}
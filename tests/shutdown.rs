@@ -0,0 +1,124 @@
+// src/tests/shutdown.rs
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{timeout, Duration};
+use trading_backend::backtest::execution_model::ExecutionModel;
+use trading_backend::config::load_signal_settings;
+use trading_backend::utils::init_bot_params::BotParams;
+use trading_backend::utils::paper_exchange::{MarketOrder, PaperExchange};
+use trading_backend::utils::reconcile_state::TradeOwnership;
+use trading_backend::utils::runtime_settings::RuntimeSettings;
+use trading_backend::utils::shutdown::{apply_exit_policy, shutdown_channel, until_shutdown, ExitPolicy};
+use trading_backend::utils::state_store::{load_state, save_state, BotState};
+
+// Bot params paper trading with one open position and one pending order
+async fn paper_bot_params() -> BotParams {
+    let mut exchange = PaperExchange::new(ExecutionModel { latency_ticks: 1, ..Default::default() }, 1_000_000.0, 0.001);
+    exchange.submit_market_order(MarketOrder { is_buy: true, quantity: 100.0, signal_price: 100_000.0, stoploss: 95_000.0, takeprofit: 110_000.0, atr: None }, 0);
+    exchange.on_price(100_000.0, 1_000);
+    exchange.on_price(101_000.0, 2_000);
+    exchange.submit_market_order(MarketOrder { is_buy: false, quantity: 50.0, signal_price: 101_000.0, stoploss: 105_000.0, takeprofit: 90_000.0, atr: None }, 2_000);
+
+    BotParams {
+        user_data: None,
+        ticker_data: None,
+        market_data: None,
+        indicators: None,
+        trades: None,
+        running_trades: Vec::new(),
+        position_groups: Vec::new(),
+        paper_exchange: Some(exchange),
+        runtime: RuntimeSettings {
+            paused: false,
+            dry_run: true,
+            risk_per_trade_percent: 0.01,
+            risk_to_reward_ratio: 0.25,
+            risk_to_loss_ratio: 0.25,
+            signal_settings: load_signal_settings().await,
+        },
+        last_signals: VecDeque::new(),
+        ignored_trade_ids: HashSet::new(),
+        trade_ownership: TradeOwnership::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_exit_policy() {
+        assert_eq!("leave".parse::<ExitPolicy>(), Ok(ExitPolicy::Leave));
+        assert_eq!("CANCEL_ORDERS".parse::<ExitPolicy>(), Ok(ExitPolicy::CancelOrders));
+        assert_eq!("close_all".parse::<ExitPolicy>(), Ok(ExitPolicy::CloseAll));
+        assert!("sell_everything".parse::<ExitPolicy>().is_err());
+        assert_eq!(ExitPolicy::CloseAll.to_string(), "close_all");
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_tasks() {
+        let (trigger, shutdown) = shutdown_channel();
+        assert!(!shutdown.is_triggered());
+
+        let task = tokio::spawn(until_shutdown(shutdown.clone(), std::future::pending()));
+        trigger.trigger();
+        assert!(shutdown.is_triggered());
+        timeout(Duration::from_secs(1), task).await.expect("task not stopped").unwrap();
+
+        // Receivers created after the trigger return immediately
+        let mut late = shutdown.clone();
+        timeout(Duration::from_secs(1), late.wait()).await.expect("shutdown not seen");
+    }
+
+    #[tokio::test]
+    async fn test_exit_policy_on_paper_exchange() {
+        let (closed_trade_tx, mut closed_trade_rx) = mpsc::channel(10);
+
+        // Leave: nothing changes
+        let bot_params = Arc::new(Mutex::new(paper_bot_params().await));
+        let result = apply_exit_policy(ExitPolicy::Leave, "http://127.0.0.1:1", &bot_params, &closed_trade_tx).await;
+        assert!(result.canceled.is_empty() && result.closed.is_empty());
+        assert_eq!(bot_params.lock().await.paper_exchange.as_ref().unwrap().orders.len(), 1);
+
+        // Cancel orders: the position keeps running
+        let result = apply_exit_policy(ExitPolicy::CancelOrders, "http://127.0.0.1:1", &bot_params, &closed_trade_tx).await;
+        assert_eq!(result.canceled.len(), 1);
+        assert!(result.closed.is_empty());
+        {
+            let bot_params = bot_params.lock().await;
+            let exchange = bot_params.paper_exchange.as_ref().unwrap();
+            assert!(exchange.orders.is_empty());
+            assert_eq!(exchange.positions.len(), 1);
+        }
+
+        // Close all: the position is closed at the last price
+        let bot_params = Arc::new(Mutex::new(paper_bot_params().await));
+        let result = apply_exit_policy(ExitPolicy::CloseAll, "http://127.0.0.1:1", &bot_params, &closed_trade_tx).await;
+        assert_eq!(result.canceled.len(), 1);
+        assert_eq!(result.closed.len(), 1);
+        assert!(result.errors.is_empty());
+        assert!(result.closed_pl > 0.0);
+        assert!(bot_params.lock().await.paper_exchange.as_ref().unwrap().positions.is_empty());
+        assert_eq!(closed_trade_rx.recv().await.unwrap().trade_id, result.closed[0]);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_load_state() {
+        let path = std::env::temp_dir().join(format!("bot_state_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert!(load_state(path).unwrap().is_none());
+
+        let state = BotState::capture(&paper_bot_params().await);
+        save_state(path, &state).unwrap();
+        let loaded = load_state(path).unwrap().unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(loaded.runtime.dry_run);
+        let paper = loaded.paper.unwrap();
+        assert_eq!(paper.positions.len(), 1);
+        assert_eq!(paper.orders.len(), 1);
+        assert_eq!(paper.balance, state.paper.unwrap().balance);
+    }
+}
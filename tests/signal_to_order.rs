@@ -0,0 +1,394 @@
+// src/tests/signal_to_order.rs
+
+mod common;
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use axum::http::{Method, StatusCode};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{sleep, Duration, Instant};
+use common::mock_lnm::{MockLnm, MockResponse};
+use trading_backend::backtest::execution_model::ExecutionModel;
+use trading_backend::config::{load_signal_settings, SignalConditioning};
+use trading_backend::futures::create_trade::{create_market_order, create_trade, CreateTradeParams, Sizing};
+use trading_backend::futures::get_market::get_market;
+use trading_backend::futures::trade::Side;
+use trading_backend::math::create_trade_from_signal::{create_trade_from_signal, CreateTradeResult, TradeSettings};
+use trading_backend::math::get_indicators::Indicators;
+use trading_backend::math::indicator_set::{IndicatorSet, IndicatorSource, IndicatorValue, ATR};
+use trading_backend::math::get_market_regime::RegimeIndicators;
+use trading_backend::math::get_signals::{Signal, SignalExplanation, SignalResponse};
+use trading_backend::utils::control_api::{control_router, ControlState};
+use trading_backend::utils::filter_signals::SkipReason;
+use trading_backend::utils::init_bot_params::BotParams;
+use trading_backend::utils::process_signals::{process_signals, SignalProcessingSettings};
+use trading_backend::utils::reconcile_state::{own_trades, ExternalTradePolicy, TradeOwnership};
+use trading_backend::utils::runtime_settings::RuntimeSettings;
+use trading_backend::utils::shutdown::{apply_exit_policy, shutdown_channel, ExitPolicy};
+use trading_backend::utils::trade_journal::read_trade_journal;
+
+// Live (not dry run) bot params with the market data of the mock
+async fn live_bot_params(mock: &MockLnm) -> Arc<Mutex<BotParams>> {
+    Arc::new(Mutex::new(BotParams {
+        user_data: None,
+        ticker_data: None,
+        market_data: Some(get_market(&mock.api_url).await.unwrap()),
+        indicators: None,
+        trades: None,
+        running_trades: Vec::new(),
+        position_groups: Vec::new(),
+        paper_exchange: None,
+        runtime: RuntimeSettings {
+            paused: false,
+            dry_run: false,
+            risk_per_trade_percent: 0.01,
+            risk_to_reward_ratio: 0.25,
+            risk_to_loss_ratio: 0.25,
+            signal_settings: load_signal_settings().await,
+        },
+        last_signals: VecDeque::new(),
+        ignored_trade_ids: HashSet::new(),
+        trade_ownership: TradeOwnership::default(),
+    }))
+}
+
+fn indicators(atr: f64) -> Indicators {
+    let mut values = IndicatorSet::default();
+    values.insert(IndicatorSource::Ohlc, ATR, IndicatorValue::Single(atr));
+    Indicators {
+        ohlc_data: vec![],
+        price_data: vec![],
+        index_price_data: vec![],
+        values,
+        regime_indicators: RegimeIndicators::default(),
+        regime: None,
+        basis: None,
+    }
+}
+
+fn signal_response(signal: Signal, score: f64, time: i64) -> SignalResponse {
+    SignalResponse {
+        signal,
+        explanation: SignalExplanation {
+            price: 100_000.0,
+            time,
+            contributions: Vec::new(),
+            score,
+            bucket: 1,
+            regime: None,
+            invalid_reason: None,
+        },
+        indicators: Some(indicators(200.0)),
+    }
+}
+
+fn journal_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("trade_journal_{}_{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path.to_string_lossy().to_string()
+}
+
+// Leverage 20 and 1% risk per trade, stoploss and takeprofit at 0.25 ATR x leverage
+fn trade_settings(scale_out_targets: Vec<f64>, journal: &str) -> TradeSettings {
+    TradeSettings {
+        leverage: 20,
+        risk_per_trade_percent: 0.01,
+        risk_to_reward_ratio: 0.25,
+        risk_to_loss_ratio: 0.25,
+        scale_out_targets,
+        emulate_partial_closes: false,
+        trade_journal_path: journal.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_buy_signal_creates_signed_order() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("buy");
+
+        let result = create_trade_from_signal(
+            Signal::Buy,
+            None,
+            &mock.api_url,
+            Arc::clone(&bot_params),
+            Some(indicators(200.0)),
+            &trade_settings(Vec::new(), &journal),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, CreateTradeResult::TradeCreated));
+
+        // Running trades, user and ticker are fetched before the order, all signed
+        let requests = mock.requests().await;
+        assert!(requests.iter().all(|request| request.signature_valid));
+        let orders = mock.requests_to(Method::POST, "/v2/futures").await;
+        assert_eq!(orders.len(), 1);
+
+        // Market buy at the ask, stoploss below and takeprofit above (ATR 200 x leverage 20 x 0.25)
+        let body: Value = serde_json::from_str(&orders[0].body).unwrap();
+        assert_eq!(body["side"], "b");
+        assert_eq!(body["type"], "m");
+        assert_eq!(body["leverage"], 20);
+        assert_eq!(body["stoploss"], 99_000);
+        assert_eq!(body["takeprofit"], 101_000);
+        assert!(body["quantity"].as_u64().unwrap() > 0);
+
+        let entries = read_trade_journal(&journal).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].trade_ids, vec!["mock-trade-1".to_string()]);
+        assert_eq!(entries[0].entry_price, 100_000.5);
+        std::fs::remove_file(&journal).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scale_out_signal_creates_child_orders() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("scale_out");
+
+        let result = create_trade_from_signal(
+            Signal::StrongSell,
+            None,
+            &mock.api_url,
+            Arc::clone(&bot_params),
+            // Low volatility: quantity large enough for two children
+            Some(indicators(2.0)),
+            &trade_settings(vec![1.0, 2.0], &journal),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, CreateTradeResult::TradeCreated));
+
+        // One sell per target with a shared stoploss and staggered takeprofits
+        let orders = mock.requests_to(Method::POST, "/v2/futures").await;
+        assert_eq!(orders.len(), 2);
+        let bodies: Vec<Value> = orders.iter().map(|order| serde_json::from_str(&order.body).unwrap()).collect();
+        assert!(bodies.iter().all(|body| body["side"] == "s" && body["stoploss"] == bodies[0]["stoploss"]));
+        assert!(bodies[1]["takeprofit"].as_u64() < bodies[0]["takeprofit"].as_u64());
+
+        let bot_params = bot_params.lock().await;
+        assert_eq!(bot_params.position_groups.len(), 1);
+        assert_eq!(bot_params.position_groups[0].children.len(), 2);
+        let _ = std::fs::remove_file(&journal);
+    }
+
+    #[tokio::test]
+    async fn test_single_scale_out_target_creates_group() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("single_target");
+
+        let result = create_trade_from_signal(
+            Signal::Buy,
+            None,
+            &mock.api_url,
+            Arc::clone(&bot_params),
+            Some(indicators(200.0)),
+            &trade_settings(vec![2.0], &journal),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, CreateTradeResult::TradeCreated));
+
+        // The takeprofit is placed at 2R, not at the risk-to-reward takeprofit
+        let orders = mock.requests_to(Method::POST, "/v2/futures").await;
+        assert_eq!(orders.len(), 1);
+        let body: Value = serde_json::from_str(&orders[0].body).unwrap();
+        assert_eq!(body["stoploss"], 99_000);
+        assert_eq!(body["takeprofit"], 102_001);
+
+        let bot_params = bot_params.lock().await;
+        assert_eq!(bot_params.position_groups.len(), 1);
+        assert_eq!(bot_params.position_groups[0].children.len(), 1);
+        let _ = std::fs::remove_file(&journal);
+    }
+
+    #[tokio::test]
+    async fn test_missing_atr_skips_trade() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("missing_atr");
+
+        let result = create_trade_from_signal(
+            Signal::Buy,
+            None,
+            &mock.api_url,
+            Arc::clone(&bot_params),
+            None,
+            &trade_settings(Vec::new(), &journal),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, CreateTradeResult::NoTradeCreated(SkipReason::MissingIndicators)));
+        assert!(mock.requests_to(Method::POST, "/v2/futures").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_signals_end_to_end() {
+        let mock = MockLnm::start().await;
+        // The first order fails, the next signal is still processed
+        mock.script(Method::POST, "/v2/futures", vec![MockResponse::Status(StatusCode::INTERNAL_SERVER_ERROR)]).await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("process");
+
+        let (signal_tx, signal_rx) = mpsc::channel(8);
+        let (_closed_trade_tx, closed_trade_rx) = mpsc::channel(8);
+        let (shutdown_trigger, shutdown) = shutdown_channel();
+        let conditioning = SignalConditioning {
+            entry_threshold: 0.2,
+            exit_threshold: 0.1,
+            confirmation_ticks: 1,
+            confirmation_seconds: 0,
+            loss_cooldown_seconds: 0,
+        };
+        let processing = tokio::spawn(process_signals(
+            signal_rx,
+            closed_trade_rx,
+            Arc::from(mock.api_url.as_str()),
+            Arc::clone(&bot_params),
+            SignalProcessingSettings {
+                trade_gap_seconds: 0,
+                scale_out_targets: Vec::new(),
+                emulate_partial_closes: false,
+                signal_conditioning: conditioning,
+                candle_seconds: 60,
+                trade_journal_path: journal.clone(),
+                order_timeout: Duration::from_secs(5),
+            },
+            shutdown,
+        ));
+
+        // Hold and a score below the entry threshold create no order
+        signal_tx.send(signal_response(Signal::Hold, 0.0, 1_000)).await.unwrap();
+        signal_tx.send(signal_response(Signal::Buy, 0.1, 2_000)).await.unwrap();
+        signal_tx.send(signal_response(Signal::Buy, 0.8, 3_000)).await.unwrap();
+        // Further signals wait for the failed order, which enters no direction
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while mock.requests_to(Method::POST, "/v2/futures").await.is_empty() && Instant::now() < deadline {
+            sleep(Duration::from_millis(20)).await;
+        }
+        sleep(Duration::from_millis(100)).await;
+        signal_tx.send(signal_response(Signal::Hold, 0.0, 4_000)).await.unwrap();
+        signal_tx.send(signal_response(Signal::Buy, 0.8, 5_000)).await.unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while mock.trades().await.is_empty() && Instant::now() < deadline {
+            sleep(Duration::from_millis(20)).await;
+        }
+        shutdown_trigger.trigger();
+        assert_eq!(processing.await.unwrap(), 0);
+
+        assert_eq!(mock.requests_to(Method::POST, "/v2/futures").await.len(), 2);
+        assert_eq!(mock.trades().await.len(), 1);
+        assert_eq!(bot_params.lock().await.last_signals.len(), 5);
+        assert_eq!(read_trade_journal(&journal).unwrap().len(), 1);
+        std::fs::remove_file(&journal).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_close_all_leaves_external_trades() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let journal = journal_path("external");
+
+        // A trade of the bot, then a market trade and a limit order opened outside of the bot
+        let result = create_trade_from_signal(
+            Signal::Buy,
+            None,
+            &mock.api_url,
+            Arc::clone(&bot_params),
+            Some(indicators(200.0)),
+            &trade_settings(Vec::new(), &journal),
+        )
+        .await
+        .unwrap();
+        assert!(matches!(result, CreateTradeResult::TradeCreated));
+        create_market_order(&mock.api_url, Side::Sell, 10, Sizing::Quantity(5), None, None).await.unwrap();
+        create_trade(&mock.api_url, CreateTradeParams::limit(Side::Buy, 10, 90_000, Sizing::Quantity(5), None, None)).await.unwrap();
+        bot_params.lock().await.trade_ownership.policy = ExternalTradePolicy::Ignore;
+
+        let (closed_trade_tx, _closed_trade_rx) = mpsc::channel(10);
+        let result = apply_exit_policy(ExitPolicy::CloseAll, &mock.api_url, &bot_params, &closed_trade_tx).await;
+        assert_eq!(result.closed, vec!["mock-trade-1".to_string()]);
+        assert!(result.canceled.is_empty());
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let trades = mock.trades().await;
+        assert_eq!(trades[0]["closed"], true);
+        assert_eq!(trades[1]["running"], true);
+        assert_eq!(trades[2]["open"], true);
+        assert!(mock.requests_to(Method::DELETE, "/v2/futures/all/close").await.is_empty());
+        assert!(mock.requests_to(Method::DELETE, "/v2/futures/all/cancel").await.is_empty());
+
+        let ignored = &bot_params.lock().await.ignored_trade_ids;
+        assert!(ignored.contains("mock-trade-2") && ignored.contains("mock-trade-3"));
+        std::fs::remove_file(&journal).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_control_api_acts_on_own_trades() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let own = create_market_order(&mock.api_url, Side::Buy, 10, Sizing::Quantity(5), None, None).await.unwrap();
+        let external = create_market_order(&mock.api_url, Side::Sell, 10, Sizing::Quantity(5), None, None).await.unwrap();
+        bot_params.lock().await.trade_ownership.known_trade_ids.insert(own.id.clone());
+
+        let (closed_trade_tx, _closed_trade_rx) = mpsc::channel(1);
+        let state = ControlState {
+            api_url: mock.api_url.as_str().into(),
+            bot_params: Arc::clone(&bot_params),
+            token: "secret".into(),
+            execution_model: ExecutionModel::default(),
+            paper_fee_rate: 0.001,
+            closed_trade_tx,
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, control_router(state)).await });
+        let client = reqwest::Client::new();
+
+        // The ignored external trade is neither listed nor closed
+        let trades: Vec<Value> = client.get(format!("{}/trades", url)).bearer_auth("secret").send().await.unwrap().json().await.unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0]["id"], own.id.as_str());
+        let response = client.post(format!("{}/trades/{}/close", url, external.id)).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = client.post(format!("{}/trades/{}/close", url, own.id)).bearer_auth("secret").send().await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let trades = mock.trades().await;
+        assert_eq!(trades[0]["closed"], true);
+        assert_eq!(trades[1]["running"], true);
+    }
+
+    #[tokio::test]
+    async fn test_external_trades_opened_after_startup() {
+        let mock = MockLnm::start().await;
+        let bot_params = live_bot_params(&mock).await;
+        let trade = create_market_order(&mock.api_url, Side::Buy, 10, Sizing::Quantity(5), None, None).await.unwrap();
+
+        // Order of the bot in flight: left out, but not ignored for good
+        bot_params.lock().await.trade_ownership.begin_order();
+        assert!(own_trades(&bot_params, vec![trade.clone()]).await.is_empty());
+        assert!(bot_params.lock().await.ignored_trade_ids.is_empty());
+
+        // Ignored once no order is in flight anymore, never managed
+        bot_params.lock().await.trade_ownership.end_order(None);
+        assert!(own_trades(&bot_params, vec![trade.clone()]).await.is_empty());
+        assert!(bot_params.lock().await.ignored_trade_ids.contains(&trade.id));
+
+        // Adopted: managed like the trades of the bot
+        let bot_params = live_bot_params(&mock).await;
+        bot_params.lock().await.trade_ownership = TradeOwnership { policy: ExternalTradePolicy::Adopt, ..Default::default() };
+        let own = own_trades(&bot_params, vec![trade.clone()]).await;
+        assert_eq!(own.len(), 1);
+        assert!(bot_params.lock().await.ignored_trade_ids.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+// src/tests/trade.rs
+
+use serde_json::{json, Value};
+use trading_backend::futures::create_trade::{CreateTradeParams, OrderKind, Sizing};
+use trading_backend::futures::get_trades::GetTradesParams;
+use trading_backend::futures::trade::{OrderType, Side, Trade, TradeStatus};
+use trading_backend::utils::get_headers::encode_query_params;
+
+fn fixture() -> Value {
+    serde_json::from_str(include_str!("fixtures/lnm/trade.json")).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_wire_format() {
+        let trade: Trade = serde_json::from_value(fixture()).unwrap();
+        assert_eq!((trade.side, trade.order_type), (Side::Buy, OrderType::Market));
+        assert_eq!(trade.status(), TradeStatus::Running);
+        assert_eq!(trade.market_filled_ts, Some(1_735_689_600_000));
+        assert_eq!(trade.exit_price, None);
+
+        // Serialized back to the wire values
+        let value = serde_json::to_value(&trade).unwrap();
+        assert_eq!((&value["side"], &value["type"]), (&json!("b"), &json!("m")));
+
+        // Unfilled limit order and closed short
+        let mut limit = fixture();
+        limit["type"] = json!("l");
+        limit["side"] = json!("s");
+        limit["running"] = json!(false);
+        limit["open"] = json!(true);
+        limit["market_filled_ts"] = json!(null);
+        let limit: Trade = serde_json::from_value(limit).unwrap();
+        assert_eq!((limit.side, limit.order_type, limit.status()), (Side::Sell, OrderType::Limit, TradeStatus::Open));
+        assert_eq!(limit.market_filled_ts, None);
+        assert_eq!(Trade { canceled: true, ..limit }.status(), TradeStatus::Closed);
+
+        // Unknown values are rejected
+        let mut invalid = fixture();
+        invalid["side"] = json!("buy");
+        assert!(serde_json::from_value::<Trade>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_trade_status_query() {
+        let params = GetTradesParams { r#type: TradeStatus::Closed, limit: Some(10), ..Default::default() };
+        assert_eq!(encode_query_params(&params).as_deref(), Some("limit=10&type=closed"));
+        assert_eq!(encode_query_params(&GetTradesParams::default()).as_deref(), Some("type=open"));
+        assert_eq!(TradeStatus::ALL.map(|status| status.to_string()), ["running", "open", "closed"]);
+    }
+
+    #[test]
+    fn test_create_trade_params() {
+        let market = CreateTradeParams::market(Side::Sell, 10, Sizing::Quantity(100), None, Some(105_000));
+        assert_eq!((market.kind(), market.side(), market.sizing()), (OrderKind::Market, Side::Sell, Sizing::Quantity(100)));
+        let body = serde_json::to_value(&market).unwrap();
+        assert_eq!(body, json!({ "type": "m", "side": "s", "leverage": 10, "quantity": 100, "stoploss": 105_000 }));
+
+        // The limit price and the sizing are sent from the enums, never both quantity and margin
+        let limit = CreateTradeParams::limit(Side::Buy, 10, 95_000, Sizing::Margin(5_000), Some(99_000), None);
+        assert_eq!(limit.kind(), OrderKind::Limit { price: 95_000 });
+        let body = serde_json::to_value(&limit).unwrap();
+        assert_eq!(body, json!({ "type": "l", "side": "b", "leverage": 10, "price": 95_000, "margin": 5_000, "takeprofit": 99_000 }));
+    }
+}
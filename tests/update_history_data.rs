@@ -0,0 +1,75 @@
+// src/tests/update_history_data.rs
+
+mod common;
+
+use std::sync::Arc;
+use axum::http::Method;
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{timeout, Duration};
+use common::mock_lnm::MockLnm;
+use trading_backend::futures::get_price_history::PriceHistoryEntry;
+use trading_backend::utils::get_timestamps::get_current_time_ms;
+use trading_backend::utils::timeframe::Timeframe;
+use trading_backend::utils::update_history_data::{merge_history, update_history_data, HistoryBuffers};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_entries(times: &[i64]) -> Vec<PriceHistoryEntry> {
+        times.iter().map(|time| PriceHistoryEntry { time: *time, value: *time as f64 }).collect()
+    }
+
+    fn times(entries: &[PriceHistoryEntry]) -> Vec<i64> {
+        entries.iter().map(|entry| entry.time).collect()
+    }
+
+    #[test]
+    fn test_merge_history_appends_sorted() {
+        let mut buffer = create_entries(&[1, 2, 3]);
+        // Newest first (API order), including an entry that is already known
+        merge_history(&mut buffer, create_entries(&[5, 4, 3]), |entry| entry.time);
+
+        assert_eq!(times(&buffer), vec![3, 4, 5]); // Bounded to the initial length, oldest dropped
+    }
+
+    #[test]
+    fn test_merge_history_without_new_data() {
+        let mut buffer = create_entries(&[1, 2, 3]);
+        merge_history(&mut buffer, create_entries(&[2, 3]), |entry| entry.time);
+
+        assert_eq!(times(&buffer), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_merge_history_empty_buffer() {
+        let mut buffer = Vec::new();
+        merge_history(&mut buffer, create_entries(&[2, 1]), |entry| entry.time);
+
+        assert_eq!(times(&buffer), vec![1, 2]); // Unbounded without an initial length
+    }
+
+    #[tokio::test]
+    async fn test_empty_buffer_starts_at_configured_from() {
+        let mock = MockLnm::start().await;
+        let start = get_current_time_ms() - 10 * 60_000;
+        mock.set_candles(start, 60_000, &[100.0, 101.0, 102.0]).await;
+
+        let buffers = HistoryBuffers { ohlc_data: Arc::new(Mutex::new(Vec::new())), price_data: None, index_data: None };
+        let (tx, mut rx) = mpsc::channel(1);
+        let (tick_tx, tick_rx) = mpsc::channel(1);
+        let api_url = mock.api_url.clone();
+        tokio::spawn(async move {
+            update_history_data(&api_url, Timeframe::Minutes(1), start - 1, buffers, Duration::from_secs(60), tx, Some(tick_rx)).await
+        });
+
+        tick_tx.send(()).await.unwrap();
+        let update = timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+
+        // Fetched from the configured start, not from the epoch, and not truncated to the empty length
+        assert_eq!(update.ohlc_data.len(), 3);
+        let requests = mock.requests_to(Method::GET, "/v2/futures/ohlcs").await;
+        let query = requests[0].query.clone().unwrap_or_default();
+        assert!(query.contains(&format!("from={}", start - 1)), "{}", query);
+    }
+}